@@ -0,0 +1,426 @@
+// `cargo embed-plist check`: finds the binaries in the workspace that embed
+// property lists, checks the embedded files, and checks the linked binaries.
+
+use crate::{
+    object::{self, Kind},
+    relative,
+    source::{self, Call},
+};
+use embed_plist::{
+    keys::{self, Key, ValueType},
+    macho,
+    value::{Dictionary, Value},
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Where to look for linked binaries.
+pub struct Options {
+    pub target_dir: Option<PathBuf>,
+    /// The directory name of the profile, such as `debug` or `release`.
+    pub profile: String,
+    pub target: Option<String>,
+}
+
+// A binary target of a workspace package.
+struct Binary {
+    package: String,
+    name: String,
+    manifest_dir: PathBuf,
+    // The binary's own root, followed by the package's library, if any.
+    roots: Vec<PathBuf>,
+}
+
+/// Checks every binary in the workspace and returns `false` if any check
+/// failed.
+pub fn run(options: &Options) -> Result<bool, String> {
+    let metadata =
+        crate::cargo(&["metadata", "--format-version", "1", "--no-deps"])?;
+    let metadata = Value::from_json(without_nulls(&metadata).as_bytes())
+        .map_err(|error| format!("cannot read `cargo metadata`: {}", error))?;
+    let metadata = metadata
+        .as_dictionary()
+        .ok_or("`cargo metadata` did not print an object")?;
+
+    let root = PathBuf::from(string(metadata, "workspace_root")?);
+    let target_dir = match &options.target_dir {
+        Some(dir) => dir.clone(),
+        None => PathBuf::from(string(metadata, "target_directory")?),
+    };
+    let mut profile_dir = target_dir;
+    if let Some(target) = &options.target {
+        profile_dir.push(target);
+    }
+    profile_dir.push(&options.profile);
+
+    let mut passed = true;
+    let mut checked = 0;
+    for binary in binaries(metadata)? {
+        let mut calls = Vec::new();
+        let mut errors = Vec::new();
+        for root in &binary.roots {
+            source::scan_crate(
+                root,
+                &binary.manifest_dir,
+                &mut calls,
+                &mut errors,
+            );
+        }
+        if calls.is_empty() && errors.is_empty() {
+            continue;
+        }
+        checked += 1;
+
+        println!("bin `{}` in package `{}`", binary.name, binary.package);
+        let mut report = Report::default();
+        for error in errors {
+            report.error(error);
+        }
+        let plists = check_calls(&calls, &root, &mut report);
+        let path = profile_dir.join(&binary.name);
+        check_binary(&path, &root, &calls, &plists, &mut report);
+        passed &= report.passed;
+    }
+
+    if checked == 0 {
+        println!("No binaries in the workspace embed a property list.");
+    }
+    Ok(passed)
+}
+
+// Prints results and remembers whether all passed.
+struct Report {
+    passed: bool,
+}
+
+impl Default for Report {
+    fn default() -> Self {
+        Report { passed: true }
+    }
+}
+
+impl Report {
+    fn ok(&mut self, message: impl std::fmt::Display) {
+        println!("    ok: {}", message);
+    }
+
+    fn note(&mut self, message: impl std::fmt::Display) {
+        println!("    note: {}", message);
+    }
+
+    // Reports a likely mistake that does not fail the check.
+    fn warning(&mut self, message: impl std::fmt::Display) {
+        println!("    warning: {}", message);
+    }
+
+    fn error(&mut self, message: impl std::fmt::Display) {
+        println!("    error: {}", message);
+        self.passed = false;
+    }
+}
+
+// Parses the files embedded by each call, returning them by kind.
+fn check_calls(
+    calls: &[Call],
+    root: &Path,
+    report: &mut Report,
+) -> Vec<(Kind, PathBuf, Value)> {
+    let mut plists = Vec::new();
+    for call in calls {
+        println!(
+            "  {}:{}: {}!",
+            relative(&call.file, root).display(),
+            call.line,
+            call.name
+        );
+        let paths = match &call.paths {
+            Some(paths) => paths,
+            None => {
+                report.note(
+                    "the path is not a string literal, so it was not checked",
+                );
+                continue;
+            }
+        };
+        for path in paths {
+            let shown = relative(path, root).display();
+            let bytes = match fs::read(path) {
+                Ok(bytes) => bytes,
+                Err(error) => {
+                    report.error(format_args!(
+                        "cannot read `{}`: {}",
+                        shown, error
+                    ));
+                    continue;
+                }
+            };
+            match parse(&bytes) {
+                Ok(Value::Dictionary(dict)) => {
+                    report.ok(&shown);
+                    check_keys(call.kind, &dict, &shown, report);
+                    plists.push((
+                        call.kind,
+                        path.clone(),
+                        Value::Dictionary(dict),
+                    ));
+                }
+                Ok(_) => report.error(format_args!(
+                    "`{}` is a property list, but its root is not a dictionary",
+                    shown
+                )),
+                Err(error) => report.error(format_args!(
+                    "`{}` is not a property list: {}",
+                    shown, error
+                )),
+            }
+        }
+    }
+    plists
+}
+
+// Checks the top-level keys of a property list against the documented keys.
+fn check_keys(
+    kind: Kind,
+    dict: &Dictionary,
+    shown: &dyn std::fmt::Display,
+    report: &mut Report,
+) {
+    type Lookup = fn(&str) -> Option<&'static Key>;
+    let (find, closest): (Lookup, Lookup) = match kind {
+        Kind::InfoPlist => (keys::info::find, keys::info::closest),
+        Kind::LaunchdPlist => (keys::launchd::find, keys::launchd::closest),
+    };
+    for (name, value) in dict.iter() {
+        let key = match find(name) {
+            Some(key) => key,
+            None => {
+                if let Some(key) = closest(name) {
+                    report.warning(format_args!(
+                        "unknown key `{}` in `{}`; did you mean `{}`?",
+                        name, shown, key
+                    ));
+                }
+                continue;
+            }
+        };
+        let ty = ValueType::of(value);
+        if !key.accepts(ty) {
+            let expected: Vec<String> =
+                key.types().iter().map(|ty| format!("`{}`", ty)).collect();
+            report.error(format_args!(
+                "`{}` in `{}` is `{}`, but must be {}",
+                name,
+                shown,
+                ty,
+                expected.join(" or ")
+            ));
+        } else if key.is_deprecated() {
+            report.warning(format_args!(
+                "`{}` in `{}` is deprecated: {}",
+                name,
+                shown,
+                key.description()
+            ));
+        }
+    }
+}
+
+// Checks that the linked binary, if it has been built, has each kind of
+// property list that is embedded in its sources.
+fn check_binary(
+    path: &Path,
+    root: &Path,
+    calls: &[Call],
+    plists: &[(Kind, PathBuf, Value)],
+    report: &mut Report,
+) {
+    let shown = relative(path, root).display();
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            println!("  {}", shown);
+            report.note("not built, so the linked binary was not checked");
+            return;
+        }
+    };
+    println!("  {}", shown);
+
+    for &kind in Kind::ALL.iter() {
+        if !calls.iter().any(|call| call.kind == kind) {
+            continue;
+        }
+        let expected: Vec<&(Kind, PathBuf, Value)> =
+            plists.iter().filter(|plist| plist.0 == kind).collect();
+
+        if bytes.starts_with(b"\x7fELF") {
+            // The `portable` feature embeds into a plain static.
+            let mut hits = Vec::new();
+            object::scan(&bytes, &mut hits);
+            if hits.iter().any(|hit| hit.kind == kind && hit.found.symbol) {
+                report.ok(format_args!("defines `{}`", kind.symbol()));
+            } else {
+                report
+                    .error(format_args!("does not define `{}`", kind.symbol()));
+            }
+            continue;
+        }
+
+        let images = match macho::images(&bytes) {
+            Ok(images) => images,
+            Err(error) => {
+                report.error(format_args!("cannot read the binary: {}", error));
+                return;
+            }
+        };
+        for image in images {
+            let image = match image {
+                Ok(image) => image,
+                Err(error) => {
+                    report.error(format_args!(
+                        "cannot read the binary: {}",
+                        error
+                    ));
+                    continue;
+                }
+            };
+            let section = format!("__TEXT,{}", kind.section());
+            let contents = match image.section("__TEXT", kind.section()) {
+                Ok(Some(contents)) => contents,
+                Ok(None) => {
+                    report.error(format_args!("`{}` is missing", section));
+                    continue;
+                }
+                Err(error) => {
+                    report.error(format_args!(
+                        "cannot read `{}`: {}",
+                        section, error
+                    ));
+                    continue;
+                }
+            };
+            let value = match parse(contents) {
+                Ok(value) => value,
+                Err(error) => {
+                    report.error(format_args!(
+                        "`{}` is not a property list: {}",
+                        section, error
+                    ));
+                    continue;
+                }
+            };
+            // Options like `canonicalize` change the bytes but not the values.
+            match expected.iter().find(|plist| plist.2.semantic_eq(&value)) {
+                Some(plist) => report.ok(format_args!(
+                    "`{}` matches `{}`",
+                    section,
+                    relative(&plist.1, root).display()
+                )),
+                None if expected.is_empty() => report.ok(format_args!("`{}` is present", section)),
+                None => report.error(format_args!(
+                    "`{}` does not match the embedded file; rebuild to update it",
+                    section
+                )),
+            }
+        }
+    }
+}
+
+// Parses an XML or binary property list.
+fn parse(bytes: &[u8]) -> Result<Value, String> {
+    if bytes.starts_with(b"bplist") {
+        Value::from_binary(bytes).map_err(|error| error.to_string())
+    } else {
+        Value::from_xml(bytes).map_err(|error| error.to_string())
+    }
+}
+
+// Returns the binary targets of the workspace packages.
+fn binaries(metadata: &Dictionary) -> Result<Vec<Binary>, String> {
+    let packages = metadata
+        .get("packages")
+        .and_then(Value::as_array)
+        .ok_or("`cargo metadata` has no packages")?;
+
+    let mut binaries = Vec::new();
+    for package in packages {
+        let package = package
+            .as_dictionary()
+            .ok_or("`cargo metadata` has an invalid package")?;
+        let name = string(package, "name")?;
+        let manifest = PathBuf::from(string(package, "manifest_path")?);
+        let manifest_dir =
+            manifest.parent().unwrap_or(Path::new("")).to_owned();
+
+        let mut bins = Vec::new();
+        let mut lib = None;
+        for target in package
+            .get("targets")
+            .and_then(Value::as_array)
+            .unwrap_or(&[])
+        {
+            let target = target
+                .as_dictionary()
+                .ok_or("`cargo metadata` has an invalid target")?;
+            let kinds =
+                target.get("kind").and_then(Value::as_array).unwrap_or(&[]);
+            let has_kind =
+                |kind: &str| kinds.iter().any(|k| k.as_str() == Some(kind));
+            let src_path = PathBuf::from(string(target, "src_path")?);
+            if has_kind("bin") {
+                bins.push((string(target, "name")?.to_owned(), src_path));
+            } else if has_kind("lib") || has_kind("rlib") {
+                lib = Some(src_path);
+            }
+        }
+
+        for (bin, src_path) in bins {
+            let mut roots = vec![src_path];
+            roots.extend(lib.clone());
+            binaries.push(Binary {
+                package: name.to_owned(),
+                name: bin,
+                manifest_dir: manifest_dir.clone(),
+                roots,
+            });
+        }
+    }
+    Ok(binaries)
+}
+
+fn string<'a>(dict: &'a Dictionary, key: &str) -> Result<&'a str, String> {
+    dict.get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("`cargo metadata` has no `{}`", key))
+}
+
+// Replaces `null` with `""`, since property lists have no null and Cargo's
+// metadata uses it for unset fields that are not read here.
+fn without_nulls(json: &str) -> String {
+    let mut output = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut rest = json;
+    while let Some(ch) = rest.chars().next() {
+        if !in_string && rest.starts_with("null") {
+            output.push_str("\"\"");
+            rest = &rest[4..];
+            continue;
+        }
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+        } else if ch == '"' {
+            in_string = true;
+        }
+        output.push(ch);
+        rest = &rest[ch.len_utf8()..];
+    }
+    output
+}
@@ -0,0 +1,190 @@
+//! A Cargo subcommand for diagnosing property lists embedded by
+//! [`embed_plist`](https://docs.rs/embed_plist).
+//!
+//! Install it with `cargo install cargo-embed-plist`, then run it from within a
+//! workspace:
+//!
+//! ```text
+//! cargo embed-plist check [--release | --profile <NAME>] [--target <TRIPLE>]
+//! cargo embed-plist which [--target-dir <DIR>]
+//! ```
+//!
+//! # `check`
+//!
+//! Finds each binary target in the workspace whose sources call
+//! `embed_info_plist!`, `embed_launchd_plist!`, or their `_from_manifest`
+//! variants, and checks that each referenced file is a well-formed XML or
+//! binary property list with a dictionary at its root. The sources of a binary
+//! are the modules reachable from its root and from its package's library.
+//!
+//! Top-level keys are compared with those documented in
+//! [`embed_plist::keys`](https://docs.rs/embed_plist/latest/embed_plist/keys/).
+//! A value of the wrong type fails the check, while deprecated keys and
+//! unknown keys that look like a misspelling of a documented one are warned
+//! about.
+//!
+//! If the binary has been built with `cargo build`, the linked executable is
+//! read too. For Mach-O, each architecture must have the `__TEXT,__info_plist`
+//! or `__TEXT,__launchd_plist` section, with the same contents as one of the
+//! referenced files. For ELF, built with the `portable` feature, the symbol
+//! must be defined. Unbuilt binaries are skipped, so this can run both before
+//! and after building.
+//!
+//! The exit status is 1 if any check failed. Since this only reads files, it
+//! works on any host, including for binaries cross-compiled for Apple targets.
+//!
+//! # `which`
+//!
+//! When `embed_info_plist!` is called in more than one crate, linking fails
+//! with only "symbol `_EMBED_INFO_PLIST` is already defined". This scans the
+//! `.rlib`, `.a`, and `.o` files in the `deps` and build script `out`
+//! directories of the target directory, and lists each crate and object file
+//! that defines `_EMBED_INFO_PLIST` or `_EMBED_LAUNCHD_PLIST`, along with the
+//! crate root and included files recorded in its dep-info. Objects with a
+//! `__TEXT,__info_plist` or `__TEXT,__launchd_plist` section but no symbol,
+//! such as those added by `-sectcreate` or written by C code, are listed too.
+//!
+//! Both Mach-O and ELF objects are read, the latter for the `portable` feature.
+//! The exit status is 1 if a symbol is defined more than once for the same
+//! profile and target.
+
+mod check;
+mod object;
+mod source;
+mod which;
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::{self, Command},
+};
+
+const USAGE: &str = "\
+Diagnose property lists embedded by embed_plist
+
+Usage: cargo embed-plist <COMMAND> [OPTIONS]
+
+Commands:
+  check    Check the plists embedded by each binary in the workspace
+  which    List the crates and objects that define the embedded plists
+
+Options:
+  --target-dir <DIR>  Directory of build artifacts [default: the workspace's]
+  --release           Check binaries built with the release profile
+  --profile <NAME>    Check binaries built with the given profile
+  --target <TRIPLE>   Check binaries built for the given target
+  -h, --help          Print help";
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    // Cargo passes the subcommand name when run as `cargo embed-plist`.
+    if args.first().map(String::as_str) == Some("embed-plist") {
+        args.remove(0);
+    }
+
+    match run(&args) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            eprintln!();
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    }
+}
+
+// Returns whether the command passed.
+fn run(args: &[String]) -> Result<bool, String> {
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return Ok(true);
+    }
+    let (command, options) = match args.split_first() {
+        Some((command, options)) => (command.as_str(), options),
+        None => return Err("no command given".to_owned()),
+    };
+
+    let mut target_dir = None;
+    let mut profile = None;
+    let mut target = None;
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let mut value = || {
+            options
+                .next()
+                .cloned()
+                .ok_or_else(|| format!("`{}` needs a value", option))
+        };
+        match option.as_str() {
+            "--target-dir" => target_dir = Some(PathBuf::from(value()?)),
+            "--release" => profile = Some("release".to_owned()),
+            "--profile" => profile = Some(profile_dir(value()?)),
+            "--target" => target = Some(value()?),
+            _ => return Err(format!("unexpected argument `{}`", option)),
+        }
+    }
+
+    match command {
+        "check" => check::run(&check::Options {
+            target_dir,
+            profile: profile.unwrap_or_else(|| "debug".to_owned()),
+            target,
+        }),
+        "which" => {
+            if profile.is_some() || target.is_some() {
+                return Err("`which` lists every profile and target".to_owned());
+            }
+            let target_dir = match target_dir {
+                Some(dir) => dir,
+                None => default_target_dir()?,
+            };
+            which::run(&target_dir)
+        }
+        _ => Err(format!("unknown command `{}`", command)),
+    }
+}
+
+// Returns the directory that Cargo writes a profile's artifacts to.
+fn profile_dir(profile: String) -> String {
+    match profile.as_str() {
+        "dev" | "test" => "debug".to_owned(),
+        "bench" => "release".to_owned(),
+        _ => profile,
+    }
+}
+
+// Returns `CARGO_TARGET_DIR` or the `target` directory of the workspace.
+fn default_target_dir() -> Result<PathBuf, String> {
+    if let Some(dir) = env::var_os("CARGO_TARGET_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    let manifest =
+        cargo(&["locate-project", "--workspace", "--message-format", "plain"])?;
+    let root = Path::new(manifest.trim())
+        .parent()
+        .unwrap_or(Path::new("."));
+    Ok(root.join("target"))
+}
+
+// Runs Cargo and returns its standard output.
+pub(crate) fn cargo(args: &[&str]) -> Result<String, String> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let output = Command::new(cargo)
+        .args(args)
+        .output()
+        .map_err(|error| format!("cannot run cargo: {}", error))?;
+    if !output.status.success() {
+        return Err(format!(
+            "`cargo {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|_| "cargo printed invalid UTF-8".to_owned())
+}
+
+pub(crate) fn relative<'a>(path: &'a Path, base: &Path) -> &'a Path {
+    path.strip_prefix(base).unwrap_or(path)
+}
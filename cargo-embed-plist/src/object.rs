@@ -0,0 +1,360 @@
+// Scanning of archives and object files for the symbols and sections that the
+// embedding macros define.
+//
+// Mach-O objects are read with `embed_plist::macho`. ELF objects are also read,
+// since the `portable` feature defines the same symbols on other targets.
+// Anything else, such as `lib.rmeta` or LLVM bitcode, is skipped.
+
+use embed_plist::macho::Image;
+use std::convert::TryInto;
+
+/// A property list that the embedding macros can define.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kind {
+    InfoPlist,
+    LaunchdPlist,
+}
+
+impl Kind {
+    pub const ALL: [Kind; 2] = [Kind::InfoPlist, Kind::LaunchdPlist];
+
+    /// The symbol as written in Rust, which Mach-O prefixes with `_`.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Kind::InfoPlist => "_EMBED_INFO_PLIST",
+            Kind::LaunchdPlist => "_EMBED_LAUNCHD_PLIST",
+        }
+    }
+
+    pub fn section(self) -> &'static str {
+        match self {
+            Kind::InfoPlist => "__info_plist",
+            Kind::LaunchdPlist => "__launchd_plist",
+        }
+    }
+}
+
+/// What an object file holds for a `Kind`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Found {
+    /// The object defines the symbol.
+    pub symbol: bool,
+    /// The object has a `__TEXT` section for the property list.
+    pub section: bool,
+}
+
+/// An object within a file that holds anything for a `Kind`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hit {
+    /// The archive member, or `None` if the file is the object itself.
+    pub member: Option<String>,
+    pub kind: Kind,
+    pub found: Found,
+}
+
+const AR_MAGIC: &[u8] = b"!<arch>\n";
+const FAT_MAGIC: u32 = 0xcafe_babe;
+const FAT_MAGIC_64: u32 = 0xcafe_babf;
+
+/// Scans a file that is either an archive, such as an `.rlib` or `.a`, or an
+/// object, either of which may be a universal binary.
+pub fn scan(bytes: &[u8], hits: &mut Vec<Hit>) {
+    scan_member(None, bytes, hits);
+}
+
+fn scan_member(member: Option<&str>, bytes: &[u8], hits: &mut Vec<Hit>) {
+    if bytes.starts_with(AR_MAGIC) {
+        for (name, contents) in Archive::new(bytes) {
+            scan_member(Some(&name), contents, hits);
+        }
+        return;
+    }
+    if let Some(slices) = fat_slices(bytes) {
+        for slice in slices {
+            scan_member(member, slice, hits);
+        }
+        return;
+    }
+
+    for &kind in Kind::ALL.iter() {
+        let found = if bytes.starts_with(b"\x7fELF") {
+            Found {
+                symbol: elf_defines(bytes, kind.symbol()).unwrap_or(false),
+                section: false,
+            }
+        } else if let Ok(image) = Image::parse(bytes) {
+            Found {
+                symbol: macho_defines(&image, kind.symbol()).unwrap_or(false),
+                section: matches!(
+                    image.section("__TEXT", kind.section()),
+                    Ok(Some(_))
+                ),
+            }
+        } else {
+            return;
+        };
+        let hit = Hit {
+            member: member.map(String::from),
+            kind,
+            found,
+        };
+        // Universal binaries repeat the same object for each architecture.
+        if (found.symbol || found.section) && !hits.contains(&hit) {
+            hits.push(hit);
+        }
+    }
+}
+
+// Returns the slices of a universal binary.
+fn fat_slices(bytes: &[u8]) -> Option<Vec<&[u8]>> {
+    let reader = Reader {
+        bytes,
+        big_endian: true,
+    };
+    let is_64 = match reader.u32(0)? {
+        FAT_MAGIC => false,
+        FAT_MAGIC_64 => true,
+        _ => return None,
+    };
+    let count = reader.u32(4)? as usize;
+    let arch_len = if is_64 { 32 } else { 20 };
+    (0..count)
+        .map(|index| {
+            let arch = 8 + index * arch_len;
+            let (offset, size) = if is_64 {
+                (reader.u64(arch + 8)?, reader.u64(arch + 16)?)
+            } else {
+                (
+                    u64::from(reader.u32(arch + 8)?),
+                    u64::from(reader.u32(arch + 12)?),
+                )
+            };
+            reader.slice(offset.try_into().ok()?, size.try_into().ok()?)
+        })
+        .collect()
+}
+
+// Whether the Mach-O symbol table has a definition of `symbol`.
+fn macho_defines(image: &Image, symbol: &str) -> Option<bool> {
+    const LC_SYMTAB: u32 = 0x2;
+    const N_STAB: u8 = 0xe0;
+    const N_TYPE: u8 = 0x0e;
+    const N_ABS: u8 = 0x2;
+    const N_SECT: u8 = 0xe;
+
+    let command = image
+        .load_commands()
+        .filter_map(Result::ok)
+        .find(|command| command.cmd() == LC_SYMTAB)?;
+    let bytes = image.bytes();
+    let reader = Reader {
+        bytes,
+        big_endian: bytes.starts_with(&[0xfe, 0xed]),
+    };
+    let data = Reader {
+        bytes: command.data(),
+        big_endian: reader.big_endian,
+    };
+    let symoff = data.u32(8)? as usize;
+    let nsyms = data.u32(12)? as usize;
+    let strings =
+        reader.slice(data.u32(16)? as usize, data.u32(20)? as usize)?;
+    let entry_len = if image.is_64() { 16 } else { 12 };
+    let name = symbol_with_prefix(symbol);
+
+    for index in 0..nsyms {
+        let entry = symoff + index * entry_len;
+        let n_type = *reader.bytes.get(entry + 4)?;
+        let is_defined = n_type & N_STAB == 0
+            && (n_type & N_TYPE == N_SECT || n_type & N_TYPE == N_ABS);
+        if is_defined
+            && name_at(strings, reader.u32(entry)? as usize) == Some(&name[..])
+        {
+            return Some(true);
+        }
+    }
+    Some(false)
+}
+
+fn symbol_with_prefix(symbol: &str) -> Vec<u8> {
+    let mut name = b"_".to_vec();
+    name.extend_from_slice(symbol.as_bytes());
+    name
+}
+
+// Whether the ELF symbol table has a definition of `symbol`.
+fn elf_defines(bytes: &[u8], symbol: &str) -> Option<bool> {
+    const SHT_SYMTAB: u32 = 2;
+
+    let is_64 = match bytes.get(4)? {
+        1 => false,
+        2 => true,
+        _ => return None,
+    };
+    let reader = Reader {
+        bytes,
+        big_endian: *bytes.get(5)? == 2,
+    };
+    let (shoff, shentsize, shnum) = if is_64 {
+        (
+            reader.u64(0x28)? as usize,
+            reader.u16(0x3a)?,
+            reader.u16(0x3c)?,
+        )
+    } else {
+        (
+            reader.u32(0x20)? as usize,
+            reader.u16(0x2e)?,
+            reader.u16(0x30)?,
+        )
+    };
+    // Returns the offset, size, and linked section of a section header.
+    let section = |index: usize| -> Option<(usize, usize, usize, u32)> {
+        let header = shoff + index * shentsize as usize;
+        let sh_type = reader.u32(header + 4)?;
+        Some(if is_64 {
+            (
+                reader.u64(header + 0x18)? as usize,
+                reader.u64(header + 0x20)? as usize,
+                reader.u32(header + 0x28)? as usize,
+                sh_type,
+            )
+        } else {
+            (
+                reader.u32(header + 0x10)? as usize,
+                reader.u32(header + 0x14)? as usize,
+                reader.u32(header + 0x18)? as usize,
+                sh_type,
+            )
+        })
+    };
+
+    for index in 0..shnum as usize {
+        let (offset, size, link, sh_type) = section(index)?;
+        if sh_type != SHT_SYMTAB {
+            continue;
+        }
+        let (str_offset, str_size, _, _) = section(link)?;
+        let strings = reader.slice(str_offset, str_size)?;
+        let entry_len = if is_64 { 24 } else { 16 };
+        for entry in (offset..offset + size).step_by(entry_len) {
+            let shndx = reader.u16(entry + if is_64 { 6 } else { 14 })?;
+            if shndx != 0
+                && name_at(strings, reader.u32(entry)? as usize)
+                    == Some(symbol.as_bytes())
+            {
+                return Some(true);
+            }
+        }
+    }
+    Some(false)
+}
+
+// Returns the NUL-terminated name at `offset` of a string table.
+fn name_at(strings: &[u8], offset: usize) -> Option<&[u8]> {
+    let rest = strings.get(offset..)?;
+    let len = rest.iter().position(|&byte| byte == 0)?;
+    Some(&rest[..len])
+}
+
+#[derive(Clone, Copy)]
+struct Reader<'a> {
+    bytes: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn slice(self, offset: usize, len: usize) -> Option<&'a [u8]> {
+        self.bytes.get(offset..offset.checked_add(len)?)
+    }
+
+    fn array<const N: usize>(self, offset: usize) -> Option<[u8; N]> {
+        let mut array: [u8; N] = self.slice(offset, N)?.try_into().ok()?;
+        if self.big_endian == cfg!(target_endian = "little") {
+            array.reverse();
+        }
+        Some(array)
+    }
+
+    fn u16(self, offset: usize) -> Option<u16> {
+        self.array(offset).map(u16::from_ne_bytes)
+    }
+
+    fn u32(self, offset: usize) -> Option<u32> {
+        self.array(offset).map(u32::from_ne_bytes)
+    }
+
+    fn u64(self, offset: usize) -> Option<u64> {
+        self.array(offset).map(u64::from_ne_bytes)
+    }
+}
+
+// Iterates over the members of a System V or BSD `ar` archive, skipping
+// symbol tables.
+struct Archive<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    // The GNU table of names longer than 15 bytes.
+    long_names: &'a [u8],
+}
+
+impl<'a> Archive<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Archive {
+            bytes,
+            pos: AR_MAGIC.len(),
+            long_names: &[],
+        }
+    }
+}
+
+impl<'a> Iterator for Archive<'a> {
+    type Item = (String, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let header = self.bytes.get(self.pos..self.pos + 60)?;
+            let field = |range: std::ops::Range<usize>| {
+                std::str::from_utf8(&header[range]).ok().map(str::trim_end)
+            };
+            let name = field(0..16)?;
+            let size: usize = field(48..58)?.parse().ok()?;
+            let start = self.pos + 60;
+            let mut contents =
+                self.bytes.get(start..start.checked_add(size)?)?;
+            // Members are aligned to 2 bytes.
+            self.pos = start + size + size % 2;
+
+            let name = if name == "/"
+                || name == "/SYM64/"
+                || name.starts_with("__.SYMDEF")
+            {
+                continue;
+            } else if name == "//" {
+                self.long_names = contents;
+                continue;
+            } else if let Some(len) = name.strip_prefix("#1/") {
+                // BSD archives put long names before the contents.
+                let len: usize = len.parse().ok()?;
+                let (name, rest) = contents.split_at(len.min(contents.len()));
+                contents = rest;
+                let name =
+                    name.split(|&byte| byte == 0).next().unwrap_or_default();
+                String::from_utf8_lossy(name).into_owned()
+            } else if let Some(offset) = name.strip_prefix('/') {
+                let offset: usize = offset.parse().ok()?;
+                let rest = self.long_names.get(offset..)?;
+                let end = rest
+                    .iter()
+                    .position(|&byte| byte == b'\n')
+                    .unwrap_or(rest.len());
+                String::from_utf8_lossy(&rest[..end])
+                    .trim_end_matches('/')
+                    .to_owned()
+            } else {
+                name.trim_end_matches('/').to_owned()
+            };
+            return Some((name, contents));
+        }
+    }
+}
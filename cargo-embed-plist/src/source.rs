@@ -0,0 +1,423 @@
+// Finding the embedding macros in the source files of a crate.
+//
+// This is a lexical scan rather than a parse: it follows `mod` declarations
+// from the crate root, skips comments, and reads the string literals passed to
+// each macro. Macro calls produced by other macros, and paths that are not
+// string literals, cannot be followed.
+
+use crate::object::Kind;
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+/// A call to one of the embedding macros.
+#[derive(Clone, Debug)]
+pub struct Call {
+    pub kind: Kind,
+    /// The macro as written, such as `embed_info_plist`.
+    pub name: &'static str,
+    /// The file containing the call.
+    pub file: PathBuf,
+    pub line: usize,
+    /// The resolved paths of the embedded files, or `None` if a path is not a
+    /// string literal.
+    pub paths: Option<Vec<PathBuf>>,
+}
+
+// The macros and whether their paths are relative to the manifest directory
+// rather than the calling file.
+const MACROS: [(&str, Kind, bool); 4] = [
+    ("embed_info_plist", Kind::InfoPlist, false),
+    ("embed_launchd_plist", Kind::LaunchdPlist, false),
+    ("embed_info_plist_from_manifest", Kind::InfoPlist, true),
+    (
+        "embed_launchd_plist_from_manifest",
+        Kind::LaunchdPlist,
+        true,
+    ),
+];
+
+/// Scans the module tree of the crate rooted at `root` for embedding macros.
+///
+/// Files that cannot be read are returned as errors alongside the calls.
+pub fn scan_crate(
+    root: &Path,
+    manifest_dir: &Path,
+    calls: &mut Vec<Call>,
+    errors: &mut Vec<String>,
+) {
+    let mut queue = vec![(root.to_owned(), true)];
+    let mut seen = Vec::new();
+    while let Some((file, is_root)) = queue.pop() {
+        if seen.contains(&file) {
+            continue;
+        }
+        seen.push(file.clone());
+
+        let text = match fs::read_to_string(&file) {
+            Ok(text) => text,
+            Err(error) => {
+                errors.push(format!(
+                    "cannot read `{}`: {}",
+                    file.display(),
+                    error
+                ));
+                continue;
+            }
+        };
+        let tokens = lex(&text);
+        find_calls(&tokens, &file, manifest_dir, calls);
+
+        // Submodules of `a/b.rs` live in `a/b/`, except for crate roots and
+        // `mod.rs` files, whose submodules are next to them.
+        let dir = file.parent().unwrap_or(Path::new(""));
+        let dir = if is_root || file.file_name() == Some("mod.rs".as_ref()) {
+            dir.to_owned()
+        } else {
+            dir.join(file.file_stem().unwrap_or_default())
+        };
+        for name in external_modules(&tokens) {
+            let flat = dir.join(format!("{}.rs", name));
+            let nested = dir.join(name).join("mod.rs");
+            if flat.is_file() {
+                queue.push((flat, false));
+            } else if nested.is_file() {
+                queue.push((nested, false));
+            } else {
+                errors.push(format!(
+                    "cannot find module `{}` declared in `{}`",
+                    name,
+                    file.display()
+                ));
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token<'a> {
+    Ident(&'a str),
+    Str(String),
+    Punct(char),
+}
+
+// Splits source text into tokens with their line numbers, dropping comments.
+fn lex(text: &str) -> Vec<(Token<'_>, usize)> {
+    let bytes = text.as_bytes();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let start = pos;
+        let start_line = line;
+        let byte = bytes[pos];
+        match byte {
+            b'\n' => {
+                line += 1;
+                pos += 1;
+            }
+            b'/' if bytes.get(pos + 1) == Some(&b'/') => {
+                while pos < bytes.len() && bytes[pos] != b'\n' {
+                    pos += 1;
+                }
+            }
+            b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+                let mut depth = 0;
+                while pos < bytes.len() {
+                    if bytes[pos..].starts_with(b"/*") {
+                        depth += 1;
+                        pos += 2;
+                    } else if bytes[pos..].starts_with(b"*/") {
+                        depth -= 1;
+                        pos += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        line += usize::from(bytes[pos] == b'\n');
+                        pos += 1;
+                    }
+                }
+            }
+            b'"' => {
+                let (string, end) = string(text, pos + 1);
+                line += text[pos..end].matches('\n').count();
+                pos = end;
+                tokens.push((Token::Str(string), start_line));
+            }
+            b'b' if bytes.get(pos + 1) == Some(&b'"') => {
+                // Byte strings are not paths, but must not be read as code.
+                let (_, end) = string(text, pos + 2);
+                line += text[pos..end].matches('\n').count();
+                pos = end;
+            }
+            b'\'' => {
+                // A character literal, or a lifetime, which is skipped as
+                // punctuation followed by an identifier.
+                pos += 1;
+                let rest = &bytes[pos..];
+                if rest.first() == Some(&b'\\') {
+                    let len = rest[2.min(rest.len())..]
+                        .iter()
+                        .position(|&b| b == b'\'')
+                        .unwrap_or(0);
+                    pos += len + 3;
+                } else if rest.get(1) == Some(&b'\'') {
+                    pos += 2;
+                } else if let Some(ch) = text[pos..].chars().next() {
+                    if text[pos + ch.len_utf8()..].starts_with('\'') {
+                        pos += ch.len_utf8() + 1;
+                    }
+                }
+            }
+            _ if byte == b'_' || byte.is_ascii_alphabetic() => {
+                while pos < bytes.len()
+                    && (bytes[pos] == b'_'
+                        || bytes[pos].is_ascii_alphanumeric())
+                {
+                    pos += 1;
+                }
+                let ident = &text[start..pos];
+                match raw_string_hashes(bytes, pos) {
+                    Some(hashes) if ident == "r" || ident == "br" => {
+                        let body = pos + hashes + 1;
+                        let terminator = format!("\"{}", "#".repeat(hashes));
+                        let len = text[body..]
+                            .find(&terminator)
+                            .unwrap_or(text.len() - body);
+                        pos = (body + len + terminator.len()).min(text.len());
+                        line += text[start..pos].matches('\n').count();
+                        if ident == "r" {
+                            let string = text[body..body + len].to_owned();
+                            tokens.push((Token::Str(string), start_line));
+                        }
+                    }
+                    _ => tokens.push((Token::Ident(ident), start_line)),
+                }
+            }
+            _ if byte.is_ascii_whitespace() || byte.is_ascii_digit() => {
+                pos += 1
+            }
+            _ => {
+                let ch = text[pos..].chars().next().unwrap_or('\0');
+                pos += ch.len_utf8();
+                tokens.push((Token::Punct(ch), start_line));
+            }
+        }
+    }
+    tokens
+}
+
+// Returns the number of `#` if the body of a raw string follows `pos`.
+fn raw_string_hashes(bytes: &[u8], pos: usize) -> Option<usize> {
+    let hashes = bytes[pos..].iter().take_while(|&&b| b == b'#').count();
+    if bytes.get(pos + hashes) == Some(&b'"') {
+        Some(hashes)
+    } else {
+        None
+    }
+}
+
+// Reads a string literal body starting at `pos`, returning its unescaped
+// contents and the index after the closing quote.
+fn string(text: &str, pos: usize) -> (String, usize) {
+    let mut string = String::new();
+    let mut chars = text[pos..].char_indices();
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            '"' => return (string, pos + index + 1),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => string.push('\n'),
+                Some((_, 't')) => string.push('\t'),
+                Some((_, 'r')) => string.push('\r'),
+                Some((_, '0')) => string.push('\0'),
+                // A line continuation skips the newline and indentation.
+                Some((_, '\n')) => {
+                    let mut rest = chars.clone();
+                    while let Some((_, ch)) = rest.next() {
+                        if !ch.is_whitespace() {
+                            break;
+                        }
+                        chars = rest.clone();
+                    }
+                }
+                Some((_, ch)) => string.push(ch),
+                None => break,
+            },
+            _ => string.push(ch),
+        }
+    }
+    (string, text.len())
+}
+
+// Finds `name!(...)` calls and reads their string literal arguments.
+fn find_calls(
+    tokens: &[(Token<'_>, usize)],
+    file: &Path,
+    manifest_dir: &Path,
+    calls: &mut Vec<Call>,
+) {
+    for (index, (token, line)) in tokens.iter().enumerate() {
+        let ident = match token {
+            Token::Ident(ident) => *ident,
+            _ => continue,
+        };
+        let (name, kind, from_manifest) =
+            match MACROS.iter().find(|(name, _, _)| *name == ident) {
+                Some(&entry) => entry,
+                None => continue,
+            };
+        // Skip definitions and re-exports, such as in `use embed_plist::...`.
+        if tokens.get(index + 1).map(|t| &t.0) != Some(&Token::Punct('!')) {
+            continue;
+        }
+        let args = match group(&tokens[index + 2..]) {
+            Some(args) => args,
+            None => continue,
+        };
+
+        // A map of `target => "path"`, or a path followed by options.
+        let is_literal = |token: &Token<'_>| match token {
+            Token::Str(_) | Token::Ident(_) => true,
+            Token::Punct(ch) => ch == &',' || ch == &'=' || ch == &'>',
+        };
+        let paths = if args.iter().all(|(token, _)| is_literal(token)) {
+            let base = if from_manifest {
+                manifest_dir
+            } else {
+                file.parent().unwrap_or(Path::new(""))
+            };
+            let paths: Vec<PathBuf> = args
+                .iter()
+                .filter_map(|(token, _)| match token {
+                    Token::Str(path) => {
+                        Some(join(base, path.trim_start_matches('/')))
+                    }
+                    _ => None,
+                })
+                .collect();
+            if paths.is_empty() {
+                None
+            } else {
+                Some(paths)
+            }
+        } else {
+            None
+        };
+        calls.push(Call {
+            kind,
+            name,
+            file: file.to_owned(),
+            line: *line,
+            paths,
+        });
+    }
+}
+
+// Joins `path` to `base`, removing `..` where it follows a directory name.
+fn join(base: &Path, path: &str) -> PathBuf {
+    let mut joined = PathBuf::new();
+    for component in base.join(path).components() {
+        match component {
+            Component::ParentDir
+                if matches!(
+                    joined.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                joined.pop();
+            }
+            Component::CurDir => {}
+            _ => joined.push(component),
+        }
+    }
+    joined
+}
+
+// Returns the tokens within the delimited group at the start of `tokens`.
+fn group<'t, 'a>(
+    tokens: &'t [(Token<'a>, usize)],
+) -> Option<&'t [(Token<'a>, usize)]> {
+    match tokens.first()?.0 {
+        Token::Punct('(') | Token::Punct('[') | Token::Punct('{') => {}
+        _ => return None,
+    }
+    let mut depth = 0;
+    for (index, (token, _)) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('(') | Token::Punct('[') | Token::Punct('{') => {
+                depth += 1
+            }
+            Token::Punct(')') | Token::Punct(']') | Token::Punct('}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&tokens[1..index]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// Returns the names in `mod name;` declarations, skipping those with a
+// `#[path]` attribute, which this does not resolve.
+fn external_modules<'a>(tokens: &[(Token<'a>, usize)]) -> Vec<&'a str> {
+    let mut names = Vec::new();
+    for (index, window) in tokens.windows(3).enumerate() {
+        if let [(Token::Ident("mod"), _), (Token::Ident(name), _), (Token::Punct(';'), _)] =
+            window
+        {
+            if !has_path_attribute(&tokens[..index]) {
+                names.push(*name);
+            }
+        }
+    }
+    names
+}
+
+// Whether the item ending `tokens` has a `#[path]` attribute.
+fn has_path_attribute(tokens: &[(Token<'_>, usize)]) -> bool {
+    let mut end = tokens.len();
+    // Skip the visibility, such as `pub(crate)`.
+    while end > 0 {
+        match tokens[end - 1].0 {
+            Token::Ident("pub")
+            | Token::Ident("crate")
+            | Token::Ident("super")
+            | Token::Ident("self")
+            | Token::Ident("in")
+            | Token::Punct('(')
+            | Token::Punct(')')
+            | Token::Punct(':') => end -= 1,
+            _ => break,
+        }
+    }
+    // Walk back over each `#[...]` attribute.
+    while end > 0 && tokens[end - 1].0 == Token::Punct(']') {
+        let mut depth = 0;
+        let mut start = end;
+        while start > 0 {
+            start -= 1;
+            match tokens[start].0 {
+                Token::Punct(']') => depth += 1,
+                Token::Punct('[') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if tokens.get(start + 1).map(|t| &t.0) == Some(&Token::Ident("path")) {
+            return true;
+        }
+        if start == 0 || tokens[start - 1].0 != Token::Punct('#') {
+            return false;
+        }
+        end = start - 1;
+    }
+    false
+}
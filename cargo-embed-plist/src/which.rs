@@ -0,0 +1,291 @@
+// `cargo embed-plist which`: lists the crates and object files under the target
+// directory that define the embedded property list symbols or sections.
+
+use crate::{
+    object::{self, Hit, Kind},
+    relative,
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// An archive or object file with hits, and where it came from.
+struct Definition {
+    path: PathBuf,
+    krate: Option<String>,
+    hit: Hit,
+    // From the dep-info file that `rustc` writes next to the artifact.
+    source: Option<PathBuf>,
+    includes: Vec<PathBuf>,
+}
+
+/// Prints every definition under `target_dir`, grouped by profile directory,
+/// and returns `false` if a symbol is defined more than once in a profile.
+pub fn run(target_dir: &Path) -> Result<bool, String> {
+    let mut files = Vec::new();
+    collect(target_dir, false, &mut files).map_err(|error| {
+        format!("cannot read `{}`: {}", target_dir.display(), error)
+    })?;
+    files.sort();
+
+    // Keyed by the directory holding `deps` and `build`, such as
+    // `target/debug` or `target/aarch64-apple-ios/release`.
+    let mut profiles: BTreeMap<PathBuf, Vec<Definition>> = BTreeMap::new();
+    for path in files {
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                eprintln!(
+                    "warning: cannot read `{}`: {}",
+                    path.display(),
+                    error
+                );
+                continue;
+            }
+        };
+        let mut hits = Vec::new();
+        object::scan(&bytes, &mut hits);
+        if hits.is_empty() {
+            continue;
+        }
+
+        let (profile, krate) = origin(&path);
+        let (source, includes) = dep_info(&path);
+        let definitions = profiles.entry(profile).or_default();
+        for hit in hits {
+            definitions.push(Definition {
+                path: path.clone(),
+                krate: krate.clone(),
+                hit,
+                source: source.clone(),
+                includes: includes.clone(),
+            });
+        }
+    }
+
+    if profiles.is_empty() {
+        println!(
+            "No embedded property lists found under `{}`.",
+            target_dir.display()
+        );
+        return Ok(true);
+    }
+
+    let mut passed = true;
+    for (profile, definitions) in &profiles {
+        println!("{}", relative(profile, target_dir).display());
+        for &kind in Kind::ALL.iter() {
+            passed &= report(kind, definitions, profile);
+        }
+    }
+    if !passed {
+        println!();
+        println!(
+            "Each symbol may only be defined once per binary. Stale artifacts \
+             from earlier builds can also be listed; run `cargo clean` if a \
+             crate no longer embeds a property list."
+        );
+    }
+    Ok(passed)
+}
+
+// Prints the definitions of `kind` and returns `false` if there are several.
+fn report(kind: Kind, definitions: &[Definition], profile: &Path) -> bool {
+    let symbols: Vec<&Definition> = definitions
+        .iter()
+        .filter(|def| def.hit.kind == kind && def.hit.found.symbol)
+        .collect();
+    let sections: Vec<&Definition> = definitions
+        .iter()
+        .filter(|def| {
+            def.hit.kind == kind
+                && def.hit.found.section
+                && !def.hit.found.symbol
+        })
+        .collect();
+
+    if !symbols.is_empty() {
+        let times = match symbols.len() {
+            1 => "once".to_owned(),
+            n => format!("{} times", n),
+        };
+        println!("  `{}` is defined {}:", kind.symbol(), times);
+        for def in &symbols {
+            print_definition(def, profile);
+        }
+    }
+    if !sections.is_empty() {
+        println!(
+            "  `__TEXT,{}` is defined without `{}`:",
+            kind.section(),
+            kind.symbol()
+        );
+        for def in &sections {
+            print_definition(def, profile);
+        }
+    }
+    symbols.len() <= 1
+}
+
+fn print_definition(def: &Definition, profile: &Path) {
+    let mut line = String::from("    ");
+    if let Some(krate) = &def.krate {
+        line += &format!("crate `{}` in ", krate);
+    }
+    line += &relative(&def.path, profile).display().to_string();
+    if let Some(member) = &def.hit.member {
+        line += &format!(" ({})", member);
+    }
+    println!("{}", line);
+
+    if let Some(source) = &def.source {
+        println!("      source:   {}", source.display());
+    }
+    for include in &def.includes {
+        println!("      includes: {}", include.display());
+    }
+}
+
+// Collects archives and objects in `deps` directories, where Cargo keeps the
+// artifacts of each crate, and in the `out` directories of build scripts.
+// Uplifted copies like `target/debug/libfoo.rlib` are skipped so that each
+// artifact is only listed once.
+fn collect(
+    dir: &Path,
+    in_artifacts: bool,
+    files: &mut Vec<PathBuf>,
+) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            let name = entry.file_name();
+            if name == "incremental" || name == ".fingerprint" || name == "doc"
+            {
+                continue;
+            }
+            let in_artifacts = in_artifacts || name == "deps" || name == "out";
+            collect(&path, in_artifacts, files)?;
+        } else if in_artifacts && file_type.is_file() {
+            let extension = path.extension().and_then(|ext| ext.to_str());
+            if let Some("rlib") | Some("a") | Some("o") = extension {
+                files.push(path);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Returns the profile directory of an artifact and the crate that built it.
+fn origin(path: &Path) -> (PathBuf, Option<String>) {
+    for dir in path.ancestors().skip(1) {
+        let name = dir.file_name().and_then(|name| name.to_str());
+        match name {
+            // `deps/libfoo-0123456789abcdef.rlib`
+            Some("deps") => {
+                let stem = path.file_stem().and_then(|stem| stem.to_str());
+                let krate = stem.map(|stem| {
+                    let stem = stem.split('.').next().unwrap_or(stem);
+                    let stem = match path.extension() {
+                        Some(ext) if ext != "o" => {
+                            stem.strip_prefix("lib").unwrap_or(stem)
+                        }
+                        _ => stem,
+                    };
+                    strip_hash(stem).to_owned()
+                });
+                let profile = dir.parent().unwrap_or(dir).to_owned();
+                return (profile, krate);
+            }
+            // `build/foo-0123456789abcdef/out/...`
+            Some("out") => {
+                let package = dir.parent();
+                let krate = package
+                    .and_then(|dir| dir.file_name())
+                    .and_then(|name| name.to_str())
+                    .map(|name| strip_hash(name).to_owned());
+                let profile = package
+                    .and_then(Path::parent)
+                    .and_then(Path::parent)
+                    .unwrap_or(dir)
+                    .to_owned();
+                return (profile, krate);
+            }
+            _ => {}
+        }
+    }
+    (path.parent().unwrap_or(path).to_owned(), None)
+}
+
+// Removes the `-0123456789abcdef` suffix that Cargo adds to artifact names.
+fn strip_hash(name: &str) -> &str {
+    match name.rfind('-') {
+        Some(index)
+            if name.len() - index == 17
+                && name[index + 1..].bytes().all(|b| b.is_ascii_hexdigit()) =>
+        {
+            &name[..index]
+        }
+        _ => name,
+    }
+}
+
+// Reads the dep-info file of a `deps` artifact, returning the crate root and
+// the other files it includes, such as the embedded property list.
+fn dep_info(path: &Path) -> (Option<PathBuf>, Vec<PathBuf>) {
+    let stem = match path.file_stem().and_then(|stem| stem.to_str()) {
+        Some(stem) => stem.strip_prefix("lib").unwrap_or(stem),
+        None => return (None, Vec::new()),
+    };
+    let stem = stem.split('.').next().unwrap_or(stem);
+    let contents =
+        match fs::read_to_string(path.with_file_name(format!("{}.d", stem))) {
+            Ok(contents) => contents,
+            Err(_) => return (None, Vec::new()),
+        };
+
+    // The first rule lists every input of the crate.
+    let deps = match contents
+        .lines()
+        .next()
+        .and_then(|line| line.split_once(": "))
+    {
+        Some((_, deps)) => split_deps(deps),
+        None => return (None, Vec::new()),
+    };
+    let mut source = None;
+    let mut includes = Vec::new();
+    for dep in deps {
+        if dep.extension() == Some("rs".as_ref()) {
+            source = source.or(Some(dep));
+        } else {
+            includes.push(dep);
+        }
+    }
+    (source, includes)
+}
+
+// Splits a Makefile dependency list, in which spaces in paths are escaped.
+fn split_deps(deps: &str) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let mut current = String::new();
+    let mut chars = deps.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => current.extend(chars.next()),
+            ' ' => {
+                if !current.is_empty() {
+                    paths.push(PathBuf::from(std::mem::take(&mut current)));
+                }
+            }
+            _ => current.push(ch),
+        }
+    }
+    if !current.is_empty() {
+        paths.push(PathBuf::from(current));
+    }
+    paths
+}
@@ -0,0 +1,211 @@
+use embed_plist::build::{self, Arch, ObjectTarget, Platform};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+const INFO_PLIST: &str = include_str!("../../src/Info.plist");
+const LAUNCHD_PLIST: &str = include_str!("../../src/launchd.plist");
+
+// Writes each file, relative to a new workspace directory for a test.
+fn workspace(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join("cargo_embed_plist_check_tests")
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    for (path, contents) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    dir
+}
+
+fn manifest(name: &str) -> String {
+    format!(
+        "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2018\"\n",
+        name
+    )
+}
+
+fn check(dir: &Path, args: &[&str]) -> (Output, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-embed-plist"))
+        .args(["embed-plist", "check"])
+        .args(args)
+        .current_dir(dir)
+        .env_remove("CARGO_TARGET_DIR")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout.clone()).unwrap();
+    (output, stdout)
+}
+
+// Writes a Mach-O object with the section in place of a linked binary.
+fn link(dir: &Path, plist: &Path, binary: &str) {
+    let output = dir.join("target/debug").join(binary);
+    fs::create_dir_all(output.parent().unwrap()).unwrap();
+    let target = ObjectTarget {
+        arch: Arch::Arm64,
+        platform: Platform::MacOs,
+        minimum_os: (11, 0, 0),
+    };
+    build::info_plist_object(plist, output, target).unwrap();
+}
+
+#[test]
+fn valid_workspace() {
+    let dir = workspace(
+        "valid",
+        &[
+            ("Cargo.toml", "[workspace]\nmembers = [\"app\", \"daemon\", \"plain\"]\n"),
+            ("app/Cargo.toml", &manifest("app")),
+            ("app/Info.plist", INFO_PLIST),
+            (
+                "app/src/main.rs",
+                "// embed_plist::embed_info_plist!(\"Missing.plist\");\n\
+                 mod plist;\n\
+                 fn main() {}\n",
+            ),
+            (
+                "app/src/plist.rs",
+                "embed_plist::embed_info_plist!(\"../Info.plist\", normalize);\n",
+            ),
+            ("daemon/Cargo.toml", &manifest("daemon")),
+            ("daemon/launchd.plist", LAUNCHD_PLIST),
+            (
+                "daemon/src/lib.rs",
+                "embed_plist::embed_launchd_plist_from_manifest!(r\"launchd.plist\");\n",
+            ),
+            ("daemon/src/main.rs", "fn main() {}\n"),
+            ("plain/Cargo.toml", &manifest("plain")),
+            ("plain/src/main.rs", "fn main() {}\n"),
+        ],
+    );
+
+    let (output, stdout) = check(&dir, &[]);
+    assert!(output.status.success(), "{}", stdout);
+    assert_eq!(
+        stdout,
+        "bin `app` in package `app`\n\
+         \x20 app/src/plist.rs:1: embed_info_plist!\n\
+         \x20   ok: app/Info.plist\n\
+         \x20 target/debug/app\n\
+         \x20   note: not built, so the linked binary was not checked\n\
+         bin `daemon` in package `daemon`\n\
+         \x20 daemon/src/lib.rs:1: embed_launchd_plist_from_manifest!\n\
+         \x20   ok: daemon/launchd.plist\n\
+         \x20 target/debug/daemon\n\
+         \x20   note: not built, so the linked binary was not checked\n"
+    );
+
+    link(&dir, &dir.join("app/Info.plist"), "app");
+    let (output, stdout) = check(&dir, &[]);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains(
+        "\x20 target/debug/app\n\
+         \x20   ok: `__TEXT,__info_plist` matches `app/Info.plist`\n"
+    ));
+}
+
+#[test]
+fn invalid_workspace() {
+    let dir = workspace(
+        "invalid",
+        &[
+            ("Cargo.toml", &manifest("app")),
+            ("Info.plist", "<plist><dict><key>A</key></dict></plist>"),
+            ("Other.plist", INFO_PLIST),
+            ("Array.plist", "<plist><array/></plist>"),
+            (
+                "src/main.rs",
+                "embed_plist::embed_info_plist! {\n\
+                 \x20   macos => \"../Info.plist\",\n\
+                 \x20   ios => \"../Array.plist\",\n\
+                 \x20   tvos => \"../Missing.plist\",\n\
+                 }\n\
+                 embed_plist::embed_launchd_plist!(concat!(\"../\", \"x\"));\n\
+                 fn main() {}\n",
+            ),
+        ],
+    );
+    link(&dir, &dir.join("Other.plist"), "app");
+
+    let (output, stdout) = check(&dir, &[]);
+    assert_eq!(output.status.code(), Some(1), "{}", stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], "bin `app` in package `app`");
+    assert_eq!(lines[1], "  src/main.rs:1: embed_info_plist!");
+    assert!(lines[2]
+        .starts_with("    error: `Info.plist` is not a property list: "));
+    assert_eq!(
+        lines[3],
+        "    error: `Array.plist` is a property list, but its root is \
+         not a dictionary"
+    );
+    assert!(lines[4].starts_with("    error: cannot read `Missing.plist`: "));
+    assert_eq!(lines[5], "  src/main.rs:6: embed_launchd_plist!");
+    assert_eq!(
+        lines[6],
+        "    note: the path is not a string literal, so it was not checked"
+    );
+    assert_eq!(lines[7], "  target/debug/app");
+    assert_eq!(lines[8], "    ok: `__TEXT,__info_plist` is present");
+    assert_eq!(lines[9], "    error: `__TEXT,__launchd_plist` is missing");
+    assert_eq!(lines.len(), 10);
+
+    // Only the release profile is checked, which has not been built.
+    let (_, stdout) = check(&dir, &["--release"]);
+    assert!(stdout.contains("  target/release/app\n"));
+}
+
+#[test]
+fn documented_keys() {
+    let dir = workspace(
+        "keys",
+        &[
+            ("Cargo.toml", &manifest("app")),
+            (
+                "Info.plist",
+                "<plist><dict>\
+                 <key>CFBundleIdentifer</key><string>com.example.app</string>\
+                 <key>CFBundleSignature</key><string>????</string>\
+                 <key>LSUIElement</key><string>YES</string>\
+                 <key>Custom</key><true/>\
+                 </dict></plist>",
+            ),
+            (
+                "launchd.plist",
+                "<plist><dict>\
+                 <key>Label</key><string>com.example.app</string>\
+                 <key>RunAtLoad</key><true/>\
+                 </dict></plist>",
+            ),
+            (
+                "src/main.rs",
+                "embed_plist::embed_info_plist!(\"../Info.plist\");\n\
+                 embed_plist::embed_launchd_plist!(\"../launchd.plist\");\n\
+                 fn main() {}\n",
+            ),
+        ],
+    );
+
+    let (output, stdout) = check(&dir, &[]);
+    assert_eq!(output.status.code(), Some(1), "{}", stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines[1..7],
+        [
+            "  src/main.rs:1: embed_info_plist!",
+            "    ok: Info.plist",
+            "    warning: unknown key `CFBundleIdentifer` in `Info.plist`; \
+             did you mean `CFBundleIdentifier`?",
+            "    warning: `CFBundleSignature` in `Info.plist` is deprecated: \
+             The four-letter creator code of the bundle, which is unused.",
+            "    error: `LSUIElement` in `Info.plist` is `string`, but must be \
+             `boolean`",
+            "  src/main.rs:2: embed_launchd_plist!",
+        ]
+    );
+    assert_eq!(lines[7], "    ok: launchd.plist");
+}
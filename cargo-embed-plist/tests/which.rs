@@ -0,0 +1,214 @@
+use embed_plist::build::{self, Arch, ObjectTarget, Platform};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+const TARGET: ObjectTarget = ObjectTarget {
+    arch: Arch::Arm64,
+    platform: Platform::MacOs,
+    minimum_os: (11, 0, 0),
+};
+
+// Returns an empty target directory for a test.
+fn target_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join("cargo_embed_plist_tests")
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn object(dir: &Path, name: &str, launchd: bool) -> Vec<u8> {
+    let input = dir.join(format!("{}.plist", name));
+    let output = dir.join(format!("{}.o", name));
+    fs::write(&input, include_str!("../../src/Info.plist")).unwrap();
+    if launchd {
+        build::launchd_plist_object(&input, &output, TARGET).unwrap();
+    } else {
+        build::info_plist_object(&input, &output, TARGET).unwrap();
+    }
+    let bytes = fs::read(&output).unwrap();
+    fs::remove_file(&input).unwrap();
+    fs::remove_file(&output).unwrap();
+    bytes
+}
+
+// Writes a GNU `ar` archive like the `.rlib` files that `rustc` emits.
+fn archive(members: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut long_names = Vec::new();
+    let mut names = Vec::new();
+    for (name, _) in members {
+        if name.len() < 16 {
+            names.push(format!("{}/", name));
+        } else {
+            names.push(format!("/{}", long_names.len()));
+            long_names.extend_from_slice(format!("{}/\n", name).as_bytes());
+        }
+    }
+
+    let mut bytes = b"!<arch>\n".to_vec();
+    let mut push = |name: &str, contents: &[u8]| {
+        let header = format!(
+            "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+            name,
+            0,
+            0,
+            0,
+            644,
+            contents.len()
+        );
+        assert_eq!(header.len(), 60);
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(contents);
+        if contents.len() % 2 == 1 {
+            bytes.push(b'\n');
+        }
+    };
+    if !long_names.is_empty() {
+        push("//", &long_names);
+    }
+    for (name, (_, contents)) in names.iter().zip(members) {
+        push(name, contents);
+    }
+    bytes
+}
+
+// Writes `deps/lib<krate>-<hash>.rlib` and its dep-info file.
+fn rlib(profile: &Path, krate: &str, hash: &str, members: &[(&str, &[u8])]) {
+    let deps = profile.join("deps");
+    fs::create_dir_all(&deps).unwrap();
+    let stem = format!("{}-{}", krate, hash);
+    fs::write(deps.join(format!("lib{}.rlib", stem)), archive(members))
+        .unwrap();
+    fs::write(
+        deps.join(format!("{}.d", stem)),
+        format!(
+            "{}/deps/{}.d: {}/src/lib.rs {}/Info.plist\n\n{}/src/lib.rs:\n",
+            profile.display(),
+            stem,
+            krate,
+            krate,
+            krate
+        ),
+    )
+    .unwrap();
+}
+
+fn which(target_dir: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cargo-embed-plist"))
+        .args(["embed-plist", "which", "--target-dir"])
+        .arg(target_dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn single_definition() {
+    let dir = target_dir("single");
+    let profile = dir.join("debug");
+    let info = object(&dir, "info", false);
+    let launchd = object(&dir, "launchd", true);
+    rlib(
+        &profile,
+        "app",
+        "0123456789abcdef",
+        &[
+            ("lib.rmeta", b"rust"),
+            ("app-0123456789abcdef.app.1.rcgu.o", &info),
+            ("app-0123456789abcdef.app.2.rcgu.o", &launchd),
+        ],
+    );
+    rlib(
+        &profile,
+        "other",
+        "fedcba9876543210",
+        &[("lib.rmeta", b"rust")],
+    );
+
+    let output = which(&dir);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{}", stdout);
+    assert_eq!(
+        stdout,
+        "debug\n\
+         \x20 `_EMBED_INFO_PLIST` is defined once:\n\
+         \x20   crate `app` in deps/libapp-0123456789abcdef.rlib \
+         (app-0123456789abcdef.app.1.rcgu.o)\n\
+         \x20     source:   app/src/lib.rs\n\
+         \x20     includes: app/Info.plist\n\
+         \x20 `_EMBED_LAUNCHD_PLIST` is defined once:\n\
+         \x20   crate `app` in deps/libapp-0123456789abcdef.rlib \
+         (app-0123456789abcdef.app.2.rcgu.o)\n\
+         \x20     source:   app/src/lib.rs\n\
+         \x20     includes: app/Info.plist\n"
+    );
+}
+
+#[test]
+fn duplicate_definitions() {
+    let dir = target_dir("duplicate");
+    let info = object(&dir, "info", false);
+    for profile in &["debug", "aarch64-apple-darwin/release"] {
+        let profile = dir.join(profile);
+        rlib(&profile, "app", "0123456789abcdef", &[("app.o", &info)]);
+    }
+    rlib(
+        &dir.join("debug"),
+        "dep",
+        "fedcba9876543210",
+        &[("dep.o", &info)],
+    );
+
+    let output = which(&dir);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(output.status.code(), Some(1), "{}", stdout);
+    assert!(stdout.contains(
+        "aarch64-apple-darwin/release\n\
+         \x20 `_EMBED_INFO_PLIST` is defined once:\n"
+    ));
+    assert!(stdout.contains(
+        "debug\n\
+         \x20 `_EMBED_INFO_PLIST` is defined 2 times:\n\
+         \x20   crate `app` in deps/libapp-0123456789abcdef.rlib (app.o)\n"
+    ));
+    assert!(stdout.contains(
+        "\x20   crate `dep` in deps/libdep-fedcba9876543210.rlib (dep.o)\n\
+         \x20     source:   dep/src/lib.rs\n"
+    ));
+}
+
+#[test]
+fn build_script_object() {
+    let dir = target_dir("build_script");
+    let out = dir.join("debug/build/app-0123456789abcdef/out");
+    fs::create_dir_all(&out).unwrap();
+    let info = object(&dir, "info", false);
+    fs::write(out.join("info_plist.o"), &info).unwrap();
+
+    let output = which(&dir);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains(
+        "\x20   crate `app` in build/app-0123456789abcdef/out/info_plist.o\n"
+    ));
+}
+
+#[test]
+fn usage_errors() {
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_cargo-embed-plist"))
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    assert!(run(&["embed-plist", "--help"]).status.success());
+    assert_eq!(run(&["embed-plist"]).status.code(), Some(2));
+    assert_eq!(run(&["embed-plist", "what"]).status.code(), Some(2));
+    assert_eq!(
+        run(&["embed-plist", "which", "--target-dir"]).status.code(),
+        Some(2)
+    );
+}
@@ -0,0 +1,480 @@
+//! Derive macros for [`embed_plist`](https://docs.rs/embed_plist).
+//!
+//! Use these through the `derive` feature of `embed_plist` rather than
+//! depending on this crate directly. The generated code refers to
+//! `::embed_plist`, so that crate must be available under that name.
+
+#![warn(missing_docs)]
+
+extern crate proc_macro;
+
+use proc_macro::{
+    Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream,
+    TokenTree,
+};
+use std::fmt::Write;
+
+/// Derives writing a struct as an XML property list at compile time and reading
+/// it back at runtime.
+#[proc_macro_derive(InfoPlist, attributes(plist))]
+pub fn derive_info_plist(input: TokenStream) -> TokenStream {
+    match parse_struct(input) {
+        Ok(input) => generate(&input)
+            .parse()
+            .expect("generated code should be valid Rust"),
+        Err((span, message)) => compile_error(span, &message),
+    }
+}
+
+type Result<T> = std::result::Result<T, (Span, String)>;
+
+struct Struct {
+    name: String,
+    fields: Vec<Field>,
+}
+
+struct Field {
+    name: String,
+    key: String,
+    ty: Type,
+}
+
+enum Type {
+    Str,
+    Bool,
+    I64,
+    U64,
+    /// Another struct that derives `InfoPlist`.
+    Nested(String),
+    Option(Box<Type>),
+}
+
+fn parse_struct(input: TokenStream) -> Result<Struct> {
+    let mut tokens = input.into_iter().peekable();
+
+    // Skip attributes, including doc comments, and visibility.
+    let keyword = loop {
+        match tokens.next() {
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == '#' => {
+                tokens.next();
+            }
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "pub" => {
+                if let Some(TokenTree::Group(group)) = tokens.peek() {
+                    if group.delimiter() == Delimiter::Parenthesis {
+                        tokens.next();
+                    }
+                }
+            }
+            Some(TokenTree::Ident(ident)) => break ident,
+            other => {
+                let span = other.map_or_else(Span::call_site, |t| t.span());
+                return Err((span, "expected a struct".into()));
+            }
+        }
+    };
+    if keyword.to_string() != "struct" {
+        return Err((
+            keyword.span(),
+            "`InfoPlist` can only be derived for structs".into(),
+        ));
+    }
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(name)) => name,
+        _ => return Err((keyword.span(), "expected a struct name".into())),
+    };
+
+    match tokens.next() {
+        Some(TokenTree::Group(group))
+            if group.delimiter() == Delimiter::Brace =>
+        {
+            let fields = parse_fields(group.stream())?;
+            Ok(Struct {
+                name: name.to_string(),
+                fields,
+            })
+        }
+        Some(TokenTree::Punct(ref punct)) if punct.as_char() == '<' => Err((
+            punct.span(),
+            "`InfoPlist` cannot be derived for generic structs".into(),
+        )),
+        _ => Err((
+            name.span(),
+            "`InfoPlist` can only be derived for structs with named fields"
+                .into(),
+        )),
+    }
+}
+
+fn parse_fields(stream: TokenStream) -> Result<Vec<Field>> {
+    let mut fields: Vec<Field> = Vec::new();
+
+    // Split on commas outside of angle brackets.
+    let mut chunks = vec![Vec::new()];
+    let mut depth = 0;
+    for token in stream {
+        if let TokenTree::Punct(ref punct) = token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                ',' if depth == 0 => {
+                    chunks.push(Vec::new());
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        chunks.last_mut().unwrap().push(token);
+    }
+
+    for chunk in chunks.into_iter().filter(|chunk| !chunk.is_empty()) {
+        let field = parse_field(chunk)?;
+        if let Some(other) = fields.iter().find(|f| f.key == field.key) {
+            return Err((
+                Span::call_site(),
+                format!(
+                    "fields `{}` and `{}` have the same key `{}`",
+                    other.name, field.name, field.key,
+                ),
+            ));
+        }
+        fields.push(field);
+    }
+    Ok(fields)
+}
+
+fn parse_field(tokens: Vec<TokenTree>) -> Result<Field> {
+    let mut tokens = tokens.into_iter().peekable();
+    let mut attributes = Attributes::default();
+
+    let name = loop {
+        match tokens.next() {
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == '#' => {
+                if let Some(TokenTree::Group(group)) = tokens.next() {
+                    parse_attribute(group, &mut attributes)?;
+                }
+            }
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "pub" => {
+                if let Some(TokenTree::Group(group)) = tokens.peek() {
+                    if group.delimiter() == Delimiter::Parenthesis {
+                        tokens.next();
+                    }
+                }
+            }
+            Some(TokenTree::Ident(ident)) => break ident,
+            other => {
+                let span = other.map_or_else(Span::call_site, |t| t.span());
+                return Err((span, "expected a field name".into()));
+            }
+        }
+    };
+
+    match tokens.next() {
+        Some(TokenTree::Punct(ref punct)) if punct.as_char() == ':' => {}
+        _ => return Err((name.span(), "expected `:`".into())),
+    }
+
+    let ty: String = tokens.map(|token| token.to_string()).collect();
+    let ty = parse_type(&ty, attributes.nested).ok_or_else(|| {
+        (
+            name.span(),
+            format!(
+                "unsupported type for `{}`, expected `&'static str`, `bool`, \
+                 `i64`, `u64`, a struct that derives `InfoPlist` with \
+                 `#[plist(nested)]`, or an `Option` of one of these",
+                name,
+            ),
+        )
+    })?;
+
+    let name = name.to_string();
+    let key = attributes.rename.unwrap_or_else(|| {
+        name.strip_prefix("r#").unwrap_or(&name).to_string()
+    });
+    Ok(Field { name, key, ty })
+}
+
+#[derive(Default)]
+struct Attributes {
+    rename: Option<String>,
+    nested: bool,
+}
+
+// Reads `#[plist(rename = "Key", nested)]` into `attributes`, ignoring other
+// attributes.
+fn parse_attribute(group: Group, attributes: &mut Attributes) -> Result<()> {
+    let mut tokens = group.stream().into_iter();
+    match tokens.next() {
+        Some(TokenTree::Ident(ref ident)) if ident.to_string() == "plist" => {}
+        _ => return Ok(()),
+    }
+    let args = match tokens.next() {
+        Some(TokenTree::Group(args))
+            if args.delimiter() == Delimiter::Parenthesis =>
+        {
+            args
+        }
+        _ => return Err((group.span(), "expected `plist(...)`".into())),
+    };
+
+    let expected =
+        "expected `#[plist(rename = \"Key\")]` or `#[plist(nested)]`";
+    let mut args = args.stream().into_iter().peekable();
+    while let Some(arg) = args.next() {
+        match arg {
+            TokenTree::Ident(ref ident) if ident.to_string() == "nested" => {
+                attributes.nested = true;
+            }
+            TokenTree::Ident(ref ident) if ident.to_string() == "rename" => {
+                match (args.next(), args.next()) {
+                    (
+                        Some(TokenTree::Punct(ref punct)),
+                        Some(TokenTree::Literal(ref literal)),
+                    ) if punct.as_char() == '=' => {
+                        let key = parse_string(literal).ok_or_else(|| {
+                            (literal.span(), "expected a string".into())
+                        })?;
+                        attributes.rename = Some(key);
+                    }
+                    _ => return Err((group.span(), expected.into())),
+                }
+            }
+            _ => return Err((group.span(), expected.into())),
+        }
+        match args.next() {
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == ',' => {}
+            None => break,
+            _ => return Err((group.span(), expected.into())),
+        }
+    }
+    Ok(())
+}
+
+// Parses a string literal without escapes, or a raw string literal.
+fn parse_string(literal: &Literal) -> Option<String> {
+    let literal = literal.to_string();
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let raw = &raw[hashes..raw.len() - hashes];
+        return Some(raw.strip_prefix('"')?.strip_suffix('"')?.to_string());
+    }
+    let string = literal.strip_prefix('"')?.strip_suffix('"')?;
+    if string.contains('\\') {
+        return None;
+    }
+    Some(string.to_string())
+}
+
+// Classifies a type from its tokens joined without spaces.
+//
+// Other paths are only accepted as nested structs with `#[plist(nested)]`,
+// since a type like `f32` or `String` cannot be told apart from a struct by
+// its name.
+fn parse_type(ty: &str, nested: bool) -> Option<Type> {
+    let option = ["Option<", "core::option::Option<", "std::option::Option<"]
+        .iter()
+        .find_map(|prefix| ty.trim_start_matches("::").strip_prefix(prefix));
+    if let Some(inner) = option {
+        return match parse_type(inner.strip_suffix('>')?, nested)? {
+            Type::Option(_) => None,
+            inner => Some(Type::Option(Box::new(inner))),
+        };
+    }
+
+    Some(match ty {
+        _ if nested && is_path(ty) => Type::Nested(ty.to_string()),
+        "&'staticstr" if !nested => Type::Str,
+        "bool" if !nested => Type::Bool,
+        "i64" if !nested => Type::I64,
+        "u64" if !nested => Type::U64,
+        _ => return None,
+    })
+}
+
+fn is_path(ty: &str) -> bool {
+    !ty.is_empty()
+        && ty.split("::").enumerate().all(|(i, segment)| {
+            (i == 0 && segment.is_empty())
+                || (segment.starts_with(|ch: char| !ch.is_ascii_digit())
+                    && segment
+                        .chars()
+                        .all(|ch| ch.is_ascii_alphanumeric() || ch == '_'))
+        })
+}
+
+const DERIVE: &str = "::embed_plist::const_eval::derive";
+
+fn generate(input: &Struct) -> String {
+    let mut write = String::new();
+    let mut read = String::new();
+
+    for field in &input.fields {
+        let key = format!("{:?}", field.key);
+        let _ = write!(write, "{{ let value = &self.{}; ", field.name);
+        match &field.ty {
+            Type::Option(ty) => {
+                let _ = write!(
+                    write,
+                    "if let ::core::option::Option::Some(value) = value {{ \
+                     {d}::write_key(out, {k}); {w} }}",
+                    d = DERIVE,
+                    k = key,
+                    w = write_value(ty),
+                );
+            }
+            ty => {
+                let _ = write!(
+                    write,
+                    "{}::write_key(out, {}); {}",
+                    DERIVE,
+                    key,
+                    write_value(ty),
+                );
+            }
+        }
+        write.push_str(" }\n");
+
+        let _ = match &field.ty {
+            Type::Option(ty) => {
+                writeln!(read, "{}: {},", field.name, read_value(ty, &key))
+            }
+            ty => writeln!(
+                read,
+                "{}: match {} {{ \
+                 ::core::option::Option::Some(value) => value, \
+                 ::core::option::Option::None => return \
+                 ::core::result::Result::Err(::embed_plist::Error::MissingKey), \
+                 }},",
+                field.name,
+                read_value(ty, &key),
+            ),
+        };
+    }
+
+    format!(
+        r#"
+impl {name} {{
+    /// Returns the length of the XML property list of `self`.
+    pub const fn plist_len(&self) -> usize {{
+        let mut out = ::embed_plist::const_eval::Writer::new(&mut []);
+        {d}::write_header(&mut out);
+        self.__embed_plist_write(&mut out);
+        {d}::write_footer(&mut out);
+        out.len()
+    }}
+
+    /// Returns the XML property list of `self`, where `N` is its
+    /// `plist_len`.
+    pub const fn to_plist<const N: usize>(&self) -> [u8; N] {{
+        let mut bytes = [0; N];
+        let mut out = ::embed_plist::const_eval::Writer::new(&mut bytes);
+        {d}::write_header(&mut out);
+        self.__embed_plist_write(&mut out);
+        {d}::write_footer(&mut out);
+        if out.len() != N {{
+            ::core::panic!("`N` is not the `plist_len` of the value");
+        }}
+        bytes
+    }}
+
+    /// Reads a value from a property list written by `to_plist`, such as the
+    /// embedded one.
+    pub fn from_plist(
+        bytes: &'static [u8],
+    ) -> ::core::result::Result<Self, ::embed_plist::Error> {{
+        Self::__embed_plist_read(bytes, {d}::read_root(bytes)?)
+    }}
+
+    #[doc(hidden)]
+    pub const fn __embed_plist_write(
+        &self,
+        out: &mut ::embed_plist::const_eval::Writer<'_>,
+    ) {{
+        {d}::write_dict_start(out);
+        {write}
+        {d}::write_dict_end(out);
+    }}
+
+    #[doc(hidden)]
+    pub fn __embed_plist_read(
+        bytes: &'static [u8],
+        dict: {d}::Dict,
+    ) -> ::core::result::Result<Self, ::embed_plist::Error> {{
+        ::core::result::Result::Ok(Self {{
+            {read}
+        }})
+    }}
+}}
+"#,
+        name = input.name,
+        d = DERIVE,
+        write = write,
+        read = read,
+    )
+}
+
+// Writes `value`, which is a reference.
+fn write_value(ty: &Type) -> String {
+    match ty {
+        Type::Str => format!("{}::write_string(out, *value);", DERIVE),
+        Type::Bool => format!("{}::write_bool(out, *value);", DERIVE),
+        Type::I64 | Type::U64 => {
+            format!("{}::write_integer(out, *value as i128);", DERIVE)
+        }
+        Type::Nested(_) => "value.__embed_plist_write(out);".into(),
+        Type::Option(_) => unreachable!("nested options are rejected"),
+    }
+}
+
+// Reads an `Option` of the value for `key`.
+fn read_value(ty: &Type, key: &str) -> String {
+    let read = |function: &str| {
+        format!("{}::{}(bytes, dict, {})?", DERIVE, function, key)
+    };
+    match ty {
+        Type::Str => read("read_string"),
+        Type::Bool => read("read_bool"),
+        Type::I64 => read("read_i64"),
+        Type::U64 => read("read_u64"),
+        Type::Nested(ty) => format!(
+            "match {} {{ \
+             ::core::option::Option::Some(dict) => \
+             ::core::option::Option::Some(<{}>::__embed_plist_read(bytes, dict)?), \
+             ::core::option::Option::None => ::core::option::Option::None, \
+             }}",
+            read("read_dict"),
+            ty,
+        ),
+        Type::Option(_) => unreachable!("nested options are rejected"),
+    }
+}
+
+// Creates `compile_error!("message")` with tokens at `span`.
+fn compile_error(span: Span, message: &str) -> TokenStream {
+    let mut literal = Literal::string(message);
+    literal.set_span(span);
+    let mut args = Group::new(
+        Delimiter::Parenthesis,
+        TokenStream::from(TokenTree::Literal(literal)),
+    );
+    args.set_span(span);
+
+    let tokens: Vec<TokenTree> = vec![
+        Punct::new(':', Spacing::Joint).into(),
+        Punct::new(':', Spacing::Alone).into(),
+        Ident::new("core", span).into(),
+        Punct::new(':', Spacing::Joint).into(),
+        Punct::new(':', Spacing::Alone).into(),
+        Ident::new("compile_error", span).into(),
+        Punct::new('!', Spacing::Alone).into(),
+        args.into(),
+        Punct::new(';', Spacing::Alone).into(),
+    ];
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
@@ -0,0 +1,507 @@
+//! Reading binary property lists (`bplist00`).
+//!
+//! This module requires the `binary` feature. The reader does not allocate, so
+//! it works on the bytes returned by [`get_info_plist`] in any environment.
+//! Objects are decoded lazily as they are accessed, after
+//! [`Document::parse`] has checked the header, trailer, and offset table.
+//!
+//! With the `alloc` feature, [`Value::from_binary`] and [`Value::to_binary`]
+//! convert between binary property lists and the value tree.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use embed_plist::binary::{Document, Object};
+//!
+//! // `{ Why = true; }` in binary form.
+//! let bytes = b"bplist00\xD1\x01\x02SWhy\x09\x08\x0B\x0F\
+//!     \0\0\0\0\0\0\x01\x01\0\0\0\0\0\0\0\x03\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\x10";
+//!
+//! let document = Document::parse(bytes)?;
+//!
+//! let dict = match document.root()? {
+//!     Object::Dictionary(dict) => dict,
+//!     _ => panic!("expected a dictionary"),
+//! };
+//! assert_eq!(dict.get("Why")?, Some(Object::Boolean(true)));
+//! # Ok::<(), embed_plist::binary::Error>(())
+//! ```
+//!
+//! [`get_info_plist`]: ../fn.get_info_plist.html
+//! [`Document::parse`]: struct.Document.html#method.parse
+//! [`Value::from_binary`]: ../value/enum.Value.html#method.from_binary
+//! [`Value::to_binary`]: ../value/enum.Value.html#method.to_binary
+
+use core::{char, convert::TryFrom, fmt};
+
+/// The magic number and version at the start of a binary property list.
+pub const HEADER: &[u8; 8] = b"bplist00";
+
+const TRAILER_LEN: usize = 32;
+
+/// An error encountered while reading a binary property list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The input does not start with `bplist00`.
+    InvalidHeader,
+    /// The trailer has invalid sizes or counts.
+    InvalidTrailer,
+    /// An offset, length, or object extends past the end of its region.
+    OutOfBounds,
+    /// An object reference is not less than the number of objects.
+    InvalidReference,
+    /// An object has an unknown type marker.
+    InvalidObject,
+    /// An object is valid but has no property list equivalent, such as a set.
+    UnsupportedObject,
+    /// A string is not valid ASCII or UTF-16.
+    InvalidString,
+    /// A dictionary key is not a string.
+    InvalidKey,
+    /// An array or dictionary contains itself.
+    Cycle,
+    /// Arrays and dictionaries are nested more than 512 levels deep.
+    TooDeep,
+}
+
+impl Error {
+    /// Returns a description of this error.
+    pub const fn message(self) -> &'static str {
+        match self {
+            Error::InvalidHeader => "missing `bplist00` header",
+            Error::InvalidTrailer => "invalid binary property list trailer",
+            Error::OutOfBounds => "binary property list is truncated",
+            Error::InvalidReference => "invalid object reference",
+            Error::InvalidObject => "unknown object type in property list",
+            Error::UnsupportedObject => "unsupported object in property list",
+            Error::InvalidString => "invalid string in property list",
+            Error::InvalidKey => "dictionary key is not a string",
+            Error::Cycle => "property list contains a cycle",
+            Error::TooDeep => "property list is nested too deeply",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// A parsed binary property list.
+#[derive(Clone, Copy, Debug)]
+pub struct Document<'a> {
+    bytes: &'a [u8],
+    offset_size: usize,
+    ref_size: usize,
+    num_objects: u64,
+    top_object: u64,
+    offset_table: usize,
+}
+
+impl<'a> Document<'a> {
+    /// Checks the structure of the binary property list in `bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header or trailer is invalid, or if the offset
+    /// table does not fit in `bytes`.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, Error> {
+        if !bytes.starts_with(HEADER) {
+            return Err(Error::InvalidHeader);
+        }
+        if bytes.len() < HEADER.len() + TRAILER_LEN {
+            return Err(Error::OutOfBounds);
+        }
+
+        let trailer = &bytes[bytes.len() - TRAILER_LEN..];
+        let offset_size = usize::from(trailer[6]);
+        let ref_size = usize::from(trailer[7]);
+        let num_objects = read_uint(&trailer[8..16]);
+        let top_object = read_uint(&trailer[16..24]);
+        let offset_table = read_uint(&trailer[24..32]);
+
+        if !(1..=8).contains(&offset_size)
+            || !(1..=8).contains(&ref_size)
+            || top_object >= num_objects
+        {
+            return Err(Error::InvalidTrailer);
+        }
+
+        // The offset table sits between the objects and the trailer.
+        let table_end = (bytes.len() - TRAILER_LEN) as u64;
+        let table_len = num_objects.checked_mul(offset_size as u64);
+        let fits = table_len
+            .and_then(|len| offset_table.checked_add(len))
+            .is_some_and(|end| end <= table_end);
+        if offset_table < HEADER.len() as u64 || !fits {
+            return Err(Error::OutOfBounds);
+        }
+
+        Ok(Document {
+            bytes,
+            offset_size,
+            ref_size,
+            num_objects,
+            top_object,
+            offset_table: offset_table as usize,
+        })
+    }
+
+    /// Returns the top-level object.
+    #[inline]
+    pub fn root(&self) -> Result<Object<'a>, Error> {
+        self.object(self.top_object)
+    }
+
+    // Returns the index of the top-level object, for cycle detection.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn root_index(&self) -> u64 {
+        self.top_object
+    }
+
+    // Returns the object at `index` in the offset table.
+    pub(crate) fn object(&self, index: u64) -> Result<Object<'a>, Error> {
+        if index >= self.num_objects {
+            return Err(Error::InvalidReference);
+        }
+        let entry = self.offset_table + index as usize * self.offset_size;
+        let offset = read_uint(&self.bytes[entry..entry + self.offset_size]);
+        if offset < HEADER.len() as u64 || offset >= self.offset_table as u64 {
+            return Err(Error::OutOfBounds);
+        }
+        self.object_at(offset as usize)
+    }
+
+    // Returns the `i`th object reference in `refs`.
+    fn index_of(&self, refs: &[u8], i: usize) -> u64 {
+        read_uint(&refs[i * self.ref_size..(i + 1) * self.ref_size])
+    }
+
+    fn object_at(&self, offset: usize) -> Result<Object<'a>, Error> {
+        let marker = self.bytes[offset];
+        let (kind, info) = (marker >> 4, marker & 0x0F);
+        let rest = offset + 1;
+
+        Ok(match (kind, info) {
+            (0x0, 0x8) => Object::Boolean(false),
+            (0x0, 0x9) => Object::Boolean(true),
+            (0x0, 0x0) | (0x0, 0xF) | (0xB, _) | (0xC, _) => {
+                return Err(Error::UnsupportedObject)
+            }
+            (0x1, 0..=4) => {
+                Object::Integer(read_int(self.slice(rest, 1 << info)?))
+            }
+            (0x2, 2) => {
+                let bits = read_uint(self.slice(rest, 4)?) as u32;
+                Object::Real(f64::from(f32::from_bits(bits)))
+            }
+            (0x2, 3) => {
+                Object::Real(f64::from_bits(read_uint(self.slice(rest, 8)?)))
+            }
+            (0x3, 3) => {
+                Object::Date(f64::from_bits(read_uint(self.slice(rest, 8)?)))
+            }
+            (0x4, _) => {
+                let (start, len) = self.length(rest, info)?;
+                Object::Data(self.slice(start, len)?)
+            }
+            (0x5, _) => {
+                let (start, len) = self.length(rest, info)?;
+                let ascii = self.slice(start, len)?;
+                if !ascii.is_ascii() {
+                    return Err(Error::InvalidString);
+                }
+                // ASCII is always valid UTF-8.
+                let ascii = core::str::from_utf8(ascii)
+                    .map_err(|_| Error::InvalidString)?;
+                Object::String(Str {
+                    repr: StrRepr::Ascii(ascii),
+                })
+            }
+            (0x6, _) => {
+                let (start, len) = self.length(rest, info)?;
+                let len = len.checked_mul(2).ok_or(Error::OutOfBounds)?;
+                let utf16 = Str {
+                    repr: StrRepr::Utf16(self.slice(start, len)?),
+                };
+                if utf16.chars().any(|ch| ch.is_err()) {
+                    return Err(Error::InvalidString);
+                }
+                Object::String(utf16)
+            }
+            (0x8, _) => {
+                Object::Uid(read_uint(self.slice(rest, usize::from(info) + 1)?))
+            }
+            (0xA, _) => {
+                let (start, len) = self.length(rest, info)?;
+                let refs = self.refs(start, len)?;
+                Object::Array(Array {
+                    document: *self,
+                    refs,
+                })
+            }
+            (0xD, _) => {
+                let (start, len) = self.length(rest, info)?;
+                let keys = self.refs(start, len)?;
+                let values = self.refs(start + keys.len(), len)?;
+                Object::Dictionary(Dict {
+                    document: *self,
+                    keys,
+                    values,
+                })
+            }
+            _ => return Err(Error::InvalidObject),
+        })
+    }
+
+    // Reads the length of an object with variable size, which is either `info`
+    // or a following integer object. Returns where the contents start.
+    fn length(&self, pos: usize, info: u8) -> Result<(usize, usize), Error> {
+        if info != 0x0F {
+            return Ok((pos, usize::from(info)));
+        }
+        let marker = *self.slice(pos, 1)?.first().ok_or(Error::OutOfBounds)?;
+        if marker >> 4 != 0x1 || marker & 0x0F > 3 {
+            return Err(Error::InvalidObject);
+        }
+        let size = 1 << (marker & 0x0F);
+        let len = read_uint(self.slice(pos + 1, size)?);
+        let len = usize::try_from(len).map_err(|_| Error::OutOfBounds)?;
+        Ok((pos + 1 + size, len))
+    }
+
+    fn refs(&self, pos: usize, count: usize) -> Result<&'a [u8], Error> {
+        let len = count.checked_mul(self.ref_size).ok_or(Error::OutOfBounds)?;
+        self.slice(pos, len)
+    }
+
+    // Returns `len` bytes at `pos`, which must come before the offset table.
+    fn slice(&self, pos: usize, len: usize) -> Result<&'a [u8], Error> {
+        match pos.checked_add(len) {
+            Some(end) if end <= self.offset_table => Ok(&self.bytes[pos..end]),
+            _ => Err(Error::OutOfBounds),
+        }
+    }
+}
+
+/// An object in a binary property list.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Object<'a> {
+    /// An array of objects.
+    Array(Array<'a>),
+    /// A dictionary of string keys to objects.
+    Dictionary(Dict<'a>),
+    /// A boolean.
+    Boolean(bool),
+    /// Raw bytes.
+    Data(&'a [u8]),
+    /// Seconds since 2001-01-01T00:00:00Z.
+    Date(f64),
+    /// An integer of up to 128 bits.
+    Integer(i128),
+    /// A floating-point number.
+    Real(f64),
+    /// A string.
+    String(Str<'a>),
+    /// A unique identifier, as used by `NSKeyedArchiver`.
+    Uid(u64),
+}
+
+/// A string in a binary property list, stored as ASCII or UTF-16.
+#[derive(Clone, Copy, Debug)]
+pub struct Str<'a> {
+    repr: StrRepr<'a>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum StrRepr<'a> {
+    Ascii(&'a str),
+    /// Big-endian UTF-16, which has been validated.
+    Utf16(&'a [u8]),
+}
+
+impl<'a> Str<'a> {
+    /// Returns the string if it is stored as ASCII, which needs no decoding.
+    #[inline]
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self.repr {
+            StrRepr::Ascii(ascii) => Some(ascii),
+            StrRepr::Utf16(_) => None,
+        }
+    }
+
+    /// Returns an iterator over the characters of the string.
+    #[inline]
+    pub fn chars(&self) -> impl Iterator<Item = Result<char, Error>> + 'a {
+        let (ascii, utf16) = match self.repr {
+            StrRepr::Ascii(ascii) => (ascii, &[][..]),
+            StrRepr::Utf16(utf16) => ("", utf16),
+        };
+        let units = utf16
+            .chunks_exact(2)
+            .map(|unit| u16::from_be_bytes([unit[0], unit[1]]));
+        ascii.chars().map(Ok).chain(
+            char::decode_utf16(units)
+                .map(|ch| ch.map_err(|_| Error::InvalidString)),
+        )
+    }
+
+    /// Returns `true` if the characters equal those of `other`.
+    pub fn eq_str(&self, other: &str) -> bool {
+        match self.repr {
+            StrRepr::Ascii(ascii) => ascii == other,
+            StrRepr::Utf16(_) => {
+                self.chars().map(Result::ok).eq(other.chars().map(Some))
+            }
+        }
+    }
+}
+
+impl PartialEq for Str<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.chars()
+            .map(Result::ok)
+            .eq(other.chars().map(Result::ok))
+    }
+}
+
+impl fmt::Display for Str<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use fmt::Write;
+        for ch in self.chars() {
+            f.write_char(ch.map_err(|_| fmt::Error)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// An array in a binary property list.
+#[derive(Clone, Copy, Debug)]
+pub struct Array<'a> {
+    document: Document<'a>,
+    refs: &'a [u8],
+}
+
+impl<'a> Array<'a> {
+    /// Returns the number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.refs.len() / self.document.ref_size
+    }
+
+    /// Returns `true` if there are no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.refs.is_empty()
+    }
+
+    /// Returns the element at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Result<Option<Object<'a>>, Error> {
+        if index >= self.len() {
+            return Ok(None);
+        }
+        self.document.object(self.index(index)).map(Some)
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(&self) -> impl Iterator<Item = Result<Object<'a>, Error>> + 'a {
+        let array = *self;
+        (0..self.len()).map(move |i| array.document.object(array.index(i)))
+    }
+
+    // Returns the object index of the `i`th element.
+    pub(crate) fn index(&self, i: usize) -> u64 {
+        self.document.index_of(self.refs, i)
+    }
+}
+
+impl PartialEq for Array<'_> {
+    // Compares identity, since comparing contents could loop forever.
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self.refs, other.refs)
+    }
+}
+
+/// A dictionary in a binary property list.
+#[derive(Clone, Copy, Debug)]
+pub struct Dict<'a> {
+    document: Document<'a>,
+    keys: &'a [u8],
+    values: &'a [u8],
+}
+
+impl<'a> Dict<'a> {
+    /// Returns the number of entries.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.keys.len() / self.document.ref_size
+    }
+
+    /// Returns `true` if there are no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the value for `key`.
+    pub fn get(&self, key: &str) -> Result<Option<Object<'a>>, Error> {
+        for i in 0..self.len() {
+            if self.key(i)?.eq_str(key) {
+                return self.document.object(self.value_index(i)).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns an iterator over the entries.
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = Result<(Str<'a>, Object<'a>), Error>> + 'a {
+        let dict = *self;
+        (0..self.len()).map(move |i| {
+            let value = dict.document.object(dict.value_index(i))?;
+            Ok((dict.key(i)?, value))
+        })
+    }
+
+    pub(crate) fn key(&self, i: usize) -> Result<Str<'a>, Error> {
+        match self.document.object(self.document.index_of(self.keys, i))? {
+            Object::String(key) => Ok(key),
+            _ => Err(Error::InvalidKey),
+        }
+    }
+
+    // Returns the object index of the `i`th value.
+    pub(crate) fn value_index(&self, i: usize) -> u64 {
+        self.document.index_of(self.values, i)
+    }
+}
+
+impl PartialEq for Dict<'_> {
+    // Compares identity, since comparing contents could loop forever.
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self.keys, other.keys)
+    }
+}
+
+fn read_uint(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |value, &byte| (value << 8) | u64::from(byte))
+}
+
+// Integers of 8 or 16 bytes are signed, while smaller ones are unsigned.
+fn read_int(bytes: &[u8]) -> i128 {
+    match bytes.len() {
+        8 => i128::from(read_uint(bytes) as i64),
+        16 => bytes
+            .iter()
+            .fold(0, |value, &byte| (value << 8) | i128::from(byte)),
+        _ => i128::from(read_uint(bytes)),
+    }
+}
@@ -0,0 +1,377 @@
+use super::{invalid, out_path, rerun_if_changed, write_out};
+use crate::{
+    keys::{self, Key},
+    value::{Dictionary, Value},
+};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    format, fs, io,
+    path::Path,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Generates a module of constants for the top-level keys of the [`Info.plist`]
+/// at `path`, for use with [`info_plist_consts!`].
+///
+/// `path` is relative to the package root, and must be the same string that is
+/// passed to [`info_plist_consts!`]. The generated `info_plist` module contains
+/// a `BYTES` constant with the contents of the file, which it embeds with
+/// [`embed_info_plist_bytes!`], along with a constant for each key.
+///
+/// Key names are converted to `SCREAMING_SNAKE_CASE`, with Apple's two-letter
+/// prefixes split off. For example, `CFBundleIdentifier` becomes
+/// `CF_BUNDLE_IDENTIFIER` and `LSUIElement` becomes `LS_UI_ELEMENT`. Values
+/// have the following types:
+///
+/// | Property List Value     | Rust Type                                   |
+/// | ----------------------- | ------------------------------------------- |
+/// | `<string>`, `<date>`    | `&str`                                      |
+/// | `<true/>`, `<false/>`   | `bool`                                      |
+/// | `<integer>`             | `i64`, or `u64` if too large for `i64`      |
+/// | `<real>`                | `f64`                                       |
+/// | `<data>`                | `&[u8]`                                     |
+/// | `<array>`               | `&[T]` if all elements have the same type   |
+/// | `<dict>`                | A nested module in `snake_case`             |
+///
+/// Arrays that are empty or mix types are skipped. Constants for keys in
+/// [`keys::info`] are documented with the key's description.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// // build.rs
+/// # fn main() -> std::io::Result<()> {
+/// embed_plist::build::info_plist_consts("Info.plist")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```rust,ignore
+/// // main.rs
+/// embed_plist::info_plist_consts!("Info.plist");
+///
+/// fn main() {
+///     println!("{}", info_plist::CF_BUNDLE_IDENTIFIER);
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if reading `path` or writing to `OUT_DIR` fails, or with
+/// [`io::ErrorKind::InvalidData`] if `path` is not a valid XML property list
+/// with a dictionary at its root, or if two keys have the same name in Rust.
+///
+/// [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
+/// [`info_plist_consts!`]: ../macro.info_plist_consts.html
+/// [`embed_info_plist_bytes!`]: ../macro.embed_info_plist_bytes.html
+/// [`io::ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+/// [`keys::info`]: ../keys/info/index.html
+pub fn info_plist_consts<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    write_consts(
+        path.as_ref(),
+        "info_plist",
+        "embed_info_plist_bytes",
+        keys::info::ALL,
+    )
+}
+
+/// Generates a module of constants for the top-level keys of the
+/// [`launchd.plist`] at `path`, for use with [`launchd_plist_consts!`].
+///
+/// This works the same as [`info_plist_consts`], except that the generated
+/// module is named `launchd_plist`, embeds the file with
+/// [`embed_launchd_plist_bytes!`], and documents keys in [`keys::launchd`].
+///
+/// [`launchd.plist`]: https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html#//apple_ref/doc/uid/TP40001762-104142
+/// [`launchd_plist_consts!`]: ../macro.launchd_plist_consts.html
+/// [`info_plist_consts`]: fn.info_plist_consts.html
+/// [`embed_launchd_plist_bytes!`]: ../macro.embed_launchd_plist_bytes.html
+/// [`keys::launchd`]: ../keys/launchd/index.html
+pub fn launchd_plist_consts<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    write_consts(
+        path.as_ref(),
+        "launchd_plist",
+        "embed_launchd_plist_bytes",
+        keys::launchd::ALL,
+    )
+}
+
+// Writes to the path that `*_plist_consts!` includes.
+fn write_consts(
+    path: &Path,
+    module: &str,
+    embed: &str,
+    known: &[Key],
+) -> io::Result<()> {
+    rerun_if_changed(path);
+
+    let out_path = out_path(path, ".rs")?;
+    let source = generate(path, module, embed, known)?;
+    write_out(&out_path, source)
+}
+
+fn generate(
+    path: &Path,
+    module: &str,
+    embed: &str,
+    known: &[Key],
+) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+    let entries = match Value::from_xml(&bytes).map_err(invalid)? {
+        Value::Dictionary(entries) => entries,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "property list root is not a dictionary",
+            ))
+        }
+    };
+    let absolute = fs::canonicalize(path)?;
+
+    let mut out = String::new();
+    let file = path.display();
+    let _ = writeln!(out, "// Generated by embed_plist from `{}`.", file);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "/// Constants for the keys of `{}`.", file);
+    let _ = writeln!(out, "#[allow(dead_code)]");
+    let _ = writeln!(out, "pub mod {} {{", module);
+    let _ = writeln!(
+        out,
+        "    /// The contents of `{}`, which are embedded in the binary.",
+        file
+    );
+    let _ = writeln!(
+        out,
+        "    pub const BYTES: &[u8] = include_bytes!({:?});",
+        absolute.display().to_string()
+    );
+    let _ = writeln!(out);
+    let _ = writeln!(out, "    ::embed_plist::{}!(BYTES);", embed);
+    if !entries.is_empty() {
+        let _ = writeln!(out);
+    }
+
+    let mut names = Names::default();
+    names.insert("BYTES".into(), "BYTES")?;
+    write_entries(&mut out, &entries, 1, known, names)?;
+
+    out.push_str("}\n");
+    Ok(out)
+}
+
+// Tracks which key each generated name came from, to report collisions.
+#[derive(Default)]
+struct Names(BTreeMap<String, String>);
+
+impl Names {
+    fn insert(&mut self, name: String, key: &str) -> io::Result<()> {
+        if let Some(existing) = self.0.get(&name) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "keys `{}` and `{}` both map to `{}`",
+                    existing, key, name
+                ),
+            ));
+        }
+        self.0.insert(name, key.into());
+        Ok(())
+    }
+}
+
+fn write_entries(
+    out: &mut String,
+    entries: &Dictionary,
+    depth: usize,
+    known: &[Key],
+    mut names: Names,
+) -> io::Result<()> {
+    let indent = "    ".repeat(depth);
+
+    for (i, (key, value)) in entries.iter().enumerate() {
+        if i > 0 {
+            let _ = writeln!(out);
+        }
+
+        if let Value::Dictionary(entries) = value {
+            let name = module_name(key);
+            names.insert(name.clone(), key)?;
+            write_description(out, &indent, known, key);
+            let _ = writeln!(out, "{}/// The entries of `{}`.", indent, key);
+            let _ = writeln!(out, "{}pub mod {} {{", indent, name);
+            write_entries(out, entries, depth + 1, &[], Names::default())?;
+            let _ = writeln!(out, "{}}}", indent);
+            continue;
+        }
+
+        match constant(value) {
+            Some((ty, literal)) => {
+                let name = const_name(key);
+                names.insert(name.clone(), key)?;
+                write_description(out, &indent, known, key);
+                let _ = writeln!(out, "{}/// The value of `{}`.", indent, key);
+                let _ = writeln!(
+                    out,
+                    "{}pub const {}: {} = {};",
+                    indent, name, ty, literal
+                );
+            }
+            None => {
+                let _ = writeln!(
+                    out,
+                    "{}// `{}` is skipped because it is an empty or mixed array.",
+                    indent, key
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+// Starts the documentation of `key` with its description, if it is in `known`.
+fn write_description(out: &mut String, indent: &str, known: &[Key], key: &str) {
+    if let Some(key) = known.iter().find(|known| known.name() == key) {
+        let _ = writeln!(out, "{}/// {}", indent, key.description());
+        let _ = writeln!(out, "{}///", indent);
+    }
+}
+
+// Returns the Rust type and literal for `value`.
+fn constant(value: &Value) -> Option<(String, String)> {
+    let (ty, literal) = match value {
+        Value::String(string) => ("&str".into(), format!("{:?}", string)),
+        Value::Date(date) => ("&str".into(), format!("{:?}", date.to_string())),
+        Value::Uid(uid) => ("u64".into(), uid.to_string()),
+        Value::Boolean(boolean) => ("bool".into(), boolean.to_string()),
+        Value::Integer(int) => {
+            let ty = if *int > i128::from(i64::MAX) {
+                "u64"
+            } else {
+                "i64"
+            };
+            (ty.into(), int.to_string())
+        }
+        Value::Real(real) => ("f64".into(), real_literal(*real)),
+        Value::Data(data) => ("&[u8]".into(), format!("&{:?}", data)),
+        Value::Array(values) => {
+            let mut ty = None;
+            let mut literals = Vec::new();
+            for value in values {
+                if let Value::Array(_) = value {
+                    return None;
+                }
+                let (element_ty, literal) = constant(value)?;
+                if ty.get_or_insert_with(|| element_ty.clone()) != &element_ty {
+                    return None;
+                }
+                literals.push(literal);
+            }
+            (format!("&[{}]", ty?), format!("&[{}]", literals.join(", ")))
+        }
+        Value::Dictionary(_) => return None,
+    };
+    Some((ty, literal))
+}
+
+fn real_literal(real: f64) -> String {
+    if real.is_nan() {
+        "f64::NAN".into()
+    } else if real.is_infinite() {
+        if real > 0.0 {
+            "f64::INFINITY"
+        } else {
+            "f64::NEG_INFINITY"
+        }
+        .into()
+    } else {
+        format!("{:?}", real)
+    }
+}
+
+const PREFIXES: &[&str] = &[
+    "AV", "BG", "CF", "CK", "CL", "DT", "GK", "IO", "LS", "MK", "NS", "UI",
+    "WK",
+];
+
+// Splits a key like `LSUIElement` into `["LS", "UI", "Element"]`.
+fn words(key: &str) -> Vec<String> {
+    let mut rest = key;
+    let mut words = Vec::new();
+
+    // Peel off Apple's prefixes, which camel case splitting would merge with
+    // following acronyms.
+    'prefixes: loop {
+        for prefix in PREFIXES {
+            let is_followed_by_word = rest
+                .get(prefix.len()..)
+                .and_then(|after| after.chars().next())
+                .is_some_and(|ch| ch.is_ascii_uppercase());
+            if rest.starts_with(prefix) && is_followed_by_word {
+                words.push(prefix.to_string());
+                rest = &rest[prefix.len()..];
+                continue 'prefixes;
+            }
+        }
+        break;
+    }
+
+    let chars: Vec<char> = rest.chars().collect();
+    let mut word = String::new();
+    for (i, &ch) in chars.iter().enumerate() {
+        if !ch.is_ascii_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if ch.is_ascii_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower =
+                chars.get(i + 1).is_some_and(|c| c.is_ascii_lowercase());
+            if prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_is_lower)
+            {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(ch);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn const_name(key: &str) -> String {
+    let name = words(key).join("_").to_ascii_uppercase();
+    if name.is_empty() || name.starts_with(|ch: char| ch.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+fn module_name(key: &str) -> String {
+    let name = words(key).join("_").to_ascii_lowercase();
+    if name.is_empty() || name.starts_with(|ch: char| ch.is_ascii_digit()) {
+        format!("_{}", name)
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else if ["crate", "self", "super", "_"].contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const",
+    "continue", "do", "dyn", "else", "enum", "extern", "false", "final", "fn",
+    "for", "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod",
+    "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized",
+    "use", "virtual", "where", "while", "yield",
+];
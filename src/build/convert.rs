@@ -0,0 +1,86 @@
+use super::{invalid, out_path, rerun_if_changed, write_out};
+use crate::{value::Value, Error};
+use std::{fs, io, path::Path};
+
+/// Converts the JSON document at `path` to an XML property list, for use with
+/// [`embed_info_plist_json!`] or [`embed_launchd_plist_json!`].
+///
+/// `path` is relative to the package root, and must be the same string that is
+/// passed to the macro. The document is read with [`Value::from_json`], so
+/// dates and data are written as objects with a single `$date` or `$data` key:
+///
+/// ```json
+/// {
+///     "Label": "com.example.job",
+///     "StartInterval": 3600,
+///     "ExpiresAt": { "$date": "2030-01-01T00:00:00Z" },
+///     "Token": { "$data": "AAECAw==" }
+/// }
+/// ```
+///
+/// This also tells Cargo to rerun the build script when `path` changes.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// // build.rs
+/// # fn main() -> std::io::Result<()> {
+/// embed_plist::build::json_to_plist("job.json")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```rust,ignore
+/// // main.rs
+/// embed_plist::embed_launchd_plist_json!("job.json");
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if reading `path` or writing to `OUT_DIR` fails, or with
+/// [`io::ErrorKind::InvalidData`] if `path` is not a valid JSON document.
+///
+/// [`embed_info_plist_json!`]: ../macro.embed_info_plist_json.html
+/// [`embed_launchd_plist_json!`]: ../macro.embed_launchd_plist_json.html
+/// [`Value::from_json`]: ../value/enum.Value.html#method.from_json
+/// [`io::ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+pub fn json_to_plist<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    convert(path.as_ref(), Value::from_json)
+}
+
+/// Converts the TOML document at `path` to an XML property list, for use with
+/// [`embed_info_plist_toml!`] or [`embed_launchd_plist_toml!`].
+///
+/// This works the same as [`json_to_plist`], except that the document is read
+/// with [`Value::from_toml`]. Offset date-times are dates, so only data needs a
+/// tagged table:
+///
+/// ```toml
+/// CFBundleIdentifier = "com.example.app"
+/// LSUIElement = true
+/// BuildDate = 2020-08-30T12:34:56Z
+/// Token = { "$data" = "AAECAw==" }
+///
+/// [NSAppTransportSecurity]
+/// NSAllowsArbitraryLoads = false
+/// ```
+///
+/// [`embed_info_plist_toml!`]: ../macro.embed_info_plist_toml.html
+/// [`embed_launchd_plist_toml!`]: ../macro.embed_launchd_plist_toml.html
+/// [`json_to_plist`]: fn.json_to_plist.html
+/// [`Value::from_toml`]: ../value/enum.Value.html#method.from_toml
+pub fn toml_to_plist<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    convert(path.as_ref(), Value::from_toml)
+}
+
+// Writes to the path that `embed_*_plist_{json,toml}!` includes.
+fn convert(
+    path: &Path,
+    parse: fn(&[u8]) -> Result<Value, Error>,
+) -> io::Result<()> {
+    rerun_if_changed(path);
+
+    let out_path = out_path(path, ".plist")?;
+    let value = parse(&fs::read(path)?).map_err(invalid)?;
+    write_out(&out_path, value.to_xml())
+}
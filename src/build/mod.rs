@@ -0,0 +1,153 @@
+//! Helpers for preprocessing property lists in build scripts.
+//!
+//! This module requires the `std` feature. Add this library to your
+//! `[build-dependencies]` with that feature:
+//!
+//! ```toml
+//! [build-dependencies]
+//! embed_plist = { version = "1.2", features = ["std"] }
+//! ```
+//!
+//! Then write the processed file to `OUT_DIR` from `build.rs` and embed it from
+//! there:
+//!
+//! ```rust,no_run
+//! // build.rs
+//! # fn main() -> std::io::Result<()> {
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! let out_path = std::path::Path::new(&out_dir).join("Info.plist");
+//!
+//! let result = embed_plist::build::canonicalize("Info.plist", out_path)?;
+//! println!("cargo:warning=Info.plist: saved {} bytes", result.saved());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ```rust,ignore
+//! // main.rs
+//! embed_plist::embed_info_plist!(concat!(env!("OUT_DIR"), "/Info.plist"));
+//! ```
+
+use crate::const_eval;
+use std::{
+    borrow::ToOwned,
+    env, fs, io,
+    path::{Component, Path, PathBuf},
+    vec,
+    vec::Vec,
+};
+
+mod consts;
+mod convert;
+mod object;
+
+pub use self::consts::{info_plist_consts, launchd_plist_consts};
+pub use self::convert::{json_to_plist, toml_to_plist};
+pub use self::object::{
+    info_plist_object, launchd_plist_object, Arch, ObjectTarget, Platform,
+};
+
+/// The sizes of a property list before and after [`canonicalize`].
+///
+/// [`canonicalize`]: fn.canonicalize.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Canonicalized {
+    /// The size of the input file in bytes.
+    pub input_len: usize,
+
+    /// The size of the canonical output in bytes.
+    pub output_len: usize,
+}
+
+impl Canonicalized {
+    /// Returns how many bytes were saved, or 0 if the output is not smaller.
+    #[inline]
+    pub fn saved(&self) -> usize {
+        self.input_len.saturating_sub(self.output_len)
+    }
+}
+
+/// Writes the canonical form of the XML property list at `input` to `output`.
+///
+/// This is the same transformation as the `canonicalize` option of the
+/// embedding macros. The canonical form:
+///
+/// - Has the XML declaration but no DOCTYPE, comments, or whitespace between
+///   elements.
+///
+/// - Sorts dictionary keys by their Unicode code points.
+///
+/// - Writes `<true/>`, `<false/>`, `<dict/>`, and `<array/>` for empty
+///   elements, and `<string></string>` for empty text.
+///
+/// - Escapes only `&`, `<`, and `>` within text, and removes whitespace from
+///   `<data>`, `<date>`, `<integer>`, and `<real>`.
+///
+/// This also tells Cargo to rerun the build script when `input` changes.
+///
+/// # Errors
+///
+/// Returns an error if reading or writing fails, or with
+/// [`io::ErrorKind::InvalidData`] if `input` is not a valid XML property list,
+/// nests arrays and dictionaries more than 512 deep, or has more than 1024
+/// keys across a dictionary and the dictionaries that contain it.
+///
+/// [`io::ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+pub fn canonicalize<P, Q>(input: P, output: Q) -> io::Result<Canonicalized>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let input = input.as_ref();
+    rerun_if_changed(input);
+
+    let bytes = fs::read(input)?;
+    let canonical = canonicalize_bytes(&bytes)?;
+    fs::write(output, &canonical)?;
+
+    Ok(Canonicalized {
+        input_len: bytes.len(),
+        output_len: canonical.len(),
+    })
+}
+
+fn canonicalize_bytes(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let len = const_eval::canonicalize_into(bytes, &mut []).map_err(invalid)?;
+    let mut out = vec![0; len];
+    const_eval::canonicalize_into(bytes, &mut out).map_err(invalid)?;
+    Ok(out)
+}
+
+// Returns the path under `OUT_DIR` that the macros include for `path`, which
+// is `path` with `extension` appended.
+fn out_path(path: &Path, extension: &str) -> io::Result<PathBuf> {
+    let out_dir = env::var_os("OUT_DIR").ok_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, "`OUT_DIR` is not set")
+    })?;
+    let mut out_path = PathBuf::from(out_dir).join("embed_plist");
+    // Mirror how `concat!` joins an absolute path rather than replacing.
+    for component in path.components() {
+        if let Component::Normal(_) | Component::ParentDir = component {
+            out_path.push(component);
+        }
+    }
+    let mut file_name = out_path.file_name().unwrap_or_default().to_owned();
+    file_name.push(extension);
+    out_path.set_file_name(file_name);
+    Ok(out_path)
+}
+
+fn write_out<C: AsRef<[u8]>>(out_path: &Path, contents: C) -> io::Result<()> {
+    if let Some(parent) = out_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(out_path, contents)
+}
+
+fn invalid(error: const_eval::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.message())
+}
+
+fn rerun_if_changed(path: &Path) {
+    std::println!("cargo:rerun-if-changed={}", path.display());
+}
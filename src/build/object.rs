@@ -0,0 +1,454 @@
+use super::rerun_if_changed;
+use crate::const_eval;
+use std::{
+    env, fs, io,
+    path::Path,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+const MH_MAGIC_64: u32 = 0xfeed_facf;
+const MH_OBJECT: u32 = 1;
+const LC_SYMTAB: u32 = 0x2;
+const LC_DYSYMTAB: u32 = 0xb;
+const LC_SEGMENT_64: u32 = 0x19;
+const LC_BUILD_VERSION: u32 = 0x32;
+const S_ATTR_NO_DEAD_STRIP: u32 = 0x1000_0000;
+const N_SECT_EXT: u8 = 0x0f;
+
+const HEADER_LEN: usize = 32;
+const SEGMENT_LEN: usize = 72 + 2 * 80;
+const COMMANDS_LEN: usize = SEGMENT_LEN + 24 + 24 + 80;
+
+/// A CPU architecture for [`info_plist_object`].
+///
+/// [`info_plist_object`]: fn.info_plist_object.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Arch {
+    /// 64-bit Intel.
+    X86_64,
+    /// 64-bit ARM, as used by Apple silicon.
+    Arm64,
+}
+
+impl Arch {
+    // The `cputype` and `cpusubtype` of the header.
+    fn cpu(self) -> (u32, u32) {
+        match self {
+            Arch::X86_64 => (0x0100_0007, 3),
+            Arch::Arm64 => (0x0100_000c, 0),
+        }
+    }
+}
+
+/// An Apple platform for [`info_plist_object`].
+///
+/// [`info_plist_object`]: fn.info_plist_object.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Platform {
+    /// macOS.
+    MacOs,
+    /// iOS.
+    IOs,
+    /// tvOS.
+    TvOs,
+    /// watchOS.
+    WatchOs,
+    /// Mac Catalyst.
+    MacCatalyst,
+    /// The iOS simulator.
+    IOsSimulator,
+    /// The tvOS simulator.
+    TvOsSimulator,
+    /// The watchOS simulator.
+    WatchOsSimulator,
+    /// visionOS.
+    VisionOs,
+    /// The visionOS simulator.
+    VisionOsSimulator,
+}
+
+impl Platform {
+    // The `platform` field of `LC_BUILD_VERSION`.
+    fn raw(self) -> u32 {
+        match self {
+            Platform::MacOs => 1,
+            Platform::IOs => 2,
+            Platform::TvOs => 3,
+            Platform::WatchOs => 4,
+            Platform::MacCatalyst => 6,
+            Platform::IOsSimulator => 7,
+            Platform::TvOsSimulator => 8,
+            Platform::WatchOsSimulator => 9,
+            Platform::VisionOs => 11,
+            Platform::VisionOsSimulator => 12,
+        }
+    }
+}
+
+/// The architecture, platform, and minimum OS version of an object written by
+/// [`info_plist_object`].
+///
+/// The linker rejects objects built for a different platform than the rest of
+/// the executable, so these must match the target being linked.
+///
+/// [`info_plist_object`]: fn.info_plist_object.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ObjectTarget {
+    /// The CPU architecture.
+    pub arch: Arch,
+    /// The platform.
+    pub platform: Platform,
+    /// The minimum OS version as `(major, minor, patch)`.
+    pub minimum_os: (u16, u8, u8),
+}
+
+impl ObjectTarget {
+    /// Returns the target that Cargo is running the build script for.
+    ///
+    /// This reads `CARGO_CFG_TARGET_ARCH`, `CARGO_CFG_TARGET_OS`, and
+    /// `CARGO_CFG_TARGET_ABI`. The minimum OS version is read from the same
+    /// variable as `rustc`, such as `MACOSX_DEPLOYMENT_TARGET` or
+    /// `IPHONEOS_DEPLOYMENT_TARGET`, and falls back to `rustc`'s default. This
+    /// also tells Cargo to rerun the build script when that variable changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with [`io::ErrorKind::InvalidInput`] if the target is
+    /// not a 64-bit Apple target, or if the deployment target variable is not
+    /// a version.
+    ///
+    /// [`io::ErrorKind::InvalidInput`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+    pub fn from_env() -> io::Result<Self> {
+        let var = |name: &str| env::var(name).unwrap_or_default();
+        let arch_name = var("CARGO_CFG_TARGET_ARCH");
+        let os = var("CARGO_CFG_TARGET_OS");
+        let abi = var("CARGO_CFG_TARGET_ABI");
+
+        let arch = match arch_name.as_str() {
+            "x86_64" => Arch::X86_64,
+            "aarch64" => Arch::Arm64,
+            _ => return Err(unsupported(&arch_name, &os)),
+        };
+        // Intel targets for iOS, tvOS, and watchOS are always simulators.
+        let sim = abi == "sim" || arch == Arch::X86_64;
+        let (platform, var_name, default) = match os.as_str() {
+            "macos" => (
+                Platform::MacOs,
+                "MACOSX_DEPLOYMENT_TARGET",
+                if arch == Arch::X86_64 {
+                    (10, 12, 0)
+                } else {
+                    (11, 0, 0)
+                },
+            ),
+            "ios" if abi == "macabi" => (
+                Platform::MacCatalyst,
+                "IPHONEOS_DEPLOYMENT_TARGET",
+                (14, 0, 0),
+            ),
+            "ios" if sim => (
+                Platform::IOsSimulator,
+                "IPHONEOS_DEPLOYMENT_TARGET",
+                (14, 0, 0),
+            ),
+            "ios" => (Platform::IOs, "IPHONEOS_DEPLOYMENT_TARGET", (10, 0, 0)),
+            "tvos" if sim => (
+                Platform::TvOsSimulator,
+                "TVOS_DEPLOYMENT_TARGET",
+                (10, 0, 0),
+            ),
+            "tvos" => (Platform::TvOs, "TVOS_DEPLOYMENT_TARGET", (10, 0, 0)),
+            "watchos" if sim => (
+                Platform::WatchOsSimulator,
+                "WATCHOS_DEPLOYMENT_TARGET",
+                (5, 0, 0),
+            ),
+            "watchos" => {
+                (Platform::WatchOs, "WATCHOS_DEPLOYMENT_TARGET", (5, 0, 0))
+            }
+            "visionos" if sim => (
+                Platform::VisionOsSimulator,
+                "XROS_DEPLOYMENT_TARGET",
+                (1, 0, 0),
+            ),
+            "visionos" => {
+                (Platform::VisionOs, "XROS_DEPLOYMENT_TARGET", (1, 0, 0))
+            }
+            _ => return Err(unsupported(&arch_name, &os)),
+        };
+
+        std::println!("cargo:rerun-if-env-changed={}", var_name);
+        let minimum_os = match env::var(var_name) {
+            Ok(version) => parse_version(&version).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    std::format!("`{}` is not a version", var_name),
+                )
+            })?,
+            Err(_) => default,
+        };
+
+        Ok(ObjectTarget {
+            arch,
+            platform,
+            minimum_os,
+        })
+    }
+}
+
+fn unsupported(arch: &str, os: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        std::format!("`{}-{}` is not a 64-bit Apple target", arch, os),
+    )
+}
+
+fn parse_version(version: &str) -> Option<(u16, u8, u8)> {
+    let mut parts = version.trim().split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+    let patch = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+/// Writes a relocatable Mach-O object file to `output` that embeds the
+/// [`Info.plist`] file at `input`, for linking outside of Cargo.
+///
+/// When a Rust static library is linked into an executable by Xcode, CMake,
+/// or another build system, the static defined by [`embed_info_plist!`] can be
+/// dropped if nothing references it, or defined twice if several static
+/// libraries embed it. Instead, pass this object to the final link step. It
+/// defines the same symbols as [`embed_info_plist!`], so [`get_info_plist`],
+/// [`verify_info_plist`], and [`check_info_plist_integrity`] work from any
+/// Rust code in the executable.
+///
+/// The contents of `input` are embedded as is in the `__TEXT,__info_plist`
+/// section, which is marked `no_dead_strip`.
+///
+/// This also tells Cargo to rerun the build script when `input` changes.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// // build.rs
+/// use embed_plist::build::{self, ObjectTarget};
+///
+/// # fn main() -> std::io::Result<()> {
+/// let out_dir = std::env::var("OUT_DIR").unwrap();
+/// let object = std::path::Path::new(&out_dir).join("info_plist.o");
+///
+/// build::info_plist_object("Info.plist", &object, ObjectTarget::from_env()?)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if reading or writing fails.
+///
+/// [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
+/// [`embed_info_plist!`]: ../macro.embed_info_plist.html
+/// [`get_info_plist`]: ../fn.get_info_plist.html
+/// [`verify_info_plist`]: ../fn.verify_info_plist.html
+/// [`check_info_plist_integrity`]: ../fn.check_info_plist_integrity.html
+pub fn info_plist_object<P, Q>(
+    input: P,
+    output: Q,
+    target: ObjectTarget,
+) -> io::Result<()>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    write_object(input.as_ref(), output.as_ref(), "info_plist", target)
+}
+
+/// Writes a relocatable Mach-O object file to `output` that embeds the
+/// [`launchd.plist`] file at `input`, for linking outside of Cargo.
+///
+/// This works the same as [`info_plist_object`], except that it defines the
+/// symbols of [`embed_launchd_plist!`] in the `__TEXT,__launchd_plist`
+/// section.
+///
+/// [`launchd.plist`]: https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html#//apple_ref/doc/uid/TP40001762-104142
+/// [`info_plist_object`]: fn.info_plist_object.html
+/// [`embed_launchd_plist!`]: ../macro.embed_launchd_plist.html
+pub fn launchd_plist_object<P, Q>(
+    input: P,
+    output: Q,
+    target: ObjectTarget,
+) -> io::Result<()>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    write_object(input.as_ref(), output.as_ref(), "launchd_plist", target)
+}
+
+fn write_object(
+    input: &Path,
+    output: &Path,
+    kind: &str,
+    target: ObjectTarget,
+) -> io::Result<()> {
+    rerun_if_changed(input);
+    let plist = fs::read(input)?;
+    fs::write(output, object(&plist, kind, target))
+}
+
+// Lays out the header and load commands, then the `__TEXT,__<kind>` section,
+// a `__TEXT,__const` section with the length and SHA-256 digest, the symbol
+// table, and the string table.
+fn object(plist: &[u8], kind: &str, target: ObjectTarget) -> Vec<u8> {
+    let data_offset = HEADER_LEN + COMMANDS_LEN;
+    let const_addr = align8(plist.len());
+    let mut consts = (plist.len() as u64).to_le_bytes().to_vec();
+    consts.extend_from_slice(&const_eval::sha256(plist));
+    let data_len = const_addr + consts.len();
+
+    // Rust symbols are prefixed with `_` in Mach-O, like C symbols, and the
+    // defined symbols must be sorted by name for `LC_DYSYMTAB`.
+    let symbol = std::format!("__EMBED_{}", kind.to_uppercase());
+    let symbols: [(String, u8, usize); 3] = [
+        (symbol.clone(), 1, 0),
+        (symbol.clone() + "_LEN", 2, const_addr),
+        (symbol + "_SHA256", 2, const_addr + 8),
+    ];
+    let mut strings = vec![0];
+    let mut names = Vec::new();
+    for (name, _, _) in symbols.iter() {
+        names.push(strings.len() as u32);
+        strings.extend_from_slice(name.as_bytes());
+        strings.push(0);
+    }
+    strings.resize(align8(strings.len()), 0);
+
+    let symoff = align8(data_offset + data_len);
+    let stroff = symoff + 16 * symbols.len();
+
+    let mut out = Vec::with_capacity(stroff + strings.len());
+    let (cputype, cpusubtype) = target.arch.cpu();
+    for &field in [
+        MH_MAGIC_64,
+        cputype,
+        cpusubtype,
+        MH_OBJECT,
+        4,
+        COMMANDS_LEN as u32,
+        0,
+        0,
+    ]
+    .iter()
+    {
+        put_u32(&mut out, field);
+    }
+
+    put_u32(&mut out, LC_SEGMENT_64);
+    put_u32(&mut out, SEGMENT_LEN as u32);
+    put_name(&mut out, "");
+    put_u64(&mut out, 0);
+    put_u64(&mut out, data_len as u64);
+    put_u64(&mut out, data_offset as u64);
+    put_u64(&mut out, data_len as u64);
+    for &field in [7, 7, 2, 0].iter() {
+        put_u32(&mut out, field);
+    }
+    let sections = [
+        (
+            std::format!("__{}", kind),
+            0,
+            plist.len(),
+            0,
+            S_ATTR_NO_DEAD_STRIP,
+        ),
+        ("__const".to_string(), const_addr, consts.len(), 3, 0),
+    ];
+    for (name, addr, size, align, flags) in sections.iter() {
+        put_name(&mut out, name);
+        put_name(&mut out, "__TEXT");
+        put_u64(&mut out, *addr as u64);
+        put_u64(&mut out, *size as u64);
+        put_u32(&mut out, (data_offset + addr) as u32);
+        for &field in [*align, 0, 0, *flags, 0, 0, 0].iter() {
+            put_u32(&mut out, field);
+        }
+    }
+
+    let (major, minor, patch) = target.minimum_os;
+    let version =
+        u32::from(major) << 16 | u32::from(minor) << 8 | u32::from(patch);
+    for &field in [
+        LC_BUILD_VERSION,
+        24,
+        target.platform.raw(),
+        version,
+        version,
+        0,
+    ]
+    .iter()
+    {
+        put_u32(&mut out, field);
+    }
+
+    for &field in [
+        LC_SYMTAB,
+        24,
+        symoff as u32,
+        symbols.len() as u32,
+        stroff as u32,
+        strings.len() as u32,
+    ]
+    .iter()
+    {
+        put_u32(&mut out, field);
+    }
+
+    put_u32(&mut out, LC_DYSYMTAB);
+    put_u32(&mut out, 80);
+    // All symbols are external definitions.
+    for &field in
+        [0, 0, 0, symbols.len() as u32, symbols.len() as u32, 0].iter()
+    {
+        put_u32(&mut out, field);
+    }
+    out.resize(out.len() + 12 * 4, 0);
+
+    out.extend_from_slice(plist);
+    out.resize(data_offset + const_addr, 0);
+    out.extend_from_slice(&consts);
+    out.resize(symoff, 0);
+
+    for ((_, sect, value), &name) in symbols.iter().zip(names.iter()) {
+        put_u32(&mut out, name);
+        out.push(N_SECT_EXT);
+        out.push(*sect);
+        out.extend_from_slice(&0u16.to_le_bytes());
+        put_u64(&mut out, *value as u64);
+    }
+    out.extend_from_slice(&strings);
+    out
+}
+
+fn align8(len: usize) -> usize {
+    (len + 7) & !7
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+// Writes a NUL-padded 16-byte name like `sectname`.
+fn put_name(out: &mut Vec<u8>, name: &str) {
+    let mut field = [0; 16];
+    field[..name.len()].copy_from_slice(name.as_bytes());
+    out.extend_from_slice(&field);
+}
@@ -0,0 +1,319 @@
+//! Builders for [`Info.plist`] and [`launchd.plist`] documents.
+//!
+//! This module requires the `alloc` feature. The builders produce a
+//! [`Value`], so anything they lack can be set with their `set` methods or by
+//! editing the result.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use embed_plist::builder::{KeepAlive, LaunchdPlistBuilder};
+//!
+//! let plist = LaunchdPlistBuilder::new("com.example.daemon")
+//!     .program_arguments(&["/usr/local/bin/daemon", "--verbose"])
+//!     .keep_alive(KeepAlive::SuccessfulExit(false))
+//!     .mach_service("com.example.daemon.xpc")
+//!     .run_at_load(true)
+//!     .to_xml();
+//!
+//! let text = String::from_utf8(plist).unwrap();
+//! assert!(text.contains("<key>Label</key>\n\t<string>com.example.daemon</string>"));
+//! ```
+//!
+//! [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
+//! [`launchd.plist`]: https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html#//apple_ref/doc/uid/TP40001762-104142
+//! [`Value`]: ../value/enum.Value.html
+
+use crate::{
+    keys::{info, launchd},
+    value::{Dictionary, Value},
+};
+use alloc::{string::String, vec::Vec};
+
+// Defines setters that insert a converted value for a key in `keys`.
+macro_rules! setters {
+    ($($(#[$attr:meta])* $name:ident($ty:ty) => $module:ident::$key:ident;)+) => {
+        $(
+            $(#[$attr])*
+            #[doc = ""]
+            #[doc = concat!(
+                "This sets [`", stringify!($module), "::", stringify!($key),
+                "`](../keys/", stringify!($module), "/constant.",
+                stringify!($key), ".html).",
+            )]
+            #[inline]
+            pub fn $name(mut self, value: $ty) -> Self {
+                self.dict.insert($module::$key.name(), value);
+                self
+            }
+        )+
+    };
+}
+
+/// Builds an [`Info.plist`] document.
+///
+/// # Examples
+///
+/// ```rust
+/// use embed_plist::builder::InfoPlistBuilder;
+///
+/// let info = InfoPlistBuilder::new()
+///     .bundle_identifier("com.example.app")
+///     .bundle_short_version_string("1.0")
+///     .ui_element(true)
+///     .set("NSCameraUsageDescription", "To scan QR codes.")
+///     .build();
+///
+/// let dict = info.as_dictionary().unwrap();
+/// assert_eq!(dict.get("LSUIElement").and_then(|v| v.as_bool()), Some(true));
+/// ```
+///
+/// [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InfoPlistBuilder {
+    dict: Dictionary,
+}
+
+impl InfoPlistBuilder {
+    /// Creates a builder for an empty document.
+    #[inline]
+    pub fn new() -> Self {
+        InfoPlistBuilder::default()
+    }
+
+    setters! {
+        /// Sets the reverse-DNS identifier of the bundle.
+        bundle_identifier(&str) => info::CF_BUNDLE_IDENTIFIER;
+        /// Sets the short name of the bundle.
+        bundle_name(&str) => info::CF_BUNDLE_NAME;
+        /// Sets the name shown to users.
+        bundle_display_name(&str) => info::CF_BUNDLE_DISPLAY_NAME;
+        /// Sets the name of the executable.
+        bundle_executable(&str) => info::CF_BUNDLE_EXECUTABLE;
+        /// Sets the build version.
+        bundle_version(&str) => info::CF_BUNDLE_VERSION;
+        /// Sets the release version shown to users.
+        bundle_short_version_string(&str) => info::CF_BUNDLE_SHORT_VERSION_STRING;
+        /// Sets the four-letter type code, such as `APPL`.
+        bundle_package_type(&str) => info::CF_BUNDLE_PACKAGE_TYPE;
+        /// Sets the default language.
+        bundle_development_region(&str) => info::CF_BUNDLE_DEVELOPMENT_REGION;
+        /// Sets the minimum version of macOS.
+        minimum_system_version(&str) => info::LS_MINIMUM_SYSTEM_VERSION;
+        /// Sets whether the app runs without a Dock icon or menu bar.
+        ui_element(bool) => info::LS_UI_ELEMENT;
+        /// Sets whether the app runs only in the background.
+        background_only(bool) => info::LS_BACKGROUND_ONLY;
+        /// Sets whether the app supports Retina displays.
+        high_resolution_capable(bool) => info::NS_HIGH_RESOLUTION_CAPABLE;
+        /// Sets the copyright notice.
+        human_readable_copyright(&str) => info::NS_HUMAN_READABLE_COPYRIGHT;
+    }
+
+    /// Sets `key` to `value`, for keys without a dedicated setter.
+    #[inline]
+    pub fn set<K: Into<String>, V: Into<Value>>(
+        mut self,
+        key: K,
+        value: V,
+    ) -> Self {
+        self.dict.insert(key, value);
+        self
+    }
+
+    /// Returns the document.
+    #[inline]
+    pub fn build(self) -> Value {
+        Value::Dictionary(self.dict)
+    }
+
+    /// Returns the document as an XML property list.
+    #[inline]
+    pub fn to_xml(&self) -> Vec<u8> {
+        Value::Dictionary(self.dict.clone()).to_xml()
+    }
+
+    /// Returns the document as a binary property list.
+    #[inline]
+    pub fn to_binary(&self) -> Vec<u8> {
+        Value::Dictionary(self.dict.clone()).to_binary()
+    }
+}
+
+/// When `launchd` should keep a job running, set by
+/// [`LaunchdPlistBuilder::keep_alive`].
+///
+/// [`LaunchdPlistBuilder::keep_alive`]: struct.LaunchdPlistBuilder.html#method.keep_alive
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeepAlive {
+    /// Always or never restart the job.
+    Always(bool),
+    /// Restart the job if it exits with a zero status (`true`) or a non-zero
+    /// status (`false`).
+    SuccessfulExit(bool),
+    /// Restart the job if it crashed (`true`) or did not crash (`false`).
+    Crashed(bool),
+    /// Keep the job running while the path exists (`true`) or does not exist
+    /// (`false`).
+    PathState(String, bool),
+    /// Keep the job running while the other job is loaded (`true`) or not
+    /// loaded (`false`).
+    OtherJobEnabled(String, bool),
+}
+
+/// Builds a [`launchd.plist`] document.
+///
+/// [`launchd.plist`]: https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html#//apple_ref/doc/uid/TP40001762-104142
+#[derive(Clone, Debug, PartialEq)]
+pub struct LaunchdPlistBuilder {
+    dict: Dictionary,
+}
+
+impl LaunchdPlistBuilder {
+    /// Creates a builder for a job with the required `Label`.
+    #[inline]
+    pub fn new(label: &str) -> Self {
+        LaunchdPlistBuilder {
+            dict: Dictionary::new(),
+        }
+        .label(label)
+    }
+
+    setters! {
+        /// Sets the unique identifier of the job.
+        label(&str) => launchd::LABEL;
+        /// Sets the path of the executable.
+        program(&str) => launchd::PROGRAM;
+        /// Sets whether the job is started when it is loaded.
+        run_at_load(bool) => launchd::RUN_AT_LOAD;
+        /// Sets whether the job is disabled.
+        disabled(bool) => launchd::DISABLED;
+        /// Sets the number of seconds between starts of the job.
+        start_interval(u64) => launchd::START_INTERVAL;
+        /// Sets the minimum number of seconds between restarts of the job.
+        throttle_interval(u64) => launchd::THROTTLE_INTERVAL;
+        /// Sets the directory that the job runs in.
+        working_directory(&str) => launchd::WORKING_DIRECTORY;
+        /// Sets the path that standard output is written to.
+        standard_out_path(&str) => launchd::STANDARD_OUT_PATH;
+        /// Sets the path that standard error is written to.
+        standard_error_path(&str) => launchd::STANDARD_ERROR_PATH;
+        /// Sets the user to run the job as.
+        user_name(&str) => launchd::USER_NAME;
+        /// Sets the group to run the job as.
+        group_name(&str) => launchd::GROUP_NAME;
+        /// Sets the resource limits class, such as `Background`.
+        process_type(&str) => launchd::PROCESS_TYPE;
+    }
+
+    /// Sets the arguments of the job, starting with the executable.
+    ///
+    /// This sets `ProgramArguments`.
+    pub fn program_arguments<I>(mut self, arguments: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let arguments: Vec<Value> = arguments
+            .into_iter()
+            .map(|argument| argument.as_ref().into())
+            .collect();
+        self.dict
+            .insert(launchd::PROGRAM_ARGUMENTS.name(), arguments);
+        self
+    }
+
+    /// Sets when the job is kept running.
+    ///
+    /// Conditions other than `Always` are combined with those of previous
+    /// calls, as `launchd` allows.
+    ///
+    /// This sets `KeepAlive`.
+    pub fn keep_alive(mut self, keep_alive: KeepAlive) -> Self {
+        let (key, value) = match keep_alive {
+            KeepAlive::Always(always) => {
+                self.dict.insert(launchd::KEEP_ALIVE.name(), always);
+                return self;
+            }
+            KeepAlive::SuccessfulExit(value) => ("SuccessfulExit", value),
+            KeepAlive::Crashed(value) => ("Crashed", value),
+            KeepAlive::PathState(path, value) => {
+                self.keep_alive_dict("PathState").insert(path, value);
+                return self;
+            }
+            KeepAlive::OtherJobEnabled(label, value) => {
+                self.keep_alive_dict("OtherJobEnabled").insert(label, value);
+                return self;
+            }
+        };
+        self.keep_alive_conditions().insert(key, value);
+        self
+    }
+
+    // Returns the `KeepAlive` dictionary, replacing a boolean.
+    fn keep_alive_conditions(&mut self) -> &mut Dictionary {
+        sub_dict(&mut self.dict, launchd::KEEP_ALIVE.name())
+    }
+
+    fn keep_alive_dict(&mut self, key: &str) -> &mut Dictionary {
+        sub_dict(self.keep_alive_conditions(), key)
+    }
+
+    /// Registers a Mach service that the job provides.
+    ///
+    /// This adds to `MachServices`.
+    pub fn mach_service(mut self, name: &str) -> Self {
+        sub_dict(&mut self.dict, launchd::MACH_SERVICES.name())
+            .insert(name, true);
+        self
+    }
+
+    /// Sets an environment variable of the job.
+    ///
+    /// This adds to `EnvironmentVariables`.
+    pub fn environment_variable(mut self, name: &str, value: &str) -> Self {
+        sub_dict(&mut self.dict, launchd::ENVIRONMENT_VARIABLES.name())
+            .insert(name, value);
+        self
+    }
+
+    /// Sets `key` to `value`, for keys without a dedicated setter.
+    #[inline]
+    pub fn set<K: Into<String>, V: Into<Value>>(
+        mut self,
+        key: K,
+        value: V,
+    ) -> Self {
+        self.dict.insert(key, value);
+        self
+    }
+
+    /// Returns the document.
+    #[inline]
+    pub fn build(self) -> Value {
+        Value::Dictionary(self.dict)
+    }
+
+    /// Returns the document as an XML property list.
+    #[inline]
+    pub fn to_xml(&self) -> Vec<u8> {
+        Value::Dictionary(self.dict.clone()).to_xml()
+    }
+
+    /// Returns the document as a binary property list.
+    #[inline]
+    pub fn to_binary(&self) -> Vec<u8> {
+        Value::Dictionary(self.dict.clone()).to_binary()
+    }
+}
+
+// Returns the dictionary for `key`, creating it or replacing another value.
+fn sub_dict<'a>(dict: &'a mut Dictionary, key: &str) -> &'a mut Dictionary {
+    if dict.get(key).and_then(Value::as_dictionary).is_none() {
+        dict.insert(key, Dictionary::new());
+    }
+    dict.get_mut(key)
+        .and_then(Value::as_dictionary_mut)
+        .expect("dictionary was just inserted")
+}
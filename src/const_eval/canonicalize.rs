@@ -0,0 +1,369 @@
+use super::xml::{self, Error, Tag, Text};
+use super::{tri, Writer};
+
+/// The most keys that can be sorted at once, across a dictionary and the
+/// dictionaries that contain it.
+pub(crate) const MAX_KEYS: usize = 1024;
+
+/// Returns the length of `bytes` after [`canonicalize`].
+///
+/// # Panics
+///
+/// Panics if `bytes` is not a valid XML property list.
+pub const fn canonical_len(bytes: &[u8]) -> usize {
+    match canonicalize_into(bytes, &mut []) {
+        Ok(len) => len,
+        Err(error) => panic!("{}", error.message()),
+    }
+}
+
+/// Re-serializes the XML property list in `bytes` into its canonical form.
+///
+/// # Panics
+///
+/// Panics if `bytes` is not a valid XML property list.
+pub const fn canonicalize<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut out = [0; N];
+    match canonicalize_into(bytes, &mut out) {
+        Ok(_) => out,
+        Err(error) => panic!("{}", error.message()),
+    }
+}
+
+/// Writes as much of the canonical form of `bytes` as fits into `out` and
+/// returns its full length.
+///
+/// The canonical form:
+///
+/// - Has the XML declaration but no DOCTYPE, comments, or whitespace between
+///   elements.
+///
+/// - Sorts dictionary keys by their Unicode code points.
+///
+/// - Writes `<true/>`, `<false/>`, `<dict/>`, and `<array/>` for empty
+///   elements, and `<string></string>` for empty text.
+///
+/// - Escapes only `&`, `<`, and `>` within text, and removes whitespace from
+///   `<data>`, `<date>`, `<integer>`, and `<real>`.
+///
+/// Arrays and dictionaries nested more than 512 deep are rejected with
+/// `Error::TooDeep`. Dictionaries with more than 1024 keys, counting those of
+/// the dictionaries that contain them, are rejected with `Error::TooManyKeys`.
+pub const fn canonicalize_into(
+    bytes: &[u8],
+    out: &mut [u8],
+) -> Result<usize, Error> {
+    let mut out = Writer::new(out);
+    let mut keys = [0; MAX_KEYS];
+    out.push_all(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+    out.push_all(b"<plist version=\"1.0\">");
+
+    let pos = tri!(xml::skip_misc(bytes, 0));
+    let tag = tri!(xml::read_tag(bytes, pos));
+
+    let end = if tag.is(bytes, b"plist") {
+        if !tag.is_start() {
+            return Err(Error::ExpectedValue);
+        }
+        let pos = tri!(xml::skip_misc(bytes, tag.end));
+        let value = tri!(xml::read_tag(bytes, pos));
+        let pos = tri!(write_value(bytes, value, 0, &mut keys, &mut out));
+        let pos = tri!(xml::skip_misc(bytes, pos));
+        tri!(xml::expect_end(bytes, pos, b"plist"))
+    } else {
+        tri!(write_value(bytes, tag, 0, &mut keys, &mut out))
+    };
+
+    out.push_all(b"</plist>");
+
+    if tri!(xml::skip_misc(bytes, end)) != bytes.len() {
+        return Err(Error::TrailingData);
+    }
+    Ok(out.len())
+}
+
+// Writes the value opened by `tag`, within `depth` arrays and dictionaries, and
+// returns the position after it. Dictionaries sort their keys in `keys`.
+const fn write_value(
+    bytes: &[u8],
+    tag: Tag,
+    depth: usize,
+    keys: &mut [usize],
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    if tag.is_end() {
+        return Err(Error::ExpectedValue);
+    }
+
+    if tag.is(bytes, b"dict") {
+        write_dict(bytes, tag, depth, keys, out)
+    } else if tag.is(bytes, b"array") {
+        write_array(bytes, tag, depth, keys, out)
+    } else if tag.is(bytes, b"string") {
+        write_text(bytes, tag, b"string", false, out)
+    } else if tag.is(bytes, b"data") {
+        write_text(bytes, tag, b"data", true, out)
+    } else if tag.is(bytes, b"date") {
+        write_text(bytes, tag, b"date", true, out)
+    } else if tag.is(bytes, b"integer") {
+        write_text(bytes, tag, b"integer", true, out)
+    } else if tag.is(bytes, b"real") {
+        write_text(bytes, tag, b"real", true, out)
+    } else if tag.is(bytes, b"true") {
+        write_bool(bytes, tag, b"true", out)
+    } else if tag.is(bytes, b"false") {
+        write_bool(bytes, tag, b"false", out)
+    } else if tag.is(bytes, b"key") {
+        Err(Error::ExpectedValue)
+    } else {
+        Err(Error::UnexpectedElement)
+    }
+}
+
+const fn write_bool(
+    bytes: &[u8],
+    tag: Tag,
+    name: &[u8],
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    let end = if tag.is_empty() {
+        tag.end
+    } else {
+        let pos = tri!(xml::skip_misc(bytes, tag.end));
+        tri!(xml::expect_end(bytes, pos, name))
+    };
+    out.push(b'<');
+    out.push_all(name);
+    out.push_all(b"/>");
+    Ok(end)
+}
+
+const fn write_text(
+    bytes: &[u8],
+    tag: Tag,
+    name: &[u8],
+    strip_space: bool,
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    out.push(b'<');
+    out.push_all(name);
+    out.push(b'>');
+
+    let end = if tag.is_empty() {
+        tag.end
+    } else {
+        let mut text = Text::new(tag.end);
+        while let Some(ch) = tri!(text.next(bytes)) {
+            if !(strip_space && xml::is_space_char(ch)) {
+                write_escaped(ch, out);
+            }
+        }
+        tri!(xml::expect_end(bytes, text.pos(), name))
+    };
+
+    out.push_all(b"</");
+    out.push_all(name);
+    out.push(b'>');
+    Ok(end)
+}
+
+pub(crate) const fn write_escaped(ch: u32, out: &mut Writer) {
+    match ch {
+        0x26 => out.push_all(b"&amp;"),
+        0x3C => out.push_all(b"&lt;"),
+        0x3E => out.push_all(b"&gt;"),
+        // Only possible through `&#13;`, so it must stay a reference.
+        0x0D => out.push_all(b"&#13;"),
+        _ => out.push_char(ch),
+    }
+}
+
+const fn write_array(
+    bytes: &[u8],
+    tag: Tag,
+    depth: usize,
+    keys: &mut [usize],
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    if depth == xml::MAX_DEPTH {
+        return Err(Error::TooDeep);
+    }
+    if tag.is_empty() {
+        out.push_all(b"<array/>");
+        return Ok(tag.end);
+    }
+
+    let pos = tri!(xml::skip_misc(bytes, tag.end));
+    let mut next = tri!(xml::read_tag(bytes, pos));
+
+    if next.is_end() {
+        out.push_all(b"<array/>");
+        return xml::expect_end(bytes, pos, b"array");
+    }
+
+    out.push_all(b"<array>");
+    loop {
+        let pos = tri!(write_value(bytes, next, depth + 1, keys, out));
+        let pos = tri!(xml::skip_misc(bytes, pos));
+        next = tri!(xml::read_tag(bytes, pos));
+        if next.is_end() {
+            out.push_all(b"</array>");
+            return xml::expect_end(bytes, pos, b"array");
+        }
+    }
+}
+
+const fn write_dict(
+    bytes: &[u8],
+    tag: Tag,
+    depth: usize,
+    keys: &mut [usize],
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    if depth == xml::MAX_DEPTH {
+        return Err(Error::TooDeep);
+    }
+    let (len, end) = tri!(sort_keys(bytes, tag, keys));
+    if len == 0 {
+        out.push_all(b"<dict/>");
+        return Ok(end);
+    }
+
+    // Nested dictionaries sort their keys into the rest of `keys`.
+    let (sorted, rest) = keys.split_at_mut(len);
+    out.push_all(b"<dict>");
+    let mut i = 0;
+    while i < len {
+        let (key, value) = tri!(read_entry(bytes, sorted[i]));
+        out.push_all(b"<key>");
+        let mut text = key;
+        while let Some(ch) = tri!(text.next(bytes)) {
+            write_escaped(ch, out);
+        }
+        out.push_all(b"</key>");
+        tri!(write_value(bytes, value, depth + 1, rest, out));
+        i += 1;
+    }
+    out.push_all(b"</dict>");
+    Ok(end)
+}
+
+// Stores the position of each `<key>` of the dictionary opened by `tag` in
+// `keys`, in order of the keys, and returns their number and the position
+// after the dictionary.
+//
+// The dictionary is scanned once, and each key is placed by binary search, so
+// this stays well within the limits of `const` evaluation for large
+// dictionaries.
+pub(crate) const fn sort_keys(
+    bytes: &[u8],
+    tag: Tag,
+    keys: &mut [usize],
+) -> Result<(usize, usize), Error> {
+    if tag.is_empty() {
+        return Ok((0, tag.end));
+    }
+
+    let mut len = 0;
+    let mut pos = tag.end;
+    loop {
+        pos = tri!(xml::skip_misc(bytes, pos));
+        let key_pos = pos;
+        let key_tag = tri!(xml::read_tag(bytes, pos));
+        if key_tag.is_end() {
+            let end = tri!(xml::expect_end(bytes, pos, b"dict"));
+            return Ok((len, end));
+        }
+        if !key_tag.is(bytes, b"key") {
+            return Err(Error::ExpectedKey);
+        }
+
+        let (key, value_pos) = tri!(read_key(bytes, key_tag));
+        let value_pos = tri!(xml::skip_misc(bytes, value_pos));
+        let value = tri!(xml::read_tag(bytes, value_pos));
+        if value.is_end() {
+            return Err(Error::ExpectedValue);
+        }
+        pos = tri!(xml::skip_element(bytes, value));
+
+        let mut low = 0;
+        let mut high = len;
+        while low < high {
+            let mid = (low + high) / 2;
+            let other = tri!(key_at(bytes, keys[mid]));
+            let ordering = tri!(compare(bytes, key, other));
+            if ordering == 0 {
+                return Err(Error::DuplicateKey);
+            } else if ordering < 0 {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        if len == keys.len() {
+            return Err(Error::TooManyKeys);
+        }
+        let mut i = len;
+        while i > low {
+            keys[i] = keys[i - 1];
+            i -= 1;
+        }
+        keys[low] = key_pos;
+        len += 1;
+    }
+}
+
+// Returns the key and value of the entry whose `<key>` is at `pos`.
+pub(crate) const fn read_entry(
+    bytes: &[u8],
+    pos: usize,
+) -> Result<(Text, Tag), Error> {
+    let key_tag = tri!(xml::read_tag(bytes, pos));
+    let (key, value_pos) = tri!(read_key(bytes, key_tag));
+    let value_pos = tri!(xml::skip_misc(bytes, value_pos));
+    let value = tri!(xml::read_tag(bytes, value_pos));
+    Ok((key, value))
+}
+
+// Returns the text of the key whose `<key>` is at `pos`, without reading to
+// its end.
+const fn key_at(bytes: &[u8], pos: usize) -> Result<Text, Error> {
+    let tag = tri!(xml::read_tag(bytes, pos));
+    if tag.is_empty() {
+        Ok(Text::empty(tag.end))
+    } else {
+        Ok(Text::new(tag.end))
+    }
+}
+
+// Returns the text of the key opened by `tag` and the position after it.
+pub(crate) const fn read_key(
+    bytes: &[u8],
+    tag: Tag,
+) -> Result<(Text, usize), Error> {
+    if tag.is_empty() {
+        return Ok((Text::empty(tag.end), tag.end));
+    }
+    let key = Text::new(tag.end);
+    let mut text = key;
+    while tri!(text.next(bytes)).is_some() {}
+    let end = tri!(xml::expect_end(bytes, text.pos(), b"key"));
+    Ok((key, end))
+}
+
+// Compares the characters of `a` and `b`, returning the sign of the result.
+const fn compare(bytes: &[u8], mut a: Text, mut b: Text) -> Result<i32, Error> {
+    loop {
+        match (tri!(a.next(bytes)), tri!(b.next(bytes))) {
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return Ok(if x < y { -1 } else { 1 });
+                }
+            }
+            (Some(_), None) => return Ok(1),
+            (None, Some(_)) => return Ok(-1),
+            (None, None) => return Ok(0),
+        }
+    }
+}
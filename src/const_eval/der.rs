@@ -0,0 +1,263 @@
+// Encoding of entitlements in the DER profile that Apple uses for
+// `__TEXT,__ents_der` and the DER entitlements blob of code signatures.
+//
+// The document is `[APPLICATION 16] { INTEGER 1, dict }`, where:
+//
+// - A dictionary is `[CONTEXT 16]` holding a `SEQUENCE { UTF8String, value }`
+//   for each entry, sorted by key.
+// - An array is a `SEQUENCE` of its values.
+// - Strings are `UTF8String`, booleans are `BOOLEAN`, and integers are
+//   `INTEGER`.
+//
+// Data, dates, and reals have no encoding in this profile.
+
+use super::canonicalize::{read_entry, sort_keys, MAX_KEYS};
+use super::lookup::read_integer;
+use super::xml::{self, Error, Tag, Text};
+use super::{tri, Writer};
+
+const APPLICATION_16: u8 = 0x70;
+const CONTEXT_16: u8 = 0xB0;
+const SEQUENCE: u8 = 0x30;
+const UTF8_STRING: u8 = 0x0C;
+const BOOLEAN: u8 = 0x01;
+const INTEGER: u8 = 0x02;
+
+/// Returns the length of `bytes` after [`entitlements_der`].
+///
+/// # Panics
+///
+/// Panics if `bytes` is not a valid XML property list with a dictionary root,
+/// or if it has a value that the DER profile cannot encode.
+pub const fn entitlements_der_len(bytes: &[u8]) -> usize {
+    match entitlements_der_into(bytes, &mut []) {
+        Ok(len) => len,
+        Err(error) => panic!("{}", error.message()),
+    }
+}
+
+/// Encodes the XML entitlements in `bytes` as DER.
+///
+/// # Panics
+///
+/// Panics if `bytes` is not a valid XML property list with a dictionary root,
+/// or if it has a value that the DER profile cannot encode.
+pub const fn entitlements_der<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut out = [0; N];
+    match entitlements_der_into(bytes, &mut out) {
+        Ok(_) => out,
+        Err(error) => panic!("{}", error.message()),
+    }
+}
+
+/// Writes as much of the DER encoding of `bytes` as fits into `out` and
+/// returns its full length.
+///
+/// DER needs the length of each element before its contents, so every
+/// container is encoded twice: once to count its length and once to write it.
+/// This doubles the work per level of nesting, which is fine for the shallow
+/// documents that entitlements are.
+pub const fn entitlements_der_into(
+    bytes: &[u8],
+    out: &mut [u8],
+) -> Result<usize, Error> {
+    let pos = tri!(xml::skip_misc(bytes, 0));
+    let tag = tri!(xml::read_tag(bytes, pos));
+
+    let (root, has_plist) = if tag.is(bytes, b"plist") {
+        if !tag.is_start() {
+            return Err(Error::ExpectedValue);
+        }
+        let pos = tri!(xml::skip_misc(bytes, tag.end));
+        (tri!(xml::read_tag(bytes, pos)), true)
+    } else {
+        (tag, false)
+    };
+    if root.is_end() {
+        return Err(Error::ExpectedValue);
+    }
+    if !root.is(bytes, b"dict") {
+        return Err(Error::TypeMismatch);
+    }
+
+    let mut out = Writer::new(out);
+    let mut keys = [0; MAX_KEYS];
+    let mut counter = Writer::new(&mut []);
+    tri!(write_value(bytes, root, &mut keys, &mut counter));
+
+    out.push(APPLICATION_16);
+    write_len(3 + counter.len(), &mut out);
+    out.push_all(&[INTEGER, 1, 1]);
+    let mut end = tri!(write_value(bytes, root, &mut keys, &mut out));
+
+    if has_plist {
+        let pos = tri!(xml::skip_misc(bytes, end));
+        end = tri!(xml::expect_end(bytes, pos, b"plist"));
+    }
+    if tri!(xml::skip_misc(bytes, end)) != bytes.len() {
+        return Err(Error::TrailingData);
+    }
+    Ok(out.len())
+}
+
+// Writes a definite length in its shortest form.
+const fn write_len(len: usize, out: &mut Writer) {
+    if len < 0x80 {
+        out.push(len as u8);
+        return;
+    }
+    let be = (len as u64).to_be_bytes();
+    let mut start = 0;
+    while be[start] == 0 {
+        start += 1;
+    }
+    out.push(0x80 | (8 - start) as u8);
+    while start < 8 {
+        out.push(be[start]);
+        start += 1;
+    }
+}
+
+// Writes the value opened by `tag` and returns the position after it.
+// Dictionaries sort their keys in `keys`.
+const fn write_value(
+    bytes: &[u8],
+    tag: Tag,
+    keys: &mut [usize],
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    if tag.is_end() || tag.is(bytes, b"key") {
+        return Err(Error::ExpectedValue);
+    }
+
+    if tag.is(bytes, b"dict") || tag.is(bytes, b"array") {
+        let is_dict = tag.is(bytes, b"dict");
+        let mut counter = Writer::new(&mut []);
+        tri!(write_contents(bytes, tag, is_dict, keys, &mut counter));
+
+        out.push(if is_dict { CONTEXT_16 } else { SEQUENCE });
+        write_len(counter.len(), out);
+        write_contents(bytes, tag, is_dict, keys, out)
+    } else if tag.is(bytes, b"string") {
+        if tag.is_empty() {
+            out.push_all(&[UTF8_STRING, 0]);
+            return Ok(tag.end);
+        }
+        let end = tri!(write_string(bytes, Text::new(tag.end), out));
+        xml::expect_end(bytes, end, b"string")
+    } else if tag.is(bytes, b"true") || tag.is(bytes, b"false") {
+        let value = if tag.is(bytes, b"true") { 0xFF } else { 0 };
+        out.push_all(&[BOOLEAN, 1, value]);
+        if tag.is_empty() {
+            return Ok(tag.end);
+        }
+        let pos = tri!(xml::skip_misc(bytes, tag.end));
+        let name: &[u8] = if value != 0 { b"true" } else { b"false" };
+        xml::expect_end(bytes, pos, name)
+    } else if tag.is(bytes, b"integer") {
+        let value = tri!(read_integer(bytes, tag));
+        write_integer(value, out);
+        xml::skip_element(bytes, tag)
+    } else if tag.is(bytes, b"data")
+        || tag.is(bytes, b"date")
+        || tag.is(bytes, b"real")
+    {
+        Err(Error::UnsupportedValue)
+    } else {
+        Err(Error::UnexpectedElement)
+    }
+}
+
+// Writes `text` as a `UTF8String` and returns the position after it.
+const fn write_string(
+    bytes: &[u8],
+    text: Text,
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    let mut counter = Writer::new(&mut []);
+    let mut chars = text;
+    while let Some(ch) = tri!(chars.next(bytes)) {
+        counter.push_char(ch);
+    }
+
+    out.push(UTF8_STRING);
+    write_len(counter.len(), out);
+    let mut chars = text;
+    while let Some(ch) = tri!(chars.next(bytes)) {
+        out.push_char(ch);
+    }
+    Ok(chars.pos())
+}
+
+// Writes the fewest two's complement bytes that hold `value`.
+const fn write_integer(value: i128, out: &mut Writer) {
+    let be = value.to_be_bytes();
+    let mut start = 0;
+    while start < 15 {
+        let redundant = (be[start] == 0 && be[start + 1] & 0x80 == 0)
+            || (be[start] == 0xFF && be[start + 1] & 0x80 != 0);
+        if !redundant {
+            break;
+        }
+        start += 1;
+    }
+    out.push(INTEGER);
+    out.push((16 - start) as u8);
+    while start < 16 {
+        out.push(be[start]);
+        start += 1;
+    }
+}
+
+// Writes the children of a dictionary or array without its header, and returns
+// the position after its closing tag.
+const fn write_contents(
+    bytes: &[u8],
+    tag: Tag,
+    is_dict: bool,
+    keys: &mut [usize],
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    if tag.is_empty() {
+        return Ok(tag.end);
+    }
+    if is_dict {
+        return write_entries(bytes, tag, keys, out);
+    }
+
+    let mut pos = tag.end;
+    loop {
+        pos = tri!(xml::skip_misc(bytes, pos));
+        let next = tri!(xml::read_tag(bytes, pos));
+        if next.is_end() {
+            return xml::expect_end(bytes, pos, b"array");
+        }
+        pos = tri!(write_value(bytes, next, keys, out));
+    }
+}
+
+// Writes entries in order of their keys, the same way as `canonicalize`.
+const fn write_entries(
+    bytes: &[u8],
+    tag: Tag,
+    keys: &mut [usize],
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    let (len, end) = tri!(sort_keys(bytes, tag, keys));
+    let (sorted, rest) = keys.split_at_mut(len);
+    let mut i = 0;
+    while i < len {
+        let (key, value) = tri!(read_entry(bytes, sorted[i]));
+
+        let mut counter = Writer::new(&mut []);
+        tri!(write_string(bytes, key, &mut counter));
+        tri!(write_value(bytes, value, rest, &mut counter));
+
+        out.push(SEQUENCE);
+        write_len(counter.len(), out);
+        tri!(write_string(bytes, key, out));
+        tri!(write_value(bytes, value, rest, out));
+        i += 1;
+    }
+    Ok(end)
+}
@@ -0,0 +1,254 @@
+// Support for the code generated by `#[derive(InfoPlist)]`.
+//
+// Writers produce the same form as `canonicalize`, except that dictionary keys
+// are kept in field order. Readers borrow strings from the input, which is why
+// strings that need escaping are written as CDATA sections.
+
+use super::lookup::{find_key, read_integer, root_dict};
+use super::xml::{self, Error, Tag};
+use super::{starts_with_at, tri, Writer};
+
+/// A dictionary within a property list being read.
+#[derive(Clone, Copy)]
+pub struct Dict {
+    tag: Tag,
+}
+
+pub const fn write_header(out: &mut Writer) {
+    out.push_all(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+    out.push_all(b"<plist version=\"1.0\">");
+}
+
+pub const fn write_footer(out: &mut Writer) {
+    out.push_all(b"</plist>");
+}
+
+pub const fn write_dict_start(out: &mut Writer) {
+    out.push_all(b"<dict>");
+}
+
+pub const fn write_dict_end(out: &mut Writer) {
+    out.push_all(b"</dict>");
+}
+
+pub const fn write_key(out: &mut Writer, key: &str) {
+    out.push_all(b"<key>");
+    write_escaped(out, key.as_bytes());
+    out.push_all(b"</key>");
+}
+
+pub const fn write_string(out: &mut Writer, value: &str) {
+    let value = value.as_bytes();
+    // Neither can be written as text that is borrowed when read back, since
+    // carriage returns are read as line feeds even within CDATA.
+    if contains(value, b"]]>") || contains(value, b"\r") {
+        panic!("`InfoPlist` strings cannot contain `]]>` or carriage returns");
+    }
+    out.push_all(b"<string>");
+    if contains(value, b"&") || contains(value, b"<") {
+        out.push_all(b"<![CDATA[");
+        out.push_all(value);
+        out.push_all(b"]]>");
+    } else {
+        out.push_all(value);
+    }
+    out.push_all(b"</string>");
+}
+
+pub const fn write_bool(out: &mut Writer, value: bool) {
+    out.push_all(if value { b"<true/>" } else { b"<false/>" });
+}
+
+pub const fn write_integer(out: &mut Writer, value: i128) {
+    out.push_all(b"<integer>");
+    if value < 0 {
+        out.push(b'-');
+    }
+    let mut magnitude = value.unsigned_abs();
+    let mut digits = [0; 39];
+    let mut len = 0;
+    loop {
+        digits[len] = b'0' + (magnitude % 10) as u8;
+        len += 1;
+        magnitude /= 10;
+        if magnitude == 0 {
+            break;
+        }
+    }
+    while len > 0 {
+        len -= 1;
+        out.push(digits[len]);
+    }
+    out.push_all(b"</integer>");
+}
+
+const fn write_escaped(out: &mut Writer, text: &[u8]) {
+    let mut i = 0;
+    while i < text.len() {
+        match text[i] {
+            b'&' => out.push_all(b"&amp;"),
+            b'<' => out.push_all(b"&lt;"),
+            b'>' => out.push_all(b"&gt;"),
+            b'\r' => out.push_all(b"&#13;"),
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+}
+
+const fn contains(text: &[u8], pattern: &[u8]) -> bool {
+    let mut i = 0;
+    while i < text.len() {
+        if starts_with_at(text, i, pattern) {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+pub const fn read_root(bytes: &[u8]) -> Result<Dict, Error> {
+    Ok(Dict {
+        tag: tri!(root_dict(bytes)),
+    })
+}
+
+pub const fn read_dict(
+    bytes: &[u8],
+    dict: Dict,
+    key: &str,
+) -> Result<Option<Dict>, Error> {
+    match tri!(find_key(bytes, dict.tag, key.as_bytes())) {
+        Some(tag) if tag.is(bytes, b"dict") => Ok(Some(Dict { tag })),
+        Some(_) => Err(Error::TypeMismatch),
+        None => Ok(None),
+    }
+}
+
+pub const fn read_string<'a>(
+    bytes: &'a [u8],
+    dict: Dict,
+    key: &str,
+) -> Result<Option<&'a str>, Error> {
+    let tag = match tri!(find_key(bytes, dict.tag, key.as_bytes())) {
+        Some(tag) if tag.is(bytes, b"string") => tag,
+        Some(_) => return Err(Error::TypeMismatch),
+        None => return Ok(None),
+    };
+    match borrow_text(bytes, tag, b"string") {
+        Ok(text) => Ok(Some(text)),
+        Err(error) => Err(error),
+    }
+}
+
+// Borrows the text of the element opened by `tag`, which must be a single
+// CDATA section or have no references, comments, or carriage returns.
+pub(crate) const fn borrow_text<'a>(
+    bytes: &'a [u8],
+    tag: Tag,
+    name: &[u8],
+) -> Result<&'a str, Error> {
+    if tag.is_empty() {
+        return Ok("");
+    }
+
+    let (start, end) = if starts_with_at(bytes, tag.end, b"<![CDATA[") {
+        let start = tag.end + 9;
+        let mut end = start;
+        while !starts_with_at(bytes, end, b"]]>") {
+            if end >= bytes.len() {
+                return Err(Error::UnexpectedEnd);
+            }
+            end += 1;
+        }
+        tri!(expect_text_end(bytes, end + 3, name));
+        (start, end)
+    } else {
+        let mut end = tag.end;
+        while end < bytes.len() && bytes[end] != b'<' {
+            if matches!(bytes[end], b'&' | b'\r') {
+                return Err(Error::UnsupportedString);
+            }
+            end += 1;
+        }
+        tri!(expect_text_end(bytes, end, name));
+        (tag.end, end)
+    };
+
+    let (_, rest) = bytes.split_at(start);
+    let (text, _) = rest.split_at(end - start);
+    match core::str::from_utf8(text) {
+        Ok(text) => Ok(text),
+        Err(_) => Err(Error::InvalidUtf8),
+    }
+}
+
+// Requires the closing tag of `name` at `pos`, since anything else would have to
+// be decoded.
+const fn expect_text_end(
+    bytes: &[u8],
+    pos: usize,
+    name: &[u8],
+) -> Result<(), Error> {
+    if starts_with_at(bytes, pos, b"</") {
+        tri!(xml::expect_end(bytes, pos, name));
+        Ok(())
+    } else {
+        Err(Error::UnsupportedString)
+    }
+}
+
+pub const fn read_bool(
+    bytes: &[u8],
+    dict: Dict,
+    key: &str,
+) -> Result<Option<bool>, Error> {
+    match tri!(find_key(bytes, dict.tag, key.as_bytes())) {
+        Some(tag) if tag.is(bytes, b"true") => Ok(Some(true)),
+        Some(tag) if tag.is(bytes, b"false") => Ok(Some(false)),
+        Some(_) => Err(Error::TypeMismatch),
+        None => Ok(None),
+    }
+}
+
+pub const fn read_i64(
+    bytes: &[u8],
+    dict: Dict,
+    key: &str,
+) -> Result<Option<i64>, Error> {
+    match tri!(read_int(bytes, dict, key)) {
+        Some(value)
+            if value >= i64::MIN as i128 && value <= i64::MAX as i128 =>
+        {
+            Ok(Some(value as i64))
+        }
+        Some(_) => Err(Error::InvalidValue),
+        None => Ok(None),
+    }
+}
+
+pub const fn read_u64(
+    bytes: &[u8],
+    dict: Dict,
+    key: &str,
+) -> Result<Option<u64>, Error> {
+    match tri!(read_int(bytes, dict, key)) {
+        Some(value) if value >= 0 => Ok(Some(value as u64)),
+        Some(_) => Err(Error::InvalidValue),
+        None => Ok(None),
+    }
+}
+
+const fn read_int(
+    bytes: &[u8],
+    dict: Dict,
+    key: &str,
+) -> Result<Option<i128>, Error> {
+    match tri!(find_key(bytes, dict.tag, key.as_bytes())) {
+        Some(tag) if tag.is(bytes, b"integer") => {
+            Ok(Some(tri!(read_integer(bytes, tag))))
+        }
+        Some(_) => Err(Error::TypeMismatch),
+        None => Ok(None),
+    }
+}
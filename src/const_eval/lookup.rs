@@ -0,0 +1,302 @@
+use super::canonicalize::read_key;
+use super::xml::{self, Error, Tag, Text};
+use super::{tri, Writer};
+
+/// Returns the length of the string for `key` in the top-level dictionary.
+///
+/// # Panics
+///
+/// Panics if `key` is missing or its value is not a `<string>`.
+pub const fn string_len(bytes: &[u8], key: &str) -> usize {
+    string_into(bytes, key, &mut [])
+}
+
+/// Returns the UTF-8 bytes of the string for `key` in the top-level
+/// dictionary.
+///
+/// # Panics
+///
+/// Panics if `key` is missing or its value is not a `<string>`.
+pub const fn string_value<const N: usize>(bytes: &[u8], key: &str) -> [u8; N] {
+    let mut out = [0; N];
+    string_into(bytes, key, &mut out);
+    out
+}
+
+const fn string_into(bytes: &[u8], key: &str, out: &mut [u8]) -> usize {
+    let tag = expect_value(bytes, key, b"string", "value is not a `<string>`");
+    let mut out = Writer::new(out);
+    if !tag.is_empty() {
+        let mut text = Text::new(tag.end);
+        while let Some(ch) = unwrap(text.next(bytes)) {
+            out.push_char(ch);
+        }
+    }
+    out.len()
+}
+
+/// Returns the boolean for `key` in the top-level dictionary.
+///
+/// # Panics
+///
+/// Panics if `key` is missing or its value is not `<true/>` or `<false/>`.
+pub const fn bool_value(bytes: &[u8], key: &str) -> bool {
+    let tag = unwrap(find_value(bytes, key.as_bytes()));
+    match read_bool(bytes, tag) {
+        Ok(value) => value,
+        Err(Error::TypeMismatch) => {
+            panic!("value is not `<true/>` or `<false/>`")
+        }
+        Err(error) => panic!("{}", error.message()),
+    }
+}
+
+// Reads the boolean opened by `tag`, which may only be followed by its closing
+// tag.
+pub(crate) const fn read_bool(bytes: &[u8], tag: Tag) -> Result<bool, Error> {
+    let (value, name): (bool, &[u8]) = if tag.is(bytes, b"true") {
+        (true, b"true")
+    } else if tag.is(bytes, b"false") {
+        (false, b"false")
+    } else {
+        return Err(Error::TypeMismatch);
+    };
+    if !tag.is_empty() {
+        let pos = tri!(xml::skip_misc(bytes, tag.end));
+        if xml::expect_end(bytes, pos, name).is_err() {
+            return Err(Error::InvalidValue);
+        }
+    }
+    Ok(value)
+}
+
+/// Returns the integer for `key` in the top-level dictionary.
+///
+/// # Panics
+///
+/// Panics if `key` is missing, its value is not an `<integer>`, or it does not
+/// fit in an `i64`.
+pub const fn i64_value(bytes: &[u8], key: &str) -> i64 {
+    let value = integer_value(bytes, key);
+    if value < i64::MIN as i128 || value > i64::MAX as i128 {
+        panic!("integer does not fit in `i64`");
+    }
+    value as i64
+}
+
+/// Returns the integer for `key` in the top-level dictionary.
+///
+/// # Panics
+///
+/// Panics if `key` is missing, its value is not an `<integer>`, or it does not
+/// fit in a `u64`.
+pub const fn u64_value(bytes: &[u8], key: &str) -> u64 {
+    let value = integer_value(bytes, key);
+    if value < 0 || value > u64::MAX as i128 {
+        panic!("integer does not fit in `u64`");
+    }
+    value as u64
+}
+
+const fn integer_value(bytes: &[u8], key: &str) -> i128 {
+    let tag =
+        expect_value(bytes, key, b"integer", "value is not an `<integer>`");
+    unwrap(read_integer(bytes, tag))
+}
+
+// Parses decimal or `0x`-prefixed hexadecimal with an optional sign, which is
+// what Core Foundation accepts. Whitespace may surround the number but not
+// split it.
+pub(crate) const fn read_integer(
+    bytes: &[u8],
+    tag: Tag,
+) -> Result<i128, Error> {
+    if tag.is_empty() {
+        return Err(Error::InvalidValue);
+    }
+
+    let mut text = Text::new(tag.end);
+    let mut negative = false;
+    let mut radix = 10;
+    let mut digits = 0;
+    let mut value: i128 = 0;
+    let mut prev = 0;
+    let mut ended = false;
+
+    while let Some(ch) = tri!(text.next(bytes)) {
+        if xml::is_space_char(ch) {
+            ended = prev != 0;
+            continue;
+        }
+        if ended {
+            return Err(Error::InvalidValue);
+        }
+        let digit = match ch {
+            0x2B | 0x2D if digits == 0 && prev == 0 => {
+                negative = ch == 0x2D;
+                prev = ch;
+                continue;
+            }
+            0x78 | 0x58 if radix == 10 && digits == 1 && value == 0 => {
+                radix = 16;
+                digits = 0;
+                continue;
+            }
+            0x30..=0x39 => ch - 0x30,
+            0x61..=0x66 if radix == 16 => ch - 0x61 + 10,
+            0x41..=0x46 if radix == 16 => ch - 0x41 + 10,
+            _ => return Err(Error::InvalidValue),
+        };
+        value = value * radix + digit as i128;
+        if value > u64::MAX as i128 {
+            return Err(Error::InvalidValue);
+        }
+        digits += 1;
+        prev = ch;
+    }
+
+    if digits == 0 {
+        return Err(Error::InvalidValue);
+    }
+    tri!(xml::expect_end(bytes, text.pos(), b"integer"));
+    Ok(if negative { -value } else { value })
+}
+
+const fn expect_value(
+    bytes: &[u8],
+    key: &str,
+    name: &[u8],
+    message: &str,
+) -> Tag {
+    let tag = unwrap(find_value(bytes, key.as_bytes()));
+    if !tag.is(bytes, name) {
+        panic!("{}", message);
+    }
+    tag
+}
+
+// The longest panic message built by `fail`, beyond which it is truncated.
+const MAX_MESSAGE_LEN: usize = 192;
+
+// Panics with the concatenation of `parts`.
+pub(crate) const fn fail(parts: &[&[u8]]) -> ! {
+    let mut message = [0; MAX_MESSAGE_LEN];
+    let mut out = Writer::new(&mut message);
+    let mut i = 0;
+    while i < parts.len() {
+        out.push_all(parts[i]);
+        i += 1;
+    }
+    let len = if out.len() < MAX_MESSAGE_LEN {
+        out.len()
+    } else {
+        MAX_MESSAGE_LEN
+    };
+    let (message, _) = message.split_at(len);
+    let message = match core::str::from_utf8(message) {
+        Ok(message) => message,
+        // Truncation split a character.
+        Err(error) => {
+            let (message, _) = message.split_at(error.valid_up_to());
+            match core::str::from_utf8(message) {
+                Ok(message) => message,
+                Err(_) => "",
+            }
+        }
+    };
+    panic!("{}", message)
+}
+
+pub(crate) const fn unwrap<T: Copy>(result: Result<T, Error>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(error) => panic!("{}", error.message()),
+    }
+}
+
+// Returns the opening tag of the value for `key` in the top-level dictionary.
+const fn find_value(bytes: &[u8], key: &[u8]) -> Result<Tag, Error> {
+    let dict = match root_dict(bytes) {
+        Err(Error::TypeMismatch) => panic!("property list is not a dictionary"),
+        result => tri!(result),
+    };
+    match tri!(find_key(bytes, dict, key)) {
+        Some(value) => Ok(value),
+        None => fail(&[b"key `", key, b"` not found in property list"]),
+    }
+}
+
+// Returns the opening tag of the top-level dictionary.
+pub(crate) const fn root_dict(bytes: &[u8]) -> Result<Tag, Error> {
+    let pos = tri!(xml::skip_misc(bytes, 0));
+    let mut tag = tri!(xml::read_tag(bytes, pos));
+    if tag.is(bytes, b"plist") && tag.is_start() {
+        let pos = tri!(xml::skip_misc(bytes, tag.end));
+        tag = tri!(xml::read_tag(bytes, pos));
+    }
+    if tag.is_end() || !tag.is(bytes, b"dict") {
+        return Err(Error::TypeMismatch);
+    }
+    Ok(tag)
+}
+
+// Returns the opening tag of the value for `key` in the dictionary opened by
+// `dict`.
+pub(crate) const fn find_key(
+    bytes: &[u8],
+    dict: Tag,
+    key: &[u8],
+) -> Result<Option<Tag>, Error> {
+    if dict.is_empty() {
+        return Ok(None);
+    }
+
+    let mut pos = dict.end;
+    loop {
+        pos = tri!(xml::skip_misc(bytes, pos));
+        let key_tag = tri!(xml::read_tag(bytes, pos));
+        if key_tag.is_end() {
+            return Ok(None);
+        }
+        if !key_tag.is(bytes, b"key") {
+            return Err(Error::ExpectedKey);
+        }
+
+        let (text, value_pos) = tri!(read_key(bytes, key_tag));
+        let value_pos = tri!(xml::skip_misc(bytes, value_pos));
+        let value = tri!(xml::read_tag(bytes, value_pos));
+        if value.is_end() {
+            return Err(Error::ExpectedValue);
+        }
+        if tri!(text_eq(bytes, text, key)) {
+            return Ok(Some(value));
+        }
+        pos = tri!(xml::skip_element(bytes, value));
+    }
+}
+
+// Whether the characters of `text` encode to exactly `expected` in UTF-8.
+const fn text_eq(
+    bytes: &[u8],
+    mut text: Text,
+    expected: &[u8],
+) -> Result<bool, Error> {
+    let mut pos = 0;
+    while let Some(ch) = tri!(text.next(bytes)) {
+        let mut encoded = [0; 4];
+        let len = {
+            let mut out = Writer::new(&mut encoded);
+            out.push_char(ch);
+            out.len()
+        };
+        let mut i = 0;
+        while i < len {
+            if pos >= expected.len() || expected[pos] != encoded[i] {
+                return Ok(false);
+            }
+            pos += 1;
+            i += 1;
+        }
+    }
+    Ok(pos == expected.len())
+}
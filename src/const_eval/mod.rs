@@ -0,0 +1,121 @@
+// Compile-time preprocessing used by the options of the embedding macros and by
+// `const_value!`.
+//
+// Everything here is a `const fn` so that it can run both at compile time and
+// from build scripts.
+//
+// This is not part of this crate's public API, so I reserve the right to change
+// or remove this in a SemVer-compatible update.
+
+mod canonicalize;
+mod der;
+pub mod derive;
+pub(crate) mod lookup;
+mod normalize;
+mod openstep;
+mod sha256;
+mod target;
+pub(crate) mod xml;
+
+pub use self::canonicalize::{canonical_len, canonicalize, canonicalize_into};
+pub use self::der::{
+    entitlements_der, entitlements_der_into, entitlements_der_len,
+};
+pub use self::lookup::{
+    bool_value, i64_value, string_len, string_value, u64_value,
+};
+pub use self::normalize::{normalize, normalized_len};
+pub use self::openstep::{openstep_len, openstep_to_xml, openstep_to_xml_into};
+pub use self::sha256::sha256;
+pub use self::target::check_info_plist_target;
+pub use self::xml::Error;
+
+// Propagates the error of a `Result` like `?`, which is unavailable in
+// `const fn`.
+macro_rules! tri {
+    ($result:expr) => {
+        match $result {
+            Ok(value) => value,
+            Err(error) => return Err(error),
+        }
+    };
+}
+
+use tri;
+
+// Writes as many bytes as fit into `out`, while counting all of them.
+//
+// Writing into an empty buffer computes the output length, which is needed for
+// the array type before the output itself can be produced.
+pub struct Writer<'a> {
+    out: &'a mut [u8],
+    len: usize,
+}
+
+// `len` is a running count rather than the size of a collection.
+#[allow(clippy::len_without_is_empty)]
+impl<'a> Writer<'a> {
+    pub const fn new(out: &'a mut [u8]) -> Self {
+        Writer { out, len: 0 }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) const fn push(&mut self, byte: u8) {
+        if self.len < self.out.len() {
+            self.out[self.len] = byte;
+        }
+        self.len += 1;
+    }
+
+    pub(crate) const fn push_all(&mut self, bytes: &[u8]) {
+        let mut i = 0;
+        while i < bytes.len() {
+            self.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    // Encodes `ch` as UTF-8.
+    pub(crate) const fn push_char(&mut self, ch: u32) {
+        if ch < 0x80 {
+            self.push(ch as u8);
+        } else if ch < 0x800 {
+            self.push(0xC0 | (ch >> 6) as u8);
+            self.push(0x80 | (ch & 0x3F) as u8);
+        } else if ch < 0x10000 {
+            self.push(0xE0 | (ch >> 12) as u8);
+            self.push(0x80 | ((ch >> 6) & 0x3F) as u8);
+            self.push(0x80 | (ch & 0x3F) as u8);
+        } else {
+            self.push(0xF0 | (ch >> 18) as u8);
+            self.push(0x80 | ((ch >> 12) & 0x3F) as u8);
+            self.push(0x80 | ((ch >> 6) & 0x3F) as u8);
+            self.push(0x80 | (ch & 0x3F) as u8);
+        }
+    }
+}
+
+pub(crate) const fn starts_with(bytes: &[u8], prefix: &[u8]) -> bool {
+    starts_with_at(bytes, 0, prefix)
+}
+
+pub(crate) const fn starts_with_at(
+    bytes: &[u8],
+    pos: usize,
+    prefix: &[u8],
+) -> bool {
+    if bytes.len() < pos || bytes.len() - pos < prefix.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < prefix.len() {
+        if bytes[pos + i] != prefix[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
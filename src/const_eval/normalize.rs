@@ -0,0 +1,65 @@
+use super::{starts_with, Writer};
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Returns the length of `bytes` after [`normalize`].
+pub const fn normalized_len(bytes: &[u8]) -> usize {
+    normalize_into(bytes, &mut [])
+}
+
+/// Converts CRLF and CR line endings to LF and strips the UTF-8 byte order mark.
+///
+/// # Panics
+///
+/// Panics if `bytes` appear to be UTF-16, which would otherwise be corrupted
+/// by line ending conversion.
+pub const fn normalize<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut out = [0; N];
+    normalize_into(bytes, &mut out);
+    out
+}
+
+// Writes as much of the normalized `bytes` as fits into `out` and returns the
+// full normalized length.
+const fn normalize_into(bytes: &[u8], out: &mut [u8]) -> usize {
+    if is_utf16(bytes) {
+        panic!("UTF-16 property lists cannot be normalized; convert to UTF-8");
+    }
+
+    let mut out = Writer::new(out);
+    let mut i = if starts_with(bytes, UTF8_BOM) {
+        UTF8_BOM.len()
+    } else {
+        0
+    };
+
+    while i < bytes.len() {
+        let byte = match bytes[i] {
+            b'\r' => {
+                // Skip the CR of CRLF, or turn a lone CR into LF.
+                if i + 1 < bytes.len() && bytes[i + 1] == b'\n' {
+                    i += 1;
+                    continue;
+                }
+                b'\n'
+            }
+            byte => byte,
+        };
+        out.push(byte);
+        i += 1;
+    }
+
+    out.len()
+}
+
+// Detects UTF-16 by its byte order mark, or by a zero byte next to the leading
+// `<` of an XML document.
+const fn is_utf16(bytes: &[u8]) -> bool {
+    if bytes.len() < 2 {
+        return false;
+    }
+    matches!(
+        (bytes[0], bytes[1]),
+        (0xFE, 0xFF) | (0xFF, 0xFE) | (0, _) | (_, 0)
+    )
+}
@@ -0,0 +1,580 @@
+use super::canonicalize::write_escaped;
+use super::xml::{self, Error};
+use super::{tri, Writer};
+
+/// Returns the length of `bytes` after [`openstep_to_xml`].
+///
+/// # Panics
+///
+/// Panics if `bytes` is not a valid OpenStep property list.
+pub const fn openstep_len(bytes: &[u8]) -> usize {
+    match openstep_to_xml_into(bytes, &mut []) {
+        Ok(len) => len,
+        Err(error) => panic!("{}", error.message()),
+    }
+}
+
+/// Converts the OpenStep property list in `bytes` to XML.
+///
+/// # Panics
+///
+/// Panics if `bytes` is not a valid OpenStep property list.
+pub const fn openstep_to_xml<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut out = [0; N];
+    match openstep_to_xml_into(bytes, &mut out) {
+        Ok(_) => out,
+        Err(error) => panic!("{}", error.message()),
+    }
+}
+
+/// Writes as much of the XML form of the OpenStep property list in `bytes` as
+/// fits into `out` and returns its full length.
+///
+/// The XML is in the same form as `canonicalize_into`, except that dictionary
+/// keys keep their order. OpenStep has only strings, data, arrays, and
+/// dictionaries, so GNUstep's `<*I...>`, `<*R...>`, `<*BY>`, `<*BN>`,
+/// and `<*D...>` extensions are accepted for other types.
+///
+/// Arrays and dictionaries nested more than 512 deep are rejected with
+/// `Error::TooDeep`.
+pub const fn openstep_to_xml_into(
+    bytes: &[u8],
+    out: &mut [u8],
+) -> Result<usize, Error> {
+    let mut out = Writer::new(out);
+    out.push_all(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+    out.push_all(b"<plist version=\"1.0\">");
+
+    let pos = tri!(skip_space(bytes, 0));
+    let pos = tri!(write_value(bytes, pos, 0, &mut out));
+    if tri!(skip_space(bytes, pos)) != bytes.len() {
+        return Err(Error::TrailingData);
+    }
+
+    out.push_all(b"</plist>");
+    Ok(out.len())
+}
+
+// Skips whitespace and `//` and `/* */` comments.
+const fn skip_space(bytes: &[u8], mut pos: usize) -> Result<usize, Error> {
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b' ' | b'\t' | b'\n' | b'\r' | 0x0B | 0x0C => pos += 1,
+            b'/' if pos + 1 < bytes.len() && bytes[pos + 1] == b'/' => {
+                while pos < bytes.len() && bytes[pos] != b'\n' {
+                    pos += 1;
+                }
+            }
+            b'/' if pos + 1 < bytes.len() && bytes[pos + 1] == b'*' => {
+                pos += 2;
+                loop {
+                    if pos + 1 >= bytes.len() {
+                        return Err(Error::UnexpectedEnd);
+                    }
+                    if bytes[pos] == b'*' && bytes[pos + 1] == b'/' {
+                        break;
+                    }
+                    pos += 1;
+                }
+                pos += 2;
+            }
+            _ => break,
+        }
+    }
+    Ok(pos)
+}
+
+// Returns the position after the expected `byte`, skipping space before it.
+const fn expect(bytes: &[u8], pos: usize, byte: u8) -> Result<usize, Error> {
+    let pos = tri!(skip_space(bytes, pos));
+    if pos >= bytes.len() {
+        Err(Error::UnexpectedEnd)
+    } else if bytes[pos] != byte {
+        Err(Error::UnexpectedChar)
+    } else {
+        Ok(pos + 1)
+    }
+}
+
+// Writes the value at `pos`, within `depth` arrays and dictionaries, and
+// returns the position after it.
+const fn write_value(
+    bytes: &[u8],
+    pos: usize,
+    depth: usize,
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    if pos >= bytes.len() {
+        return Err(Error::UnexpectedEnd);
+    }
+    match bytes[pos] {
+        b'{' | b'(' if depth == xml::MAX_DEPTH => Err(Error::TooDeep),
+        b'{' => write_dict(bytes, pos + 1, depth, out),
+        b'(' => write_array(bytes, pos + 1, depth, out),
+        b'<' if pos + 1 < bytes.len() && bytes[pos + 1] == b'*' => {
+            write_typed(bytes, pos + 2, out)
+        }
+        b'<' => write_data(bytes, pos + 1, out),
+        _ => {
+            let string = tri!(Str::new(bytes, pos));
+            out.push_all(b"<string>");
+            let end = tri!(write_str(bytes, string, out));
+            out.push_all(b"</string>");
+            Ok(end)
+        }
+    }
+}
+
+const fn write_dict(
+    bytes: &[u8],
+    start: usize,
+    depth: usize,
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    let mut pos = start;
+    let mut is_empty = true;
+    loop {
+        pos = tri!(skip_space(bytes, pos));
+        if pos >= bytes.len() {
+            return Err(Error::UnexpectedEnd);
+        }
+        if bytes[pos] == b'}' {
+            out.push_all(if is_empty { b"<dict/>" } else { b"</dict>" });
+            return Ok(pos + 1);
+        }
+        if is_empty {
+            out.push_all(b"<dict>");
+            is_empty = false;
+        }
+
+        let key = match Str::new(bytes, pos) {
+            Ok(key) => key,
+            Err(Error::UnexpectedChar) => return Err(Error::ExpectedKey),
+            Err(error) => return Err(error),
+        };
+        tri!(check_unique(bytes, start, depth, key));
+        out.push_all(b"<key>");
+        pos = tri!(write_str(bytes, key, out));
+        out.push_all(b"</key>");
+
+        pos = tri!(expect(bytes, pos, b'='));
+        pos = tri!(skip_space(bytes, pos));
+        pos = tri!(write_value(bytes, pos, depth + 1, out));
+        pos = tri!(expect(bytes, pos, b';'));
+    }
+}
+
+// Returns an error if a key before `key` in the dictionary body at `start`
+// equals it. This is quadratic, but does not need any storage.
+const fn check_unique(
+    bytes: &[u8],
+    start: usize,
+    depth: usize,
+    key: Str,
+) -> Result<(), Error> {
+    let mut pos = tri!(skip_space(bytes, start));
+    while pos < key.pos {
+        let other = tri!(Str::new(bytes, pos));
+        if tri!(str_eq(bytes, other, key)) {
+            return Err(Error::DuplicateKey);
+        }
+        pos = tri!(skip_str(bytes, other));
+        pos = tri!(expect(bytes, pos, b'='));
+        pos = tri!(skip_space(bytes, pos));
+        // Writing into an empty buffer only finds the end of the value.
+        let mut end = Writer::new(&mut []);
+        pos = tri!(write_value(bytes, pos, depth + 1, &mut end));
+        pos = tri!(expect(bytes, pos, b';'));
+        pos = tri!(skip_space(bytes, pos));
+    }
+    Ok(())
+}
+
+const fn write_array(
+    bytes: &[u8],
+    mut pos: usize,
+    depth: usize,
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    pos = tri!(skip_space(bytes, pos));
+    if pos < bytes.len() && bytes[pos] == b')' {
+        out.push_all(b"<array/>");
+        return Ok(pos + 1);
+    }
+
+    out.push_all(b"<array>");
+    loop {
+        pos = tri!(write_value(bytes, pos, depth + 1, out));
+        pos = tri!(skip_space(bytes, pos));
+        if pos >= bytes.len() {
+            return Err(Error::UnexpectedEnd);
+        }
+        match bytes[pos] {
+            b')' => break,
+            b',' => {
+                pos = tri!(skip_space(bytes, pos + 1));
+                // A trailing comma is allowed.
+                if pos < bytes.len() && bytes[pos] == b')' {
+                    break;
+                }
+            }
+            _ => return Err(Error::UnexpectedChar),
+        }
+    }
+    out.push_all(b"</array>");
+    Ok(pos + 1)
+}
+
+// Writes `<hex digits>` as base64, ignoring whitespace between the digits.
+const fn write_data(
+    bytes: &[u8],
+    mut pos: usize,
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    let mut group = [0; 3];
+    let mut group_len = 0;
+    let mut high: Option<u8> = None;
+    let mut is_empty = true;
+
+    loop {
+        if pos >= bytes.len() {
+            return Err(Error::UnexpectedEnd);
+        }
+        let byte = bytes[pos];
+        pos += 1;
+        let digit = match byte {
+            b'0'..=b'9' => byte - b'0',
+            b'a'..=b'f' => byte - b'a' + 10,
+            b'A'..=b'F' => byte - b'A' + 10,
+            b' ' | b'\t' | b'\n' | b'\r' => continue,
+            b'>' if high.is_none() => break,
+            _ => return Err(Error::InvalidValue),
+        };
+        match high {
+            None => high = Some(digit),
+            Some(high_digit) => {
+                if is_empty {
+                    out.push_all(b"<data>");
+                    is_empty = false;
+                }
+                group[group_len] = high_digit << 4 | digit;
+                group_len += 1;
+                high = None;
+                if group_len == 3 {
+                    write_base64(group, 3, out);
+                    group_len = 0;
+                }
+            }
+        }
+    }
+
+    if is_empty {
+        out.push_all(b"<data></data>");
+    } else {
+        if group_len > 0 {
+            write_base64(group, group_len, out);
+        }
+        out.push_all(b"</data>");
+    }
+    Ok(pos)
+}
+
+// Writes the first `len` bytes of `group` as four base64 characters.
+const fn write_base64(group: [u8; 3], len: usize, out: &mut Writer) {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let bits =
+        (group[0] as u32) << 16 | (group[1] as u32) << 8 | group[2] as u32;
+    let mut i = 0;
+    while i < 4 {
+        if i <= len {
+            out.push(ALPHABET[((bits >> (18 - 6 * i)) & 0x3F) as usize]);
+        } else {
+            out.push(b'=');
+        }
+        i += 1;
+    }
+}
+
+// Writes GNUstep's `<*Ttext>`, where `T` is `I` for integers, `R` for reals,
+// `B` for booleans, and `D` for dates like `2020-08-30 00:00:00 +0000`.
+const fn write_typed(
+    bytes: &[u8],
+    pos: usize,
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    let mut end = pos;
+    while end < bytes.len() && bytes[end] != b'>' {
+        end += 1;
+    }
+    if end >= bytes.len() {
+        return Err(Error::UnexpectedEnd);
+    } else if end == pos {
+        return Err(Error::InvalidValue);
+    }
+    let (kind, start) = (bytes[pos], pos + 1);
+
+    match kind {
+        b'I' | b'R' => {
+            let mut i = start;
+            while i < end {
+                let valid = match bytes[i] {
+                    b'0'..=b'9' | b'+' | b'-' => true,
+                    b'.' | b'e' | b'E' => kind == b'R',
+                    _ => false,
+                };
+                if !valid {
+                    return Err(Error::InvalidValue);
+                }
+                i += 1;
+            }
+            if start == end {
+                return Err(Error::InvalidValue);
+            }
+            let name: &[u8] = if kind == b'I' { b"integer" } else { b"real" };
+            out.push(b'<');
+            out.push_all(name);
+            out.push(b'>');
+            let mut i = start;
+            while i < end {
+                out.push(bytes[i]);
+                i += 1;
+            }
+            out.push_all(b"</");
+            out.push_all(name);
+            out.push(b'>');
+        }
+        b'B' if end - start == 1 && bytes[start] == b'Y' => {
+            out.push_all(b"<true/>")
+        }
+        b'B' if end - start == 1 && bytes[start] == b'N' => {
+            out.push_all(b"<false/>")
+        }
+        b'D' => tri!(write_date(bytes, start, end, out)),
+        _ => return Err(Error::InvalidValue),
+    }
+    Ok(end + 1)
+}
+
+// Writes a date in UTC, which is the only time zone that XML can express.
+const fn write_date(
+    bytes: &[u8],
+    start: usize,
+    end: usize,
+    out: &mut Writer,
+) -> Result<(), Error> {
+    const FORMAT: &[u8] = b"0000-00-00 00:00:00 +0000";
+
+    if end - start != FORMAT.len()
+        || !super::starts_with_at(bytes, end - 5, b"+0000")
+    {
+        return Err(Error::InvalidValue);
+    }
+    let mut i = 0;
+    while i < FORMAT.len() {
+        let byte = bytes[start + i];
+        let valid = if FORMAT[i] == b'0' {
+            byte.is_ascii_digit()
+        } else {
+            byte == FORMAT[i]
+        };
+        if !valid {
+            return Err(Error::InvalidValue);
+        }
+        i += 1;
+    }
+
+    out.push_all(b"<date>");
+    let mut i = 0;
+    while i < 19 {
+        out.push(if i == 10 { b'T' } else { bytes[start + i] });
+        i += 1;
+    }
+    out.push_all(b"Z</date>");
+    Ok(())
+}
+
+// A quoted or unquoted string, decoded one character at a time.
+#[derive(Clone, Copy)]
+struct Str {
+    // The position of the first byte, including any quote.
+    pos: usize,
+    // The quote character, or 0 if unquoted.
+    quote: u8,
+}
+
+impl Str {
+    const fn new(bytes: &[u8], pos: usize) -> Result<Str, Error> {
+        if pos >= bytes.len() {
+            return Err(Error::UnexpectedEnd);
+        }
+        match bytes[pos] {
+            quote @ (b'"' | b'\'') => Ok(Str { pos, quote }),
+            byte if is_unquoted(byte) => Ok(Str { pos, quote: 0 }),
+            _ => Err(Error::UnexpectedChar),
+        }
+    }
+
+    const fn start(&self) -> usize {
+        if self.quote == 0 {
+            self.pos
+        } else {
+            self.pos + 1
+        }
+    }
+
+    // Returns the character at `pos` and the position after it, or `None` and
+    // the position after the string.
+    const fn next(
+        &self,
+        bytes: &[u8],
+        pos: usize,
+    ) -> Result<(Option<u32>, usize), Error> {
+        if self.quote == 0 {
+            return if pos < bytes.len() && is_unquoted(bytes[pos]) {
+                Ok((Some(bytes[pos] as u32), pos + 1))
+            } else {
+                Ok((None, pos))
+            };
+        }
+
+        if pos >= bytes.len() {
+            return Err(Error::UnexpectedEnd);
+        }
+        match bytes[pos] {
+            byte if byte == self.quote => Ok((None, pos + 1)),
+            b'\\' => next_escape(bytes, pos + 1),
+            _ => {
+                let (ch, len) = tri!(xml::decode_utf8(bytes, pos));
+                Ok((Some(ch), pos + len))
+            }
+        }
+    }
+}
+
+// The characters that can appear in a string without quotes.
+const fn is_unquoted(byte: u8) -> bool {
+    matches!(
+        byte,
+        b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_' | b'$' | b'+' | b'/' | b':' | b'.' | b'-'
+    )
+}
+
+// Decodes the escape sequence after a backslash at `pos`.
+const fn next_escape(
+    bytes: &[u8],
+    pos: usize,
+) -> Result<(Option<u32>, usize), Error> {
+    if pos >= bytes.len() {
+        return Err(Error::UnexpectedEnd);
+    }
+    let ch = match bytes[pos] {
+        b'a' => 0x07,
+        b'b' => 0x08,
+        b'f' => 0x0C,
+        b'n' => b'\n' as u32,
+        b'r' => b'\r' as u32,
+        b't' => b'\t' as u32,
+        b'v' => 0x0B,
+        b'U' => {
+            let high = tri!(read_hex4(bytes, pos + 1));
+            // Characters outside the BMP are escaped as UTF-16 surrogate pairs.
+            if high >= 0xD800
+                && high <= 0xDBFF
+                && super::starts_with_at(bytes, pos + 5, b"\\U")
+            {
+                let low = tri!(read_hex4(bytes, pos + 7));
+                if low >= 0xDC00 && low <= 0xDFFF {
+                    let ch = 0x10000 + ((high - 0xD800) << 10 | (low - 0xDC00));
+                    return Ok((Some(ch), pos + 11));
+                }
+            }
+            if !xml::is_char(high) {
+                return Err(Error::InvalidCharRef);
+            }
+            return Ok((Some(high), pos + 5));
+        }
+        b'0'..=b'7' => {
+            let mut ch = 0;
+            let mut i = pos;
+            while i < bytes.len()
+                && i < pos + 3
+                && matches!(bytes[i], b'0'..=b'7')
+            {
+                ch = ch << 3 | (bytes[i] - b'0') as u32;
+                i += 1;
+            }
+            if ch == 0 || ch > 0x7F {
+                return Err(Error::InvalidCharRef);
+            }
+            return Ok((Some(ch), i));
+        }
+        _ => {
+            let (ch, len) = tri!(xml::decode_utf8(bytes, pos));
+            return Ok((Some(ch), pos + len));
+        }
+    };
+    Ok((Some(ch), pos + 1))
+}
+
+// Reads the 4 hexadecimal digits of a `\U` escape.
+const fn read_hex4(bytes: &[u8], pos: usize) -> Result<u32, Error> {
+    if pos + 4 > bytes.len() {
+        return Err(Error::UnexpectedEnd);
+    }
+    let mut value = 0;
+    let mut i = pos;
+    while i < pos + 4 {
+        let digit = match bytes[i] {
+            byte @ b'0'..=b'9' => byte - b'0',
+            byte @ b'a'..=b'f' => byte - b'a' + 10,
+            byte @ b'A'..=b'F' => byte - b'A' + 10,
+            _ => return Err(Error::InvalidCharRef),
+        };
+        value = value << 4 | digit as u32;
+        i += 1;
+    }
+    Ok(value)
+}
+
+// Writes the escaped characters of `string` and returns the position after it.
+const fn write_str(
+    bytes: &[u8],
+    string: Str,
+    out: &mut Writer,
+) -> Result<usize, Error> {
+    let mut pos = string.start();
+    loop {
+        match tri!(string.next(bytes, pos)) {
+            (Some(ch), next) => {
+                // XML cannot represent other control characters at all.
+                if ch < 0x20 && !matches!(ch, 0x09 | 0x0A | 0x0D) {
+                    return Err(Error::InvalidCharRef);
+                }
+                write_escaped(ch, out);
+                pos = next;
+            }
+            (None, end) => return Ok(end),
+        }
+    }
+}
+
+const fn skip_str(bytes: &[u8], string: Str) -> Result<usize, Error> {
+    write_str(bytes, string, &mut Writer::new(&mut []))
+}
+
+const fn str_eq(bytes: &[u8], a: Str, b: Str) -> Result<bool, Error> {
+    let (mut a_pos, mut b_pos) = (a.start(), b.start());
+    loop {
+        let (a_ch, a_next) = tri!(a.next(bytes, a_pos));
+        let (b_ch, b_next) = tri!(b.next(bytes, b_pos));
+        match (a_ch, b_ch) {
+            (Some(x), Some(y)) if x == y => {}
+            (None, None) => return Ok(true),
+            _ => return Ok(false),
+        }
+        a_pos = a_next;
+        b_pos = b_next;
+    }
+}
@@ -0,0 +1,144 @@
+// SHA-256 (FIPS 180-4), usable at compile time for the embedded content hash.
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1,
+    0x923f82a4, 0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3,
+    0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786,
+    0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147,
+    0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13,
+    0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+    0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a,
+    0x5b9cca4f, 0x682e6ff3, 0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208,
+    0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const H: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c,
+    0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Returns the SHA-256 digest of `bytes`.
+pub const fn sha256(bytes: &[u8]) -> [u8; 32] {
+    let mut state = H;
+
+    // Whole blocks of the input.
+    let mut i = 0;
+    while i + 64 <= bytes.len() {
+        let mut block = [0; 64];
+        let mut j = 0;
+        while j < 64 {
+            block[j] = bytes[i + j];
+            j += 1;
+        }
+        state = compress(state, &block);
+        i += 64;
+    }
+
+    // The rest of the input, followed by a 1 bit, zeros, and the bit length.
+    // This takes a second block if the length does not fit after the rest.
+    let mut tail = [0; 128];
+    let rest = bytes.len() - i;
+    let mut j = 0;
+    while j < rest {
+        tail[j] = bytes[i + j];
+        j += 1;
+    }
+    tail[rest] = 0x80;
+
+    let tail_len = if rest < 56 { 64 } else { 128 };
+    let bit_len = (bytes.len() as u64).wrapping_mul(8).to_be_bytes();
+    let mut j = 0;
+    while j < 8 {
+        tail[tail_len - 8 + j] = bit_len[j];
+        j += 1;
+    }
+
+    let mut offset = 0;
+    while offset < tail_len {
+        let mut block = [0; 64];
+        let mut j = 0;
+        while j < 64 {
+            block[j] = tail[offset + j];
+            j += 1;
+        }
+        state = compress(state, &block);
+        offset += 64;
+    }
+
+    let mut digest = [0; 32];
+    let mut j = 0;
+    while j < 8 {
+        let word = state[j].to_be_bytes();
+        digest[4 * j] = word[0];
+        digest[4 * j + 1] = word[1];
+        digest[4 * j + 2] = word[2];
+        digest[4 * j + 3] = word[3];
+        j += 1;
+    }
+    digest
+}
+
+const fn compress(state: [u32; 8], block: &[u8; 64]) -> [u32; 8] {
+    let mut w = [0u32; 64];
+    let mut t = 0;
+    while t < 16 {
+        w[t] = u32::from_be_bytes([
+            block[4 * t],
+            block[4 * t + 1],
+            block[4 * t + 2],
+            block[4 * t + 3],
+        ]);
+        t += 1;
+    }
+    while t < 64 {
+        let s0 = w[t - 15].rotate_right(7)
+            ^ w[t - 15].rotate_right(18)
+            ^ (w[t - 15] >> 3);
+        let s1 = w[t - 2].rotate_right(17)
+            ^ w[t - 2].rotate_right(19)
+            ^ (w[t - 2] >> 10);
+        w[t] = w[t - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[t - 7])
+            .wrapping_add(s1);
+        t += 1;
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    let mut t = 0;
+    while t < 64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let temp1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K[t])
+            .wrapping_add(w[t]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let temp2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+        t += 1;
+    }
+
+    [
+        state[0].wrapping_add(a),
+        state[1].wrapping_add(b),
+        state[2].wrapping_add(c),
+        state[3].wrapping_add(d),
+        state[4].wrapping_add(e),
+        state[5].wrapping_add(f),
+        state[6].wrapping_add(g),
+        state[7].wrapping_add(h),
+    ]
+}
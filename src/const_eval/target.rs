@@ -0,0 +1,127 @@
+use super::canonicalize::read_key;
+use super::lookup::{fail, root_dict, unwrap};
+use super::{starts_with, xml, Writer};
+use crate::keys::{info, Key, Platforms};
+
+// The longest key that is looked up, which is longer than any in `keys::info`.
+const MAX_KEY_LEN: usize = 64;
+
+/// Checks that the `Info.plist` in `bytes` has no key from `keys::info` that
+/// `target`, one of the targets of `check_info_plist_targets!`, does not read.
+///
+/// # Panics
+///
+/// Panics if the property list is invalid, if `target` is unknown, or if it has
+/// a key from `keys::info` that no platform of `target` reads.
+pub const fn check_info_plist_target(bytes: &[u8], target: &str) {
+    let platforms = match platforms(target.as_bytes()) {
+        Some(platforms) => platforms,
+        None => fail(&[
+            b"unsupported target `",
+            target.as_bytes(),
+            b"`, expected one of: `macos`, `ios`, `tvos`, `watchos`, `catalyst`",
+        ]),
+    };
+
+    let dict = match root_dict(bytes) {
+        Err(xml::Error::TypeMismatch) => {
+            panic!("property list is not a dictionary")
+        }
+        result => unwrap(result),
+    };
+    let mut pos = dict.end;
+    while !dict.is_empty() {
+        pos = unwrap(xml::skip_misc(bytes, pos));
+        let key_tag = unwrap(xml::read_tag(bytes, pos));
+        if key_tag.is_end() {
+            break;
+        }
+        if !key_tag.is(bytes, b"key") {
+            panic!("{}", xml::Error::ExpectedKey.message());
+        }
+
+        let (mut text, value_pos) = unwrap(read_key(bytes, key_tag));
+        let mut name = [0; MAX_KEY_LEN];
+        let mut out = Writer::new(&mut name);
+        while let Some(ch) = unwrap(text.next(bytes)) {
+            out.push_char(ch);
+        }
+        let len = out.len();
+        if len <= MAX_KEY_LEN {
+            let (name, _) = name.split_at(len);
+            if let Some(key) = find(name) {
+                if !key.platforms().intersects(platforms) {
+                    fail(&[
+                        b"`Info.plist` for `",
+                        target.as_bytes(),
+                        b"` has `",
+                        name,
+                        b"`, which is not read there",
+                    ]);
+                }
+            }
+        }
+
+        let value_pos = unwrap(xml::skip_misc(bytes, value_pos));
+        let value = unwrap(xml::read_tag(bytes, value_pos));
+        if value.is_end() {
+            panic!("{}", xml::Error::ExpectedValue.message());
+        }
+        pos = unwrap(xml::skip_element(bytes, value));
+    }
+}
+
+// Returns the platforms that read the `Info.plist` of `target`.
+const fn platforms(target: &[u8]) -> Option<Platforms> {
+    Some(if is(target, b"macos") {
+        Platforms::MACOS
+    } else if is(target, b"ios") {
+        Platforms::IOS
+    } else if is(target, b"tvos") {
+        Platforms::TVOS
+    } else if is(target, b"watchos") {
+        Platforms::WATCHOS
+    } else if is(target, b"catalyst") {
+        // Mac Catalyst apps run on macOS but also read iOS keys.
+        Platforms::MACOS.union(Platforms::IOS)
+    } else {
+        return None;
+    })
+}
+
+const fn is(target: &[u8], name: &[u8]) -> bool {
+    target.len() == name.len() && starts_with(target, name)
+}
+
+// Returns the key in `keys::info` named `name`.
+const fn find(name: &[u8]) -> Option<&'static Key> {
+    let mut low = 0;
+    let mut high = info::ALL.len();
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let key = &info::ALL[mid];
+        match compare(key.name().as_bytes(), name) {
+            0 => return Some(key),
+            ordering if ordering < 0 => low = mid + 1,
+            _ => high = mid,
+        }
+    }
+    None
+}
+
+const fn compare(a: &[u8], b: &[u8]) -> i8 {
+    let mut i = 0;
+    while i < a.len() && i < b.len() {
+        if a[i] != b[i] {
+            return if a[i] < b[i] { -1 } else { 1 };
+        }
+        i += 1;
+    }
+    if a.len() < b.len() {
+        -1
+    } else if a.len() > b.len() {
+        1
+    } else {
+        0
+    }
+}
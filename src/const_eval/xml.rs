@@ -0,0 +1,564 @@
+// A minimal XML scanner that works in `const fn`.
+//
+// This only understands as much XML as property lists use. Entity declarations
+// within a DTD are skipped rather than expanded.
+
+use super::{starts_with_at, tri};
+
+/// The most arrays and dictionaries that can be nested, which matches binary
+/// property lists.
+pub(crate) const MAX_DEPTH: usize = 512;
+
+/// An error encountered while reading an XML property list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The input ended before the document was complete.
+    UnexpectedEnd,
+    /// The input is not valid UTF-8.
+    InvalidUtf8,
+    /// Markup such as a tag or comment is malformed.
+    InvalidSyntax,
+    /// An element that is not part of the property list format was found.
+    UnexpectedElement,
+    /// A closing tag does not match its opening tag.
+    MismatchedTag,
+    /// A value was expected but a closing tag or `<key>` was found.
+    ExpectedValue,
+    /// A dictionary entry does not begin with `<key>`.
+    ExpectedKey,
+    /// A dictionary contains the same key more than once.
+    DuplicateKey,
+    /// An entity reference other than the five predefined by XML was found.
+    UnknownEntity,
+    /// A character reference does not refer to a valid character.
+    InvalidCharRef,
+    /// There is content after the end of the document.
+    TrailingData,
+    /// An `<integer>`, `<real>`, or `<data>` value is malformed.
+    InvalidValue,
+    /// A dictionary is missing a required key.
+    MissingKey,
+    /// A value has a different type than expected.
+    TypeMismatch,
+    /// A string contains references or comments, so it cannot be borrowed
+    /// from the property list.
+    UnsupportedString,
+    /// A character is not valid at its position in an OpenStep property
+    /// list.
+    UnexpectedChar,
+    /// An array index is past the end of the array.
+    IndexOutOfRange,
+    /// A query path is malformed.
+    InvalidPath,
+    /// Arrays and dictionaries are nested too deeply.
+    TooDeep,
+    /// A `<data>`, `<date>`, or `<real>` value cannot be encoded as DER
+    /// entitlements.
+    UnsupportedValue,
+    /// A buffer is too small for a decoded string.
+    BufferTooSmall,
+    /// Dictionaries have too many keys to sort at compile time.
+    TooManyKeys,
+}
+
+impl Error {
+    /// Returns a description of this error.
+    pub const fn message(self) -> &'static str {
+        match self {
+            Error::UnexpectedEnd => "unexpected end of property list",
+            Error::InvalidUtf8 => "property list is not valid UTF-8",
+            Error::InvalidSyntax => "malformed XML markup in property list",
+            Error::UnexpectedElement => "unexpected element in property list",
+            Error::MismatchedTag => "mismatched closing tag in property list",
+            Error::ExpectedValue => "expected a value in property list",
+            Error::ExpectedKey => {
+                "expected `<key>` in property list dictionary"
+            }
+            Error::DuplicateKey => "duplicate key in property list dictionary",
+            Error::UnknownEntity => "unknown entity reference in property list",
+            Error::InvalidCharRef => {
+                "invalid character reference in property list"
+            }
+            Error::TrailingData => "unexpected data after property list",
+            Error::InvalidValue => "malformed value in property list",
+            Error::MissingKey => "missing key in property list dictionary",
+            Error::TypeMismatch => "unexpected value type in property list",
+            Error::UnsupportedString => {
+                "string in property list cannot be borrowed"
+            }
+            Error::UnexpectedChar => "unexpected character in property list",
+            Error::IndexOutOfRange => {
+                "array index out of range in property list"
+            }
+            Error::InvalidPath => "malformed property list query path",
+            Error::TooDeep => "property list is nested too deeply",
+            Error::UnsupportedValue => {
+                "entitlements cannot contain data, dates, or reals"
+            }
+            Error::BufferTooSmall => {
+                "buffer is too small for string in property list"
+            }
+            Error::TooManyKeys => {
+                "property list dictionaries have too many keys to sort"
+            }
+        }
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[derive(Clone, Copy)]
+pub(crate) enum TagKind {
+    /// `<name>`
+    Start,
+    /// `</name>`
+    End,
+    /// `<name/>`
+    Empty,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Tag {
+    pub kind: TagKind,
+    name_start: usize,
+    name_end: usize,
+    /// The position just after `>`.
+    pub end: usize,
+}
+
+impl Tag {
+    pub(crate) const fn is(&self, bytes: &[u8], name: &[u8]) -> bool {
+        self.name_end - self.name_start == name.len()
+            && starts_with_at(bytes, self.name_start, name)
+    }
+
+    pub(crate) const fn is_start(&self) -> bool {
+        matches!(self.kind, TagKind::Start)
+    }
+
+    pub(crate) const fn is_end(&self) -> bool {
+        matches!(self.kind, TagKind::End)
+    }
+
+    pub(crate) const fn is_empty(&self) -> bool {
+        matches!(self.kind, TagKind::Empty)
+    }
+}
+
+const fn is_space(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+pub(crate) const fn is_space_char(ch: u32) -> bool {
+    ch < 0x80 && is_space(ch as u8)
+}
+
+// Returns the position just after the next occurrence of `pattern`.
+const fn skip_past(
+    bytes: &[u8],
+    mut pos: usize,
+    pattern: &[u8],
+) -> Result<usize, Error> {
+    while pos < bytes.len() {
+        if starts_with_at(bytes, pos, pattern) {
+            return Ok(pos + pattern.len());
+        }
+        pos += 1;
+    }
+    Err(Error::UnexpectedEnd)
+}
+
+/// Skips whitespace, comments, processing instructions, the DOCTYPE, and a
+/// leading byte order mark.
+pub(crate) const fn skip_misc(
+    bytes: &[u8],
+    mut pos: usize,
+) -> Result<usize, Error> {
+    if pos == 0 && starts_with_at(bytes, 0, b"\xEF\xBB\xBF") {
+        pos = 3;
+    }
+    while pos < bytes.len() {
+        if is_space(bytes[pos]) {
+            pos += 1;
+        } else if starts_with_at(bytes, pos, b"<!--") {
+            pos = tri!(skip_past(bytes, pos + 4, b"-->"));
+        } else if starts_with_at(bytes, pos, b"<?") {
+            pos = tri!(skip_past(bytes, pos + 2, b"?>"));
+        } else if starts_with_at(bytes, pos, b"<!DOCTYPE") {
+            pos = tri!(skip_doctype(bytes, pos + 9));
+        } else {
+            break;
+        }
+    }
+    Ok(pos)
+}
+
+// Skips the rest of `<!DOCTYPE ...>`, including any internal subset. Entities
+// declared within it are intentionally never expanded.
+const fn skip_doctype(bytes: &[u8], mut pos: usize) -> Result<usize, Error> {
+    let mut quote = 0;
+    let mut in_subset = false;
+    while pos < bytes.len() {
+        let byte = bytes[pos];
+        pos += 1;
+        if quote != 0 {
+            if byte == quote {
+                quote = 0;
+            }
+        } else if byte == b'"' || byte == b'\'' {
+            quote = byte;
+        } else if byte == b'[' {
+            in_subset = true;
+        } else if byte == b']' {
+            in_subset = false;
+        } else if byte == b'>' && !in_subset {
+            return Ok(pos);
+        }
+    }
+    Err(Error::UnexpectedEnd)
+}
+
+/// Reads the tag starting at `pos`, skipping its attributes.
+pub(crate) const fn read_tag(
+    bytes: &[u8],
+    mut pos: usize,
+) -> Result<Tag, Error> {
+    if pos >= bytes.len() {
+        return Err(Error::UnexpectedEnd);
+    }
+    if bytes[pos] != b'<' {
+        return Err(Error::InvalidSyntax);
+    }
+    pos += 1;
+
+    let is_end = pos < bytes.len() && bytes[pos] == b'/';
+    if is_end {
+        pos += 1;
+    }
+
+    let name_start = pos;
+    while pos < bytes.len()
+        && !is_space(bytes[pos])
+        && bytes[pos] != b'/'
+        && bytes[pos] != b'>'
+    {
+        pos += 1;
+    }
+    let name_end = pos;
+    if name_start == name_end {
+        return Err(Error::InvalidSyntax);
+    }
+
+    let mut quote = 0;
+    let mut last = 0;
+    while pos < bytes.len() {
+        let byte = bytes[pos];
+        pos += 1;
+        if quote != 0 {
+            if byte == quote {
+                quote = 0;
+            }
+        } else if byte == b'"' || byte == b'\'' {
+            if is_end {
+                return Err(Error::InvalidSyntax);
+            }
+            quote = byte;
+        } else if byte == b'>' {
+            let kind = if is_end {
+                TagKind::End
+            } else if last == b'/' {
+                TagKind::Empty
+            } else {
+                TagKind::Start
+            };
+            return Ok(Tag {
+                kind,
+                name_start,
+                name_end,
+                end: pos,
+            });
+        } else if byte == b'<' {
+            return Err(Error::InvalidSyntax);
+        }
+        if !is_space(byte) {
+            last = byte;
+        }
+    }
+    Err(Error::UnexpectedEnd)
+}
+
+/// Reads the closing tag for `name` at `pos`, returning the position after it.
+pub(crate) const fn expect_end(
+    bytes: &[u8],
+    pos: usize,
+    name: &[u8],
+) -> Result<usize, Error> {
+    let tag = tri!(read_tag(bytes, pos));
+    if tag.is_end() && tag.is(bytes, name) {
+        Ok(tag.end)
+    } else {
+        Err(Error::MismatchedTag)
+    }
+}
+
+/// Returns the position after the element opened by `tag`, including all of
+/// its children.
+pub(crate) const fn skip_element(
+    bytes: &[u8],
+    tag: Tag,
+) -> Result<usize, Error> {
+    if !tag.is_start() {
+        return Ok(tag.end);
+    }
+    let mut depth = 1;
+    let mut pos = tag.end;
+    while pos < bytes.len() {
+        if bytes[pos] != b'<' {
+            pos += 1;
+        } else if starts_with_at(bytes, pos, b"<!--") {
+            pos = tri!(skip_past(bytes, pos + 4, b"-->"));
+        } else if starts_with_at(bytes, pos, b"<![CDATA[") {
+            pos = tri!(skip_past(bytes, pos + 9, b"]]>"));
+        } else if starts_with_at(bytes, pos, b"<?") {
+            pos = tri!(skip_past(bytes, pos + 2, b"?>"));
+        } else {
+            let tag = tri!(read_tag(bytes, pos));
+            match tag.kind {
+                TagKind::Start => depth += 1,
+                TagKind::End => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(tag.end);
+                    }
+                }
+                TagKind::Empty => {}
+            }
+            pos = tag.end;
+        }
+    }
+    Err(Error::UnexpectedEnd)
+}
+
+/// Decodes the character data of an element, one character at a time.
+///
+/// Entity and character references, CDATA sections, comments, and line endings
+/// are handled according to XML.
+#[derive(Clone, Copy)]
+pub(crate) struct Text {
+    pos: usize,
+    in_cdata: bool,
+    done: bool,
+}
+
+impl Text {
+    /// Text starting at `pos`, just after a start tag.
+    pub(crate) const fn new(pos: usize) -> Self {
+        Text {
+            pos,
+            in_cdata: false,
+            done: false,
+        }
+    }
+
+    /// The text of an empty element, like `<string/>`.
+    pub(crate) const fn empty(pos: usize) -> Self {
+        Text {
+            pos,
+            in_cdata: false,
+            done: true,
+        }
+    }
+
+    /// The current position, which is at the closing tag once `next` returns
+    /// `None` for non-empty text.
+    pub(crate) const fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the next character, or `None` at the closing tag.
+    pub(crate) const fn next(
+        &mut self,
+        bytes: &[u8],
+    ) -> Result<Option<u32>, Error> {
+        loop {
+            if self.done {
+                return Ok(None);
+            }
+            let pos = self.pos;
+            if pos >= bytes.len() {
+                return Err(Error::UnexpectedEnd);
+            }
+
+            if self.in_cdata {
+                if starts_with_at(bytes, pos, b"]]>") {
+                    self.pos += 3;
+                    self.in_cdata = false;
+                    continue;
+                }
+                return self.next_char(bytes);
+            }
+
+            match bytes[pos] {
+                b'<' => {
+                    if starts_with_at(bytes, pos, b"<![CDATA[") {
+                        self.pos += 9;
+                        self.in_cdata = true;
+                    } else if starts_with_at(bytes, pos, b"<!--") {
+                        self.pos = tri!(skip_past(bytes, pos + 4, b"-->"));
+                    } else if starts_with_at(bytes, pos, b"<?") {
+                        self.pos = tri!(skip_past(bytes, pos + 2, b"?>"));
+                    } else if starts_with_at(bytes, pos, b"</") {
+                        self.done = true;
+                        return Ok(None);
+                    } else {
+                        return Err(Error::UnexpectedElement);
+                    }
+                }
+                b'&' => return self.next_reference(bytes),
+                _ => return self.next_char(bytes),
+            }
+        }
+    }
+
+    // Decodes a UTF-8 character, converting CRLF and CR to LF.
+    const fn next_char(&mut self, bytes: &[u8]) -> Result<Option<u32>, Error> {
+        let pos = self.pos;
+        let first = bytes[pos];
+
+        if first == b'\r' {
+            self.pos += if pos + 1 < bytes.len() && bytes[pos + 1] == b'\n' {
+                2
+            } else {
+                1
+            };
+            return Ok(Some(b'\n' as u32));
+        }
+
+        let (ch, len) = tri!(decode_utf8(bytes, pos));
+        self.pos += len;
+        Ok(Some(ch))
+    }
+
+    // Decodes `&name;`, `&#dec;`, or `&#xhex;`.
+    const fn next_reference(
+        &mut self,
+        bytes: &[u8],
+    ) -> Result<Option<u32>, Error> {
+        let start = self.pos + 1;
+        let mut end = start;
+        while end < bytes.len() && bytes[end] != b';' {
+            // No valid reference is this long.
+            if end - start > 10 {
+                return Err(Error::UnknownEntity);
+            }
+            end += 1;
+        }
+        if end >= bytes.len() {
+            return Err(Error::UnexpectedEnd);
+        }
+        self.pos = end + 1;
+
+        let ch = if end > start && bytes[start] == b'#' {
+            tri!(parse_char_ref(bytes, start + 1, end))
+        } else if is_name(bytes, start, end, b"amp") {
+            b'&' as u32
+        } else if is_name(bytes, start, end, b"lt") {
+            b'<' as u32
+        } else if is_name(bytes, start, end, b"gt") {
+            b'>' as u32
+        } else if is_name(bytes, start, end, b"quot") {
+            b'"' as u32
+        } else if is_name(bytes, start, end, b"apos") {
+            b'\'' as u32
+        } else {
+            return Err(Error::UnknownEntity);
+        };
+        Ok(Some(ch))
+    }
+}
+
+// Decodes the UTF-8 character at `pos`, returning it and its length.
+pub(crate) const fn decode_utf8(
+    bytes: &[u8],
+    pos: usize,
+) -> Result<(u32, usize), Error> {
+    let first = bytes[pos];
+    let (len, min, mut ch) = match first {
+        0x00..=0x7F => return Ok((first as u32, 1)),
+        0xC2..=0xDF => (2, 0x80, (first & 0x1F) as u32),
+        0xE0..=0xEF => (3, 0x800, (first & 0x0F) as u32),
+        0xF0..=0xF4 => (4, 0x10000, (first & 0x07) as u32),
+        _ => return Err(Error::InvalidUtf8),
+    };
+    if pos + len > bytes.len() {
+        return Err(Error::InvalidUtf8);
+    }
+
+    let mut i = 1;
+    while i < len {
+        let byte = bytes[pos + i];
+        if byte & 0xC0 != 0x80 {
+            return Err(Error::InvalidUtf8);
+        }
+        ch = (ch << 6) | (byte & 0x3F) as u32;
+        i += 1;
+    }
+    if ch < min || !is_char(ch) {
+        return Err(Error::InvalidUtf8);
+    }
+    Ok((ch, len))
+}
+
+const fn is_name(bytes: &[u8], start: usize, end: usize, name: &[u8]) -> bool {
+    end - start == name.len() && starts_with_at(bytes, start, name)
+}
+
+// Parses the digits of a character reference between `start` and `end`.
+const fn parse_char_ref(
+    bytes: &[u8],
+    mut start: usize,
+    end: usize,
+) -> Result<u32, Error> {
+    let radix = if start < end && bytes[start] == b'x' {
+        start += 1;
+        16
+    } else {
+        10
+    };
+    if start == end {
+        return Err(Error::InvalidCharRef);
+    }
+
+    let mut ch: u32 = 0;
+    while start < end {
+        let digit = match bytes[start] {
+            byte @ b'0'..=b'9' => (byte - b'0') as u32,
+            byte @ b'a'..=b'f' if radix == 16 => (byte - b'a' + 10) as u32,
+            byte @ b'A'..=b'F' if radix == 16 => (byte - b'A' + 10) as u32,
+            _ => return Err(Error::InvalidCharRef),
+        };
+        ch = ch * radix + digit;
+        if ch > 0x10FFFF {
+            return Err(Error::InvalidCharRef);
+        }
+        start += 1;
+    }
+
+    if ch == 0 || !is_char(ch) {
+        return Err(Error::InvalidCharRef);
+    }
+    Ok(ch)
+}
+
+// Whether `ch` is a Unicode scalar value.
+pub(crate) const fn is_char(ch: u32) -> bool {
+    ch <= 0x10FFFF && !(ch >= 0xD800 && ch <= 0xDFFF)
+}
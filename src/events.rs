@@ -0,0 +1,423 @@
+//! Reading XML property lists as a stream of events, without allocating.
+//!
+//! This module requires the `const_eval` feature, which needs Rust 1.83 or
+//! later. [`PlistEvents`] yields each array, dictionary, key, and value in
+//! document order, so a single key can be found without building a
+//! [`Value`] tree:
+//!
+//! ```rust
+//! use embed_plist::events::{Event, PlistEvents};
+//!
+//! let plist = br#"<?xml version="1.0" encoding="UTF-8"?>
+//! <plist version="1.0">
+//! <dict>
+//!     <key>CFBundleName</key>
+//!     <string>Tom &amp; Jerry</string>
+//!     <key>LSUIElement</key>
+//!     <true/>
+//! </dict>
+//! </plist>"#;
+//!
+//! let mut events = PlistEvents::new(plist);
+//! while let Some(event) = events.next() {
+//!     if let Event::Key(key) = event? {
+//!         if key.eq_str("CFBundleName") {
+//!             if let Some(Ok(Event::String(name))) = events.next() {
+//!                 assert_eq!(name.as_str(), None); // `&amp;` must be decoded
+//!                 assert!(name.eq_str("Tom & Jerry"));
+//!             }
+//!         }
+//!     }
+//! }
+//! # Ok::<(), embed_plist::Error>(())
+//! ```
+//!
+//! Entity and character references, CDATA sections, and comments are decoded
+//! like in any XML parser. Entities declared in the DOCTYPE are never expanded,
+//! so documents like the "billion laughs" attack cannot cause unbounded work.
+//!
+//! [`PlistEvents`]: struct.PlistEvents.html
+//! [`Value`]: ../value/enum.Value.html
+
+use crate::const_eval::{
+    derive::borrow_text,
+    lookup::read_integer,
+    xml::{self, Tag, Text},
+    Error,
+};
+use core::{char, fmt};
+
+/// An item of an XML property list, as yielded by [`PlistEvents`].
+///
+/// [`PlistEvents`]: struct.PlistEvents.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event<'a> {
+    /// The start of an `<array>`.
+    StartArray,
+    /// The end of an `<array>`.
+    EndArray,
+    /// The start of a `<dict>`.
+    StartDict,
+    /// The end of a `<dict>`.
+    EndDict,
+    /// A dictionary `<key>`, which is followed by the events of its value.
+    Key(Str<'a>),
+    /// A `<string>`.
+    String(Str<'a>),
+    /// A `<true/>` or `<false/>`.
+    Boolean(bool),
+    /// The base64 text of a `<data>`.
+    Data(Str<'a>),
+    /// The ISO 8601 text of a `<date>`.
+    Date(Str<'a>),
+    /// An `<integer>`, which can hold any `i64` or `u64`.
+    Integer(i128),
+    /// A `<real>`.
+    Real(f64),
+}
+
+/// The text of a key or value, borrowed from the property list and decoded as
+/// it is read.
+///
+/// The text was already checked when its [`Event`] was yielded, so reading it
+/// cannot fail.
+///
+/// [`Event`]: enum.Event.html
+#[derive(Clone, Copy)]
+pub struct Str<'a> {
+    bytes: &'a [u8],
+    tag: Tag,
+    name: &'static [u8],
+}
+
+impl<'a> Str<'a> {
+    /// Returns the text if it can be borrowed as-is, which is when it has no
+    /// references, comments, or carriage returns, or is a single CDATA
+    /// section.
+    #[inline]
+    pub fn as_str(&self) -> Option<&'a str> {
+        borrow_text(self.bytes, self.tag, self.name).ok()
+    }
+
+    /// Returns an iterator over the decoded characters.
+    #[inline]
+    pub fn chars(&self) -> Chars<'a> {
+        Chars {
+            bytes: self.bytes,
+            text: if self.tag.is_empty() {
+                Text::empty(self.tag.end)
+            } else {
+                Text::new(self.tag.end)
+            },
+        }
+    }
+
+    /// Returns `true` if the decoded text equals `other`.
+    pub fn eq_str(&self, other: &str) -> bool {
+        self.chars().eq(other.chars())
+    }
+}
+
+impl PartialEq for Str<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.chars().eq(other.chars())
+    }
+}
+
+impl fmt::Display for Str<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use fmt::Write;
+        for ch in self.chars() {
+            f.write_char(ch)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Str<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use fmt::Write;
+        f.write_char('"')?;
+        for ch in self.chars() {
+            for escaped in ch.escape_debug() {
+                f.write_char(escaped)?;
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+/// An iterator over the characters of a [`Str`].
+///
+/// [`Str`]: struct.Str.html
+#[derive(Clone)]
+pub struct Chars<'a> {
+    bytes: &'a [u8],
+    text: Text,
+}
+
+impl Iterator for Chars<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        // The text was validated when its event was yielded.
+        let ch = self.text.next(self.bytes).ok()??;
+        char::from_u32(ch)
+    }
+}
+
+// The maximum nesting of arrays and dictionaries, which fits in the bits of
+// `PlistEvents::dicts`.
+const MAX_DEPTH: u32 = 128;
+
+/// An iterator over the [`Event`]s of an XML property list.
+///
+/// Each item is checked as it is reached, and iteration stops after the first
+/// error. Dictionaries are checked to alternate between keys and values, but
+/// duplicate keys are not detected, since that would require allocating.
+/// Arrays and dictionaries can be nested up to 128 levels deep.
+///
+/// [`Event`]: enum.Event.html
+#[derive(Clone)]
+pub struct PlistEvents<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    state: State,
+    depth: u32,
+    // Bit `n` is set if the container at depth `n + 1` is a dictionary.
+    dicts: u128,
+    expect_key: bool,
+    in_plist: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Start,
+    Values,
+    // An empty `<array/>` or `<dict/>` whose end has not been yielded.
+    EndEmpty,
+    Finished,
+}
+
+impl<'a> PlistEvents<'a> {
+    /// Creates an iterator over the events of the XML property list `bytes`,
+    /// such as from [`get_info_plist`].
+    ///
+    /// [`get_info_plist`]: ../fn.get_info_plist.html
+    #[inline]
+    pub fn new(bytes: &'a [u8]) -> Self {
+        PlistEvents {
+            bytes,
+            pos: 0,
+            state: State::Start,
+            depth: 0,
+            dicts: 0,
+            expect_key: false,
+            in_plist: false,
+        }
+    }
+
+    /// Returns how many arrays and dictionaries are open after the last
+    /// event, such as 1 for the keys of a root dictionary.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth as usize
+    }
+
+    fn in_dict(&self) -> bool {
+        self.depth > 0 && self.dicts & (1 << (self.depth - 1)) != 0
+    }
+
+    fn step(&mut self) -> Result<Option<Event<'a>>, Error> {
+        let bytes = self.bytes;
+        match self.state {
+            State::Finished => return Ok(None),
+            State::EndEmpty => {
+                let is_dict = self.in_dict();
+                self.state = State::Values;
+                self.depth -= 1;
+                self.end_value();
+                return Ok(Some(if is_dict {
+                    Event::EndDict
+                } else {
+                    Event::EndArray
+                }));
+            }
+            State::Start => {
+                self.state = State::Values;
+                let pos = xml::skip_misc(bytes, 0)?;
+                let tag = xml::read_tag(bytes, pos)?;
+                if tag.is(bytes, b"plist") && tag.is_start() {
+                    self.in_plist = true;
+                    self.pos = tag.end;
+                } else {
+                    self.pos = pos;
+                }
+            }
+            State::Values => {}
+        }
+
+        let pos = xml::skip_misc(bytes, self.pos)?;
+        let tag = xml::read_tag(bytes, pos)?;
+        self.pos = tag.end;
+
+        if tag.is_end() {
+            let is_dict = self.in_dict();
+            if self.depth == 0 {
+                return Err(Error::ExpectedValue);
+            } else if !tag.is(bytes, if is_dict { b"dict" } else { b"array" }) {
+                return Err(Error::MismatchedTag);
+            } else if is_dict && !self.expect_key {
+                return Err(Error::ExpectedValue);
+            }
+            self.depth -= 1;
+            self.end_value();
+            return Ok(Some(if is_dict {
+                Event::EndDict
+            } else {
+                Event::EndArray
+            }));
+        }
+
+        if self.in_dict() && self.expect_key {
+            if !tag.is(bytes, b"key") {
+                return Err(Error::ExpectedKey);
+            }
+            self.expect_key = false;
+            return self.text(tag, b"key").map(|key| Some(Event::Key(key)));
+        }
+
+        let event = if tag.is(bytes, b"array") || tag.is(bytes, b"dict") {
+            let is_dict = tag.is(bytes, b"dict");
+            if self.depth == MAX_DEPTH {
+                return Err(Error::TooDeep);
+            }
+            if is_dict {
+                self.dicts |= 1 << self.depth;
+            } else {
+                self.dicts &= !(1 << self.depth);
+            }
+            self.depth += 1;
+            self.expect_key = true;
+            if tag.is_empty() {
+                self.state = State::EndEmpty;
+            }
+            return Ok(Some(if is_dict {
+                Event::StartDict
+            } else {
+                Event::StartArray
+            }));
+        } else if tag.is(bytes, b"string") {
+            Event::String(self.text(tag, b"string")?)
+        } else if tag.is(bytes, b"data") {
+            Event::Data(self.text(tag, b"data")?)
+        } else if tag.is(bytes, b"date") {
+            Event::Date(self.text(tag, b"date")?)
+        } else if tag.is(bytes, b"true") || tag.is(bytes, b"false") {
+            if tag.is_start() {
+                let name: &[u8] = if tag.is(bytes, b"true") {
+                    b"true"
+                } else {
+                    b"false"
+                };
+                self.pos = xml::expect_end(bytes, tag.end, name)?;
+            }
+            Event::Boolean(tag.is(bytes, b"true"))
+        } else if tag.is(bytes, b"integer") {
+            let int = read_integer(bytes, tag)?;
+            self.pos = xml::skip_element(bytes, tag)?;
+            Event::Integer(int)
+        } else if tag.is(bytes, b"real") {
+            Event::Real(self.real(tag)?)
+        } else if tag.is(bytes, b"key") && self.in_dict() {
+            return Err(Error::ExpectedValue);
+        } else {
+            return Err(Error::UnexpectedElement);
+        };
+        self.end_value();
+        Ok(Some(event))
+    }
+
+    // Updates the state after a complete value, and checks the end of the
+    // document after the root.
+    fn end_value(&mut self) {
+        if self.depth > 0 {
+            self.expect_key = true;
+        } else {
+            self.state = State::Finished;
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        let mut pos = self.pos;
+        if self.in_plist {
+            pos = xml::skip_misc(self.bytes, pos)?;
+            pos = xml::expect_end(self.bytes, pos, b"plist")?;
+        }
+        if xml::skip_misc(self.bytes, pos)? != self.bytes.len() {
+            return Err(Error::TrailingData);
+        }
+        Ok(())
+    }
+
+    // Checks the text of the element opened by `tag` and moves past it.
+    fn text(
+        &mut self,
+        tag: Tag,
+        name: &'static [u8],
+    ) -> Result<Str<'a>, Error> {
+        if tag.is_start() {
+            let mut text = Text::new(tag.end);
+            while text.next(self.bytes)?.is_some() {}
+            self.pos = xml::expect_end(self.bytes, text.pos(), name)?;
+        }
+        Ok(Str {
+            bytes: self.bytes,
+            tag,
+            name,
+        })
+    }
+
+    fn real(&mut self, tag: Tag) -> Result<f64, Error> {
+        let text = self.text(tag, b"real")?;
+        // Decode into a buffer, since the text may contain references.
+        let mut buffer = [0; 64];
+        let mut len = 0;
+        for ch in text.chars() {
+            let slot = buffer.get_mut(len).ok_or(Error::InvalidValue)?;
+            if !ch.is_ascii() {
+                return Err(Error::InvalidValue);
+            }
+            *slot = ch as u8;
+            len += 1;
+        }
+        let text = core::str::from_utf8(&buffer[..len])
+            .map_err(|_| Error::InvalidValue)?;
+        text.trim().parse().map_err(|_| Error::InvalidValue)
+    }
+}
+
+impl<'a> Iterator for PlistEvents<'a> {
+    type Item = Result<Event<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let was_running = self.state != State::Finished;
+        let result = match self.step() {
+            Ok(Some(event)) => Ok(event),
+            Ok(None) => return None,
+            Err(error) => Err(error),
+        };
+        // Check what follows the root as soon as it ends, so that the last
+        // event is an error rather than the end of a malformed document.
+        let result = match result {
+            Ok(event) if was_running && self.state == State::Finished => {
+                self.finish().map(|()| event)
+            }
+            result => result,
+        };
+        if result.is_err() {
+            self.state = State::Finished;
+        }
+        Some(result)
+    }
+}
@@ -0,0 +1,150 @@
+// Runtime integrity checks against the hash computed when embedding.
+
+use crate::const_eval::sha256;
+use core::fmt;
+
+/// Returns the SHA-256 digest of the embedded [`Info.plist`], as computed at
+/// compile time by [`embed_info_plist!`] with the `integrity` option.
+///
+/// This is stable across builds of the same contents, so tools can use it as a
+/// cache key.
+///
+/// This requires the `const_eval` feature, which needs Rust 1.83 or later.
+/// Without the `integrity` option, linking fails.
+///
+/// [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
+/// [`embed_info_plist!`]: macro.embed_info_plist.html
+#[inline]
+pub fn info_plist_sha256() -> &'static [u8; 32] {
+    extern "C" {
+        #[link_name = "_EMBED_INFO_PLIST_SHA256"]
+        static SHA256: [u8; 32];
+    }
+    unsafe { &SHA256 }
+}
+
+/// Returns the SHA-256 digest of the embedded [`launchd.plist`], as computed
+/// at compile time by [`embed_launchd_plist!`] with the `integrity` option.
+///
+/// This requires the `const_eval` feature, which needs Rust 1.83 or later.
+/// Without the `integrity` option, linking fails.
+///
+/// [`launchd.plist`]: https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html#//apple_ref/doc/uid/TP40001762-104142
+/// [`embed_launchd_plist!`]: macro.embed_launchd_plist.html
+#[inline]
+pub fn launchd_plist_sha256() -> &'static [u8; 32] {
+    extern "C" {
+        #[link_name = "_EMBED_LAUNCHD_PLIST_SHA256"]
+        static SHA256: [u8; 32];
+    }
+    unsafe { &SHA256 }
+}
+
+/// Hashes [`get_info_plist`] at runtime and compares it with
+/// [`info_plist_sha256`], returning the bytes if they match.
+///
+/// This catches the embedded property list being patched after the binary was
+/// built. It does not catch a patch that also replaces the stored hash, so on
+/// its own it is not a substitute for code signing.
+///
+/// This requires the `const_eval` feature, which needs Rust 1.83 or later.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(any(target_vendor = "apple", feature = "portable"))] {
+/// embed_plist::embed_info_plist!("Info.plist", integrity);
+///
+/// let plist = embed_plist::check_info_plist_integrity().unwrap();
+/// assert_eq!(plist, embed_plist::get_info_plist());
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`IntegrityError`] with both digests if they differ.
+///
+/// [`get_info_plist`]: fn.get_info_plist.html
+/// [`info_plist_sha256`]: fn.info_plist_sha256.html
+/// [`IntegrityError`]: struct.IntegrityError.html
+pub fn check_info_plist_integrity() -> Result<&'static [u8], IntegrityError> {
+    IntegrityError::check(crate::get_info_plist(), info_plist_sha256())
+}
+
+/// Hashes [`get_launchd_plist`] at runtime and compares it with
+/// [`launchd_plist_sha256`], returning the bytes if they match.
+///
+/// This works the same as [`check_info_plist_integrity`].
+///
+/// # Errors
+///
+/// Returns an [`IntegrityError`] with both digests if they differ.
+///
+/// [`get_launchd_plist`]: fn.get_launchd_plist.html
+/// [`launchd_plist_sha256`]: fn.launchd_plist_sha256.html
+/// [`check_info_plist_integrity`]: fn.check_info_plist_integrity.html
+/// [`IntegrityError`]: struct.IntegrityError.html
+pub fn check_launchd_plist_integrity() -> Result<&'static [u8], IntegrityError>
+{
+    IntegrityError::check(crate::get_launchd_plist(), launchd_plist_sha256())
+}
+
+/// The embedded bytes no longer match the hash computed when embedding them,
+/// as returned by [`check_info_plist_integrity`] and
+/// [`check_launchd_plist_integrity`].
+///
+/// [`check_info_plist_integrity`]: fn.check_info_plist_integrity.html
+/// [`check_launchd_plist_integrity`]: fn.check_launchd_plist_integrity.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IntegrityError {
+    expected: [u8; 32],
+    actual: [u8; 32],
+}
+
+impl IntegrityError {
+    fn check(
+        bytes: &'static [u8],
+        expected: &[u8; 32],
+    ) -> Result<&'static [u8], Self> {
+        let actual = sha256(bytes);
+        if actual == *expected {
+            Ok(bytes)
+        } else {
+            Err(Self {
+                expected: *expected,
+                actual,
+            })
+        }
+    }
+
+    /// Returns the digest computed at compile time.
+    #[inline]
+    pub fn expected(&self) -> &[u8; 32] {
+        &self.expected
+    }
+
+    /// Returns the digest of the bytes found at runtime.
+    #[inline]
+    pub fn actual(&self) -> &[u8; 32] {
+        &self.actual
+    }
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("embedded property list was modified: expected SHA-256 ")?;
+        hex(&self.expected, f)?;
+        f.write_str(", found ")?;
+        hex(&self.actual, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IntegrityError {}
+
+fn hex(digest: &[u8; 32], f: &mut fmt::Formatter) -> fmt::Result {
+    for byte in digest.iter() {
+        write!(f, "{:02x}", byte)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,281 @@
+//! Keys of [`Info.plist`] files.
+//!
+//! [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
+
+use super::{Key, Platforms, ValueType};
+
+keys! {
+    BUILD_MACHINE_OS_BUILD = "BuildMachineOSBuild": String, [ALL],
+        "The build of macOS that the bundle was built on, set by Xcode.";
+    CF_APPLE_HELP_ANCHOR = "CFAppleHelpAnchor": String, [MACOS],
+        "The name of the bundle's HTML help file.";
+    CF_BUNDLE_ALLOW_MIXED_LOCALIZATIONS = "CFBundleAllowMixedLocalizations":
+        Boolean, [ALL],
+        "Whether the app uses localizations from frameworks it loads.";
+    CF_BUNDLE_DEVELOPMENT_REGION = "CFBundleDevelopmentRegion": String, [ALL],
+        "The default language and region of the bundle.";
+    CF_BUNDLE_DISPLAY_NAME = "CFBundleDisplayName": String, [ALL],
+        "The name of the bundle shown to users.";
+    CF_BUNDLE_DOCUMENT_TYPES = "CFBundleDocumentTypes": Array, [ALL],
+        "The document types that the app can open.";
+    CF_BUNDLE_EXECUTABLE = "CFBundleExecutable": String, [ALL],
+        "The name of the bundle's executable file.";
+    CF_BUNDLE_GET_INFO_STRING = "CFBundleGetInfoString": String, [MACOS],
+        deprecated, "Replaced by `NSHumanReadableCopyright`.";
+    CF_BUNDLE_HELP_BOOK_FOLDER = "CFBundleHelpBookFolder": String, [MACOS],
+        "The folder that holds the bundle's help book.";
+    CF_BUNDLE_HELP_BOOK_NAME = "CFBundleHelpBookName": String, [MACOS],
+        "The name of the bundle's help book.";
+    CF_BUNDLE_ICON_FILE = "CFBundleIconFile": String, [MACOS, IOS],
+        "The file name of the bundle's icon.";
+    CF_BUNDLE_ICON_FILES = "CFBundleIconFiles": Array, [IOS], deprecated,
+        "Replaced by `CFBundleIcons`.";
+    CF_BUNDLE_ICON_NAME = "CFBundleIconName": String, [ALL],
+        "The name of the bundle's icon in its asset catalog.";
+    CF_BUNDLE_ICONS = "CFBundleIcons": Dictionary, [IOS, TVOS],
+        "The icons of the bundle, including alternate icons.";
+    CF_BUNDLE_IDENTIFIER = "CFBundleIdentifier": String, [ALL],
+        "The unique identifier of the bundle, in reverse-DNS form.";
+    CF_BUNDLE_INFO_DICTIONARY_VERSION = "CFBundleInfoDictionaryVersion":
+        String, [ALL],
+        "The version of the `Info.plist` format, which is `6.0`.";
+    CF_BUNDLE_LOCALIZATIONS = "CFBundleLocalizations": Array, [ALL],
+        "The localizations that the bundle supports.";
+    CF_BUNDLE_NAME = "CFBundleName": String, [ALL],
+        "The short name of the bundle.";
+    CF_BUNDLE_PACKAGE_TYPE = "CFBundlePackageType": String, [ALL],
+        "The four-letter type code of the bundle, such as `APPL`.";
+    CF_BUNDLE_SHORT_VERSION_STRING = "CFBundleShortVersionString":
+        String, [ALL], "The release version of the bundle shown to users.";
+    CF_BUNDLE_SIGNATURE = "CFBundleSignature": String, [MACOS], deprecated,
+        "The four-letter creator code of the bundle, which is unused.";
+    CF_BUNDLE_SPOKEN_NAME = "CFBundleSpokenName": String, [ALL],
+        "The name of the bundle as spoken by accessibility features.";
+    CF_BUNDLE_SUPPORTED_PLATFORMS = "CFBundleSupportedPlatforms": Array, [ALL],
+        "The platforms that the bundle was built for, set by Xcode.";
+    CF_BUNDLE_URL_TYPES = "CFBundleURLTypes": Array, [ALL],
+        "The URL schemes that the app handles.";
+    CF_BUNDLE_VERSION = "CFBundleVersion": String, [ALL],
+        "The build version of the bundle.";
+    DT_COMPILER = "DTCompiler": String, [ALL],
+        "The compiler that built the bundle, set by Xcode.";
+    DT_PLATFORM_BUILD = "DTPlatformBuild": String, [ALL],
+        "The build of the platform SDK, set by Xcode.";
+    DT_PLATFORM_NAME = "DTPlatformName": String, [ALL],
+        "The name of the platform SDK, set by Xcode.";
+    DT_PLATFORM_VERSION = "DTPlatformVersion": String, [ALL],
+        "The version of the platform SDK, set by Xcode.";
+    DT_SDK_BUILD = "DTSDKBuild": String, [ALL],
+        "The build of the SDK, set by Xcode.";
+    DT_SDK_NAME = "DTSDKName": String, [ALL],
+        "The name of the SDK, set by Xcode.";
+    DT_XCODE = "DTXcode": String, [ALL],
+        "The version of Xcode that built the bundle, set by Xcode.";
+    DT_XCODE_BUILD = "DTXcodeBuild": String, [ALL],
+        "The build of Xcode that built the bundle, set by Xcode.";
+    ITS_APP_USES_NON_EXEMPT_ENCRYPTION = "ITSAppUsesNonExemptEncryption":
+        Boolean, [ALL],
+        "Whether the app uses encryption that requires export compliance.";
+    LS_APPLICATION_CATEGORY_TYPE = "LSApplicationCategoryType": String, [MACOS],
+        "The App Store category of the app.";
+    LS_APPLICATION_QUERIES_SCHEMES = "LSApplicationQueriesSchemes":
+        Array, [IOS], "The URL schemes that the app checks with `canOpenURL`.";
+    LS_ARCHITECTURE_PRIORITY = "LSArchitecturePriority": Array, [MACOS],
+        "The architectures to run the app with, in order of preference.";
+    LS_BACKGROUND_ONLY = "LSBackgroundOnly": Boolean, [MACOS],
+        "Whether the app runs only in the background.";
+    LS_ENVIRONMENT = "LSEnvironment": Dictionary, [MACOS],
+        "Environment variables to set when Launch Services starts the app.";
+    LS_FILE_QUARANTINE_ENABLED = "LSFileQuarantineEnabled": Boolean, [MACOS],
+        "Whether files created by the app are quarantined.";
+    LS_MINIMUM_SYSTEM_VERSION = "LSMinimumSystemVersion": String, [MACOS],
+        "The minimum version of macOS that the app runs on.";
+    LS_MULTIPLE_INSTANCES_PROHIBITED = "LSMultipleInstancesProhibited":
+        Boolean, [MACOS],
+        "Whether only one instance of the app may run across users.";
+    LS_REQUIRES_I_PHONE_OS = "LSRequiresIPhoneOS":
+        Boolean, [IOS, TVOS, WATCHOS],
+        "Whether the app runs only on iOS-based platforms.";
+    LS_REQUIRES_NATIVE_EXECUTION = "LSRequiresNativeExecution":
+        Boolean, [MACOS],
+        "Whether the app must run natively rather than under Rosetta.";
+    LS_SUPPORTS_OPENING_DOCUMENTS_IN_PLACE =
+        "LSSupportsOpeningDocumentsInPlace": Boolean, [IOS],
+        "Whether the app opens documents without copying them.";
+    LS_UI_ELEMENT = "LSUIElement": Boolean, [MACOS],
+        "Whether the app runs without a Dock icon or menu bar.";
+    LS_UI_PRESENTATION_MODE = "LSUIPresentationMode": Integer, [MACOS],
+        "How the app shows the Dock and menu bar, from 0 to 4.";
+    MINIMUM_OS_VERSION = "MinimumOSVersion":
+        String, [IOS, TVOS, WATCHOS, VISIONOS],
+        "The minimum version of the OS that the app runs on.";
+    NS_APP_TRANSPORT_SECURITY = "NSAppTransportSecurity": Dictionary, [ALL],
+        "Exceptions to the security requirements for network connections.";
+    NS_APPLE_EVENTS_USAGE_DESCRIPTION = "NSAppleEventsUsageDescription":
+        String, [MACOS],
+        "Why the app sends Apple events to control other apps.";
+    NS_APPLE_MUSIC_USAGE_DESCRIPTION = "NSAppleMusicUsageDescription":
+        String, [MACOS, IOS, VISIONOS],
+        "Why the app accesses the user's media library.";
+    NS_BLUETOOTH_ALWAYS_USAGE_DESCRIPTION = "NSBluetoothAlwaysUsageDescription":
+        String, [ALL], "Why the app uses Bluetooth.";
+    NS_BLUETOOTH_PERIPHERAL_USAGE_DESCRIPTION =
+        "NSBluetoothPeripheralUsageDescription": String, [IOS, TVOS, WATCHOS],
+        deprecated, "Replaced by `NSBluetoothAlwaysUsageDescription`.";
+    NS_CALENDARS_FULL_ACCESS_USAGE_DESCRIPTION =
+        "NSCalendarsFullAccessUsageDescription":
+        String, [MACOS, IOS, WATCHOS, VISIONOS],
+        "Why the app reads and writes the user's calendar events.";
+    NS_CALENDARS_USAGE_DESCRIPTION = "NSCalendarsUsageDescription":
+        String, [MACOS, IOS, WATCHOS, VISIONOS], deprecated,
+        "Replaced by `NSCalendarsFullAccessUsageDescription`.";
+    NS_CALENDARS_WRITE_ONLY_ACCESS_USAGE_DESCRIPTION =
+        "NSCalendarsWriteOnlyAccessUsageDescription":
+        String, [MACOS, IOS, WATCHOS, VISIONOS],
+        "Why the app adds events to the user's calendars.";
+    NS_CAMERA_USAGE_DESCRIPTION = "NSCameraUsageDescription":
+        String, [MACOS, IOS, VISIONOS], "Why the app uses the camera.";
+    NS_CONTACTS_USAGE_DESCRIPTION = "NSContactsUsageDescription":
+        String, [MACOS, IOS, WATCHOS, VISIONOS],
+        "Why the app accesses the user's contacts.";
+    NS_DESKTOP_FOLDER_USAGE_DESCRIPTION = "NSDesktopFolderUsageDescription":
+        String, [MACOS], "Why the app accesses the user's Desktop folder.";
+    NS_DOCUMENTS_FOLDER_USAGE_DESCRIPTION = "NSDocumentsFolderUsageDescription":
+        String, [MACOS], "Why the app accesses the user's Documents folder.";
+    NS_DOWNLOADS_FOLDER_USAGE_DESCRIPTION = "NSDownloadsFolderUsageDescription":
+        String, [MACOS], "Why the app accesses the user's Downloads folder.";
+    NS_FACE_ID_USAGE_DESCRIPTION = "NSFaceIDUsageDescription":
+        String, [IOS, VISIONOS], "Why the app authenticates with Face ID.";
+    NS_HEALTH_SHARE_USAGE_DESCRIPTION = "NSHealthShareUsageDescription":
+        String, [IOS, WATCHOS, VISIONOS],
+        "Why the app reads the user's health data.";
+    NS_HEALTH_UPDATE_USAGE_DESCRIPTION = "NSHealthUpdateUsageDescription":
+        String, [IOS, WATCHOS, VISIONOS],
+        "Why the app writes to the user's health data.";
+    NS_HIGH_RESOLUTION_CAPABLE = "NSHighResolutionCapable": Boolean, [MACOS],
+        "Whether the app supports Retina displays.";
+    NS_HOME_KIT_USAGE_DESCRIPTION = "NSHomeKitUsageDescription": String, [ALL],
+        "Why the app accesses the user's HomeKit data.";
+    NS_HUMAN_READABLE_COPYRIGHT = "NSHumanReadableCopyright": String, [MACOS],
+        "The copyright notice of the bundle.";
+    NS_LOCAL_NETWORK_USAGE_DESCRIPTION = "NSLocalNetworkUsageDescription":
+        String, [MACOS, IOS, TVOS, VISIONOS],
+        "Why the app connects to devices on the local network.";
+    NS_LOCATION_ALWAYS_AND_WHEN_IN_USE_USAGE_DESCRIPTION =
+        "NSLocationAlwaysAndWhenInUseUsageDescription":
+        String, [MACOS, IOS, WATCHOS, VISIONOS],
+        "Why the app accesses the user's location at any time.";
+    NS_LOCATION_ALWAYS_USAGE_DESCRIPTION = "NSLocationAlwaysUsageDescription":
+        String, [MACOS, IOS, WATCHOS], deprecated,
+        "Replaced by `NSLocationAlwaysAndWhenInUseUsageDescription`.";
+    NS_LOCATION_USAGE_DESCRIPTION = "NSLocationUsageDescription":
+        String, [MACOS], deprecated,
+        "Replaced by `NSLocationWhenInUseUsageDescription`.";
+    NS_LOCATION_WHEN_IN_USE_USAGE_DESCRIPTION =
+        "NSLocationWhenInUseUsageDescription": String, [ALL],
+        "Why the app accesses the user's location while in use.";
+    NS_MAIN_NIB_FILE = "NSMainNibFile": String, [MACOS, IOS],
+        "The name of the nib file loaded when the app starts.";
+    NS_MAIN_STORYBOARD_FILE = "NSMainStoryboardFile": String, [MACOS],
+        "The name of the storyboard loaded when the app starts.";
+    NS_MICROPHONE_USAGE_DESCRIPTION = "NSMicrophoneUsageDescription":
+        String, [MACOS, IOS, WATCHOS, VISIONOS],
+        "Why the app uses the microphone.";
+    NS_MOTION_USAGE_DESCRIPTION = "NSMotionUsageDescription":
+        String, [IOS, WATCHOS, VISIONOS],
+        "Why the app reads motion and fitness data.";
+    NS_NETWORK_VOLUMES_USAGE_DESCRIPTION = "NSNetworkVolumesUsageDescription":
+        String, [MACOS], "Why the app accesses files on network volumes.";
+    NS_PHOTO_LIBRARY_ADD_USAGE_DESCRIPTION =
+        "NSPhotoLibraryAddUsageDescription": String, [MACOS, IOS, VISIONOS],
+        "Why the app adds to the user's photo library.";
+    NS_PHOTO_LIBRARY_USAGE_DESCRIPTION = "NSPhotoLibraryUsageDescription":
+        String, [MACOS, IOS, TVOS, VISIONOS],
+        "Why the app accesses the user's photo library.";
+    NS_PRINCIPAL_CLASS = "NSPrincipalClass": String, [MACOS],
+        "The name of the bundle's main class, such as `NSApplication`.";
+    NS_REMINDERS_FULL_ACCESS_USAGE_DESCRIPTION =
+        "NSRemindersFullAccessUsageDescription":
+        String, [MACOS, IOS, WATCHOS, VISIONOS],
+        "Why the app reads and writes the user's reminders.";
+    NS_REMINDERS_USAGE_DESCRIPTION = "NSRemindersUsageDescription":
+        String, [MACOS, IOS, WATCHOS, VISIONOS], deprecated,
+        "Replaced by `NSRemindersFullAccessUsageDescription`.";
+    NS_REMOVABLE_VOLUMES_USAGE_DESCRIPTION =
+        "NSRemovableVolumesUsageDescription": String, [MACOS],
+        "Why the app accesses files on removable volumes.";
+    NS_REQUIRES_AQUA_SYSTEM_APPEARANCE = "NSRequiresAquaSystemAppearance":
+        Boolean, [MACOS], "Whether the app always uses the light appearance.";
+    NS_SERVICES = "NSServices": Array, [MACOS],
+        "The services that the app provides to other apps.";
+    NS_SPEECH_RECOGNITION_USAGE_DESCRIPTION =
+        "NSSpeechRecognitionUsageDescription": String, [MACOS, IOS, VISIONOS],
+        "Why the app sends user data to Apple's speech recognition.";
+    NS_SUPPORTS_AUTOMATIC_GRAPHICS_SWITCHING =
+        "NSSupportsAutomaticGraphicsSwitching": Boolean, [MACOS],
+        "Whether the app can run on the integrated GPU.";
+    NS_SUPPORTS_SUDDEN_TERMINATION = "NSSupportsSuddenTermination":
+        Boolean, [MACOS],
+        "Whether the system may kill the app to log out or shut down.";
+    NS_SYSTEM_ADMINISTRATION_USAGE_DESCRIPTION =
+        "NSSystemAdministrationUsageDescription": String, [MACOS],
+        "Why the app changes system administration settings.";
+    NS_UBIQUITOUS_CONTAINERS = "NSUbiquitousContainers": Dictionary, [ALL],
+        "The iCloud Drive containers of the app.";
+    NS_USER_ACTIVITY_TYPES = "NSUserActivityTypes": Array, [ALL],
+        "The activity types that the app supports for Handoff and Siri.";
+    NS_USER_TRACKING_USAGE_DESCRIPTION = "NSUserTrackingUsageDescription":
+        String, [MACOS, IOS, TVOS, VISIONOS],
+        "Why the app tracks the user across apps and websites.";
+    UI_APP_FONTS = "UIAppFonts": Array, [IOS, TVOS, WATCHOS, VISIONOS],
+        "The font files in the bundle to register when the app starts.";
+    UI_APPLICATION_EXITS_ON_SUSPEND = "UIApplicationExitsOnSuspend":
+        Boolean, [IOS], deprecated,
+        "Whether the app exits instead of suspending, which is ignored.";
+    UI_APPLICATION_SCENE_MANIFEST = "UIApplicationSceneManifest":
+        Dictionary, [IOS, TVOS, VISIONOS],
+        "The scenes of the app and whether it supports multiple windows.";
+    UI_BACKGROUND_MODES = "UIBackgroundModes":
+        Array, [IOS, TVOS, WATCHOS, VISIONOS],
+        "The services that the app provides in the background.";
+    UI_DEVICE_FAMILY = "UIDeviceFamily": Array, [IOS, TVOS, WATCHOS],
+        "The device families that the app runs on, set by Xcode.";
+    UI_FILE_SHARING_ENABLED = "UIFileSharingEnabled": Boolean, [IOS],
+        "Whether the app's documents are shared with the Files app.";
+    UI_LAUNCH_SCREEN = "UILaunchScreen": Dictionary, [IOS, TVOS],
+        "The launch screen, configured without a storyboard.";
+    UI_LAUNCH_STORYBOARD_NAME = "UILaunchStoryboardName": String, [IOS, TVOS],
+        "The name of the storyboard for the launch screen.";
+    UI_MAIN_STORYBOARD_FILE = "UIMainStoryboardFile": String, [IOS, TVOS],
+        "The name of the storyboard loaded when the app starts.";
+    UI_PRERENDERED_ICON = "UIPrerenderedIcon": Boolean, [IOS], deprecated,
+        "Whether the icon already has a gloss effect, which is ignored.";
+    UI_REQUIRED_DEVICE_CAPABILITIES = "UIRequiredDeviceCapabilities":
+        Array|Dictionary, [IOS, TVOS, WATCHOS, VISIONOS],
+        "The device capabilities that the app requires.";
+    UI_REQUIRES_FULL_SCREEN = "UIRequiresFullScreen": Boolean, [IOS],
+        "Whether the app opts out of multitasking on iPad.";
+    UI_REQUIRES_PERSISTENT_WI_FI = "UIRequiresPersistentWiFi": Boolean, [IOS],
+        "Whether the app keeps a Wi-Fi connection open.";
+    UI_STATUS_BAR_HIDDEN = "UIStatusBarHidden": Boolean, [IOS],
+        "Whether the status bar is hidden when the app starts.";
+    UI_STATUS_BAR_STYLE = "UIStatusBarStyle": String, [IOS],
+        "The style of the status bar when the app starts.";
+    UI_SUPPORTED_INTERFACE_ORIENTATIONS = "UISupportedInterfaceOrientations":
+        Array, [IOS], "The interface orientations that the app supports.";
+    UI_USER_INTERFACE_STYLE = "UIUserInterfaceStyle": String, [IOS, TVOS],
+        "Whether the app always uses the light or dark appearance.";
+    UI_VIEW_CONTROLLER_BASED_STATUS_BAR_APPEARANCE =
+        "UIViewControllerBasedStatusBarAppearance": Boolean, [IOS],
+        "Whether view controllers set the status bar style.";
+    WK_APPLICATION = "WKApplication": Boolean, [WATCHOS],
+        "Whether the bundle is a watchOS app using the SwiftUI lifecycle.";
+    WK_COMPANION_APP_BUNDLE_IDENTIFIER = "WKCompanionAppBundleIdentifier":
+        String, [WATCHOS],
+        "The bundle identifier of the watchOS app's companion iOS app.";
+    WK_RUNS_INDEPENDENTLY_OF_COMPANION_APP =
+        "WKRunsIndependentlyOfCompanionApp": Boolean, [WATCHOS],
+        "Whether the watchOS app works without its companion iOS app.";
+    WK_WATCH_ONLY = "WKWatchOnly": Boolean, [WATCHOS],
+        "Whether the watchOS app has no companion iOS app.";
+}
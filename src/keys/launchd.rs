@@ -0,0 +1,109 @@
+//! Keys of [`launchd.plist`] files, which are only read on macOS.
+//!
+//! [`launchd.plist`]: https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html#//apple_ref/doc/uid/TP40001762-104142
+
+use super::{Key, Platforms, ValueType};
+
+keys! {
+    ABANDON_PROCESS_GROUP = "AbandonProcessGroup": Boolean, [MACOS],
+        "Whether processes the job spawns survive when it exits.";
+    ASSOCIATED_BUNDLE_IDENTIFIERS = "AssociatedBundleIdentifiers":
+        String|Array, [MACOS],
+        "The bundles shown as the job's owner in Login Items settings.";
+    DEBUG = "Debug": Boolean, [MACOS],
+        "Whether `launchd` logs more about the job.";
+    DISABLED = "Disabled": Boolean, [MACOS], "Whether the job is not loaded.";
+    ENABLE_GLOBBING = "EnableGlobbing": Boolean, [MACOS],
+        "Whether to expand wildcards in `ProgramArguments`.";
+    ENABLE_PRESSURED_EXIT = "EnablePressuredExit": Boolean, [MACOS],
+        "Whether the system may kill the idle job under memory pressure.";
+    ENABLE_TRANSACTIONS = "EnableTransactions": Boolean, [MACOS],
+        "Whether the job tracks transactions to tell when it is idle.";
+    ENVIRONMENT_VARIABLES = "EnvironmentVariables": Dictionary, [MACOS],
+        "Environment variables to set before running the job.";
+    EXIT_TIME_OUT = "ExitTimeOut": Integer, [MACOS],
+        "Seconds to wait after `SIGTERM` before sending `SIGKILL`.";
+    GROUP_NAME = "GroupName": String, [MACOS], "The group to run the job as.";
+    HARD_RESOURCE_LIMITS = "HardResourceLimits": Dictionary, [MACOS],
+        "Hard resource limits to apply to the job.";
+    INIT_GROUPS = "InitGroups": Boolean, [MACOS],
+        "Whether to initialize the groups of `UserName` before running.";
+    KEEP_ALIVE = "KeepAlive": Boolean|Dictionary, [MACOS],
+        "Whether or when to keep the job running.";
+    LABEL = "Label": String, [MACOS],
+        "The unique identifier of the job, which is required.";
+    LAUNCH_EVENTS = "LaunchEvents": Dictionary, [MACOS],
+        "Events that start the job, by event stream.";
+    LAUNCH_ONLY_ONCE = "LaunchOnlyOnce": Boolean, [MACOS],
+        "Whether the job runs only once per boot.";
+    LIMIT_LOAD_FROM_HARDWARE = "LimitLoadFromHardware": Dictionary, [MACOS],
+        "Hardware that prevents the job from loading.";
+    LIMIT_LOAD_FROM_HOSTS = "LimitLoadFromHosts": Array, [MACOS], deprecated,
+        "Host names that prevent the job from loading, which is ignored.";
+    LIMIT_LOAD_TO_HARDWARE = "LimitLoadToHardware": Dictionary, [MACOS],
+        "Hardware that the job only loads on.";
+    LIMIT_LOAD_TO_HOSTS = "LimitLoadToHosts": Array, [MACOS], deprecated,
+        "Host names that the job only loads on, which is ignored.";
+    LIMIT_LOAD_TO_SESSION_TYPE = "LimitLoadToSessionType":
+        String|Array, [MACOS],
+        "The session types that the job loads in, such as `Aqua`.";
+    LOW_PRIORITY_BACKGROUND_IO = "LowPriorityBackgroundIO": Boolean, [MACOS],
+        "Whether the job's I/O is throttled while in the background.";
+    LOW_PRIORITY_IO = "LowPriorityIO": Boolean, [MACOS],
+        "Whether the job's file system I/O is throttled.";
+    MACH_SERVICES = "MachServices": Dictionary, [MACOS],
+        "The Mach services that the job provides.";
+    MATERIALIZE_DATALESS_FILES = "MaterializeDatalessFiles": Boolean, [MACOS],
+        "Whether reading dataless files downloads them.";
+    NICE = "Nice": Integer, [MACOS],
+        "The scheduling priority of the job, from -20 to 20.";
+    ON_DEMAND = "OnDemand": Boolean, [MACOS], deprecated,
+        "Replaced by `KeepAlive`.";
+    PROCESS_TYPE = "ProcessType": String, [MACOS],
+        "The resource limits class of the job, such as `Background`.";
+    PROGRAM = "Program": String, [MACOS], "The path of the executable.";
+    PROGRAM_ARGUMENTS = "ProgramArguments": Array, [MACOS],
+        "The arguments of the job, starting with the executable.";
+    QUEUE_DIRECTORIES = "QueueDirectories": Array, [MACOS],
+        "Directories that start the job while they are not empty.";
+    ROOT_DIRECTORY = "RootDirectory": String, [MACOS],
+        "The directory to `chroot` to before running the job.";
+    RUN_AT_LOAD = "RunAtLoad": Boolean, [MACOS],
+        "Whether the job is started when it is loaded.";
+    SERVICE_IPC = "ServiceIPC": Boolean, [MACOS],
+        "Whether the job checks in with `launchd`.";
+    SESSION_CREATE = "SessionCreate": Boolean, [MACOS],
+        "Whether the job runs in its own security audit session.";
+    SOCKETS = "Sockets": Dictionary, [MACOS],
+        "Sockets that `launchd` listens on and passes to the job.";
+    SOFT_RESOURCE_LIMITS = "SoftResourceLimits": Dictionary, [MACOS],
+        "Soft resource limits to apply to the job.";
+    STANDARD_ERROR_PATH = "StandardErrorPath": String, [MACOS],
+        "The path that standard error is written to.";
+    STANDARD_IN_PATH = "StandardInPath": String, [MACOS],
+        "The path that standard input is read from.";
+    STANDARD_OUT_PATH = "StandardOutPath": String, [MACOS],
+        "The path that standard output is written to.";
+    START_CALENDAR_INTERVAL = "StartCalendarInterval":
+        Dictionary|Array, [MACOS],
+        "Calendar dates on which the job is started, like `cron`.";
+    START_INTERVAL = "StartInterval": Integer, [MACOS],
+        "The number of seconds between starts of the job.";
+    START_ON_MOUNT = "StartOnMount": Boolean, [MACOS],
+        "Whether the job is started when a file system is mounted.";
+    THROTTLE_INTERVAL = "ThrottleInterval": Integer, [MACOS],
+        "The minimum number of seconds between restarts of the job.";
+    TIME_OUT = "TimeOut": Integer, [MACOS],
+        "Seconds that the job may be idle before it is asked to exit.";
+    UMASK = "Umask": Integer|String, [MACOS],
+        "The file mode creation mask of the job.";
+    USER_NAME = "UserName": String, [MACOS], "The user to run the job as.";
+    WAIT_FOR_DEBUGGER = "WaitForDebugger": Boolean, [MACOS],
+        "Whether the job waits for a debugger to attach before running.";
+    WATCH_PATHS = "WatchPaths": Array, [MACOS],
+        "Paths that start the job when they are modified.";
+    WORKING_DIRECTORY = "WorkingDirectory": String, [MACOS],
+        "The directory that the job runs in.";
+    INETD_COMPATIBILITY = "inetdCompatibility": Dictionary, [MACOS],
+        "Whether the job runs like an `inetd` service.";
+}
@@ -0,0 +1,374 @@
+//! Documented keys of [`Info.plist`] and [`launchd.plist`] files.
+//!
+//! Each key is a [`Key`] constant that carries its name along with the types
+//! it accepts, the platforms that read it, whether it is deprecated, and a
+//! short description. Constants are named the same way as those generated by
+//! [`info_plist_consts!`], so `NSCameraUsageDescription` is
+//! [`info::NS_CAMERA_USAGE_DESCRIPTION`].
+//!
+//! Unlike most of this crate, this module needs no features.
+//!
+//! # Examples
+//!
+//! Keys can be looked up by name, and a misspelled name can be matched to the
+//! key it was probably meant to be:
+//!
+//! ```rust
+//! use embed_plist::keys::{info, Platforms, ValueType};
+//!
+//! let key = info::find("NSCameraUsageDescription").unwrap();
+//! assert_eq!(key, &info::NS_CAMERA_USAGE_DESCRIPTION);
+//! assert_eq!(key.types(), [ValueType::String]);
+//! assert!(key.platforms().contains(Platforms::IOS));
+//!
+//! assert_eq!(info::find("NSCameraUsageDescripton"), None);
+//! assert_eq!(
+//!     info::closest("NSCameraUsageDescripton"),
+//!     Some(&info::NS_CAMERA_USAGE_DESCRIPTION),
+//! );
+//! ```
+//!
+//! [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
+//! [`launchd.plist`]: https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html#//apple_ref/doc/uid/TP40001762-104142
+//! [`Key`]: struct.Key.html
+//! [`info_plist_consts!`]: ../macro.info_plist_consts.html
+//! [`info::NS_CAMERA_USAGE_DESCRIPTION`]: info/constant.NS_CAMERA_USAGE_DESCRIPTION.html
+
+use core::fmt;
+
+// Defines a constant for each key, documented by its description, and `ALL`
+// with every constant in order.
+macro_rules! keys {
+    ($(
+        $name:ident = $key:literal: $($ty:ident)|+, [$($platform:ident),+],
+            $($deprecated:ident,)? $description:literal;
+    )+) => {
+        $(
+            #[doc = $description]
+            pub const $name: Key = Key {
+                name: $key,
+                types: &[$(ValueType::$ty),+],
+                platforms: Platforms::NONE$(.union(Platforms::$platform))+,
+                deprecated: keys!(@deprecated $($deprecated)?),
+                description: $description,
+            };
+        )+
+
+        /// Every key in this module, sorted by name.
+        pub const ALL: &[Key] = &[$($name),+];
+
+        /// Returns the key named `name`, which is case-sensitive.
+        #[inline]
+        pub fn find(name: &str) -> Option<&'static Key> {
+            super::find(ALL, name)
+        }
+
+        /// Returns the key that `name` most likely misspells, if any is within
+        /// two edits of it ignoring case, or `None` if `name` is a key.
+        #[inline]
+        pub fn closest(name: &str) -> Option<&'static Key> {
+            super::closest(ALL, name)
+        }
+    };
+    (@deprecated deprecated) => { true };
+    (@deprecated) => { false };
+}
+
+pub mod info;
+pub mod launchd;
+
+/// A documented property list key.
+///
+/// Keys are the constants in [`info`] and [`launchd`], and cannot be created
+/// otherwise.
+///
+/// [`info`]: info/index.html
+/// [`launchd`]: launchd/index.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Key {
+    name: &'static str,
+    types: &'static [ValueType],
+    platforms: Platforms,
+    deprecated: bool,
+    description: &'static str,
+}
+
+impl Key {
+    /// Returns the name of the key, as written in the property list.
+    #[inline]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the types of value that the key accepts, most common first.
+    #[inline]
+    pub const fn types(&self) -> &'static [ValueType] {
+        self.types
+    }
+
+    /// Returns whether the key accepts a value of type `ty`.
+    #[inline]
+    pub fn accepts(&self, ty: ValueType) -> bool {
+        self.types.contains(&ty)
+    }
+
+    /// Returns the platforms that read the key.
+    #[inline]
+    pub const fn platforms(&self) -> Platforms {
+        self.platforms
+    }
+
+    /// Returns whether Apple has deprecated the key, either in favor of
+    /// another key or because it no longer has any effect.
+    #[inline]
+    pub const fn is_deprecated(&self) -> bool {
+        self.deprecated
+    }
+
+    /// Returns a short description of the key, which for deprecated keys also
+    /// names any replacement.
+    #[inline]
+    pub const fn description(&self) -> &'static str {
+        self.description
+    }
+}
+
+impl AsRef<str> for Key {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.name
+    }
+}
+
+impl fmt::Display for Key {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+/// The type of a property list value, as accepted by a [`Key`].
+///
+/// [`Key`]: struct.Key.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueType {
+    /// An `<array>`.
+    Array,
+    /// A `<dict>`.
+    Dictionary,
+    /// A `<true/>` or `<false/>`.
+    Boolean,
+    /// A `<data>`.
+    Data,
+    /// A `<date>`.
+    Date,
+    /// An `<integer>`.
+    Integer,
+    /// A `<real>`.
+    Real,
+    /// A `<string>`.
+    String,
+}
+
+impl ValueType {
+    /// Returns the type of `value`.
+    ///
+    /// A [`Value::Uid`] is written as a `<dict>`, so it is a
+    /// [`Dictionary`](#variant.Dictionary).
+    ///
+    /// This requires the `alloc` feature.
+    ///
+    /// [`Value::Uid`]: ../value/enum.Value.html#variant.Uid
+    #[cfg(feature = "alloc")]
+    pub fn of(value: &crate::value::Value) -> Self {
+        use crate::value::Value;
+
+        match value {
+            Value::Array(_) => ValueType::Array,
+            Value::Dictionary(_) | Value::Uid(_) => ValueType::Dictionary,
+            Value::Boolean(_) => ValueType::Boolean,
+            Value::Data(_) => ValueType::Data,
+            Value::Date(_) => ValueType::Date,
+            Value::Integer(_) => ValueType::Integer,
+            Value::Real(_) => ValueType::Real,
+            Value::String(_) => ValueType::String,
+        }
+    }
+}
+
+#[cfg(feature = "const_eval")]
+impl From<crate::NodeKind> for ValueType {
+    #[inline]
+    fn from(kind: crate::NodeKind) -> Self {
+        use crate::NodeKind;
+
+        match kind {
+            NodeKind::Array => ValueType::Array,
+            NodeKind::Dictionary => ValueType::Dictionary,
+            NodeKind::Boolean => ValueType::Boolean,
+            NodeKind::Data => ValueType::Data,
+            NodeKind::Date => ValueType::Date,
+            NodeKind::Integer => ValueType::Integer,
+            NodeKind::Real => ValueType::Real,
+            NodeKind::String => ValueType::String,
+        }
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ValueType::Array => "array",
+            ValueType::Dictionary => "dict",
+            ValueType::Boolean => "boolean",
+            ValueType::Data => "data",
+            ValueType::Date => "date",
+            ValueType::Integer => "integer",
+            ValueType::Real => "real",
+            ValueType::String => "string",
+        })
+    }
+}
+
+/// A set of Apple platforms that read a [`Key`].
+///
+/// [`Key`]: struct.Key.html
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Platforms(u8);
+
+impl Platforms {
+    /// No platforms.
+    pub const NONE: Platforms = Platforms(0);
+
+    /// macOS.
+    pub const MACOS: Platforms = Platforms(1);
+
+    /// iOS and iPadOS.
+    pub const IOS: Platforms = Platforms(1 << 1);
+
+    /// tvOS.
+    pub const TVOS: Platforms = Platforms(1 << 2);
+
+    /// watchOS.
+    pub const WATCHOS: Platforms = Platforms(1 << 3);
+
+    /// visionOS.
+    pub const VISIONOS: Platforms = Platforms(1 << 4);
+
+    /// Every platform.
+    pub const ALL: Platforms = Platforms(0b1_1111);
+
+    const NAMES: [(Platforms, &'static str); 5] = [
+        (Platforms::MACOS, "macOS"),
+        (Platforms::IOS, "iOS"),
+        (Platforms::TVOS, "tvOS"),
+        (Platforms::WATCHOS, "watchOS"),
+        (Platforms::VISIONOS, "visionOS"),
+    ];
+
+    /// Returns the platforms in either `self` or `other`.
+    #[inline]
+    pub const fn union(self, other: Platforms) -> Platforms {
+        Platforms(self.0 | other.0)
+    }
+
+    /// Returns whether every platform in `other` is in `self`.
+    #[inline]
+    pub const fn contains(self, other: Platforms) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns whether any platform in `other` is in `self`.
+    #[inline]
+    pub const fn intersects(self, other: Platforms) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Returns whether `self` has no platforms.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl fmt::Debug for Platforms {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut set = f.debug_set();
+        for &(platform, name) in Platforms::NAMES.iter() {
+            if self.contains(platform) {
+                set.entry(&format_args!("{}", name));
+            }
+        }
+        set.finish()
+    }
+}
+
+/// Writes the platforms as a list, such as `macOS, iOS`.
+impl fmt::Display for Platforms {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut first = true;
+        for &(platform, name) in Platforms::NAMES.iter() {
+            if self.contains(platform) {
+                if !first {
+                    f.write_str(", ")?;
+                }
+                f.write_str(name)?;
+                first = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Returns the key named `name` in `keys`, which is sorted by name.
+fn find(keys: &'static [Key], name: &str) -> Option<&'static Key> {
+    keys.binary_search_by(|key| key.name.cmp(name))
+        .ok()
+        .map(|index| &keys[index])
+}
+
+// The most edits for a name to be considered a misspelling of a key.
+const MAX_EDITS: usize = 2;
+
+// The longest name that is compared, which is longer than any key.
+const MAX_LEN: usize = 64;
+
+// Returns the key in `keys` that `name` most likely misspells.
+fn closest(keys: &'static [Key], name: &str) -> Option<&'static Key> {
+    if name.len() > MAX_LEN || find(keys, name).is_some() {
+        // Keys are shorter than `MAX_LEN`, so a longer name is never close.
+        return None;
+    }
+    let mut best: Option<(usize, &'static Key)> = None;
+    for key in keys {
+        let edits = edit_distance(key.name.as_bytes(), name.as_bytes());
+        let is_better = match best {
+            Some((fewest, _)) => edits < fewest,
+            None => edits <= MAX_EDITS,
+        };
+        if is_better {
+            best = Some((edits, key));
+        }
+    }
+    best.map(|(_, key)| key)
+}
+
+// Returns the Levenshtein distance between `a` and `b`, ignoring ASCII case.
+// Neither may be longer than `MAX_LEN`.
+fn edit_distance(a: &[u8], b: &[u8]) -> usize {
+    let mut row = [0; MAX_LEN + 1];
+    for (j, cell) in row.iter_mut().enumerate().take(b.len() + 1) {
+        *cell = j;
+    }
+    for (i, &a_byte) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &b_byte) in b.iter().enumerate() {
+            let substitution =
+                diagonal + usize::from(!a_byte.eq_ignore_ascii_case(&b_byte));
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
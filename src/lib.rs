@@ -27,11 +27,13 @@
 //! 3. [Minimum Supported Rust Version](#minimum-supported-rust-version)
 //! 4. [Multi-Target Considerations](#multi-target-considerations)
 //! 5. [Get Embedded Property Lists](#get-embedded-property-lists)
-//! 6. [Accidental Reuse Protection](#accidental-reuse-protection)
-//! 7. [Implementation](#implementation)
-//! 8. [License](#license)
-//! 9. [Macros](#macros)
-//! 10. [Functions](#functions)
+//! 6. [Preprocessing](#preprocessing)
+//! 7. [Accidental Reuse Protection](#accidental-reuse-protection)
+//! 8. [Implementation](#implementation)
+//! 9. [License](#license)
+//! 10. [Modules](#modules)
+//! 11. [Macros](#macros)
+//! 12. [Functions](#functions)
 //!
 //! # Motivation
 //!
@@ -67,6 +69,10 @@
 //! embed_plist::embed_launchd_plist!("launchd.plist");
 //! ```
 //!
+//! Like [`include_bytes!`], paths are relative to the calling source file. To
+//! resolve them relative to your package root instead, use
+//! [`embed_info_plist_from_manifest!`] and its `launchd.plist` equivalent.
+//!
 //! Done! It's that simple. 🙂
 //!
 //! See [implementation](#implementation) for details on this sorcery.
@@ -80,6 +86,10 @@
 //! result in a "major" library version update. In other words: `0.1.z` would
 //! become `0.2.0`, or `1.y.z` would become `2.0.0`.
 //!
+//! The optional `const_eval`, `binary`, `macho`, `alloc`, `std`, and `derive`
+//! features, which enable processing property lists at compile time and runtime,
+//! require Rust 1.83 or later.
+//!
 //! # Multi-Target Considerations
 //!
 //! This library only works for [Mach-O](https://en.wikipedia.org/wiki/Mach-O)
@@ -91,6 +101,32 @@
 //! embed_plist::embed_info_plist!("Info.plist");
 //! ```
 //!
+//! Alternatively, enabling the `portable` feature makes these macros embed into
+//! a plain static on targets other than Apple's. Then [`get_info_plist`] and
+//! [`get_launchd_plist`] return the embedded bytes on every target, which lets
+//! shared code read its own metadata without `#[cfg]`:
+//!
+//! ```toml
+//! [dependencies]
+//! embed_plist = { version = "1.2", features = ["portable"] }
+//! ```
+//!
+//! Apple platforms also differ in which `Info.plist` keys they expect. Rather
+//! than writing a `#[cfg]` for each one, [`embed_info_plist!`] can be given a
+//! file per target. Only the file for the current target is embedded, and
+//! nothing is embedded for targets without an entry:
+//!
+//! ```rust
+//! embed_plist::embed_info_plist! {
+//!     macos    => "Info-macOS.plist",
+//!     ios      => "Info-iOS.plist",
+//!     catalyst => "Info-macOS.plist",
+//! }
+//! ```
+//!
+//! With the `const_eval` feature, [`check_info_plist_targets!`] checks each file
+//! at compile time for keys that its target does not read.
+//!
 //! # Get Embedded Property Lists
 //!
 //! After using these macros, you can get their contents by calling
@@ -112,6 +148,44 @@
 //! # }
 //! ```
 //!
+//! With the `const_eval` feature, [`query`] reads a value out of the embedded
+//! bytes by its path, without allocating:
+//!
+//! ```rust
+//! # #[cfg(all(
+//! #     feature = "const_eval",
+//! #     any(target_vendor = "apple", feature = "portable"),
+//! # ))] {
+//! embed_plist::embed_info_plist!("Info.plist");
+//!
+//! let why = embed_plist::query(embed_plist::get_info_plist(), "Why");
+//! assert_eq!(why.and_then(|why| why.as_str()), Ok("To use in doc tests"));
+//! # }
+//! ```
+//!
+//! Strings are borrowed, so [`Node::as_str`] fails for one that contains a
+//! reference like `&amp;`. [`Node::read_str`] decodes it into a buffer instead.
+//!
+//! For reading a whole document, [`events`] provides a streaming pull parser
+//! that works the same way.
+//!
+//! If another object or a `-sectcreate` linker flag also adds data to the
+//! section, the `get_*` functions return it along with the property list.
+//! [`verify_info_plist`] and [`verify_launchd_plist`] check for this at runtime
+//! and describe any foreign contents, given the `verify` option:
+//!
+//! ```rust
+//! # #[cfg(any(target_vendor = "apple", feature = "portable"))] {
+//! embed_plist::embed_info_plist!("Info.plist", verify);
+//!
+//! assert!(embed_plist::verify_info_plist().is_ok());
+//! # }
+//! ```
+//!
+//! With the `const_eval` feature, the `integrity` option also stores a SHA-256
+//! digest of the bytes, and [`check_info_plist_integrity`] re-hashes them at
+//! runtime to detect the binary being patched after it was built.
+//!
 //! If the appropriate macro has not been called, each function creates a
 //! compile-time error by failing to reference the symbol defined by that macro:
 //!
@@ -121,6 +195,79 @@
 //! let embedded_plist = embed_plist::get_info_plist();
 //! ```
 //!
+//! # Preprocessing
+//!
+//! With the `const_eval` feature, the embedding macros accept options that
+//! transform the file at compile time before embedding it:
+//!
+//! ```rust
+//! # #[cfg(feature = "const_eval")]
+//! embed_plist::embed_info_plist!("Info.plist", normalize, canonicalize);
+//! ```
+//!
+//! - `normalize` converts line endings to LF and strips the UTF-8 byte order
+//!   mark.
+//!
+//! - `canonicalize` sorts keys and removes insignificant whitespace, comments,
+//!   and the DOCTYPE.
+//!
+//! - `openstep` converts an OpenStep (ASCII) property list, as written by older
+//!   tools and `defaults`, to XML.
+//!
+//! The [`build`] module, enabled by the `std` feature, provides the same
+//! transformations for build scripts, where they can also report what changed.
+//!
+//! When Rust code is linked into an executable by Xcode, CMake, or another build
+//! system, [`build::info_plist_object`] writes a Mach-O object file that embeds
+//! the property list and can be passed to the final link step instead.
+//!
+//! The `const_eval` feature also provides [`const_value!`] for reading values
+//! out of property list bytes at compile time.
+//!
+//! To have a constant for every key instead, call
+//! [`build::info_plist_consts`] from a build script and include the generated
+//! module with [`info_plist_consts!`], which also embeds the file.
+//!
+//! Property lists can also be written in JSON or TOML. Convert them from a
+//! build script with [`build::json_to_plist`] or [`build::toml_to_plist`], and
+//! embed the result with [`embed_info_plist_json!`], [`embed_info_plist_toml!`],
+//! [`embed_launchd_plist_json!`], or [`embed_launchd_plist_toml!`]. Dates and
+//! data are written as `{ "$date": "2020-08-30T12:34:56Z" }` and
+//! `{ "$data": "<base64>" }`, and TOML's own date-times also work.
+//!
+//! With the `derive` feature, the property list can instead be written as a
+//! Rust struct that derives [`InfoPlist`], which produces the bytes to embed at
+//! compile time and reads them back at runtime.
+//!
+//! For property lists built at runtime, such as by installers, the `alloc`
+//! feature provides the [`value`] module and builders in the [`builder`] module.
+//!
+//! To compare property lists by their contents rather than their bytes, such as
+//! after canonicalizing or converting to binary, use [`assert_plist_eq!`] in tests
+//! or [`Value::diff`] for a list of changes that can be applied as a patch.
+//!
+//! Apple's tools often convert `Info.plist` to the binary format. The `binary`
+//! feature provides the [`binary`] module for reading it without allocating, such
+//! as from [`get_info_plist`], and with `alloc`, [`Value`] reads and writes it.
+//!
+//! To check a built binary from any platform, the `macho` feature provides the
+//! [`macho`] module, which reads `__TEXT,__info_plist` from a Mach-O file and
+//! compares it with the `Info.plist` hash bound by its code signature and with
+//! the platform and minimum OS version of its load commands.
+//!
+//! For a whole workspace, `cargo embed-plist check` from the
+//! [`cargo-embed-plist`](https://crates.io/crates/cargo-embed-plist) subcommand
+//! checks the file passed to each embedding macro, and after `cargo build`,
+//! that each linked binary contains its sections. It also flags keys that have
+//! the wrong type, are deprecated, or look like typos, using the documented keys
+//! of `Info.plist` and `launchd.plist` in the [`keys`] module.
+//!
+//! Entitlements can be embedded with [`embed_entitlements!`], which places the
+//! file in `__TEXT,__entitlements` and a DER encoding computed at compile time
+//! in `__TEXT,__ents_der`, as the linker does for simulator builds. This
+//! requires the `const_eval` feature, and [`Value::from_der`] reads the DER
+//! form back.
+//!
 //! # Accidental Reuse Protection
 //!
 //! Only one copy of `Info.plist` or `launchd.plist` should exist in a binary.
@@ -151,6 +298,12 @@
 //! error: aborting due to previous error
 //! ```
 //!
+//! When the macros are called in different crates, the linker only names the
+//! symbol. Running `cargo embed-plist which` from the
+//! [`cargo-embed-plist`](https://crates.io/crates/cargo-embed-plist) subcommand
+//! lists each crate and object file in the target directory that defines it,
+//! along with the source and property list files they were built from.
+//!
 //! <p style="background:rgba(255, 181, 77, 0.16);padding:0.75em;">
 //! <b>Warning:</b> Although the name
 //! <code style="background:rgba(41, 24, 0, 0.1);">_EMBED_INFO_PLIST</code>
@@ -293,6 +446,38 @@
 //!
 //! at your choosing.
 //!
+//! [`embed_info_plist!`]: macro.embed_info_plist.html
+//! [`check_info_plist_targets!`]: macro.check_info_plist_targets.html
+//! [`embed_info_plist_from_manifest!`]: macro.embed_info_plist_from_manifest.html
+//! [`binary`]:            binary/index.html
+//! [`macho`]:             macho/index.html
+//! [`build`]:             build/index.html
+//! [`keys`]:              keys/index.html
+//! [`builder`]:           builder/index.html
+//! [`value`]:             value/index.html
+//! [`Value`]:             value/enum.Value.html
+//! [`const_value!`]:      macro.const_value.html
+//! [`info_plist_consts!`]: macro.info_plist_consts.html
+//! [`build::info_plist_consts`]: build/fn.info_plist_consts.html
+//! [`build::json_to_plist`]: build/fn.json_to_plist.html
+//! [`build::toml_to_plist`]: build/fn.toml_to_plist.html
+//! [`build::info_plist_object`]: build/fn.info_plist_object.html
+//! [`embed_info_plist_json!`]: macro.embed_info_plist_json.html
+//! [`embed_info_plist_toml!`]: macro.embed_info_plist_toml.html
+//! [`embed_launchd_plist_json!`]: macro.embed_launchd_plist_json.html
+//! [`embed_launchd_plist_toml!`]: macro.embed_launchd_plist_toml.html
+//! [`InfoPlist`]: derive.InfoPlist.html
+//! [`query`]:             fn.query.html
+//! [`Node::as_str`]:      struct.Node.html#method.as_str
+//! [`Node::read_str`]:    struct.Node.html#method.read_str
+//! [`events`]:            events/index.html
+//! [`verify_info_plist`]: fn.verify_info_plist.html
+//! [`verify_launchd_plist`]: fn.verify_launchd_plist.html
+//! [`check_info_plist_integrity`]: fn.check_info_plist_integrity.html
+//! [`embed_entitlements!`]: macro.embed_entitlements.html
+//! [`Value::from_der`]:   value/enum.Value.html#method.from_der
+//! [`assert_plist_eq!`]: macro.assert_plist_eq.html
+//! [`Value::diff`]:       value/enum.Value.html#method.diff
 //! [`get_info_plist`]:    fn.get_info_plist.html
 //! [`get_launchd_plist`]: fn.get_launchd_plist.html
 //!
@@ -321,11 +506,188 @@
 #[doc(hidden)]
 pub use core as _core;
 
+// Compile-time preprocessing for the options of the embedding macros. It is not
+// part of this crate's public API, so I reserve the right to change or remove
+// this in a SemVer-compatible update.
+#[cfg(feature = "const_eval")]
+#[doc(hidden)]
+pub mod const_eval;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "binary")]
+pub mod binary;
+
+#[cfg(feature = "std")]
+pub mod build;
+
+#[cfg(feature = "const_eval")]
+pub mod events;
+
+#[cfg(feature = "macho")]
+pub mod macho;
+
+#[cfg(feature = "alloc")]
+pub mod builder;
+
+pub mod keys;
+
+#[cfg(feature = "alloc")]
+pub mod value;
+
+#[cfg(feature = "const_eval")]
+mod query;
+
+#[cfg(feature = "const_eval")]
+mod integrity;
+
+mod verify;
+
+#[cfg(feature = "const_eval")]
+pub use crate::const_eval::Error;
+
+#[cfg(feature = "const_eval")]
+pub use crate::query::{query, Node, NodeKind};
+
+#[cfg(feature = "const_eval")]
+pub use crate::integrity::{
+    check_info_plist_integrity, check_launchd_plist_integrity,
+    info_plist_sha256, launchd_plist_sha256, IntegrityError,
+};
+
+pub use crate::verify::{
+    verify_info_plist, verify_launchd_plist, SectionMismatch,
+};
+
+/// Derives writing a struct as an [`Info.plist`] at compile time and reading it
+/// back at runtime.
+///
+/// This requires the `derive` feature, which needs Rust 1.83 or later.
+///
+/// Each field becomes a key of the top-level dictionary, named after the field
+/// unless it has `#[plist(rename = "Key")]`. Fields can be `&'static str`,
+/// `bool`, `i64`, `u64`, another struct that derives `InfoPlist` marked with
+/// `#[plist(nested)]` (which becomes a nested dictionary), or an `Option` of
+/// one of these (which is omitted when `None`). Both can be given at once, as
+/// in `#[plist(rename = "Key", nested)]`.
+///
+/// The derive adds these methods:
+///
+/// - `const fn plist_len(&self) -> usize` returns the length of the XML
+///   property list.
+///
+/// - `const fn to_plist<const N: usize>(&self) -> [u8; N]` writes the XML
+///   property list, where `N` is the `plist_len`.
+///
+/// - `fn from_plist(bytes: &'static [u8]) -> Result<Self, Error>` reads the
+///   value back, such as from [`get_info_plist`].
+///
+/// Keys are written in field order. Strings are borrowed when read, so
+/// `from_plist` fails with [`Error::UnsupportedString`] for strings that
+/// contain character references. `to_plist` avoids these by writing strings
+/// containing `&` or `<` as CDATA sections. Strings containing `]]>` or
+/// carriage returns cannot be read back this way, so `plist_len` and
+/// `to_plist` panic for them, which is a compile-time error in a `const`.
+///
+/// # Examples
+///
+/// ```rust
+/// use embed_plist::InfoPlist;
+///
+/// #[derive(InfoPlist)]
+/// struct Info {
+///     #[plist(rename = "CFBundleIdentifier")]
+///     identifier: &'static str,
+///     #[plist(rename = "LSUIElement")]
+///     ui_element: bool,
+///     #[plist(rename = "NSHumanReadableCopyright")]
+///     copyright: Option<&'static str>,
+/// }
+///
+/// const INFO: Info = Info {
+///     identifier: "com.example.app",
+///     ui_element: true,
+///     copyright: None,
+/// };
+///
+/// embed_plist::embed_info_plist_bytes!(&INFO.to_plist::<{ INFO.plist_len() }>());
+///
+/// # #[cfg(any(target_vendor = "apple", feature = "portable"))] {
+/// let info = Info::from_plist(embed_plist::get_info_plist()).unwrap();
+/// assert_eq!(info.identifier, "com.example.app");
+/// # }
+/// ```
+///
+/// Unsupported field types are rejected at compile time, including structs
+/// without `#[plist(nested)]`:
+///
+/// ```compile_fail
+/// #[derive(embed_plist::InfoPlist)]
+/// struct Info {
+///     version: f32,
+/// }
+/// ```
+///
+/// [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
+/// [`get_info_plist`]: fn.get_info_plist.html
+/// [`Error::UnsupportedString`]: enum.Error.html#variant.UnsupportedString
+#[cfg(feature = "derive")]
+pub use embed_plist_derive::InfoPlist;
+
 /// Embeds the [`Info.plist`] file at `$path` directly in the current binary.
 ///
 /// After using this macro, you can get its content by calling
 /// [`get_info_plist`] from anywhere in your program.
 ///
+/// # Multiple Targets
+///
+/// Different Apple platforms expect different keys, such as
+/// `LSMinimumSystemVersion` on macOS and `MinimumOSVersion` on iOS. Instead of
+/// a single `$path`, this macro can be given a `target => $path` map. The file
+/// for the target being built is embedded and the rest are ignored.
+///
+/// ```rust
+/// embed_plist::embed_info_plist! {
+///     macos    => "Info-macOS.plist",
+///     ios      => "Info-iOS.plist",
+///     tvos     => "Info-iOS.plist",
+///     watchos  => "Info-iOS.plist",
+///     catalyst => "Info-macOS.plist",
+/// }
+/// ```
+///
+/// The supported targets are:
+///
+/// | Target     | Condition                                                  |
+/// | ---------- | ---------------------------------------------------------- |
+/// | `macos`    | `target_os = "macos"`                                      |
+/// | `ios`      | `target_os = "ios"` and not `target_abi = "macabi"`        |
+/// | `tvos`     | `target_os = "tvos"`                                       |
+/// | `watchos`  | `target_os = "watchos"`                                    |
+/// | `catalyst` | `target_os = "ios"` and `target_abi = "macabi"`            |
+///
+/// Nothing is embedded when building for a target that is not in the map, in
+/// which case calling [`get_info_plist`] is a linker error.
+///
+/// Note that `target_abi` is only set by Rust 1.78 and later. With earlier
+/// versions, Mac Catalyst builds use the `ios` entry.
+///
+/// The files are embedded as they are. To also check them against the
+/// platforms in [`keys::info`], pass the same map to
+/// [`check_info_plist_targets!`].
+///
+/// Targets other than the ones above are a compile error:
+///
+/// ```compile_fail
+/// embed_plist::embed_info_plist! {
+///     linux => "Info.plist",
+/// }
+/// ```
+///
 /// # Accidental Reuse Protection
 ///
 /// Only one copy of `Info.plist` should exist in a binary. Accidentally embedding
@@ -366,14 +728,156 @@ pub use core as _core;
 /// </p>
 ///
 /// [`get_info_plist`]: fn.get_info_plist.html
+/// [`keys::info`]: keys/info/index.html
+/// [`check_info_plist_targets!`]: macro.check_info_plist_targets.html
 /// [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
 #[macro_export]
 macro_rules! embed_info_plist {
+    ($($target:ident => $path:expr),+ $(,)?) => {
+        $(
+            $crate::_embed_plist_for_target!(
+                $target,
+                $crate::embed_info_plist!($path);
+            );
+        )+
+    };
+    ($path:expr, $($option:ident),+ $(,)?) => {
+        $crate::embed_info_plist_bytes!(
+            $crate::_core::include_bytes!($path),
+            $($option),+
+        );
+    };
     ($path:expr) => {
         $crate::embed_info_plist_bytes!($crate::_core::include_bytes!($path));
     };
 }
 
+// Emits `$item` only when building for `$target`.
+//
+// This is not part of this crate's public API, so I reserve the right to change
+// or remove this in a SemVer-compatible update.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _embed_plist_for_target {
+    (macos, $($item:tt)*) => {
+        #[cfg(target_os = "macos")]
+        $($item)*
+    };
+    (ios, $($item:tt)*) => {
+        #[cfg(all(target_os = "ios", not(target_abi = "macabi")))]
+        $($item)*
+    };
+    (tvos, $($item:tt)*) => {
+        #[cfg(target_os = "tvos")]
+        $($item)*
+    };
+    (watchos, $($item:tt)*) => {
+        #[cfg(target_os = "watchos")]
+        $($item)*
+    };
+    (catalyst, $($item:tt)*) => {
+        #[cfg(all(target_os = "ios", target_abi = "macabi"))]
+        $($item)*
+    };
+    ($target:ident, $($item:tt)*) => {
+        $crate::_core::compile_error!($crate::_core::concat!(
+            "unsupported target `",
+            $crate::_core::stringify!($target),
+            "`, expected one of: `macos`, `ios`, `tvos`, `watchos`, `catalyst`",
+        ));
+    };
+}
+
+/// Checks the [`Info.plist`] file of each target in a `target => $path` map,
+/// as given to [`embed_info_plist!`], at compile time.
+///
+/// Each file is checked for keys in [`keys::info`] that its target does not
+/// read, such as `LSMinimumSystemVersion` for `ios`. Mac Catalyst reads both
+/// macOS and iOS keys. Keys that are not in [`keys::info`] are allowed, and no
+/// key is required.
+///
+/// The platforms in [`keys::info`] are maintained by hand from Apple's
+/// documentation, which does not always list every platform that reads a key.
+/// This is why the check is separate from [`embed_info_plist!`]: a file that it
+/// rejects may still be valid.
+///
+/// This requires the `const_eval` feature, which needs Rust 1.83 or later.
+///
+/// # Examples
+///
+/// Every file is checked, not just the one for the target being built:
+///
+/// ```rust
+/// # #[cfg(feature = "const_eval")]
+/// embed_plist::check_info_plist_targets! {
+///     macos    => "Info-macOS.plist",
+///     ios      => "Info-iOS.plist",
+///     catalyst => "Info-macOS.plist",
+/// }
+/// ```
+///
+/// A key of another platform is a compile-time error:
+///
+/// ```compile_fail
+/// embed_plist::check_info_plist_targets! {
+///     ios => "Info-macOS.plist",
+/// }
+/// ```
+///
+/// [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
+/// [`embed_info_plist!`]: macro.embed_info_plist.html
+/// [`keys::info`]: keys/info/index.html
+#[cfg(feature = "const_eval")]
+#[macro_export]
+macro_rules! check_info_plist_targets {
+    ($($target:ident => $path:expr),+ $(,)?) => {
+        $(
+            const _: () = $crate::const_eval::check_info_plist_target(
+                $crate::_core::include_bytes!($path),
+                $crate::_core::stringify!($target),
+            );
+        )+
+    };
+}
+
+/// Embeds the [`Info.plist`] file at `$path` relative to the package root.
+///
+/// Unlike [`embed_info_plist!`], which resolves `$path` relative to the
+/// calling source file like [`include_bytes!`], this macro resolves it relative
+/// to the directory containing your crate's `Cargo.toml`. This means the call
+/// can be moved between modules without updating `$path`.
+///
+/// # Examples
+///
+/// ```rust
+/// embed_plist::embed_info_plist_from_manifest!("src/Info.plist");
+/// ```
+///
+/// If the file does not exist, the compile error includes the full path that
+/// was searched:
+///
+/// ```text
+/// error: couldn't read `/path/to/my-crate/resources/Info.plist`: No such file or directory (os error 2)
+/// ```
+///
+/// [`embed_info_plist!`]: macro.embed_info_plist.html
+///
+/// [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
+/// [`include_bytes!`]: https://doc.rust-lang.org/std/macro.include_bytes.html
+#[macro_export]
+macro_rules! embed_info_plist_from_manifest {
+    ($path:expr $(, $option:ident)* $(,)?) => {
+        $crate::embed_info_plist_bytes!(
+            $crate::_core::include_bytes!($crate::_core::concat!(
+                $crate::_core::env!("CARGO_MANIFEST_DIR"),
+                "/",
+                $path,
+            )),
+            $($option),*
+        );
+    };
+}
+
 /// Embeds the [`Info.plist`] file in `&[u8]` directly in the current binary.
 ///
 /// This enables you to have more control over what bytes are embedded into your
@@ -383,6 +887,50 @@ macro_rules! embed_info_plist {
 /// The [`embed_info_plist!`] macro is a convenience wrapper around this and
 /// [`include_bytes!`].
 ///
+/// # Options
+///
+/// Options may be listed after `$bytes` to preprocess them at compile time.
+/// Except for `verify`, these require the `const_eval` feature, which needs
+/// Rust 1.83 or later.
+///
+/// - `normalize`: converts CRLF and CR line endings to LF and strips a leading
+///   UTF-8 byte order mark. This makes the embedded bytes identical regardless
+///   of how the file was checked out, such as with Git's `core.autocrlf`.
+///   UTF-16 input is rejected with a compile-time error.
+///
+/// - `canonicalize`: re-serializes the property list into a canonical form
+///   with sorted keys and no whitespace, comments, or DOCTYPE. This shrinks the
+///   embedded section and makes embedded property lists comparable across
+///   binaries. Invalid property lists are a compile-time error. See
+///   [`build::canonicalize`] for the exact form and for reporting the size
+///   saved.
+///
+/// - `openstep`: converts an OpenStep (ASCII) property list, such as
+///   `{ Label = "com.example"; ProgramArguments = (a, b); }`, to XML. Keys
+///   keep their order, and GNUstep's `<*I1>`, `<*R1.5>`, `<*BY>`, `<*BN>`,
+///   and `<*D2020-08-30 00:00:00 +0000>` forms become integers, reals,
+///   booleans, and dates. Other values are strings, as in OpenStep. Put this
+///   before `canonicalize` to also sort the keys.
+///
+/// - `verify`: also stores the length of the embedded bytes, which
+///   [`verify_info_plist`] needs to detect foreign data in the section. This
+///   adds a symbol to the binary, so it is left out unless requested.
+///
+/// - `integrity`: also stores a SHA-256 digest of the embedded bytes, which
+///   [`check_info_plist_integrity`] compares against at runtime.
+///
+/// ```rust
+/// # #[cfg(feature = "const_eval")]
+/// embed_plist::embed_info_plist_bytes!(b"\xEF\xBB\xBF<plist/>\r\n", normalize);
+/// ```
+///
+/// The same options are accepted by [`embed_info_plist!`]:
+///
+/// ```rust
+/// # #[cfg(feature = "const_eval")]
+/// embed_plist::embed_info_plist!("Info.plist", normalize);
+/// ```
+///
 /// # Examples
 ///
 /// After using this macro, you can get its content by calling
@@ -408,12 +956,20 @@ macro_rules! embed_info_plist {
 ///
 /// [`get_info_plist`]: fn.get_info_plist.html
 /// [`embed_info_plist!`]: macro.embed_info_plist.html
+/// [`build::canonicalize`]: build/fn.canonicalize.html
+/// [`verify_info_plist`]: fn.verify_info_plist.html
+/// [`check_info_plist_integrity`]: fn.check_info_plist_integrity.html
 ///
 /// [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
 /// [`include_bytes!`]: https://doc.rust-lang.org/std/macro.include_bytes.html
 #[macro_export]
 macro_rules! embed_info_plist_bytes {
-    ($bytes:expr) => {
+    ($bytes:expr, $($option:ident),+ $(,)?) => {
+        $crate::_embed_plist_with_options!(
+            info_plist [], $bytes; $($option),+
+        );
+    };
+    ($bytes:expr $(,)?) => {
         // The wildcard `_` prevents polluting the call site with identifiers.
         const _: () = {
             // Because `len` is a `const fn`, we can use it to turn `SLICE` into
@@ -431,13 +987,7 @@ macro_rules! embed_info_plist_bytes {
             const PTR: *const [u8; LEN] = SLICE.as_ptr() as *const _;
             const REF: &[u8; LEN] = unsafe { Transmute { from: PTR }.into };
 
-            // Prevents this from being optimized out of the binary.
-            #[used]
-            // Places this data in the correct location.
-            #[link_section = "__TEXT,__info_plist,regular,no_dead_strip"]
-            // Prevents repeated use by creating a linker error.
-            #[no_mangle]
-            pub static _EMBED_INFO_PLIST: [u8; LEN] = *REF;
+            $crate::_embed_plist_static!(info_plist: [u8; LEN] = *REF);
         };
     };
 }
@@ -490,6 +1040,12 @@ macro_rules! embed_info_plist_bytes {
 /// [`launchd.plist`]: https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html#//apple_ref/doc/uid/TP40001762-104142
 #[macro_export]
 macro_rules! embed_launchd_plist {
+    ($path:expr, $($option:ident),+ $(,)?) => {
+        $crate::embed_launchd_plist_bytes!(
+            $crate::_core::include_bytes!($path),
+            $($option),+
+        );
+    };
     ($path:expr) => {
         $crate::embed_launchd_plist_bytes!($crate::_core::include_bytes!(
             $path
@@ -497,6 +1053,44 @@ macro_rules! embed_launchd_plist {
     };
 }
 
+/// Embeds the [`launchd.plist`] file at `$path` relative to the package root.
+///
+/// Unlike [`embed_launchd_plist!`], which resolves `$path` relative to the
+/// calling source file like [`include_bytes!`], this macro resolves it relative
+/// to the directory containing your crate's `Cargo.toml`. This means the call
+/// can be moved between modules without updating `$path`.
+///
+/// # Examples
+///
+/// ```rust
+/// embed_plist::embed_launchd_plist_from_manifest!("src/launchd.plist");
+/// ```
+///
+/// If the file does not exist, the compile error includes the full path that
+/// was searched:
+///
+/// ```text
+/// error: couldn't read `/path/to/my-crate/resources/launchd.plist`: No such file or directory (os error 2)
+/// ```
+///
+/// [`embed_launchd_plist!`]: macro.embed_launchd_plist.html
+///
+/// [`launchd.plist`]: https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html#//apple_ref/doc/uid/TP40001762-104142
+/// [`include_bytes!`]: https://doc.rust-lang.org/std/macro.include_bytes.html
+#[macro_export]
+macro_rules! embed_launchd_plist_from_manifest {
+    ($path:expr $(, $option:ident)* $(,)?) => {
+        $crate::embed_launchd_plist_bytes!(
+            $crate::_core::include_bytes!($crate::_core::concat!(
+                $crate::_core::env!("CARGO_MANIFEST_DIR"),
+                "/",
+                $path,
+            )),
+            $($option),*
+        );
+    };
+}
+
 /// Embeds the [`launchd.plist`] file in `&[u8]` directly in the current binary.
 ///
 /// This enables you to have more control over what bytes are embedded into your
@@ -506,6 +1100,50 @@ macro_rules! embed_launchd_plist {
 /// The [`embed_launchd_plist!`] macro is a convenience wrapper around this and
 /// [`include_bytes!`].
 ///
+/// # Options
+///
+/// Options may be listed after `$bytes` to preprocess them at compile time.
+/// Except for `verify`, these require the `const_eval` feature, which needs
+/// Rust 1.83 or later.
+///
+/// - `normalize`: converts CRLF and CR line endings to LF and strips a leading
+///   UTF-8 byte order mark. This makes the embedded bytes identical regardless
+///   of how the file was checked out, such as with Git's `core.autocrlf`.
+///   UTF-16 input is rejected with a compile-time error.
+///
+/// - `canonicalize`: re-serializes the property list into a canonical form
+///   with sorted keys and no whitespace, comments, or DOCTYPE. This shrinks the
+///   embedded section and makes embedded property lists comparable across
+///   binaries. Invalid property lists are a compile-time error. See
+///   [`build::canonicalize`] for the exact form and for reporting the size
+///   saved.
+///
+/// - `openstep`: converts an OpenStep (ASCII) property list, such as
+///   `{ Label = "com.example"; ProgramArguments = (a, b); }`, to XML. Keys
+///   keep their order, and GNUstep's `<*I1>`, `<*R1.5>`, `<*BY>`, `<*BN>`,
+///   and `<*D2020-08-30 00:00:00 +0000>` forms become integers, reals,
+///   booleans, and dates. Other values are strings, as in OpenStep. Put this
+///   before `canonicalize` to also sort the keys.
+///
+/// - `verify`: also stores the length of the embedded bytes, which
+///   [`verify_launchd_plist`] needs to detect foreign data in the section.
+///   This adds a symbol to the binary, so it is left out unless requested.
+///
+/// - `integrity`: also stores a SHA-256 digest of the embedded bytes, which
+///   [`check_launchd_plist_integrity`] compares against at runtime.
+///
+/// ```rust
+/// # #[cfg(feature = "const_eval")]
+/// embed_plist::embed_launchd_plist_bytes!(b"\xEF\xBB\xBF<plist/>\r\n", normalize);
+/// ```
+///
+/// The same options are accepted by [`embed_launchd_plist!`]:
+///
+/// ```rust
+/// # #[cfg(feature = "const_eval")]
+/// embed_plist::embed_launchd_plist!("launchd.plist", normalize);
+/// ```
+///
 /// # Examples
 ///
 /// After using this macro, you can get its content by calling
@@ -538,12 +1176,20 @@ macro_rules! embed_launchd_plist {
 ///
 /// [`get_launchd_plist`]: fn.get_launchd_plist.html
 /// [`embed_launchd_plist!`]: macro.embed_launchd_plist.html
+/// [`build::canonicalize`]: build/fn.canonicalize.html
+/// [`verify_launchd_plist`]: fn.verify_launchd_plist.html
+/// [`check_launchd_plist_integrity`]: fn.check_launchd_plist_integrity.html
 ///
 /// [`launchd.plist`]: https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html#//apple_ref/doc/uid/TP40001762-104142
 /// [`include_bytes!`]: https://doc.rust-lang.org/std/macro.include_bytes.html
 #[macro_export]
 macro_rules! embed_launchd_plist_bytes {
-    ($bytes:expr) => {
+    ($bytes:expr, $($option:ident),+ $(,)?) => {
+        $crate::_embed_plist_with_options!(
+            launchd_plist [], $bytes; $($option),+
+        );
+    };
+    ($bytes:expr $(,)?) => {
         // The wildcard `_` prevents polluting the call site with identifiers.
         const _: () = {
             // Because `len` is a `const fn`, we can use it to turn `SLICE` into
@@ -561,68 +1207,818 @@ macro_rules! embed_launchd_plist_bytes {
             const PTR: *const [u8; LEN] = SLICE.as_ptr() as *const _;
             const REF: &[u8; LEN] = unsafe { Transmute { from: PTR }.into };
 
-            // Prevents this from being optimized out of the binary.
-            #[used]
-            // Places this data in the correct location.
-            #[link_section = "__TEXT,__launchd_plist,regular,no_dead_strip"]
-            // Prevents repeated use by creating a linker error.
-            #[no_mangle]
-            pub static _EMBED_LAUNCHD_PLIST: [u8; LEN] = *REF;
+            $crate::_embed_plist_static!(launchd_plist: [u8; LEN] = *REF);
         };
     };
 }
 
-/// Returns the contents of the embedded [`Info.plist`] file.
+/// Embeds the [entitlements] file at `$path` directly in the current binary, in
+/// both XML and DER form.
 ///
-/// # Examples
-///
-/// We can verify that the result is correct by checking it against the file at
-/// runtime:
+/// The file is embedded as is in the `__TEXT,__entitlements` section, and its
+/// DER encoding is computed at compile time and embedded in the
+/// `__TEXT,__ents_der` section. These are the sections that the linker fills
+/// for simulator builds, where entitlements cannot come from a code signature.
+/// After using this macro, you can get their contents by calling
+/// [`get_entitlements`] and [`get_entitlements_der`].
 ///
-/// ```rust
-/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
-/// # std::env::set_current_dir("./src")?;
-/// embed_plist::embed_info_plist!("Info.plist");
+/// The DER form follows Apple's profile: the root dictionary and its entries
+/// are sorted by key, and only dictionaries, arrays, strings, booleans, and
+/// integers are allowed. Invalid entitlements, or ones with `<data>`, `<date>`,
+/// or `<real>` values, are a compile-time error.
 ///
-/// let embedded_plist = embed_plist::get_info_plist();
-/// let read_plist = std::fs::read("Info.plist")?;
+/// This requires the `const_eval` feature, which needs Rust 1.83 or later.
 ///
-/// assert_eq!(embedded_plist, read_plist.as_slice());
-/// # Ok(())
-/// # }
-/// ```
+/// # Accidental Reuse Protection
 ///
-/// If `embed_info_plist!` has not been called, this function creates a
-/// compile-time error by failing to reference the symbol defined by that macro:
+/// Like [`embed_info_plist!`], embedding entitlements more than once is a
+/// compile-time error:
 ///
 /// ```compile_fail
 /// # #[cfg(pass_reuse_doctest)]
 /// # compile_error!("hack to force a doctest compile error pre 1.43");
-/// let embedded_plist = embed_plist::get_info_plist();
+/// embed_plist::embed_entitlements!("app.entitlements");
+/// embed_plist::embed_entitlements!("app.entitlements");
 /// ```
 ///
-/// # Safety
-///
-/// This function relies on `_EMBED_INFO_PLIST` being defined within the
-/// `__TEXT,__info_plist` section. You **should not** define this symbol outside
-/// of using the macros provided by this library.
+/// <p style="background:rgba(255, 181, 77, 0.16);padding:0.75em;">
+/// <b>Warning:</b> Although the names
+/// <code style="background:rgba(41, 24, 0, 0.1);">_EMBED_ENTITLEMENTS</code>
+/// and
+/// <code style="background:rgba(41, 24, 0, 0.1);">_EMBED_ENTITLEMENTS_DER</code>
+/// can be seen here, you <strong>should not</strong> reference these symbols
+/// with e.g. an
+/// <code style="background:rgba(41, 24, 0, 0.1);">extern "C"</code>
+/// block. I reserve the right to change these names in a SemVer-compatible
+/// update.
+/// </p>
 ///
-/// [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
-#[inline]
-pub fn get_info_plist() -> &'static [u8] {
-    extern "C" {
-        // Using this symbol instead of section start to force a linker error if
-        // `embed_info_plist!` has not been called.
-        #[link_name = "_EMBED_INFO_PLIST"]
-        static START: [u8; 0];
+/// [entitlements]: https://developer.apple.com/documentation/bundleresources/entitlements
+/// [`get_entitlements`]: fn.get_entitlements.html
+/// [`get_entitlements_der`]: fn.get_entitlements_der.html
+/// [`embed_info_plist!`]: macro.embed_info_plist.html
+#[macro_export]
+macro_rules! embed_entitlements {
+    ($path:expr) => {
+        $crate::embed_entitlements_bytes!($crate::_core::include_bytes!($path));
+    };
+}
 
-        #[link_name = "\x01section$end$__TEXT$__info_plist"]
-        static END: [u8; 0];
-    }
-    unsafe {
+/// Embeds the [entitlements] in `&[u8]` directly in the current binary, in both
+/// XML and DER form.
+///
+/// The [`embed_entitlements!`] macro is a convenience wrapper around this and
+/// [`include_bytes!`].
+///
+/// This requires the `const_eval` feature, which needs Rust 1.83 or later.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(all(
+/// #     feature = "const_eval",
+/// #     any(target_vendor = "apple", feature = "portable"),
+/// # ))] {
+/// const ENTITLEMENTS: &[u8] = br#"
+///     <?xml version="1.0" encoding="UTF-8"?>
+///     <!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+///     <plist version="1.0">
+///     <dict>
+///         <key>com.apple.security.app-sandbox</key>
+///         <true/>
+///     </dict>
+///     </plist>
+/// "#;
+///
+/// embed_plist::embed_entitlements_bytes!(ENTITLEMENTS);
+///
+/// assert_eq!(embed_plist::get_entitlements(), ENTITLEMENTS);
+/// assert_eq!(embed_plist::get_entitlements_der(), &[
+///     0x70, 0x2A, 0x02, 0x01, 0x01, 0xB0, 0x25, 0x30, 0x23, 0x0C, 0x1E,
+///     b'c', b'o', b'm', b'.', b'a', b'p', b'p', b'l', b'e', b'.',
+///     b's', b'e', b'c', b'u', b'r', b'i', b't', b'y', b'.',
+///     b'a', b'p', b'p', b'-', b's', b'a', b'n', b'd', b'b', b'o', b'x',
+///     0x01, 0x01, 0xFF,
+/// ][..]);
+/// # }
+/// ```
+///
+/// [entitlements]: https://developer.apple.com/documentation/bundleresources/entitlements
+/// [`embed_entitlements!`]: macro.embed_entitlements.html
+/// [`include_bytes!`]: https://doc.rust-lang.org/std/macro.include_bytes.html
+#[macro_export]
+macro_rules! embed_entitlements_bytes {
+    ($bytes:expr $(,)?) => {
+        $crate::_embed_entitlements!($bytes);
+    };
+}
+
+// Embeds entitlements and their DER encoding.
+//
+// This is not part of this crate's public API, so I reserve the right to change
+// or remove this in a SemVer-compatible update.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "const_eval")]
+macro_rules! _embed_entitlements {
+    ($bytes:expr) => {
+        // The wildcard `_` prevents polluting the call site with identifiers.
+        const _: () = {
+            // See `embed_info_plist_bytes!` for why this is an array.
+            const SLICE: &[u8] = $bytes;
+            const LEN: usize = SLICE.len();
+
+            union Transmute {
+                from: *const [u8; LEN],
+                into: &'static [u8; LEN],
+            }
+
+            const PTR: *const [u8; LEN] = SLICE.as_ptr() as *const _;
+            const REF: &[u8; LEN] = unsafe { Transmute { from: PTR }.into };
+
+            const DER_LEN: usize =
+                $crate::const_eval::entitlements_der_len(SLICE);
+            const DER: [u8; DER_LEN] =
+                $crate::const_eval::entitlements_der(SLICE);
+
+            $crate::_embed_plist_static!(
+                entitlements: [u8; LEN] = *REF
+            );
+            $crate::_embed_plist_static!(
+                entitlements_der: [u8; DER_LEN] = DER
+            );
+        };
+    };
+}
+
+// The DER encoding is computed at compile time, which requires the
+// `const_eval` feature.
+//
+// This is not part of this crate's public API, so I reserve the right to change
+// or remove this in a SemVer-compatible update.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "const_eval"))]
+macro_rules! _embed_entitlements {
+    ($bytes:expr) => {
+        $crate::_core::compile_error!(
+            "embedding entitlements requires the `const_eval` feature of `embed_plist`"
+        );
+    };
+}
+
+// Applies each option of `embed_*_plist_bytes!` to `$bytes` in order, then
+// embeds the result.
+//
+// This is not part of this crate's public API, so I reserve the right to change
+// or remove this in a SemVer-compatible update.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "const_eval")]
+macro_rules! _embed_plist_with_options {
+    ($kind:ident $extras:tt, $bytes:expr; normalize $(, $rest:ident)*) => {
+        $crate::_embed_plist_with_options!($kind $extras, {
+            const SLICE: &[u8] = $bytes;
+            const LEN: usize = $crate::const_eval::normalized_len(SLICE);
+            const NORMALIZED: [u8; LEN] = $crate::const_eval::normalize(SLICE);
+            &NORMALIZED
+        }; $($rest),*);
+    };
+    ($kind:ident $extras:tt, $bytes:expr; openstep $(, $rest:ident)*) => {
+        $crate::_embed_plist_with_options!($kind $extras, {
+            const SLICE: &[u8] = $bytes;
+            const LEN: usize = $crate::const_eval::openstep_len(SLICE);
+            const XML: [u8; LEN] = $crate::const_eval::openstep_to_xml(SLICE);
+            &XML
+        }; $($rest),*);
+    };
+    ($kind:ident $extras:tt, $bytes:expr; canonicalize $(, $rest:ident)*) => {
+        $crate::_embed_plist_with_options!($kind $extras, {
+            const SLICE: &[u8] = $bytes;
+            const LEN: usize = $crate::const_eval::canonical_len(SLICE);
+            const CANONICAL: [u8; LEN] = $crate::const_eval::canonicalize(SLICE);
+            &CANONICAL
+        }; $($rest),*);
+    };
+    ($kind:ident [$($extra:ident)*], $bytes:expr; verify $(, $rest:ident)*) => {
+        $crate::_embed_plist_with_options!(
+            $kind [$($extra)* verify], $bytes; $($rest),*
+        );
+    };
+    ($kind:ident [$($extra:ident)*], $bytes:expr; integrity $(, $rest:ident)*) => {
+        $crate::_embed_plist_with_options!(
+            $kind [$($extra)* integrity], $bytes; $($rest),*
+        );
+    };
+    ($kind:ident $extras:tt, $bytes:expr;) => {
+        $crate::_embed_plist_with_extras!($kind $extras, $bytes);
+    };
+    ($kind:ident $extras:tt, $bytes:expr; $option:ident $(, $rest:ident)*) => {
+        $crate::_core::compile_error!($crate::_core::concat!(
+            "unknown option `",
+            $crate::_core::stringify!($option),
+            "`, expected `normalize`, `openstep`, `canonicalize`, `verify`, ",
+            "or `integrity`",
+        ));
+    };
+}
+
+// Options other than `verify` are evaluated at compile time, which requires
+// the `const_eval` feature.
+//
+// This is not part of this crate's public API, so I reserve the right to change
+// or remove this in a SemVer-compatible update.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "const_eval"))]
+macro_rules! _embed_plist_with_options {
+    ($kind:ident [$($extra:ident)*], $bytes:expr; verify $(, $rest:ident)*) => {
+        $crate::_embed_plist_with_options!(
+            $kind [$($extra)* verify], $bytes; $($rest),*
+        );
+    };
+    ($kind:ident $extras:tt, $bytes:expr;) => {
+        $crate::_embed_plist_with_extras!($kind $extras, $bytes);
+    };
+    ($kind:ident $extras:tt, $bytes:expr; $($option:ident),+) => {
+        $crate::_core::compile_error!(
+            "embedding options other than `verify` require the `const_eval` feature of `embed_plist`"
+        );
+    };
+}
+
+// Embeds `$bytes`, then defines the symbols that the `verify` and `integrity`
+// options add for the embedded bytes.
+//
+// This is not part of this crate's public API, so I reserve the right to change
+// or remove this in a SemVer-compatible update.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _embed_plist_with_extras {
+    (info_plist [$($extra:ident)*], $bytes:expr) => {
+        const _: () = {
+            const BYTES: &[u8] = $bytes;
+            $crate::embed_info_plist_bytes!(BYTES);
+            $($crate::_embed_plist_with_extras!(info_plist $extra: BYTES);)*
+        };
+    };
+    (launchd_plist [$($extra:ident)*], $bytes:expr) => {
+        const _: () = {
+            const BYTES: &[u8] = $bytes;
+            $crate::embed_launchd_plist_bytes!(BYTES);
+            $($crate::_embed_plist_with_extras!(launchd_plist $extra: BYTES);)*
+        };
+    };
+    ($kind:ident verify: $bytes:expr) => {
+        $crate::_embed_plist_len!($kind: $bytes.len());
+    };
+    ($kind:ident integrity: $bytes:expr) => {
+        $crate::_embed_plist_hash!($kind: $bytes);
+    };
+}
+
+// Defines the static for `embed_*_plist_bytes!`.
+//
+// This is not part of this crate's public API, so I reserve the right to change
+// or remove this in a SemVer-compatible update.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(all(feature = "portable", not(target_vendor = "apple"))))]
+macro_rules! _embed_plist_static {
+    (info_plist: $ty:ty = $value:expr) => {
+        // Prevents this from being optimized out of the binary.
+        #[used]
+        // Places this data in the correct location.
+        #[link_section = "__TEXT,__info_plist,regular,no_dead_strip"]
+        // Prevents repeated use by creating a linker error.
+        #[no_mangle]
+        pub static _EMBED_INFO_PLIST: $ty = $value;
+    };
+    (launchd_plist: $ty:ty = $value:expr) => {
+        // Prevents this from being optimized out of the binary.
+        #[used]
+        // Places this data in the correct location.
+        #[link_section = "__TEXT,__launchd_plist,regular,no_dead_strip"]
+        // Prevents repeated use by creating a linker error.
+        #[no_mangle]
+        pub static _EMBED_LAUNCHD_PLIST: $ty = $value;
+    };
+    (entitlements: $ty:ty = $value:expr) => {
+        #[used]
+        #[link_section = "__TEXT,__entitlements,regular,no_dead_strip"]
+        #[no_mangle]
+        pub static _EMBED_ENTITLEMENTS: $ty = $value;
+    };
+    (entitlements_der: $ty:ty = $value:expr) => {
+        #[used]
+        #[link_section = "__TEXT,__ents_der,regular,no_dead_strip"]
+        #[no_mangle]
+        pub static _EMBED_ENTITLEMENTS_DER: $ty = $value;
+    };
+}
+
+// Defines the static for `embed_*_plist_bytes!` on targets without Mach-O
+// sections when the `portable` feature is enabled.
+//
+// This is not part of this crate's public API, so I reserve the right to change
+// or remove this in a SemVer-compatible update.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(feature = "portable", not(target_vendor = "apple")))]
+macro_rules! _embed_plist_static {
+    (info_plist: $ty:ty = $value:expr) => {
+        // Prevents repeated use by creating a linker error.
+        #[used]
+        #[no_mangle]
+        pub static _EMBED_INFO_PLIST: $ty = $value;
+
+        // There is no section end to measure up to, so the length is stored
+        // alongside the data.
+        #[used]
+        #[no_mangle]
+        pub static _EMBED_INFO_PLIST_LEN: usize =
+            $crate::_core::mem::size_of::<$ty>();
+    };
+    (launchd_plist: $ty:ty = $value:expr) => {
+        // Prevents repeated use by creating a linker error.
+        #[used]
+        #[no_mangle]
+        pub static _EMBED_LAUNCHD_PLIST: $ty = $value;
+
+        // There is no section end to measure up to, so the length is stored
+        // alongside the data.
+        #[used]
+        #[no_mangle]
+        pub static _EMBED_LAUNCHD_PLIST_LEN: usize =
+            $crate::_core::mem::size_of::<$ty>();
+    };
+    (entitlements: $ty:ty = $value:expr) => {
+        #[used]
+        #[no_mangle]
+        pub static _EMBED_ENTITLEMENTS: $ty = $value;
+
+        #[used]
+        #[no_mangle]
+        pub static _EMBED_ENTITLEMENTS_LEN: usize =
+            $crate::_core::mem::size_of::<$ty>();
+    };
+    (entitlements_der: $ty:ty = $value:expr) => {
+        #[used]
+        #[no_mangle]
+        pub static _EMBED_ENTITLEMENTS_DER: $ty = $value;
+
+        #[used]
+        #[no_mangle]
+        pub static _EMBED_ENTITLEMENTS_DER_LEN: usize =
+            $crate::_core::mem::size_of::<$ty>();
+    };
+}
+
+// Defines the length of the embedded bytes for the `verify` option, which lets
+// `verify_*_plist` detect foreign data in the section.
+//
+// This is not part of this crate's public API, so I reserve the right to change
+// or remove this in a SemVer-compatible update.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(all(feature = "portable", not(target_vendor = "apple"))))]
+macro_rules! _embed_plist_len {
+    (info_plist: $len:expr) => {
+        #[used]
+        #[no_mangle]
+        pub static _EMBED_INFO_PLIST_LEN: usize = $len;
+    };
+    (launchd_plist: $len:expr) => {
+        #[used]
+        #[no_mangle]
+        pub static _EMBED_LAUNCHD_PLIST_LEN: usize = $len;
+    };
+}
+
+// The `portable` static always has its length defined alongside it.
+//
+// This is not part of this crate's public API, so I reserve the right to change
+// or remove this in a SemVer-compatible update.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(feature = "portable", not(target_vendor = "apple")))]
+macro_rules! _embed_plist_len {
+    ($kind:ident: $len:expr) => {};
+}
+
+// Defines the SHA-256 digest of the embedded bytes for the `integrity` option,
+// which lets `check_*_plist_integrity` detect the bytes being modified.
+//
+// This is not part of this crate's public API, so I reserve the right to change
+// or remove this in a SemVer-compatible update.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "const_eval")]
+macro_rules! _embed_plist_hash {
+    (info_plist: $bytes:expr) => {
+        #[used]
+        #[no_mangle]
+        pub static _EMBED_INFO_PLIST_SHA256: [u8; 32] =
+            $crate::const_eval::sha256($bytes);
+    };
+    (launchd_plist: $bytes:expr) => {
+        #[used]
+        #[no_mangle]
+        pub static _EMBED_LAUNCHD_PLIST_SHA256: [u8; 32] =
+            $crate::const_eval::sha256($bytes);
+    };
+}
+
+/// Reads the value for a key of a property list at compile time.
+///
+/// `$bytes` must be a `const` XML property list whose root is a dictionary,
+/// such as the same bytes passed to [`embed_info_plist_bytes!`]. This lets
+/// values like the bundle identifier be used as constants without repeating
+/// them in Rust code.
+///
+/// The value is a `&'static str` by default. Other types can be requested with
+/// a third argument:
+///
+/// | Type   | Property List Value        |
+/// | ------ | -------------------------- |
+/// | `str`  | `<string>`                 |
+/// | `bool` | `<true/>` or `<false/>`    |
+/// | `i64`  | `<integer>`                |
+/// | `u64`  | `<integer>`                |
+///
+/// This requires the `const_eval` feature, which needs Rust 1.83 or later.
+///
+/// # Examples
+///
+/// ```rust
+/// const PLIST: &[u8] = br#"
+///     <?xml version="1.0" encoding="UTF-8"?>
+///     <!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+///     <plist version="1.0">
+///     <dict>
+///         <key>CFBundleIdentifier</key>
+///         <string>com.example.app</string>
+///         <key>LSUIElement</key>
+///         <true/>
+///         <key>Build</key>
+///         <integer>42</integer>
+///     </dict>
+///     </plist>
+/// "#;
+///
+/// embed_plist::embed_info_plist_bytes!(PLIST);
+///
+/// const BUNDLE_ID: &str = embed_plist::const_value!(PLIST, "CFBundleIdentifier");
+/// const UI_ELEMENT: bool = embed_plist::const_value!(PLIST, "LSUIElement", bool);
+/// const BUILD: u64 = embed_plist::const_value!(PLIST, "Build", u64);
+///
+/// assert_eq!(BUNDLE_ID, "com.example.app");
+/// assert!(UI_ELEMENT);
+/// assert_eq!(BUILD, 42);
+/// ```
+///
+/// The build fails if the key is missing or its value has a different type:
+///
+/// ```compile_fail
+/// # const PLIST: &[u8] = b"<plist><dict><key>LSUIElement</key><true/></dict></plist>";
+/// const UI_ELEMENT: &str = embed_plist::const_value!(PLIST, "LSUIElement");
+/// ```
+///
+/// [`embed_info_plist_bytes!`]: macro.embed_info_plist_bytes.html
+#[cfg(feature = "const_eval")]
+#[macro_export]
+macro_rules! const_value {
+    ($bytes:expr, $key:expr $(, str)? $(,)?) => {{
+        const BYTES: &[u8] = $bytes;
+        const LEN: usize = $crate::const_eval::string_len(BYTES, $key);
+        const VALUE: &[u8; LEN] =
+            &$crate::const_eval::string_value(BYTES, $key);
+        match $crate::_core::str::from_utf8(VALUE) {
+            Ok(value) => value,
+            Err(_) => $crate::_core::panic!("string is not valid UTF-8"),
+        }
+    }};
+    ($bytes:expr, $key:expr, bool $(,)?) => {
+        $crate::const_eval::bool_value($bytes, $key)
+    };
+    ($bytes:expr, $key:expr, i64 $(,)?) => {
+        $crate::const_eval::i64_value($bytes, $key)
+    };
+    ($bytes:expr, $key:expr, u64 $(,)?) => {
+        $crate::const_eval::u64_value($bytes, $key)
+    };
+}
+
+/// Includes the module of constants generated for the [`Info.plist`] file at
+/// `$path` by [`build::info_plist_consts`].
+///
+/// This expands to a `pub mod info_plist` with a constant for each top-level
+/// key, and embeds the file in the same way as [`embed_info_plist_bytes!`].
+/// `$path` must be the same string that the build script passed to
+/// [`build::info_plist_consts`].
+///
+/// # Examples
+///
+/// With `embed_plist` in both `[dependencies]` and `[build-dependencies]`, the
+/// latter with the `std` feature:
+///
+/// ```rust,no_run
+/// // build.rs
+/// # fn main() -> std::io::Result<()> {
+/// # #[cfg(feature = "std")]
+/// embed_plist::build::info_plist_consts("Info.plist")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```rust,ignore
+/// // main.rs
+/// embed_plist::info_plist_consts!("Info.plist");
+///
+/// fn main() {
+///     let id: &str = info_plist::CF_BUNDLE_IDENTIFIER;
+///     let ui_element: bool = info_plist::LS_UI_ELEMENT;
+/// }
+/// ```
+///
+/// [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
+/// [`build::info_plist_consts`]: build/fn.info_plist_consts.html
+/// [`embed_info_plist_bytes!`]: macro.embed_info_plist_bytes.html
+#[macro_export]
+macro_rules! info_plist_consts {
+    ($path:expr $(,)?) => {
+        $crate::_core::include!($crate::_core::concat!(
+            $crate::_core::env!("OUT_DIR"),
+            "/embed_plist/",
+            $path,
+            ".rs",
+        ));
+    };
+}
+
+/// Includes the module of constants generated for the [`launchd.plist`] file at
+/// `$path` by [`build::launchd_plist_consts`].
+///
+/// This works the same as [`info_plist_consts!`], except that the module is
+/// named `launchd_plist`.
+///
+/// [`launchd.plist`]: https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html#//apple_ref/doc/uid/TP40001762-104142
+/// [`build::launchd_plist_consts`]: build/fn.launchd_plist_consts.html
+/// [`info_plist_consts!`]: macro.info_plist_consts.html
+#[macro_export]
+macro_rules! launchd_plist_consts {
+    ($path:expr $(,)?) => {
+        $crate::_core::include!($crate::_core::concat!(
+            $crate::_core::env!("OUT_DIR"),
+            "/embed_plist/",
+            $path,
+            ".rs",
+        ));
+    };
+}
+
+/// Embeds the [`Info.plist`] converted from the JSON file at `$path` by
+/// [`build::json_to_plist`].
+///
+/// `$path` must be the same string that the build script passed to
+/// [`build::json_to_plist`]. Options are the same as for
+/// [`embed_info_plist_bytes!`], and apply to the converted XML.
+///
+/// # Examples
+///
+/// With `embed_plist` in both `[dependencies]` and `[build-dependencies]`, the
+/// latter with the `std` feature:
+///
+/// ```rust,no_run
+/// // build.rs
+/// # fn main() -> std::io::Result<()> {
+/// # #[cfg(feature = "std")]
+/// embed_plist::build::json_to_plist("Info.json")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```rust,ignore
+/// // main.rs
+/// embed_plist::embed_info_plist_json!("Info.json");
+/// ```
+///
+/// [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
+/// [`build::json_to_plist`]: build/fn.json_to_plist.html
+/// [`embed_info_plist_bytes!`]: macro.embed_info_plist_bytes.html
+#[macro_export]
+macro_rules! embed_info_plist_json {
+    ($path:expr $(, $option:ident)* $(,)?) => {
+        $crate::embed_info_plist_bytes!(
+            $crate::_core::include_bytes!($crate::_core::concat!(
+                $crate::_core::env!("OUT_DIR"),
+                "/embed_plist/",
+                $path,
+                ".plist",
+            )),
+            $($option),*
+        );
+    };
+}
+
+/// Embeds the [`Info.plist`] converted from the TOML file at `$path` by
+/// [`build::toml_to_plist`].
+///
+/// This works the same as [`embed_info_plist_json!`].
+///
+/// [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
+/// [`build::toml_to_plist`]: build/fn.toml_to_plist.html
+/// [`embed_info_plist_json!`]: macro.embed_info_plist_json.html
+#[macro_export]
+macro_rules! embed_info_plist_toml {
+    ($path:expr $(, $option:ident)* $(,)?) => {
+        $crate::embed_info_plist_bytes!(
+            $crate::_core::include_bytes!($crate::_core::concat!(
+                $crate::_core::env!("OUT_DIR"),
+                "/embed_plist/",
+                $path,
+                ".plist",
+            )),
+            $($option),*
+        );
+    };
+}
+
+/// Embeds the [`launchd.plist`] converted from the JSON file at `$path` by
+/// [`build::json_to_plist`].
+///
+/// This works the same as [`embed_info_plist_json!`].
+///
+/// [`launchd.plist`]: https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html#//apple_ref/doc/uid/TP40001762-104142
+/// [`build::json_to_plist`]: build/fn.json_to_plist.html
+/// [`embed_info_plist_json!`]: macro.embed_info_plist_json.html
+#[macro_export]
+macro_rules! embed_launchd_plist_json {
+    ($path:expr $(, $option:ident)* $(,)?) => {
+        $crate::embed_launchd_plist_bytes!(
+            $crate::_core::include_bytes!($crate::_core::concat!(
+                $crate::_core::env!("OUT_DIR"),
+                "/embed_plist/",
+                $path,
+                ".plist",
+            )),
+            $($option),*
+        );
+    };
+}
+
+/// Embeds the [`launchd.plist`] converted from the TOML file at `$path` by
+/// [`build::toml_to_plist`].
+///
+/// This works the same as [`embed_info_plist_json!`].
+///
+/// [`launchd.plist`]: https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html#//apple_ref/doc/uid/TP40001762-104142
+/// [`build::toml_to_plist`]: build/fn.toml_to_plist.html
+/// [`embed_info_plist_json!`]: macro.embed_info_plist_json.html
+#[macro_export]
+macro_rules! embed_launchd_plist_toml {
+    ($path:expr $(, $option:ident)* $(,)?) => {
+        $crate::embed_launchd_plist_bytes!(
+            $crate::_core::include_bytes!($crate::_core::concat!(
+                $crate::_core::env!("OUT_DIR"),
+                "/embed_plist/",
+                $path,
+                ".plist",
+            )),
+            $($option),*
+        );
+    };
+}
+
+/// Asserts that two property lists are semantically equal, like
+/// [`assert_eq!`].
+///
+/// Each side can be a [`Value`] or the bytes of an XML or binary property list,
+/// such as from [`get_info_plist`]. Key order, whitespace, and the format do
+/// not matter, only what the property lists contain, as compared by
+/// [`Value::semantic_eq`]. On failure, the panic message lists each
+/// difference with its path, as found by [`Value::diff`].
+///
+/// This requires the `alloc` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use embed_plist::value::Value;
+///
+/// let canonical = br#"<plist version="1.0"><dict><key>a</key><true/><key>b</key><string>c</string></dict></plist>"#;
+/// let reordered = "<plist>\n<dict>\n\t<key>b</key>\n\t<string>c</string>\n\t<key>a</key>\n\t<true/>\n</dict>\n</plist>\n";
+/// let binary = Value::from_xml(canonical).unwrap().to_binary();
+///
+/// embed_plist::assert_plist_eq!(canonical, reordered);
+/// embed_plist::assert_plist_eq!(binary, canonical, "after converting to binary");
+/// ```
+///
+/// ```rust,should_panic
+/// embed_plist::assert_plist_eq!(
+///     "<dict><key>a</key><true/></dict>",
+///     "<dict><key>a</key><false/></dict>",
+/// );
+/// // assertion `left == right` failed
+/// // property lists differ from left to right:
+/// // ~ a: Boolean(true) -> Boolean(false)
+/// ```
+///
+/// [`assert_eq!`]: https://doc.rust-lang.org/std/macro.assert_eq.html
+/// [`Value`]: value/enum.Value.html
+/// [`get_info_plist`]: fn.get_info_plist.html
+/// [`Value::semantic_eq`]: value/enum.Value.html#method.semantic_eq
+/// [`Value::diff`]: value/enum.Value.html#method.diff
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! assert_plist_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                $crate::value::_assert_plist_eq(left, right, $crate::_core::option::Option::None)
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => $crate::value::_assert_plist_eq(
+                left,
+                right,
+                $crate::_core::option::Option::Some($crate::_core::format_args!($($arg)+)),
+            ),
+        }
+    };
+}
+
+/// Returns the contents of the embedded [`Info.plist`] file.
+///
+/// # Examples
+///
+/// We can verify that the result is correct by checking it against the file at
+/// runtime:
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # std::env::set_current_dir("./src")?;
+/// embed_plist::embed_info_plist!("Info.plist");
+///
+/// let embedded_plist = embed_plist::get_info_plist();
+/// let read_plist = std::fs::read("Info.plist")?;
+///
+/// assert_eq!(embedded_plist, read_plist.as_slice());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// If `embed_info_plist!` has not been called, this function creates a
+/// compile-time error by failing to reference the symbol defined by that macro:
+///
+/// ```compile_fail
+/// # #[cfg(pass_reuse_doctest)]
+/// # compile_error!("hack to force a doctest compile error pre 1.43");
+/// let embedded_plist = embed_plist::get_info_plist();
+/// ```
+///
+/// # Safety
+///
+/// This function relies on `_EMBED_INFO_PLIST` being defined within the
+/// `__TEXT,__info_plist` section. You **should not** define this symbol outside
+/// of using the macros provided by this library.
+///
+/// With the `portable` feature on non-Apple targets, the length is instead read
+/// from a symbol defined next to the data by the same macro.
+///
+/// [`Info.plist`]: https://developer.apple.com/library/archive/documentation/General/Reference/InfoPlistKeyReference/Introduction/Introduction.html
+#[inline]
+pub fn get_info_plist() -> &'static [u8] {
+    extern "C" {
+        // Using this symbol instead of section start to force a linker error if
+        // `embed_info_plist!` has not been called.
+        #[link_name = "_EMBED_INFO_PLIST"]
+        static START: [u8; 0];
+    }
+
+    #[cfg(not(all(feature = "portable", not(target_vendor = "apple"))))]
+    extern "C" {
+        #[link_name = "\x01section$end$__TEXT$__info_plist"]
+        static END: [u8; 0];
+    }
+
+    #[cfg(all(feature = "portable", not(target_vendor = "apple")))]
+    extern "C" {
+        #[link_name = "_EMBED_INFO_PLIST_LEN"]
+        static LEN: usize;
+    }
+
+    unsafe {
         let start = START.as_ptr();
-        let end = END.as_ptr();
-        let len = end as usize - start as usize;
+
+        #[cfg(not(all(feature = "portable", not(target_vendor = "apple"))))]
+        let len = END.as_ptr() as usize - start as usize;
+
+        #[cfg(all(feature = "portable", not(target_vendor = "apple")))]
+        let len = LEN;
+
         core::slice::from_raw_parts(start, len)
     }
 }
@@ -662,6 +2058,9 @@ pub fn get_info_plist() -> &'static [u8] {
 /// `__TEXT,__launchd_plist` section. You **should not** define this symbol
 /// outside of using the macros provided by this library.
 ///
+/// With the `portable` feature on non-Apple targets, the length is instead read
+/// from a symbol defined next to the data by the same macro.
+///
 /// [`embed_launchd_plist!`]: macro.embed_launchd_plist.html
 /// [`launchd.plist`]: https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html#//apple_ref/doc/uid/TP40001762-104142
 #[inline]
@@ -671,14 +2070,126 @@ pub fn get_launchd_plist() -> &'static [u8] {
         // `embed_launchd_plist!` has not been called.
         #[link_name = "_EMBED_LAUNCHD_PLIST"]
         static START: [u8; 0];
+    }
 
+    #[cfg(not(all(feature = "portable", not(target_vendor = "apple"))))]
+    extern "C" {
         #[link_name = "\x01section$end$__TEXT$__launchd_plist"]
         static END: [u8; 0];
     }
+
+    #[cfg(all(feature = "portable", not(target_vendor = "apple")))]
+    extern "C" {
+        #[link_name = "_EMBED_LAUNCHD_PLIST_LEN"]
+        static LEN: usize;
+    }
+
     unsafe {
         let start = START.as_ptr();
-        let end = END.as_ptr();
-        let len = end as usize - start as usize;
+
+        #[cfg(not(all(feature = "portable", not(target_vendor = "apple"))))]
+        let len = END.as_ptr() as usize - start as usize;
+
+        #[cfg(all(feature = "portable", not(target_vendor = "apple")))]
+        let len = LEN;
+
+        core::slice::from_raw_parts(start, len)
+    }
+}
+
+/// Returns the contents of the embedded [entitlements] file.
+///
+/// This requires the `const_eval` feature, which needs Rust 1.83 or later.
+///
+/// If [`embed_entitlements!`] has not been called, this function creates a
+/// compile-time error by failing to reference the symbol defined by that macro.
+///
+/// # Safety
+///
+/// This function relies on `_EMBED_ENTITLEMENTS` being defined within the
+/// `__TEXT,__entitlements` section. You **should not** define this symbol
+/// outside of using the macros provided by this library.
+///
+/// [entitlements]: https://developer.apple.com/documentation/bundleresources/entitlements
+/// [`embed_entitlements!`]: macro.embed_entitlements.html
+#[cfg(feature = "const_eval")]
+#[inline]
+pub fn get_entitlements() -> &'static [u8] {
+    extern "C" {
+        #[link_name = "_EMBED_ENTITLEMENTS"]
+        static START: [u8; 0];
+    }
+
+    #[cfg(not(all(feature = "portable", not(target_vendor = "apple"))))]
+    extern "C" {
+        #[link_name = "\x01section$end$__TEXT$__entitlements"]
+        static END: [u8; 0];
+    }
+
+    #[cfg(all(feature = "portable", not(target_vendor = "apple")))]
+    extern "C" {
+        #[link_name = "_EMBED_ENTITLEMENTS_LEN"]
+        static LEN: usize;
+    }
+
+    unsafe {
+        let start = START.as_ptr();
+
+        #[cfg(not(all(feature = "portable", not(target_vendor = "apple"))))]
+        let len = END.as_ptr() as usize - start as usize;
+
+        #[cfg(all(feature = "portable", not(target_vendor = "apple")))]
+        let len = LEN;
+
+        core::slice::from_raw_parts(start, len)
+    }
+}
+
+/// Returns the DER encoding of the embedded [entitlements] file.
+///
+/// [`Value::from_der`] can read this back, such as to check it against
+/// [`get_entitlements`].
+///
+/// This requires the `const_eval` feature, which needs Rust 1.83 or later.
+///
+/// # Safety
+///
+/// This function relies on `_EMBED_ENTITLEMENTS_DER` being defined within the
+/// `__TEXT,__ents_der` section. You **should not** define this symbol outside
+/// of using the macros provided by this library.
+///
+/// [entitlements]: https://developer.apple.com/documentation/bundleresources/entitlements
+/// [`Value::from_der`]: value/enum.Value.html#method.from_der
+/// [`get_entitlements`]: fn.get_entitlements.html
+#[cfg(feature = "const_eval")]
+#[inline]
+pub fn get_entitlements_der() -> &'static [u8] {
+    extern "C" {
+        #[link_name = "_EMBED_ENTITLEMENTS_DER"]
+        static START: [u8; 0];
+    }
+
+    #[cfg(not(all(feature = "portable", not(target_vendor = "apple"))))]
+    extern "C" {
+        #[link_name = "\x01section$end$__TEXT$__ents_der"]
+        static END: [u8; 0];
+    }
+
+    #[cfg(all(feature = "portable", not(target_vendor = "apple")))]
+    extern "C" {
+        #[link_name = "_EMBED_ENTITLEMENTS_DER_LEN"]
+        static LEN: usize;
+    }
+
+    unsafe {
+        let start = START.as_ptr();
+
+        #[cfg(not(all(feature = "portable", not(target_vendor = "apple"))))]
+        let len = END.as_ptr() as usize - start as usize;
+
+        #[cfg(all(feature = "portable", not(target_vendor = "apple")))]
+        let len = LEN;
+
         core::slice::from_raw_parts(start, len)
     }
 }
@@ -0,0 +1,329 @@
+use crate::const_eval::sha256;
+use core::fmt;
+
+/// A hash algorithm used by a [`CodeDirectory`].
+///
+/// [`CodeDirectory`]: struct.CodeDirectory.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HashType {
+    /// SHA-1, used by signatures for older systems.
+    Sha1,
+    /// SHA-256.
+    Sha256,
+    /// SHA-256 truncated to 20 bytes.
+    Sha256Truncated,
+    /// SHA-384.
+    Sha384,
+}
+
+impl HashType {
+    /// Returns the hash type for the `hashType` field of a code directory.
+    pub fn from_raw(raw: u8) -> Option<Self> {
+        match raw {
+            1 => Some(HashType::Sha1),
+            2 => Some(HashType::Sha256),
+            3 => Some(HashType::Sha256Truncated),
+            4 => Some(HashType::Sha384),
+            _ => None,
+        }
+    }
+
+    /// Returns the length of a digest in bytes.
+    pub fn digest_len(self) -> usize {
+        match self {
+            HashType::Sha1 | HashType::Sha256Truncated => 20,
+            HashType::Sha256 => 32,
+            HashType::Sha384 => 48,
+        }
+    }
+
+    /// Returns the digest of `bytes`.
+    pub fn hash(self, bytes: &[u8]) -> Digest {
+        let mut digest = Digest {
+            bytes: [0; 48],
+            len: self.digest_len(),
+        };
+        match self {
+            HashType::Sha1 => digest.bytes[..20].copy_from_slice(&sha1(bytes)),
+            HashType::Sha256 | HashType::Sha256Truncated => {
+                digest.bytes[..digest.len]
+                    .copy_from_slice(&sha256(bytes)[..digest.len]);
+            }
+            HashType::Sha384 => digest.bytes.copy_from_slice(&sha384(bytes)),
+        }
+        digest
+    }
+}
+
+/// A digest produced by a [`HashType`], of up to 48 bytes.
+///
+/// Its [`Display`] and [`Debug`] implementations write it in hexadecimal.
+///
+/// [`HashType`]: enum.HashType.html
+/// [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+/// [`Debug`]: https://doc.rust-lang.org/core/fmt/trait.Debug.html
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Digest {
+    bytes: [u8; 48],
+    len: usize,
+}
+
+impl Digest {
+    // Copies a digest read from a code directory.
+    pub(crate) fn from_slice(slice: &[u8]) -> Self {
+        let mut bytes = [0; 48];
+        bytes[..slice.len()].copy_from_slice(slice);
+        Digest {
+            bytes,
+            len: slice.len(),
+        }
+    }
+
+    /// Returns the bytes of the digest.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.as_bytes() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+// Pads `bytes` like SHA-1 and SHA-2 and calls `compress` on each block of
+// `BLOCK` bytes. The bit length takes the last `LEN` bytes of the final block.
+fn for_each_block<const BLOCK: usize, const LEN: usize>(
+    bytes: &[u8],
+    mut compress: impl FnMut(&[u8; BLOCK]),
+) {
+    let mut chunks = bytes.chunks_exact(BLOCK);
+    for chunk in &mut chunks {
+        let mut block = [0; BLOCK];
+        block.copy_from_slice(chunk);
+        compress(&block);
+    }
+
+    let rest = chunks.remainder();
+    let mut tail = [[0; BLOCK]; 2];
+    let blocks = if rest.len() < BLOCK - LEN { 1 } else { 2 };
+    tail[0][..rest.len()].copy_from_slice(rest);
+    tail[0][rest.len()] = 0x80;
+
+    let bit_len = (bytes.len() as u128).wrapping_mul(8).to_be_bytes();
+    tail[blocks - 1][BLOCK - LEN..].copy_from_slice(&bit_len[16 - LEN..]);
+
+    for block in tail[..blocks].iter() {
+        compress(block);
+    }
+}
+
+fn sha1(bytes: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] =
+        [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+    for_each_block::<64, 8>(bytes, |block| {
+        let mut w = [0u32; 80];
+        for (t, word) in block.chunks_exact(4).enumerate() {
+            w[t] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for t in 16..80 {
+            w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (t, &word) in w.iter().enumerate() {
+            let (f, k) = match t {
+                0..=19 => ((b & c) | (!b & d), 0x5a827999),
+                20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+                _ => (b ^ c ^ d, 0xca62c1d6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e].iter()) {
+            *word = word.wrapping_add(*value);
+        }
+    });
+
+    let mut digest = [0; 20];
+    for (out, word) in digest.chunks_exact_mut(4).zip(state.iter()) {
+        out.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+const K512: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+fn sha384(bytes: &[u8]) -> [u8; 48] {
+    let mut state: [u64; 8] = [
+        0xcbbb9d5dc1059ed8,
+        0x629a292a367cd507,
+        0x9159015a3070dd17,
+        0x152fecd8f70e5939,
+        0x67332667ffc00b31,
+        0x8eb44a8768581511,
+        0xdb0c2e0d64f98fa7,
+        0x47b5481dbefa4fa4,
+    ];
+
+    for_each_block::<128, 16>(bytes, |block| {
+        let mut w = [0u64; 80];
+        for (t, word) in block.chunks_exact(8).enumerate() {
+            let mut be = [0; 8];
+            be.copy_from_slice(word);
+            w[t] = u64::from_be_bytes(be);
+        }
+        for t in 16..80 {
+            let s0 = w[t - 15].rotate_right(1)
+                ^ w[t - 15].rotate_right(8)
+                ^ (w[t - 15] >> 7);
+            let s1 = w[t - 2].rotate_right(19)
+                ^ w[t - 2].rotate_right(61)
+                ^ (w[t - 2] >> 6);
+            w[t] = w[t - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[t - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for (&k, &word) in K512.iter().zip(w.iter()) {
+            let s1 =
+                e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let ch = (e & f) ^ (!e & g);
+            let temp1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            let s0 =
+                a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+
+        for (word, value) in
+            state.iter_mut().zip([a, b, c, d, e, f, g, h].iter())
+        {
+            *word = word.wrapping_add(*value);
+        }
+    });
+
+    let mut digest = [0; 48];
+    for (out, word) in digest.chunks_exact_mut(8).zip(state.iter()) {
+        out.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
@@ -0,0 +1,757 @@
+//! Reading Mach-O binaries, such as to check a built executable's embedded
+//! property lists against its code signature.
+//!
+//! This module requires the `macho` feature, which needs Rust 1.83 or later.
+//! The reader does not allocate, so it works on the bytes of a binary read on
+//! any platform, including Linux release machines without `codesign`.
+//!
+//! [`images`] walks the architectures of a thin or universal (fat) binary.
+//! For each image, [`Image::check_info_plist_signature`] compares the hash that
+//! the code signature binds for `Info.plist` with the contents of
+//! `__TEXT,__info_plist`, and [`Image::check_info_plist_build_version`]
+//! compares the platform and minimum OS version of the load commands with
+//! those claimed by `Info.plist`.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use embed_plist::macho::{self, SignatureCheck};
+//!
+//! let bytes = std::fs::read("target/release/app").expect("failed to read");
+//!
+//! for image in macho::images(&bytes)? {
+//!     match image?.check_info_plist_signature()? {
+//!         SignatureCheck::Match => {}
+//!         SignatureCheck::Mismatch { .. } => panic!("plist modified after signing"),
+//!         other => println!("cannot verify: {:?}", other),
+//!     }
+//! }
+//! # Ok::<(), macho::Error>(())
+//! ```
+//!
+//! [`images`]: fn.images.html
+//! [`Image::check_info_plist_signature`]: struct.Image.html#method.check_info_plist_signature
+//! [`Image::check_info_plist_build_version`]: struct.Image.html#method.check_info_plist_build_version
+
+mod hash;
+mod version;
+
+pub use self::hash::{Digest, HashType};
+pub use self::version::{BuildVersion, BuildVersionCheck, Platform, Version};
+
+use core::{convert::TryFrom, fmt};
+
+const MH_MAGIC: u32 = 0xfeed_face;
+const MH_MAGIC_64: u32 = 0xfeed_facf;
+const FAT_MAGIC: u32 = 0xcafe_babe;
+const FAT_MAGIC_64: u32 = 0xcafe_babf;
+
+const LC_SEGMENT: u32 = 0x1;
+const LC_SEGMENT_64: u32 = 0x19;
+const LC_CODE_SIGNATURE: u32 = 0x1d;
+
+const CSMAGIC_EMBEDDED_SIGNATURE: u32 = 0xfade_0cc0;
+const CSMAGIC_CODEDIRECTORY: u32 = 0xfade_0c02;
+const CSSLOT_CODEDIRECTORY: u32 = 0;
+const CSSLOT_ALTERNATE_CODEDIRECTORIES: u32 = 0x1000;
+const CSSLOT_ALTERNATE_CODEDIRECTORY_MAX: u32 = 5;
+
+/// The special slot of a code directory that holds the hash of `Info.plist`.
+///
+/// Special slots are numbered backwards from the code hashes, so this is slot
+/// -1 in Apple's numbering.
+pub const INFO_SLOT: u32 = 1;
+
+/// An error encountered while reading a Mach-O binary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The input does not start with a Mach-O or universal binary magic number.
+    InvalidMagic,
+    /// A header, load command, or offset extends past the end of its region.
+    OutOfBounds,
+    /// A load command has an invalid size.
+    InvalidLoadCommand,
+    /// The code signature is not a valid embedded signature.
+    InvalidSignature,
+    /// A code directory uses an unknown hash type.
+    UnsupportedHashType,
+    /// The embedded `Info.plist` is not a valid XML property list.
+    InvalidInfoPlist,
+}
+
+impl Error {
+    /// Returns a description of this error.
+    pub const fn message(self) -> &'static str {
+        match self {
+            Error::InvalidMagic => "not a Mach-O binary",
+            Error::OutOfBounds => "Mach-O binary is truncated",
+            Error::InvalidLoadCommand => "invalid Mach-O load command",
+            Error::InvalidSignature => "invalid code signature",
+            Error::UnsupportedHashType => {
+                "unsupported code directory hash type"
+            }
+            Error::InvalidInfoPlist => "invalid embedded Info.plist",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+// Bounds-checked reads of integers in either byte order.
+#[derive(Clone, Copy, Debug)]
+struct Bytes<'a> {
+    bytes: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> Bytes<'a> {
+    fn slice(self, offset: usize, len: usize) -> Result<&'a [u8], Error> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(offset..end))
+            .ok_or(Error::OutOfBounds)
+    }
+
+    fn u32(self, offset: usize) -> Result<u32, Error> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.slice(offset, 4)?);
+        Ok(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    fn u64(self, offset: usize) -> Result<u64, Error> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.slice(offset, 8)?);
+        Ok(if self.big_endian {
+            u64::from_be_bytes(bytes)
+        } else {
+            u64::from_le_bytes(bytes)
+        })
+    }
+
+    fn usize(self, offset: usize) -> Result<usize, Error> {
+        to_usize(u64::from(self.u32(offset)?))
+    }
+
+    // Reads a fixed-size name like `segname`, up to its first NUL.
+    fn name(self, offset: usize) -> Result<&'a [u8], Error> {
+        let name = self.slice(offset, 16)?;
+        let len = name.iter().position(|&b| b == 0).unwrap_or(16);
+        Ok(&name[..len])
+    }
+}
+
+fn to_usize(value: u64) -> Result<usize, Error> {
+    usize::try_from(value).map_err(|_| Error::OutOfBounds)
+}
+
+/// Returns an iterator over the images of a thin or universal binary.
+///
+/// A thin binary yields itself, and a universal binary yields the image for
+/// each architecture.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidMagic`] if `bytes` is neither, or
+/// [`Error::OutOfBounds`] if the universal header is truncated. Each image is
+/// parsed as it is yielded.
+///
+/// [`Error::InvalidMagic`]: enum.Error.html#variant.InvalidMagic
+/// [`Error::OutOfBounds`]: enum.Error.html#variant.OutOfBounds
+pub fn images(bytes: &[u8]) -> Result<Images<'_>, Error> {
+    let fat = Bytes {
+        bytes,
+        big_endian: true,
+    };
+    let (is_64, count) = match fat.u32(0) {
+        Ok(FAT_MAGIC) => (false, fat.usize(4)?),
+        Ok(FAT_MAGIC_64) => (true, fat.usize(4)?),
+        _ => {
+            return Ok(Images {
+                bytes,
+                fat: None,
+                thin: Some(Image::parse(bytes)?),
+            })
+        }
+    };
+    let arch_len = if is_64 { 32 } else { 20 };
+    count
+        .checked_mul(arch_len)
+        .and_then(|len| len.checked_add(8))
+        .filter(|&len| len <= bytes.len())
+        .ok_or(Error::OutOfBounds)?;
+
+    Ok(Images {
+        bytes,
+        fat: Some(FatArchs {
+            is_64,
+            index: 0,
+            count,
+        }),
+        thin: None,
+    })
+}
+
+/// An iterator over the images of a binary, returned by [`images`].
+///
+/// [`images`]: fn.images.html
+#[derive(Clone, Debug)]
+pub struct Images<'a> {
+    bytes: &'a [u8],
+    fat: Option<FatArchs>,
+    thin: Option<Image<'a>>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct FatArchs {
+    is_64: bool,
+    index: usize,
+    count: usize,
+}
+
+impl<'a> Images<'a> {
+    fn fat_image(&self, fat: FatArchs) -> Result<Image<'a>, Error> {
+        let header = Bytes {
+            bytes: self.bytes,
+            big_endian: true,
+        };
+        let (offset, size) = if fat.is_64 {
+            let arch = 8 + fat.index * 32;
+            (header.u64(arch + 8)?, header.u64(arch + 16)?)
+        } else {
+            let arch = 8 + fat.index * 20;
+            (
+                u64::from(header.u32(arch + 8)?),
+                u64::from(header.u32(arch + 12)?),
+            )
+        };
+        let slice = header.slice(to_usize(offset)?, to_usize(size)?)?;
+        Image::parse(slice)
+    }
+}
+
+impl<'a> Iterator for Images<'a> {
+    type Item = Result<Image<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.fat {
+            Some(ref mut fat) if fat.index < fat.count => {
+                let current = *fat;
+                fat.index += 1;
+                Some(self.fat_image(current))
+            }
+            Some(_) => None,
+            None => self.thin.take().map(Ok),
+        }
+    }
+}
+
+/// A single-architecture Mach-O image.
+#[derive(Clone, Copy, Debug)]
+pub struct Image<'a> {
+    bytes: &'a [u8],
+    big_endian: bool,
+    is_64: bool,
+    cpu_type: u32,
+    cpu_subtype: u32,
+    file_type: u32,
+    ncmds: u32,
+    sizeofcmds: usize,
+}
+
+impl<'a> Image<'a> {
+    /// Parses the header of a thin Mach-O image.
+    ///
+    /// Universal binaries are read with [`images`] instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidMagic`] if `bytes` is not a Mach-O image, or
+    /// [`Error::OutOfBounds`] if its header or load commands are truncated.
+    ///
+    /// [`images`]: fn.images.html
+    /// [`Error::InvalidMagic`]: enum.Error.html#variant.InvalidMagic
+    /// [`Error::OutOfBounds`]: enum.Error.html#variant.OutOfBounds
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, Error> {
+        let le = Bytes {
+            bytes,
+            big_endian: false,
+        };
+        let (big_endian, is_64) =
+            match le.u32(0).map_err(|_| Error::InvalidMagic)? {
+                MH_MAGIC => (false, false),
+                MH_MAGIC_64 => (false, true),
+                magic if magic.swap_bytes() == MH_MAGIC => (true, false),
+                magic if magic.swap_bytes() == MH_MAGIC_64 => (true, true),
+                _ => return Err(Error::InvalidMagic),
+            };
+
+        let header = Bytes { bytes, big_endian };
+        let image = Image {
+            bytes,
+            big_endian,
+            is_64,
+            cpu_type: header.u32(4)?,
+            cpu_subtype: header.u32(8)?,
+            file_type: header.u32(12)?,
+            ncmds: header.u32(16)?,
+            sizeofcmds: header.usize(20)?,
+        };
+        header.slice(image.header_len(), image.sizeofcmds)?;
+        Ok(image)
+    }
+
+    fn header_len(&self) -> usize {
+        if self.is_64 {
+            32
+        } else {
+            28
+        }
+    }
+
+    fn reader(&self) -> Bytes<'a> {
+        Bytes {
+            bytes: self.bytes,
+            big_endian: self.big_endian,
+        }
+    }
+
+    /// Returns the bytes of this image.
+    #[inline]
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Returns `true` for a 64-bit image.
+    #[inline]
+    pub fn is_64(&self) -> bool {
+        self.is_64
+    }
+
+    /// Returns the `cputype` field of the header, such as `0x0100000c` for
+    /// arm64.
+    #[inline]
+    pub fn cpu_type(&self) -> u32 {
+        self.cpu_type
+    }
+
+    /// Returns the `cpusubtype` field of the header.
+    #[inline]
+    pub fn cpu_subtype(&self) -> u32 {
+        self.cpu_subtype
+    }
+
+    /// Returns the `filetype` field of the header, such as `2` for an
+    /// executable.
+    #[inline]
+    pub fn file_type(&self) -> u32 {
+        self.file_type
+    }
+
+    /// Returns an iterator over the load commands.
+    pub fn load_commands(&self) -> LoadCommands<'a> {
+        LoadCommands {
+            bytes: self.reader(),
+            offset: self.header_len(),
+            end: self.header_len() + self.sizeofcmds,
+            remaining: self.ncmds,
+        }
+    }
+
+    /// Returns the file contents of the section `sectname` in the segment
+    /// `segname`, such as `("__TEXT", "__info_plist")`.
+    ///
+    /// The segment name is read from the section itself, so this also finds
+    /// sections in relocatable object files, whose only segment is unnamed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a load command or the section is malformed.
+    pub fn section(
+        &self,
+        segname: &str,
+        sectname: &str,
+    ) -> Result<Option<&'a [u8]>, Error> {
+        let bytes = self.reader();
+        for command in self.load_commands() {
+            let command = command?;
+            let (header_len, section_len) = match command.cmd() {
+                LC_SEGMENT_64 => (72, 80),
+                LC_SEGMENT => (56, 68),
+                _ => continue,
+            };
+            let segment = Bytes {
+                bytes: command.data(),
+                big_endian: self.big_endian,
+            };
+            let nsects = segment.usize(header_len - 8)?;
+            for index in 0..nsects {
+                let section = header_len + index * section_len;
+                if segment.name(section)? != sectname.as_bytes()
+                    || segment.name(section + 16)? != segname.as_bytes()
+                {
+                    continue;
+                }
+                let (size, offset) = if self.is_64 {
+                    (segment.u64(section + 40)?, segment.u32(section + 48)?)
+                } else {
+                    (
+                        u64::from(segment.u32(section + 36)?),
+                        segment.u32(section + 40)?,
+                    )
+                };
+                let contents = bytes
+                    .slice(to_usize(u64::from(offset))?, to_usize(size)?)?;
+                return Ok(Some(contents));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the contents of `__TEXT,__info_plist`, as embedded by
+    /// [`embed_info_plist!`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a load command or the section is malformed.
+    ///
+    /// [`embed_info_plist!`]: ../macro.embed_info_plist.html
+    pub fn info_plist(&self) -> Result<Option<&'a [u8]>, Error> {
+        self.section("__TEXT", "__info_plist")
+    }
+
+    /// Returns the contents of `__TEXT,__launchd_plist`, as embedded by
+    /// [`embed_launchd_plist!`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a load command or the section is malformed.
+    ///
+    /// [`embed_launchd_plist!`]: ../macro.embed_launchd_plist.html
+    pub fn launchd_plist(&self) -> Result<Option<&'a [u8]>, Error> {
+        self.section("__TEXT", "__launchd_plist")
+    }
+
+    /// Returns the embedded code signature referenced by `LC_CODE_SIGNATURE`,
+    /// or `None` for an unsigned image.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a load command is malformed, or with
+    /// [`Error::InvalidSignature`] if the signature is not a `SuperBlob`.
+    ///
+    /// [`Error::InvalidSignature`]: enum.Error.html#variant.InvalidSignature
+    pub fn code_signature(&self) -> Result<Option<CodeSignature<'a>>, Error> {
+        for command in self.load_commands() {
+            let command = command?;
+            if command.cmd() != LC_CODE_SIGNATURE {
+                continue;
+            }
+            let data = Bytes {
+                bytes: command.data(),
+                big_endian: self.big_endian,
+            };
+            let blob = self.reader().slice(data.usize(8)?, data.usize(12)?)?;
+            return CodeSignature::parse(blob).map(Some);
+        }
+        Ok(None)
+    }
+
+    /// Checks the `Info.plist` hash bound by the code signature against the
+    /// contents of `__TEXT,__info_plist`.
+    ///
+    /// Every code directory that binds `Info.plist` is checked with its own
+    /// hash type, so a signature with both SHA-1 and SHA-256 directories must
+    /// match in both.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image or its code signature is malformed.
+    pub fn check_info_plist_signature(&self) -> Result<SignatureCheck, Error> {
+        let signature = match self.code_signature()? {
+            Some(signature) => signature,
+            None => return Ok(SignatureCheck::Unsigned),
+        };
+        let plist = self.info_plist()?;
+
+        let mut check = SignatureCheck::Unbound;
+        for directory in signature.code_directories() {
+            let directory = directory?;
+            let expected = match directory.special_slot(INFO_SLOT) {
+                Some(hash) if hash.iter().any(|&b| b != 0) => hash,
+                _ => continue,
+            };
+            let plist = match plist {
+                Some(plist) => plist,
+                None => return Ok(SignatureCheck::NotEmbedded),
+            };
+
+            let hash_type = directory.hash_type()?;
+            let actual = hash_type.hash(plist);
+            if actual.as_bytes() != expected {
+                return Ok(SignatureCheck::Mismatch {
+                    hash_type,
+                    expected: Digest::from_slice(expected),
+                    actual,
+                });
+            }
+            check = SignatureCheck::Match;
+        }
+        Ok(check)
+    }
+}
+
+/// The result of [`Image::check_info_plist_signature`].
+///
+/// [`Image::check_info_plist_signature`]: struct.Image.html#method.check_info_plist_signature
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureCheck {
+    /// The embedded `Info.plist` matches the hash in the code signature.
+    Match,
+    /// The embedded `Info.plist` was modified after signing.
+    Mismatch {
+        /// The hash type of the code directory that differs.
+        hash_type: HashType,
+        /// The hash bound by the code signature.
+        expected: Digest,
+        /// The hash of the embedded `Info.plist`.
+        actual: Digest,
+    },
+    /// The image has no code signature.
+    Unsigned,
+    /// The code signature does not bind an `Info.plist`.
+    Unbound,
+    /// The code signature binds an `Info.plist`, but the image does not embed
+    /// one, such as for an executable inside an app bundle.
+    NotEmbedded,
+}
+
+/// An iterator over the load commands of an [`Image`].
+///
+/// [`Image`]: struct.Image.html
+#[derive(Clone, Debug)]
+pub struct LoadCommands<'a> {
+    bytes: Bytes<'a>,
+    offset: usize,
+    end: usize,
+    remaining: u32,
+}
+
+impl<'a> Iterator for LoadCommands<'a> {
+    type Item = Result<LoadCommand<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let command = (|| {
+            let cmd = self.bytes.u32(self.offset)?;
+            let cmdsize = self.bytes.usize(self.offset + 4)?;
+            if cmdsize < 8 || cmdsize > self.end - self.offset {
+                return Err(Error::InvalidLoadCommand);
+            }
+            let data = self.bytes.slice(self.offset, cmdsize)?;
+            self.offset += cmdsize;
+            Ok(LoadCommand { cmd, data })
+        })();
+
+        // Stop after an error rather than reading from a bad offset.
+        if command.is_err() {
+            self.remaining = 0;
+        }
+        Some(command)
+    }
+}
+
+/// A load command of an [`Image`].
+///
+/// [`Image`]: struct.Image.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LoadCommand<'a> {
+    cmd: u32,
+    data: &'a [u8],
+}
+
+impl<'a> LoadCommand<'a> {
+    /// Returns the command type, such as `0x19` for `LC_SEGMENT_64`.
+    #[inline]
+    pub fn cmd(&self) -> u32 {
+        self.cmd
+    }
+
+    /// Returns the bytes of the command, including `cmd` and `cmdsize`.
+    #[inline]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+/// An embedded code signature (`SuperBlob`), returned by
+/// [`Image::code_signature`].
+///
+/// [`Image::code_signature`]: struct.Image.html#method.code_signature
+#[derive(Clone, Copy, Debug)]
+pub struct CodeSignature<'a> {
+    blob: &'a [u8],
+    count: usize,
+}
+
+// Code signatures are big-endian regardless of the image.
+fn signature_bytes(bytes: &[u8]) -> Bytes<'_> {
+    Bytes {
+        bytes,
+        big_endian: true,
+    }
+}
+
+impl<'a> CodeSignature<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Self, Error> {
+        let header = signature_bytes(bytes);
+        let invalid = |_| Error::InvalidSignature;
+        if header.u32(0).map_err(invalid)? != CSMAGIC_EMBEDDED_SIGNATURE {
+            return Err(Error::InvalidSignature);
+        }
+        let len = header.usize(4).map_err(invalid)?;
+        let blob = header.slice(0, len).map_err(invalid)?;
+        let count = signature_bytes(blob).usize(8).map_err(invalid)?;
+        Ok(CodeSignature { blob, count })
+    }
+
+    /// Returns the bytes of the `SuperBlob`.
+    #[inline]
+    pub fn bytes(&self) -> &'a [u8] {
+        self.blob
+    }
+
+    /// Returns an iterator over the code directories, starting with the
+    /// primary one and followed by any alternates.
+    pub fn code_directories(&self) -> CodeDirectories<'a> {
+        CodeDirectories {
+            blob: self.blob,
+            index: 0,
+            count: self.count,
+        }
+    }
+}
+
+/// An iterator over the code directories of a [`CodeSignature`].
+///
+/// [`CodeSignature`]: struct.CodeSignature.html
+#[derive(Clone, Debug)]
+pub struct CodeDirectories<'a> {
+    blob: &'a [u8],
+    index: usize,
+    count: usize,
+}
+
+impl<'a> Iterator for CodeDirectories<'a> {
+    type Item = Result<CodeDirectory<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = signature_bytes(self.blob);
+        while self.index < self.count {
+            let entry = 12 + self.index * 8;
+            self.index += 1;
+
+            let directory = bytes.u32(entry).and_then(|slot| {
+                let is_directory = slot == CSSLOT_CODEDIRECTORY
+                    || (CSSLOT_ALTERNATE_CODEDIRECTORIES
+                        ..CSSLOT_ALTERNATE_CODEDIRECTORIES
+                            + CSSLOT_ALTERNATE_CODEDIRECTORY_MAX)
+                        .contains(&slot);
+                if !is_directory {
+                    return Ok(None);
+                }
+                let offset = bytes.usize(entry + 4)?;
+                CodeDirectory::parse(self.blob, offset).map(Some)
+            });
+            match directory {
+                Ok(None) => continue,
+                Ok(Some(directory)) => return Some(Ok(directory)),
+                Err(_) => {
+                    self.index = self.count;
+                    return Some(Err(Error::InvalidSignature));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// A `CodeDirectory` blob of a [`CodeSignature`].
+///
+/// [`CodeSignature`]: struct.CodeSignature.html
+#[derive(Clone, Copy, Debug)]
+pub struct CodeDirectory<'a> {
+    blob: &'a [u8],
+    hash_offset: usize,
+    special_slots: u32,
+    hash_size: usize,
+    hash_type: u8,
+}
+
+impl<'a> CodeDirectory<'a> {
+    fn parse(signature: &'a [u8], offset: usize) -> Result<Self, Error> {
+        let header = signature_bytes(signature.get(offset..).unwrap_or(&[]));
+        if header.u32(0)? != CSMAGIC_CODEDIRECTORY {
+            return Err(Error::InvalidSignature);
+        }
+        let blob = header.slice(0, header.usize(4)?)?;
+        let header = signature_bytes(blob);
+        let fields = header.slice(36, 2)?;
+        Ok(CodeDirectory {
+            blob,
+            hash_offset: header.usize(16)?,
+            special_slots: header.u32(24)?,
+            hash_size: usize::from(fields[0]),
+            hash_type: fields[1],
+        })
+    }
+
+    /// Returns the bytes of the code directory.
+    #[inline]
+    pub fn bytes(&self) -> &'a [u8] {
+        self.blob
+    }
+
+    /// Returns the hash type of the code directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedHashType`] for an unknown hash type.
+    ///
+    /// [`Error::UnsupportedHashType`]: enum.Error.html#variant.UnsupportedHashType
+    pub fn hash_type(&self) -> Result<HashType, Error> {
+        HashType::from_raw(self.hash_type)
+            .filter(|hash_type| hash_type.digest_len() == self.hash_size)
+            .ok_or(Error::UnsupportedHashType)
+    }
+
+    /// Returns the hash in special slot `slot`, such as [`INFO_SLOT`], or
+    /// `None` if the code directory has fewer special slots.
+    ///
+    /// A slot of all zeros means that nothing is bound to it.
+    ///
+    /// [`INFO_SLOT`]: constant.INFO_SLOT.html
+    pub fn special_slot(&self, slot: u32) -> Option<&'a [u8]> {
+        if slot == 0 || slot > self.special_slots {
+            return None;
+        }
+        let back = (slot as usize).checked_mul(self.hash_size)?;
+        let start = self.hash_offset.checked_sub(back)?;
+        self.blob.get(start..start.checked_add(self.hash_size)?)
+    }
+}
@@ -0,0 +1,340 @@
+use super::{Bytes, Error, Image};
+use crate::{
+    keys::info,
+    query::{query, Node},
+};
+use core::fmt;
+
+const LC_VERSION_MIN_MACOSX: u32 = 0x24;
+const LC_VERSION_MIN_IPHONEOS: u32 = 0x25;
+const LC_VERSION_MIN_TVOS: u32 = 0x2f;
+const LC_VERSION_MIN_WATCHOS: u32 = 0x30;
+const LC_BUILD_VERSION: u32 = 0x32;
+
+/// The platform that an image was built for, from `LC_BUILD_VERSION` or an
+/// `LC_VERSION_MIN_*` load command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Platform {
+    /// macOS.
+    MacOs,
+    /// iOS.
+    IOs,
+    /// tvOS.
+    TvOs,
+    /// watchOS.
+    WatchOs,
+    /// bridgeOS.
+    BridgeOs,
+    /// Mac Catalyst.
+    MacCatalyst,
+    /// The iOS simulator.
+    IOsSimulator,
+    /// The tvOS simulator.
+    TvOsSimulator,
+    /// The watchOS simulator.
+    WatchOsSimulator,
+    /// DriverKit.
+    DriverKit,
+    /// visionOS.
+    VisionOs,
+    /// The visionOS simulator.
+    VisionOsSimulator,
+    /// A platform number that this crate does not know.
+    Unknown(u32),
+}
+
+impl Platform {
+    /// Returns the platform for the `platform` field of `LC_BUILD_VERSION`.
+    pub fn from_raw(raw: u32) -> Self {
+        match raw {
+            1 => Platform::MacOs,
+            2 => Platform::IOs,
+            3 => Platform::TvOs,
+            4 => Platform::WatchOs,
+            5 => Platform::BridgeOs,
+            6 => Platform::MacCatalyst,
+            7 => Platform::IOsSimulator,
+            8 => Platform::TvOsSimulator,
+            9 => Platform::WatchOsSimulator,
+            10 => Platform::DriverKit,
+            11 => Platform::VisionOs,
+            12 => Platform::VisionOsSimulator,
+            raw => Platform::Unknown(raw),
+        }
+    }
+
+    /// Returns the platform for a `CFBundleSupportedPlatforms` entry, such as
+    /// `MacOSX` or `iPhoneSimulator`.
+    pub fn from_bundle_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "MacOSX" => Platform::MacOs,
+            "iPhoneOS" => Platform::IOs,
+            "AppleTVOS" => Platform::TvOs,
+            "WatchOS" => Platform::WatchOs,
+            "iPhoneSimulator" => Platform::IOsSimulator,
+            "AppleTVSimulator" => Platform::TvOsSimulator,
+            "WatchSimulator" => Platform::WatchOsSimulator,
+            "DriverKit" => Platform::DriverKit,
+            "XROS" => Platform::VisionOs,
+            "XRSimulator" => Platform::VisionOsSimulator,
+            _ => return None,
+        })
+    }
+
+    /// Returns the `Info.plist` key for the minimum system version on this
+    /// platform: `LSMinimumSystemVersion` on macOS and `MinimumOSVersion`
+    /// elsewhere.
+    ///
+    /// Mac Catalyst returns `None`, because its load commands hold an iOS
+    /// version while `LSMinimumSystemVersion` holds a macOS version.
+    pub fn minimum_version_key(self) -> Option<&'static str> {
+        match self {
+            Platform::MacOs => Some(info::LS_MINIMUM_SYSTEM_VERSION.name()),
+            Platform::MacCatalyst | Platform::Unknown(_) => None,
+            _ => Some(info::MINIMUM_OS_VERSION.name()),
+        }
+    }
+
+    // Whether an image for `self` can be listed as `bundle` in
+    // `CFBundleSupportedPlatforms`.
+    fn matches_bundle(self, bundle: Platform) -> bool {
+        self == bundle
+            || (self == Platform::MacCatalyst && bundle == Platform::MacOs)
+    }
+}
+
+/// An OS version like `11.0.1`, encoded as `xxxx.yy.zz` in load commands.
+///
+/// The patch number is omitted when it is zero by the [`Display`]
+/// implementation.
+///
+/// [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    /// The major version.
+    pub major: u16,
+    /// The minor version.
+    pub minor: u8,
+    /// The patch version.
+    pub patch: u8,
+}
+
+impl Version {
+    /// Decodes a version from a load command.
+    pub fn from_raw(raw: u32) -> Self {
+        Version {
+            major: (raw >> 16) as u16,
+            minor: (raw >> 8) as u8,
+            patch: raw as u8,
+        }
+    }
+
+    /// Parses a version string like `10.13` or `11.0.1`, as found in
+    /// `Info.plist`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.trim().split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = match parts.next() {
+            Some(minor) => minor.parse().ok()?,
+            None => 0,
+        };
+        let patch = match parts.next() {
+            Some(patch) => patch.parse().ok()?,
+            None => 0,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Version {
+            major,
+            minor,
+            patch,
+        })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)?;
+        if self.patch != 0 {
+            write!(f, ".{}", self.patch)?;
+        }
+        Ok(())
+    }
+}
+
+/// The platform and versions from a build version load command, returned by
+/// [`Image::build_version`].
+///
+/// [`Image::build_version`]: struct.Image.html#method.build_version
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BuildVersion {
+    /// The platform the image was built for.
+    pub platform: Platform,
+    /// The minimum OS version the image requires.
+    pub minimum: Version,
+    /// The SDK version the image was built with.
+    pub sdk: Version,
+}
+
+/// The result of [`Image::check_info_plist_build_version`].
+///
+/// [`Image::check_info_plist_build_version`]: struct.Image.html#method.check_info_plist_build_version
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildVersionCheck {
+    /// `Info.plist` agrees with the load commands.
+    Match(BuildVersion),
+    /// `CFBundleSupportedPlatforms` names a different platform.
+    PlatformMismatch {
+        /// The build version from the load commands.
+        build: BuildVersion,
+        /// The first platform in `CFBundleSupportedPlatforms`.
+        plist: Platform,
+    },
+    /// The minimum version in `Info.plist` differs from the load commands.
+    VersionMismatch {
+        /// The build version from the load commands.
+        build: BuildVersion,
+        /// The key that was read, such as `LSMinimumSystemVersion`.
+        key: &'static str,
+        /// The version in `Info.plist`.
+        plist: Version,
+    },
+    /// `Info.plist` does not have the minimum version key for the platform.
+    MissingMinimumVersion {
+        /// The build version from the load commands.
+        build: BuildVersion,
+        /// The missing key, such as `MinimumOSVersion`.
+        key: &'static str,
+    },
+    /// The image has no build version load command.
+    NoBuildVersion,
+    /// The image does not embed an `Info.plist`.
+    NotEmbedded,
+}
+
+impl<'a> Image<'a> {
+    /// Returns the platform and versions from `LC_BUILD_VERSION`, or from an
+    /// `LC_VERSION_MIN_*` command in images built for older systems.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a load command is malformed.
+    pub fn build_version(&self) -> Result<Option<BuildVersion>, Error> {
+        for command in self.load_commands() {
+            let command = command?;
+            let data = Bytes {
+                bytes: command.data(),
+                big_endian: self.big_endian,
+            };
+            let (platform, minimum, sdk) = match command.cmd() {
+                LC_BUILD_VERSION => (
+                    Platform::from_raw(data.u32(8)?),
+                    data.u32(12)?,
+                    data.u32(16)?,
+                ),
+                LC_VERSION_MIN_MACOSX => {
+                    (Platform::MacOs, data.u32(8)?, data.u32(12)?)
+                }
+                LC_VERSION_MIN_IPHONEOS => {
+                    (Platform::IOs, data.u32(8)?, data.u32(12)?)
+                }
+                LC_VERSION_MIN_TVOS => {
+                    (Platform::TvOs, data.u32(8)?, data.u32(12)?)
+                }
+                LC_VERSION_MIN_WATCHOS => {
+                    (Platform::WatchOs, data.u32(8)?, data.u32(12)?)
+                }
+                _ => continue,
+            };
+            return Ok(Some(BuildVersion {
+                platform,
+                minimum: Version::from_raw(minimum),
+                sdk: Version::from_raw(sdk),
+            }));
+        }
+        Ok(None)
+    }
+
+    /// Compares the platform and minimum OS version in the load commands with
+    /// those claimed by the embedded `Info.plist`.
+    ///
+    /// The first entry of `CFBundleSupportedPlatforms`, if any, must name the
+    /// same platform. The minimum version is read from the key given by
+    /// [`Platform::minimum_version_key`] and must equal the load command's;
+    /// the versions in [`BuildVersionCheck::VersionMismatch`] can be compared
+    /// to tell which side is newer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a load command is malformed, or with
+    /// [`Error::InvalidInfoPlist`] if the embedded `Info.plist` is not a valid
+    /// XML property list or its minimum version is not a version string.
+    ///
+    /// [`Platform::minimum_version_key`]: enum.Platform.html#method.minimum_version_key
+    /// [`BuildVersionCheck::VersionMismatch`]: enum.BuildVersionCheck.html#variant.VersionMismatch
+    /// [`Error::InvalidInfoPlist`]: enum.Error.html#variant.InvalidInfoPlist
+    pub fn check_info_plist_build_version(
+        &self,
+    ) -> Result<BuildVersionCheck, Error> {
+        let build = match self.build_version()? {
+            Some(build) => build,
+            None => return Ok(BuildVersionCheck::NoBuildVersion),
+        };
+        let plist = match self.info_plist()? {
+            Some(plist) => plist,
+            None => return Ok(BuildVersionCheck::NotEmbedded),
+        };
+
+        let platform =
+            optional_str(query(plist, "CFBundleSupportedPlatforms[0]"))?
+                .and_then(Platform::from_bundle_name);
+        if let Some(platform) = platform {
+            if !build.platform.matches_bundle(platform) {
+                return Ok(BuildVersionCheck::PlatformMismatch {
+                    build,
+                    plist: platform,
+                });
+            }
+        }
+
+        let key = match build.platform.minimum_version_key() {
+            Some(key) => key,
+            None => return Ok(BuildVersionCheck::Match(build)),
+        };
+        let version = match optional_str(query(plist, key))? {
+            Some(version) => {
+                Version::parse(version).ok_or(Error::InvalidInfoPlist)?
+            }
+            None => {
+                return Ok(BuildVersionCheck::MissingMinimumVersion {
+                    build,
+                    key,
+                })
+            }
+        };
+
+        Ok(if version == build.minimum {
+            BuildVersionCheck::Match(build)
+        } else {
+            BuildVersionCheck::VersionMismatch {
+                build,
+                key,
+                plist: version,
+            }
+        })
+    }
+}
+
+// Reads a string that may be absent.
+fn optional_str<'a>(
+    node: Result<Node<'a>, crate::Error>,
+) -> Result<Option<&'a str>, Error> {
+    match node.and_then(|node| node.as_str()) {
+        Ok(s) => Ok(Some(s)),
+        Err(crate::Error::MissingKey) | Err(crate::Error::IndexOutOfRange) => {
+            Ok(None)
+        }
+        Err(_) => Err(Error::InvalidInfoPlist),
+    }
+}
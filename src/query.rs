@@ -0,0 +1,455 @@
+// Borrowed lookups into XML property lists, without allocating.
+
+use crate::const_eval::{
+    derive::borrow_text,
+    lookup::{find_key, read_bool, read_integer},
+    xml::{self, Tag, Text},
+    Error, Writer,
+};
+use core::fmt;
+
+/// Returns the value at `path` within the XML property list `bytes`, such as
+/// from [`get_info_plist`].
+///
+/// `path` is a sequence of dictionary keys separated by `.`, each of which can
+/// be followed by array indices in brackets, like
+/// `CFBundleURLTypes[0].CFBundleURLSchemes[1]`. An index may also come first
+/// for a property list whose root is an array, and an empty path returns the
+/// root. Keys that contain `.` or `[` can be reached with [`Node::get`].
+///
+/// This requires the `const_eval` feature, which needs Rust 1.83 or later.
+///
+/// # Strings
+///
+/// Nodes borrow from `bytes` rather than decoding them, so [`Node::as_str`]
+/// fails with [`Error::UnsupportedString`] for a string that contains a
+/// reference like `&amp;` or `&#233;`, or a comment. Such strings are common,
+/// since `&`, `<`, and `>` must be written as references. [`Node::read_str`]
+/// decodes any string into a caller's buffer instead.
+///
+/// # Errors
+///
+/// Returns [`Error::MissingKey`] if a dictionary does not have a key in
+/// `path`, [`Error::IndexOutOfRange`] if an array is too short,
+/// [`Error::TypeMismatch`] if a key is applied to something other than a
+/// dictionary or an index to something other than an array,
+/// [`Error::InvalidPath`] if `path` is malformed, or another [`Error`] if the
+/// property list is invalid along the way.
+///
+/// # Examples
+///
+/// ```rust
+/// const PLIST: &[u8] = br#"<plist><dict>
+///     <key>CFBundleURLTypes</key>
+///     <array>
+///         <dict>
+///             <key>CFBundleURLSchemes</key>
+///             <array><string>example</string><string>example-dev</string></array>
+///         </dict>
+///     </array>
+///     <key>LSUIElement</key><true/>
+/// </dict></plist>"#;
+///
+/// let scheme = embed_plist::query(PLIST, "CFBundleURLTypes[0].CFBundleURLSchemes[1]")?;
+/// assert_eq!(scheme.as_str(), Ok("example-dev"));
+///
+/// let ui_element = embed_plist::query(PLIST, "LSUIElement")?.as_bool();
+/// assert_eq!(ui_element, Ok(true));
+///
+/// let missing = embed_plist::query(PLIST, "CFBundleURLTypes[1]");
+/// assert_eq!(missing.unwrap_err(), embed_plist::Error::IndexOutOfRange);
+/// # Ok::<(), embed_plist::Error>(())
+/// ```
+///
+/// A string with references must be decoded:
+///
+/// ```rust
+/// use embed_plist::Error;
+///
+/// const PLIST: &[u8] = b"<plist><string>Salt &amp; Pepper</string></plist>";
+///
+/// let name = embed_plist::query(PLIST, "")?;
+/// assert_eq!(name.as_str(), Err(Error::UnsupportedString));
+///
+/// let mut buf = [0; 64];
+/// assert_eq!(name.read_str(&mut buf), Ok("Salt & Pepper"));
+/// # Ok::<(), embed_plist::Error>(())
+/// ```
+///
+/// [`get_info_plist`]: fn.get_info_plist.html
+/// [`Node::get`]: struct.Node.html#method.get
+/// [`Node::as_str`]: struct.Node.html#method.as_str
+/// [`Node::read_str`]: struct.Node.html#method.read_str
+/// [`Error::UnsupportedString`]: enum.Error.html#variant.UnsupportedString
+/// [`Error`]: enum.Error.html
+/// [`Error::MissingKey`]: enum.Error.html#variant.MissingKey
+/// [`Error::IndexOutOfRange`]: enum.Error.html#variant.IndexOutOfRange
+/// [`Error::TypeMismatch`]: enum.Error.html#variant.TypeMismatch
+/// [`Error::InvalidPath`]: enum.Error.html#variant.InvalidPath
+#[inline]
+pub fn query<'a>(bytes: &'a [u8], path: &str) -> Result<Node<'a>, Error> {
+    Node::root(bytes)?.query(path)
+}
+
+/// The type of a [`Node`].
+///
+/// [`Node`]: struct.Node.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    /// An `<array>`.
+    Array,
+    /// A `<dict>`.
+    Dictionary,
+    /// A `<true/>` or `<false/>`.
+    Boolean,
+    /// A `<data>`.
+    Data,
+    /// A `<date>`.
+    Date,
+    /// An `<integer>`.
+    Integer,
+    /// A `<real>`.
+    Real,
+    /// A `<string>`.
+    String,
+}
+
+/// A value within an XML property list, borrowed from its bytes.
+///
+/// Nodes are found with [`query`]. Only the parts of the property list that
+/// are needed to reach a node are read, so errors elsewhere go unnoticed.
+///
+/// [`query`]: fn.query.html
+#[derive(Clone, Copy)]
+pub struct Node<'a> {
+    bytes: &'a [u8],
+    tag: Tag,
+    kind: NodeKind,
+}
+
+impl<'a> Node<'a> {
+    /// Returns the root value of the XML property list `bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` does not begin with a property list value.
+    pub fn root(bytes: &'a [u8]) -> Result<Self, Error> {
+        let pos = xml::skip_misc(bytes, 0)?;
+        let mut tag = xml::read_tag(bytes, pos)?;
+        if tag.is(bytes, b"plist") && tag.is_start() {
+            let pos = xml::skip_misc(bytes, tag.end)?;
+            tag = xml::read_tag(bytes, pos)?;
+        }
+        if tag.is_end() {
+            return Err(Error::ExpectedValue);
+        }
+        Node::new(bytes, tag)
+    }
+
+    fn new(bytes: &'a [u8], tag: Tag) -> Result<Self, Error> {
+        let kinds: [(&[u8], NodeKind); 9] = [
+            (b"array", NodeKind::Array),
+            (b"dict", NodeKind::Dictionary),
+            (b"true", NodeKind::Boolean),
+            (b"false", NodeKind::Boolean),
+            (b"data", NodeKind::Data),
+            (b"date", NodeKind::Date),
+            (b"integer", NodeKind::Integer),
+            (b"real", NodeKind::Real),
+            (b"string", NodeKind::String),
+        ];
+        for &(name, kind) in kinds.iter() {
+            if tag.is(bytes, name) {
+                return Ok(Node { bytes, tag, kind });
+            }
+        }
+        Err(Error::UnexpectedElement)
+    }
+
+    /// Returns the type of this value.
+    #[inline]
+    pub fn kind(&self) -> NodeKind {
+        self.kind
+    }
+
+    /// Returns the value at `path` relative to this one.
+    ///
+    /// See [`query`] for the syntax of `path` and the errors returned.
+    ///
+    /// [`query`]: fn.query.html
+    pub fn query(self, path: &str) -> Result<Self, Error> {
+        let path = path.as_bytes();
+        let mut node = self;
+        let mut pos = 0;
+
+        while pos < path.len() {
+            if path[pos] == b'[' {
+                let start = pos + 1;
+                let len = path[start..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_digit())
+                    .count();
+                pos = start + len;
+                if len == 0 || path.get(pos) != Some(&b']') {
+                    return Err(Error::InvalidPath);
+                }
+                // The digits are ASCII, so only overflow can fail here.
+                let index = core::str::from_utf8(&path[start..pos])
+                    .ok()
+                    .and_then(|digits| digits.parse().ok())
+                    .ok_or(Error::IndexOutOfRange)?;
+                node = node.index(index)?;
+                pos += 1;
+            } else {
+                if pos > 0 {
+                    if path[pos] != b'.' {
+                        return Err(Error::InvalidPath);
+                    }
+                    pos += 1;
+                }
+                let start = pos;
+                while pos < path.len() && !matches!(path[pos], b'.' | b'[') {
+                    pos += 1;
+                }
+                if start == pos {
+                    return Err(Error::InvalidPath);
+                }
+                // Splitting at ASCII keeps each key valid UTF-8.
+                let key = core::str::from_utf8(&path[start..pos])
+                    .map_err(|_| Error::InvalidPath)?;
+                node = node.get(key)?;
+            }
+        }
+        Ok(node)
+    }
+
+    /// Returns the value for `key` if this is a dictionary.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] if this is not a dictionary, or
+    /// [`Error::MissingKey`] if it does not contain `key`.
+    ///
+    /// [`Error::TypeMismatch`]: enum.Error.html#variant.TypeMismatch
+    /// [`Error::MissingKey`]: enum.Error.html#variant.MissingKey
+    pub fn get(&self, key: &str) -> Result<Self, Error> {
+        if self.kind != NodeKind::Dictionary {
+            return Err(Error::TypeMismatch);
+        }
+        match find_key(self.bytes, self.tag, key.as_bytes())? {
+            Some(tag) => Node::new(self.bytes, tag),
+            None => Err(Error::MissingKey),
+        }
+    }
+
+    /// Returns the element at `index` if this is an array.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] if this is not an array, or
+    /// [`Error::IndexOutOfRange`] if it has no element at `index`.
+    ///
+    /// [`Error::TypeMismatch`]: enum.Error.html#variant.TypeMismatch
+    /// [`Error::IndexOutOfRange`]: enum.Error.html#variant.IndexOutOfRange
+    pub fn index(&self, index: usize) -> Result<Self, Error> {
+        if self.kind != NodeKind::Array {
+            return Err(Error::TypeMismatch);
+        }
+        let mut children = self.children();
+        for _ in 0..index {
+            match children.next()? {
+                Some(_) => {}
+                None => return Err(Error::IndexOutOfRange),
+            }
+        }
+        match children.next()? {
+            Some(tag) => Node::new(self.bytes, tag),
+            None => Err(Error::IndexOutOfRange),
+        }
+    }
+
+    /// Returns the number of elements if this is an array, or of entries if
+    /// this is a dictionary.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] if this is neither an array nor a
+    /// dictionary.
+    ///
+    /// [`Error::TypeMismatch`]: enum.Error.html#variant.TypeMismatch
+    pub fn len(&self) -> Result<usize, Error> {
+        let per_entry = match self.kind {
+            NodeKind::Array => 1,
+            NodeKind::Dictionary => 2,
+            _ => return Err(Error::TypeMismatch),
+        };
+        let mut children = self.children();
+        let mut len = 0;
+        while children.next()?.is_some() {
+            len += 1;
+        }
+        Ok(len / per_entry)
+    }
+
+    /// Returns whether this is an empty array or dictionary.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] if this is neither an array nor a
+    /// dictionary.
+    ///
+    /// [`Error::TypeMismatch`]: enum.Error.html#variant.TypeMismatch
+    pub fn is_empty(&self) -> Result<bool, Error> {
+        match self.kind {
+            NodeKind::Array | NodeKind::Dictionary => {
+                Ok(self.children().next()?.is_none())
+            }
+            _ => Err(Error::TypeMismatch),
+        }
+    }
+
+    /// Returns the string if this is a `<string>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] if this is not a string, or
+    /// [`Error::UnsupportedString`] if it contains references or comments and
+    /// so cannot be borrowed. Use [`read_str`] for those.
+    ///
+    /// [`read_str`]: #method.read_str
+    /// [`Error::TypeMismatch`]: enum.Error.html#variant.TypeMismatch
+    /// [`Error::UnsupportedString`]: enum.Error.html#variant.UnsupportedString
+    pub fn as_str(&self) -> Result<&'a str, Error> {
+        if self.kind != NodeKind::String {
+            return Err(Error::TypeMismatch);
+        }
+        borrow_text(self.bytes, self.tag, b"string")
+    }
+
+    /// Decodes the string into `buf` if this is a `<string>`, returning the
+    /// part of `buf` that it fills.
+    ///
+    /// Unlike [`as_str`], this resolves references and skips comments. The
+    /// decoded string is never longer than the element's text, so a buffer of
+    /// that length always suffices.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] if this is not a string,
+    /// [`Error::BufferTooSmall`] if the decoded string does not fit in `buf`,
+    /// or another [`Error`] if the string is malformed.
+    ///
+    /// [`as_str`]: #method.as_str
+    /// [`Error`]: enum.Error.html
+    /// [`Error::TypeMismatch`]: enum.Error.html#variant.TypeMismatch
+    /// [`Error::BufferTooSmall`]: enum.Error.html#variant.BufferTooSmall
+    pub fn read_str<'b>(&self, buf: &'b mut [u8]) -> Result<&'b str, Error> {
+        if self.kind != NodeKind::String {
+            return Err(Error::TypeMismatch);
+        }
+        let mut out = Writer::new(buf);
+        if !self.tag.is_empty() {
+            let mut text = Text::new(self.tag.end);
+            while let Some(ch) = text.next(self.bytes)? {
+                out.push_char(ch);
+            }
+            xml::expect_end(self.bytes, text.pos(), b"string")?;
+        }
+        let len = out.len();
+        let decoded = buf.get(..len).ok_or(Error::BufferTooSmall)?;
+        core::str::from_utf8(decoded).map_err(|_| Error::InvalidUtf8)
+    }
+
+    /// Returns the boolean if this is a `<true/>` or `<false/>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] if this is not a boolean, or
+    /// [`Error::InvalidValue`] if the element is not empty.
+    ///
+    /// [`Error::TypeMismatch`]: enum.Error.html#variant.TypeMismatch
+    /// [`Error::InvalidValue`]: enum.Error.html#variant.InvalidValue
+    pub fn as_bool(&self) -> Result<bool, Error> {
+        if self.kind != NodeKind::Boolean {
+            return Err(Error::TypeMismatch);
+        }
+        read_bool(self.bytes, self.tag)
+    }
+
+    /// Returns the integer if this is an `<integer>`, which can hold any `i64`
+    /// or `u64`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] if this is not an integer, or
+    /// [`Error::InvalidValue`] if it is malformed.
+    ///
+    /// [`Error::TypeMismatch`]: enum.Error.html#variant.TypeMismatch
+    /// [`Error::InvalidValue`]: enum.Error.html#variant.InvalidValue
+    pub fn as_integer(&self) -> Result<i128, Error> {
+        if self.kind != NodeKind::Integer {
+            return Err(Error::TypeMismatch);
+        }
+        read_integer(self.bytes, self.tag)
+    }
+
+    /// Returns the number if this is a `<real>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] if this is not a real, or
+    /// [`Error::InvalidValue`] if it is malformed.
+    ///
+    /// [`Error::TypeMismatch`]: enum.Error.html#variant.TypeMismatch
+    /// [`Error::InvalidValue`]: enum.Error.html#variant.InvalidValue
+    pub fn as_real(&self) -> Result<f64, Error> {
+        if self.kind != NodeKind::Real {
+            return Err(Error::TypeMismatch);
+        }
+        let text = match borrow_text(self.bytes, self.tag, b"real") {
+            Err(Error::UnsupportedString) => return Err(Error::InvalidValue),
+            result => result?,
+        };
+        text.trim().parse().map_err(|_| Error::InvalidValue)
+    }
+
+    // Iterates over the opening tags of the elements within this one, which
+    // for a dictionary alternate between keys and values.
+    fn children(&self) -> Children<'a> {
+        Children {
+            bytes: self.bytes,
+            pos: if self.tag.is_start() {
+                Some(self.tag.end)
+            } else {
+                None
+            },
+        }
+    }
+}
+
+impl fmt::Debug for Node<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Node").field("kind", &self.kind).finish()
+    }
+}
+
+struct Children<'a> {
+    bytes: &'a [u8],
+    pos: Option<usize>,
+}
+
+impl Children<'_> {
+    fn next(&mut self) -> Result<Option<Tag>, Error> {
+        let pos = match self.pos {
+            Some(pos) => xml::skip_misc(self.bytes, pos)?,
+            None => return Ok(None),
+        };
+        let tag = xml::read_tag(self.bytes, pos)?;
+        if tag.is_end() {
+            self.pos = None;
+            return Ok(None);
+        }
+        self.pos = Some(xml::skip_element(self.bytes, tag)?);
+        Ok(Some(tag))
+    }
+}
@@ -0,0 +1,253 @@
+// Reading and writing of binary property lists.
+
+use super::{Date, Dictionary, Value};
+use crate::binary::{Document, Error, Object, HEADER};
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::convert::TryFrom;
+
+// Keeps deeply nested input from overflowing the stack.
+const MAX_DEPTH: usize = 512;
+
+pub(super) fn parse(bytes: &[u8]) -> Result<Value, Error> {
+    let document = Document::parse(bytes)?;
+    let mut path = Vec::new();
+    convert(&document, document.root_index(), &mut path)
+}
+
+// Converts the object at `index`, where `path` holds the indices of the
+// containers being converted. Finding `index` among them means a cycle.
+fn convert(
+    document: &Document,
+    index: u64,
+    path: &mut Vec<u64>,
+) -> Result<Value, Error> {
+    if path.contains(&index) {
+        return Err(Error::Cycle);
+    }
+    if path.len() == MAX_DEPTH {
+        return Err(Error::TooDeep);
+    }
+
+    let value = match document.object(index)? {
+        Object::Array(array) => {
+            path.push(index);
+            let values = (0..array.len())
+                .map(|i| convert(document, array.index(i), path))
+                .collect::<Result<_, _>>()?;
+            path.pop();
+            Value::Array(values)
+        }
+        Object::Dictionary(dict) => {
+            path.push(index);
+            let mut entries = Dictionary::new();
+            for i in 0..dict.len() {
+                let key: String =
+                    dict.key(i)?.chars().collect::<Result<_, _>>()?;
+                let value = convert(document, dict.value_index(i), path)?;
+                entries.insert(key, value);
+            }
+            path.pop();
+            Value::Dictionary(entries)
+        }
+        Object::Boolean(boolean) => Value::Boolean(boolean),
+        Object::Data(data) => Value::Data(data.into()),
+        Object::Date(seconds) => Value::Date(
+            Date::from_seconds_since_2001(seconds)
+                .ok_or(Error::InvalidObject)?,
+        ),
+        Object::Integer(int) => Value::Integer(int),
+        Object::Real(real) => Value::Real(real),
+        Object::String(string) => {
+            Value::String(string.chars().collect::<Result<_, _>>()?)
+        }
+        Object::Uid(uid) => Value::Uid(uid),
+    };
+    Ok(value)
+}
+
+pub(super) fn write(value: &Value) -> Vec<u8> {
+    let mut objects = Objects::default();
+    objects.add(value);
+    let objects = objects.list;
+    let ref_size = byte_len(objects.len() as u64 - 1);
+
+    let mut out = HEADER.to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (object, refs) in &objects {
+        offsets.push(out.len() as u64);
+        write_object(*object, refs, ref_size, &mut out);
+    }
+
+    let offset_table = out.len() as u64;
+    let offset_size = byte_len(offset_table);
+    for offset in offsets {
+        write_uint(offset, offset_size, &mut out);
+    }
+
+    // The trailer starts with 5 unused bytes and a sort version of 0.
+    out.extend_from_slice(&[0; 6]);
+    out.push(offset_size as u8);
+    out.push(ref_size as u8);
+    write_uint(objects.len() as u64, 8, &mut out);
+    write_uint(0, 8, &mut out);
+    write_uint(offset_table, 8, &mut out);
+    out
+}
+
+#[derive(Clone, Copy)]
+enum Flat<'a> {
+    Value(&'a Value),
+    Key(&'a str),
+}
+
+// Lists values in the order they are written, with the indices that containers
+// refer to. Equal strings are written once, like Core Foundation does.
+#[derive(Default)]
+struct Objects<'a> {
+    list: Vec<(Flat<'a>, Vec<u64>)>,
+    strings: BTreeMap<&'a str, u64>,
+}
+
+impl<'a> Objects<'a> {
+    fn add(&mut self, value: &'a Value) -> u64 {
+        if let Value::String(string) = value {
+            return self.add_string(Flat::Value(value), string);
+        }
+
+        let index = self.list.len() as u64;
+        self.list.push((Flat::Value(value), Vec::new()));
+        let refs = match value {
+            Value::Array(array) => {
+                array.iter().map(|value| self.add(value)).collect()
+            }
+            Value::Dictionary(dict) => {
+                let keys: Vec<u64> = dict
+                    .keys()
+                    .map(|key| self.add_string(Flat::Key(key), key))
+                    .collect();
+                let values = dict.iter().map(|(_, value)| self.add(value));
+                keys.into_iter().chain(values.collect::<Vec<_>>()).collect()
+            }
+            _ => return index,
+        };
+        self.list[index as usize].1 = refs;
+        index
+    }
+
+    fn add_string(&mut self, object: Flat<'a>, string: &'a str) -> u64 {
+        if let Some(&index) = self.strings.get(string) {
+            return index;
+        }
+        let index = self.list.len() as u64;
+        self.list.push((object, Vec::new()));
+        self.strings.insert(string, index);
+        index
+    }
+}
+
+fn write_object(
+    object: Flat,
+    refs: &[u64],
+    ref_size: usize,
+    out: &mut Vec<u8>,
+) {
+    let value = match object {
+        Flat::Key(key) => return write_string(key, out),
+        Flat::Value(value) => value,
+    };
+    match value {
+        Value::Array(_) => {
+            write_marker(0xA, refs.len(), out);
+            for &index in refs {
+                write_uint(index, ref_size, out);
+            }
+        }
+        Value::Dictionary(_) => {
+            write_marker(0xD, refs.len() / 2, out);
+            for &index in refs {
+                write_uint(index, ref_size, out);
+            }
+        }
+        Value::Boolean(boolean) => out.push(0x08 | u8::from(*boolean)),
+        Value::Data(data) => {
+            write_marker(0x4, data.len(), out);
+            out.extend_from_slice(data);
+        }
+        Value::Date(date) => {
+            out.push(0x33);
+            out.extend_from_slice(&date.seconds_since_2001().to_be_bytes());
+        }
+        Value::Integer(int) => write_integer(*int, out),
+        Value::Real(real) => {
+            out.push(0x23);
+            out.extend_from_slice(&real.to_be_bytes());
+        }
+        Value::String(string) => write_string(string, out),
+        Value::Uid(uid) => {
+            let size = byte_len(*uid).next_power_of_two();
+            out.push(0x80 | (size as u8 - 1));
+            write_uint(*uid, size, out);
+        }
+    }
+}
+
+// Writes ASCII as is and anything else as big-endian UTF-16.
+fn write_string(string: &str, out: &mut Vec<u8>) {
+    if string.is_ascii() {
+        write_marker(0x5, string.len(), out);
+        out.extend_from_slice(string.as_bytes());
+    } else {
+        let units: Vec<u16> = string.encode_utf16().collect();
+        write_marker(0x6, units.len(), out);
+        for unit in units {
+            out.extend_from_slice(&unit.to_be_bytes());
+        }
+    }
+}
+
+// Uses the smallest size that Core Foundation would. Sizes below 8 bytes are
+// read as unsigned, so negative numbers take 8 and large unsigned ones take 16.
+fn write_integer(int: i128, out: &mut Vec<u8>) {
+    match int {
+        0..=0xFF => {
+            out.push(0x10);
+            write_uint(int as u64, 1, out);
+        }
+        0x100..=0xFFFF => {
+            out.push(0x11);
+            write_uint(int as u64, 2, out);
+        }
+        0x1_0000..=0xFFFF_FFFF => {
+            out.push(0x12);
+            write_uint(int as u64, 4, out);
+        }
+        _ if i64::try_from(int).is_ok() => {
+            out.push(0x13);
+            out.extend_from_slice(&(int as i64).to_be_bytes());
+        }
+        _ => {
+            out.push(0x14);
+            out.extend_from_slice(&int.to_be_bytes());
+        }
+    }
+}
+
+// Writes a type marker with `len` in its low bits, or followed by an integer
+// if it does not fit.
+fn write_marker(kind: u8, len: usize, out: &mut Vec<u8>) {
+    if len < 0x0F {
+        out.push(kind << 4 | len as u8);
+    } else {
+        out.push(kind << 4 | 0x0F);
+        write_integer(len as i128, out);
+    }
+}
+
+fn write_uint(int: u64, size: usize, out: &mut Vec<u8>) {
+    out.extend_from_slice(&int.to_be_bytes()[8 - size..]);
+}
+
+// Returns the number of bytes needed to hold `int`.
+fn byte_len(int: u64) -> usize {
+    ((64 - int.leading_zeros() as usize).div_ceil(8)).max(1)
+}
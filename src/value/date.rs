@@ -0,0 +1,177 @@
+use core::fmt;
+
+// Seconds from the Unix epoch to the Core Foundation epoch, 2001-01-01.
+const UNIX_TO_2001: i64 = 978_307_200;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A `<date>`, stored like Core Foundation as seconds since
+/// 2001-01-01T00:00:00Z.
+///
+/// Dates are limited to the years 1 through 9999, which covers everything
+/// that ISO 8601 text in an XML property list can express.
+///
+/// # Examples
+///
+/// ```rust
+/// use embed_plist::value::Date;
+///
+/// let date = Date::from_iso8601("2020-08-30T00:00:00Z").unwrap();
+/// assert_eq!(date.seconds_since_2001(), 620_438_400.0);
+/// assert_eq!(date.to_string(), "2020-08-30T00:00:00Z");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Date {
+    seconds: f64,
+}
+
+impl Date {
+    /// Creates a date from seconds since 2001-01-01T00:00:00Z, as stored in
+    /// binary property lists.
+    ///
+    /// Returns `None` if the date is not finite or is outside the supported
+    /// years.
+    pub fn from_seconds_since_2001(seconds: f64) -> Option<Self> {
+        // Anything this large is far outside the supported years, and checking
+        // first keeps the conversions below from overflowing.
+        if seconds.is_nan() || seconds.abs() >= 1e12 {
+            return None;
+        }
+        let (year, ..) =
+            civil_from_days(floor(seconds).div_euclid(SECONDS_PER_DAY));
+        if (1..=9999).contains(&year) {
+            Some(Date { seconds })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of seconds since 2001-01-01T00:00:00Z.
+    #[inline]
+    pub fn seconds_since_2001(self) -> f64 {
+        self.seconds
+    }
+
+    /// Parses the `YYYY-MM-DDTHH:MM:SSZ` format used by XML property lists.
+    pub fn from_iso8601(text: &str) -> Option<Self> {
+        let bytes = text.as_bytes();
+        if bytes.len() != 20
+            || [4, 7].iter().any(|&i| bytes[i] != b'-')
+            || bytes[10] != b'T'
+            || [13, 16].iter().any(|&i| bytes[i] != b':')
+            || bytes[19] != b'Z'
+        {
+            return None;
+        }
+        let number = |range: core::ops::Range<usize>| {
+            bytes[range].iter().try_fold(0, |n: i64, &byte| {
+                if byte.is_ascii_digit() {
+                    Some(n * 10 + i64::from(byte - b'0'))
+                } else {
+                    None
+                }
+            })
+        };
+
+        let year = number(0..4)?;
+        let month = number(5..7)?;
+        let day = number(8..10)?;
+        let hour = number(11..13)?;
+        let minute = number(14..16)?;
+        let second = number(17..19)?;
+
+        if year == 0
+            || !(1..=12).contains(&month)
+            || day < 1
+            || day > days_in_month(year, month)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return None;
+        }
+
+        let days = days_from_civil(year, month, day);
+        let seconds =
+            days * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second;
+        Some(Date {
+            seconds: (seconds - UNIX_TO_2001) as f64,
+        })
+    }
+}
+
+impl fmt::Display for Date {
+    /// Writes the `YYYY-MM-DDTHH:MM:SSZ` format used by XML property lists,
+    /// dropping fractions of a second like Core Foundation.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let seconds = floor(self.seconds);
+        let (year, month, day) =
+            civil_from_days(seconds.div_euclid(SECONDS_PER_DAY));
+        let time = seconds.rem_euclid(SECONDS_PER_DAY);
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            year,
+            month,
+            day,
+            time / 3600,
+            time / 60 % 60,
+            time % 60,
+        )
+    }
+}
+
+// Rounds toward negative infinity and converts to Unix time, since `f64::floor`
+// requires `std`.
+fn floor(seconds: f64) -> i64 {
+    let whole = seconds as i64;
+    let whole = if (whole as f64) > seconds {
+        whole - 1
+    } else {
+        whole
+    };
+    whole + UNIX_TO_2001
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Returns the number of days since 1970-01-01 in the proleptic Gregorian
+// calendar, using Howard Hinnant's algorithm.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era =
+        year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+// The inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524
+        - day_of_era / 146_096)
+        / 365;
+    let day_of_year =
+        day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
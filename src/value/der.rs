@@ -0,0 +1,176 @@
+// Reading of entitlements in Apple's DER profile, as written by
+// `embed_entitlements!`.
+
+use super::{Dictionary, Value};
+use crate::Error;
+use alloc::{string::String, vec::Vec};
+
+const MAX_DEPTH: usize = 128;
+
+pub(super) fn parse(bytes: &[u8]) -> Result<Value, Error> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let mut document = reader.element(0x70)?;
+    if reader.pos != bytes.len() {
+        return Err(Error::TrailingData);
+    }
+
+    let (tag, version) = document.any()?;
+    if tag != 0x02 || version != [1] {
+        return Err(Error::InvalidValue);
+    }
+    let (tag, dict) = document.any()?;
+    if tag != 0xB0 {
+        return Err(Error::TypeMismatch);
+    }
+    if document.pos != document.bytes.len() {
+        return Err(Error::TrailingData);
+    }
+    value(tag, dict, 0)
+}
+
+// Decodes the contents of an element with `tag`.
+fn value(tag: u8, contents: &[u8], depth: usize) -> Result<Value, Error> {
+    let mut reader = Reader {
+        bytes: contents,
+        pos: 0,
+    };
+    match tag {
+        0xB0 | 0x30 if depth == MAX_DEPTH => Err(Error::TooDeep),
+        0xB0 => {
+            let mut dict = Dictionary::new();
+            let mut prev: Option<String> = None;
+            while !reader.is_done() {
+                let mut entry = reader.element(0x30)?;
+                let (tag, key) = entry.any()?;
+                if tag != 0x0C {
+                    return Err(Error::ExpectedKey);
+                }
+                let key = string(key)?;
+                let (tag, contents) = entry.any()?;
+                if !entry.is_done() {
+                    return Err(Error::InvalidValue);
+                }
+                // Keys must be sorted for the encoding to be canonical.
+                if let Some(prev) = &prev {
+                    if key == *prev {
+                        return Err(Error::DuplicateKey);
+                    } else if key < *prev {
+                        return Err(Error::InvalidValue);
+                    }
+                }
+                dict.insert(key.clone(), value(tag, contents, depth + 1)?);
+                prev = Some(key);
+            }
+            Ok(Value::Dictionary(dict))
+        }
+        0x30 => {
+            let mut array = Vec::new();
+            while !reader.is_done() {
+                let (tag, contents) = reader.any()?;
+                array.push(value(tag, contents, depth + 1)?);
+            }
+            Ok(Value::Array(array))
+        }
+        0x0C => string(contents).map(Value::String),
+        0x01 => match contents {
+            [0xFF] => Ok(Value::Boolean(true)),
+            [0x00] => Ok(Value::Boolean(false)),
+            _ => Err(Error::InvalidValue),
+        },
+        0x02 => integer(contents).map(Value::Integer),
+        _ => Err(Error::UnexpectedElement),
+    }
+}
+
+fn string(contents: &[u8]) -> Result<String, Error> {
+    match core::str::from_utf8(contents) {
+        Ok(s) => Ok(s.into()),
+        Err(_) => Err(Error::InvalidUtf8),
+    }
+}
+
+// Reads a minimal two's complement integer that fits in an `i64` or `u64`.
+fn integer(contents: &[u8]) -> Result<i128, Error> {
+    let redundant = match contents {
+        [0x00, next, ..] => next & 0x80 == 0,
+        [0xFF, next, ..] => next & 0x80 != 0,
+        [] => true,
+        _ => false,
+    };
+    if redundant || contents.len() > 9 {
+        return Err(Error::InvalidValue);
+    }
+
+    let mut value: i128 = if contents[0] & 0x80 != 0 { -1 } else { 0 };
+    for &byte in contents {
+        value = (value << 8) | byte as i128;
+    }
+    if value < i64::MIN as i128 || value > u64::MAX as i128 {
+        return Err(Error::InvalidValue);
+    }
+    Ok(value)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_done(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        let byte = *self.bytes.get(self.pos).ok_or(Error::UnexpectedEnd)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    // Reads a definite length in its shortest form.
+    fn length(&mut self) -> Result<usize, Error> {
+        let first = self.byte()?;
+        if first < 0x80 {
+            return Ok(first as usize);
+        }
+        let count = (first & 0x7F) as usize;
+        if count == 0 || count > core::mem::size_of::<usize>() {
+            return Err(Error::InvalidValue);
+        }
+        let mut len = 0;
+        for _ in 0..count {
+            len = (len << 8) | self.byte()? as usize;
+        }
+        if len < 0x80 || len >> (8 * (count - 1)) == 0 {
+            return Err(Error::InvalidValue);
+        }
+        Ok(len)
+    }
+
+    // Reads the tag and contents of the next element.
+    fn any(&mut self) -> Result<(u8, &'a [u8]), Error> {
+        let tag = self.byte()?;
+        let len = self.length()?;
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(Error::UnexpectedEnd)?;
+        let contents = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok((tag, contents))
+    }
+
+    // Reads the next element, which must have `tag`, and returns a reader for
+    // its contents.
+    fn element(&mut self, tag: u8) -> Result<Reader<'a>, Error> {
+        let (found, contents) = self.any()?;
+        if found != tag {
+            return Err(Error::TypeMismatch);
+        }
+        Ok(Reader {
+            bytes: contents,
+            pos: 0,
+        })
+    }
+}
@@ -0,0 +1,485 @@
+// Semantic comparison of values, and the differences between them.
+
+use super::Value;
+use alloc::{string::String, vec::Vec};
+use core::{fmt, slice};
+
+/// A step from a value into one of its children.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Segment {
+    /// The value for a key of a dictionary.
+    Key(String),
+    /// The element at an index of an array.
+    Index(usize),
+}
+
+/// The location of a value within a property list, such as
+/// `CFBundleURLTypes[0].CFBundleURLSchemes`.
+///
+/// This is displayed in the syntax of [`query`], and is empty for the root.
+///
+/// [`query`]: ../fn.query.html
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct KeyPath {
+    segments: Vec<Segment>,
+}
+
+impl KeyPath {
+    /// Returns the path of the root value.
+    #[inline]
+    pub fn root() -> Self {
+        KeyPath::default()
+    }
+
+    /// Returns the steps from the root to the value.
+    #[inline]
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    fn join(&self, segment: Segment) -> Self {
+        let mut path = self.clone();
+        path.segments.push(segment);
+        path
+    }
+}
+
+impl fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Key(key) if i == 0 => f.write_str(key)?,
+                Segment::Key(key) => write!(f, ".{}", key)?,
+                Segment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A single difference found by [`Value::diff`].
+///
+/// [`Value::diff`]: enum.Value.html#method.diff
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    /// A key or array element that only exists in the new value.
+    Added {
+        /// Where the value was added.
+        path: KeyPath,
+        /// The added value.
+        value: Value,
+    },
+    /// A key or array element that only exists in the old value.
+    Removed {
+        /// Where the value was removed from.
+        path: KeyPath,
+        /// The removed value.
+        value: Value,
+    },
+    /// A value that differs in type or contents, other than in the entries of
+    /// a dictionary or the elements of an array.
+    Changed {
+        /// Where the value changed.
+        path: KeyPath,
+        /// The old value.
+        old: Value,
+        /// The new value.
+        new: Value,
+    },
+}
+
+impl Change {
+    /// Returns where this change happened.
+    #[inline]
+    pub fn path(&self) -> &KeyPath {
+        match self {
+            Change::Added { path, .. }
+            | Change::Removed { path, .. }
+            | Change::Changed { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (sign, path) = match self {
+            Change::Added { path, .. } => ('+', path),
+            Change::Removed { path, .. } => ('-', path),
+            Change::Changed { path, .. } => ('~', path),
+        };
+        if path.segments.is_empty() {
+            write!(f, "{} (root): ", sign)?;
+        } else {
+            write!(f, "{} {}: ", sign, path)?;
+        }
+        match self {
+            Change::Added { value, .. } | Change::Removed { value, .. } => {
+                write!(f, "{:?}", value)
+            }
+            Change::Changed { old, new, .. } => {
+                write!(f, "{:?} -> {:?}", old, new)
+            }
+        }
+    }
+}
+
+/// The differences between two values, which can be applied as a patch.
+///
+/// See [`Value::diff`].
+///
+/// [`Value::diff`]: enum.Value.html#method.diff
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Diff {
+    changes: Vec<Change>,
+}
+
+impl Diff {
+    /// Returns `true` if the values are semantically equal.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the number of changes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Returns the changes, ordered by where they appear in the values.
+    #[inline]
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Returns an iterator over the changes.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, Change> {
+        self.changes.iter()
+    }
+
+    /// Applies the changes to `value`, which turns the old value of
+    /// [`Value::diff`] into the new one.
+    ///
+    /// Each change checks that `value` still has the old contents at its path,
+    /// so other edits to `value` are kept as long as they do not touch the
+    /// same paths. If any change does not apply, `value` is left unmodified.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PatchError::MissingPath`] if a path to change or remove does
+    /// not exist, or [`PatchError::Conflict`] if the value there is not the
+    /// one the change expects, or a key to add already exists.
+    ///
+    /// [`Value::diff`]: enum.Value.html#method.diff
+    /// [`PatchError::MissingPath`]: enum.PatchError.html#variant.MissingPath
+    /// [`PatchError::Conflict`]: enum.PatchError.html#variant.Conflict
+    pub fn apply(&self, value: &mut Value) -> Result<(), PatchError> {
+        let mut patched = value.clone();
+        for change in &self.changes {
+            apply(change, &mut patched)?;
+        }
+        *value = patched;
+        Ok(())
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a Diff {
+    type Item = &'a Change;
+    type IntoIter = slice::Iter<'a, Change>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Diff {
+    type Item = Change;
+    type IntoIter = alloc::vec::IntoIter<Change>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.into_iter()
+    }
+}
+
+/// An error encountered while applying a [`Diff`].
+///
+/// [`Diff`]: struct.Diff.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PatchError {
+    /// A path to change or remove, or the parent of a path to add, does not
+    /// exist.
+    MissingPath(KeyPath),
+    /// The value at a path is not the one the change expects, or a key to add
+    /// already exists.
+    Conflict(KeyPath),
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatchError::MissingPath(path) => {
+                write!(f, "path `{}` does not exist", path)
+            }
+            PatchError::Conflict(path) => {
+                write!(f, "value at `{}` does not match the patch", path)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PatchError {}
+
+// Whether `a` and `b` are equal regardless of dictionary order, with NaN equal
+// to itself.
+pub(super) fn semantic_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len()
+                && a.iter().zip(b).all(|(a, b)| semantic_eq(a, b))
+        }
+        (Value::Dictionary(a), Value::Dictionary(b)) => {
+            a.len() == b.len()
+                && a.iter().all(|(key, a)| {
+                    b.get(key).is_some_and(|b| semantic_eq(a, b))
+                })
+        }
+        (Value::Real(a), Value::Real(b)) => {
+            a == b || (a.is_nan() && b.is_nan())
+        }
+        (Value::Date(a), Value::Date(b)) => {
+            a.seconds_since_2001() == b.seconds_since_2001()
+        }
+        _ => a == b,
+    }
+}
+
+pub(super) fn diff(old: &Value, new: &Value) -> Diff {
+    let mut changes = Vec::new();
+    diff_at(&KeyPath::root(), old, new, &mut changes);
+    Diff { changes }
+}
+
+fn diff_at(path: &KeyPath, old: &Value, new: &Value, out: &mut Vec<Change>) {
+    match (old, new) {
+        (Value::Dictionary(old), Value::Dictionary(new)) => {
+            for (key, old) in old {
+                let path = path.join(Segment::Key(key.into()));
+                match new.get(key) {
+                    Some(new) => diff_at(&path, old, new, out),
+                    None => out.push(Change::Removed {
+                        path,
+                        value: old.clone(),
+                    }),
+                }
+            }
+            for (key, new) in
+                new.iter().filter(|(key, _)| !old.contains_key(key))
+            {
+                out.push(Change::Added {
+                    path: path.join(Segment::Key(key.into())),
+                    value: new.clone(),
+                });
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for (index, (old, new)) in old.iter().zip(new).enumerate() {
+                diff_at(&path.join(Segment::Index(index)), old, new, out);
+            }
+            // Removals go from the back so that each index is still valid when
+            // the diff is applied in order.
+            for index in (new.len()..old.len()).rev() {
+                out.push(Change::Removed {
+                    path: path.join(Segment::Index(index)),
+                    value: old[index].clone(),
+                });
+            }
+            for (index, new) in new.iter().enumerate().skip(old.len()) {
+                out.push(Change::Added {
+                    path: path.join(Segment::Index(index)),
+                    value: new.clone(),
+                });
+            }
+        }
+        _ if semantic_eq(old, new) => {}
+        _ => out.push(Change::Changed {
+            path: path.clone(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+    }
+}
+
+fn apply(change: &Change, root: &mut Value) -> Result<(), PatchError> {
+    let path = change.path();
+    let missing = || PatchError::MissingPath(path.clone());
+    let conflict = || PatchError::Conflict(path.clone());
+
+    let (last, parents) = match path.segments.split_last() {
+        Some(split) => split,
+        None => {
+            return match change {
+                Change::Changed { old, new, .. } if semantic_eq(root, old) => {
+                    *root = new.clone();
+                    Ok(())
+                }
+                _ => Err(conflict()),
+            };
+        }
+    };
+
+    let mut parent = root;
+    for segment in parents {
+        parent = child_mut(parent, segment).ok_or_else(missing)?;
+    }
+
+    match change {
+        Change::Changed { old, new, .. } => {
+            let target = child_mut(parent, last).ok_or_else(missing)?;
+            if !semantic_eq(target, old) {
+                return Err(conflict());
+            }
+            *target = new.clone();
+        }
+        Change::Removed { value, .. } => {
+            let target = child_mut(parent, last).ok_or_else(missing)?;
+            if !semantic_eq(target, value) {
+                return Err(conflict());
+            }
+            match (parent, last) {
+                (Value::Dictionary(dict), Segment::Key(key)) => {
+                    dict.remove(key);
+                }
+                (Value::Array(array), &Segment::Index(index)) => {
+                    array.remove(index);
+                }
+                _ => unreachable!(),
+            }
+        }
+        Change::Added { value, .. } => match (parent, last) {
+            (Value::Dictionary(dict), Segment::Key(key)) => {
+                if dict.contains_key(key) {
+                    return Err(conflict());
+                }
+                dict.insert(key.as_str(), value.clone());
+            }
+            (Value::Array(array), &Segment::Index(index)) => {
+                if index > array.len() {
+                    return Err(missing());
+                }
+                array.insert(index, value.clone());
+            }
+            _ => return Err(missing()),
+        },
+    }
+    Ok(())
+}
+
+fn child_mut<'a>(
+    value: &'a mut Value,
+    segment: &Segment,
+) -> Option<&'a mut Value> {
+    match (value, segment) {
+        (Value::Dictionary(dict), Segment::Key(key)) => dict.get_mut(key),
+        (Value::Array(array), &Segment::Index(index)) => array.get_mut(index),
+        _ => None,
+    }
+}
+
+// Support for `assert_plist_eq!`, which accepts values and the bytes of XML or
+// binary property lists.
+#[doc(hidden)]
+pub trait _PlistOperand {
+    fn _to_value(&self) -> Result<Value, String>;
+}
+
+impl _PlistOperand for Value {
+    fn _to_value(&self) -> Result<Value, String> {
+        Ok(self.clone())
+    }
+}
+
+impl _PlistOperand for [u8] {
+    fn _to_value(&self) -> Result<Value, String> {
+        if self.starts_with(crate::binary::HEADER) {
+            Value::from_binary(self).map_err(|error| error.message().into())
+        } else {
+            Value::from_xml(self).map_err(|error| error.message().into())
+        }
+    }
+}
+
+impl<const N: usize> _PlistOperand for [u8; N] {
+    fn _to_value(&self) -> Result<Value, String> {
+        self[..]._to_value()
+    }
+}
+
+impl _PlistOperand for Vec<u8> {
+    fn _to_value(&self) -> Result<Value, String> {
+        self[..]._to_value()
+    }
+}
+
+impl _PlistOperand for str {
+    fn _to_value(&self) -> Result<Value, String> {
+        self.as_bytes()._to_value()
+    }
+}
+
+impl _PlistOperand for String {
+    fn _to_value(&self) -> Result<Value, String> {
+        self.as_bytes()._to_value()
+    }
+}
+
+impl<T: _PlistOperand + ?Sized> _PlistOperand for &T {
+    fn _to_value(&self) -> Result<Value, String> {
+        (**self)._to_value()
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn _assert_plist_eq<L, R>(left: &L, right: &R, args: Option<fmt::Arguments>)
+where
+    L: _PlistOperand + ?Sized,
+    R: _PlistOperand + ?Sized,
+{
+    let parse = |side: &str, operand: Result<Value, String>| match operand {
+        Ok(value) => value,
+        Err(error) => {
+            panic!("`{}` is not a valid property list: {}", side, error)
+        }
+    };
+    let left = parse("left", left._to_value());
+    let right = parse("right", right._to_value());
+
+    let diff = diff(&left, &right);
+    if diff.is_empty() {
+        return;
+    }
+    match args {
+        Some(args) => panic!(
+            "assertion `left == right` failed: {}\nproperty lists differ from left to right:\n{}",
+            args, diff
+        ),
+        None => panic!(
+            "assertion `left == right` failed\nproperty lists differ from left to right:\n{}",
+            diff
+        ),
+    }
+}
@@ -0,0 +1,251 @@
+// Reading of JSON documents as property lists.
+
+use super::{untag, Dictionary, Value};
+use crate::const_eval::xml::MAX_DEPTH;
+use crate::Error;
+use alloc::{string::String, vec::Vec};
+use core::char;
+
+pub(super) fn parse(bytes: &[u8]) -> Result<Value, Error> {
+    let text = core::str::from_utf8(bytes).map_err(|_| Error::InvalidUtf8)?;
+    let mut parser = Parser {
+        bytes: text.as_bytes(),
+        pos: 0,
+    };
+    let value = parser.value(0)?;
+    parser.skip_space();
+    if parser.pos != parser.bytes.len() {
+        return Err(Error::TrailingData);
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn skip_space(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    // Consumes `byte` after any whitespace.
+    fn expect(&mut self, byte: u8) -> Result<(), Error> {
+        self.skip_space();
+        match self.peek() {
+            Some(next) if next == byte => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(_) => Err(Error::UnexpectedChar),
+            None => Err(Error::UnexpectedEnd),
+        }
+    }
+
+    // Reads the value at the current position, within `depth` arrays and
+    // objects.
+    fn value(&mut self, depth: usize) -> Result<Value, Error> {
+        self.skip_space();
+        match self.peek().ok_or(Error::UnexpectedEnd)? {
+            b'{' | b'[' if depth == MAX_DEPTH => Err(Error::TooDeep),
+            b'{' => self.object(depth),
+            b'[' => self.array(depth),
+            b'"' => self.string().map(Value::String),
+            b't' => self.literal(b"true", Value::Boolean(true)),
+            b'f' => self.literal(b"false", Value::Boolean(false)),
+            // Property lists have no null.
+            b'n' => Err(Error::InvalidValue),
+            b'-' | b'0'..=b'9' => self.number(),
+            _ => Err(Error::UnexpectedChar),
+        }
+    }
+
+    fn literal(&mut self, word: &[u8], value: Value) -> Result<Value, Error> {
+        if self.bytes[self.pos..].starts_with(word) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            Err(Error::UnexpectedChar)
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<Value, Error> {
+        self.pos += 1;
+        let mut dict = Dictionary::new();
+        self.skip_space();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Value::Dictionary(dict));
+        }
+        loop {
+            self.skip_space();
+            if self.peek() != Some(b'"') {
+                return Err(Error::ExpectedKey);
+            }
+            let key = self.string()?;
+            if dict.contains_key(&key) {
+                return Err(Error::DuplicateKey);
+            }
+            self.expect(b':')?;
+            let value = self.value(depth + 1)?;
+            dict.insert(key, value);
+
+            self.skip_space();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return untag(dict);
+                }
+                Some(_) => return Err(Error::UnexpectedChar),
+                None => return Err(Error::UnexpectedEnd),
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<Value, Error> {
+        self.pos += 1;
+        let mut array = Vec::new();
+        self.skip_space();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Value::Array(array));
+        }
+        loop {
+            array.push(self.value(depth + 1)?);
+            self.skip_space();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Value::Array(array));
+                }
+                Some(_) => return Err(Error::UnexpectedChar),
+                None => return Err(Error::UnexpectedEnd),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.pos += 1;
+        let mut string = String::new();
+        loop {
+            let start = self.pos;
+            while let Some(byte) = self.peek() {
+                if byte == b'"' || byte == b'\\' || byte < 0x20 {
+                    break;
+                }
+                self.pos += 1;
+            }
+            // The input is valid UTF-8 and runs end at ASCII bytes.
+            string.push_str(
+                core::str::from_utf8(&self.bytes[start..self.pos])
+                    .map_err(|_| Error::InvalidUtf8)?,
+            );
+
+            match self.peek().ok_or(Error::UnexpectedEnd)? {
+                b'"' => {
+                    self.pos += 1;
+                    return Ok(string);
+                }
+                b'\\' => {
+                    self.pos += 1;
+                    string.push(self.escape()?);
+                }
+                _ => return Err(Error::UnexpectedChar),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, Error> {
+        let byte = self.peek().ok_or(Error::UnexpectedEnd)?;
+        self.pos += 1;
+        Ok(match byte {
+            b'"' => '"',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\u{8}',
+            b'f' => '\u{C}',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => {
+                let high = self.hex4()?;
+                if (0xD800..0xDC00).contains(&high)
+                    && self.bytes[self.pos..].starts_with(b"\\u")
+                {
+                    self.pos += 2;
+                    let low = self.hex4()?;
+                    let units = [high, low];
+                    let mut chars = char::decode_utf16(units.iter().copied());
+                    return chars
+                        .next()
+                        .and_then(Result::ok)
+                        .filter(|_| chars.next().is_none())
+                        .ok_or(Error::InvalidCharRef);
+                }
+                char::from_u32(u32::from(high)).ok_or(Error::InvalidCharRef)?
+            }
+            _ => return Err(Error::InvalidCharRef),
+        })
+    }
+
+    fn hex4(&mut self) -> Result<u16, Error> {
+        let digits = self
+            .bytes
+            .get(self.pos..self.pos + 4)
+            .ok_or(Error::UnexpectedEnd)?;
+        let digits =
+            core::str::from_utf8(digits).map_err(|_| Error::InvalidCharRef)?;
+        let unit = u16::from_str_radix(digits, 16)
+            .map_err(|_| Error::InvalidCharRef)?;
+        self.pos += 4;
+        Ok(unit)
+    }
+
+    // Reads an integer if there is no fraction or exponent, or a real
+    // otherwise.
+    fn number(&mut self) -> Result<Value, Error> {
+        let start = self.pos;
+        let mut is_real = false;
+        while let Some(byte) = self.peek() {
+            match byte {
+                b'0'..=b'9' | b'-' | b'+' => {}
+                b'.' | b'e' | b'E' => is_real = true,
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        let text = core::str::from_utf8(&self.bytes[start..self.pos])
+            .map_err(|_| Error::InvalidValue)?;
+
+        // JSON does not allow leading zeros or a leading `+`.
+        let digits = text.strip_prefix('-').unwrap_or(text);
+        if digits.starts_with('+')
+            || (digits.len() > 1
+                && digits.starts_with('0')
+                && digits.as_bytes()[1].is_ascii_digit())
+        {
+            return Err(Error::InvalidValue);
+        }
+
+        if is_real {
+            text.parse()
+                .map(Value::Real)
+                .map_err(|_| Error::InvalidValue)
+        } else {
+            let int: i128 = text.parse().map_err(|_| Error::InvalidValue)?;
+            if int < i128::from(i64::MIN) || int > i128::from(u64::MAX) {
+                return Err(Error::InvalidValue);
+            }
+            Ok(Value::Integer(int))
+        }
+    }
+}
@@ -0,0 +1,698 @@
+//! A property list value tree.
+//!
+//! This module requires the `alloc` feature. [`Value::from_xml`] reads the same
+//! XML that the embedding macros check at compile time, and
+//! [`Value::to_xml`] writes it back out. [`Value::from_binary`] and
+//! [`Value::to_binary`] do the same for binary property lists.
+//!
+//! [`Value::from_xml`]: enum.Value.html#method.from_xml
+//! [`Value::to_xml`]: enum.Value.html#method.to_xml
+//! [`Value::from_binary`]: enum.Value.html#method.from_binary
+//! [`Value::to_binary`]: enum.Value.html#method.to_binary
+
+use crate::{const_eval, Error};
+use alloc::{borrow::ToOwned, string::String, vec, vec::Vec};
+use core::{iter::FromIterator, slice};
+
+mod binary;
+mod date;
+mod der;
+mod diff;
+mod json;
+mod toml;
+mod xml;
+
+pub use self::date::Date;
+pub use self::diff::{Change, Diff, KeyPath, PatchError, Segment};
+
+// Support for `assert_plist_eq!`. This is not part of this crate's public API,
+// so I reserve the right to change or remove this in a SemVer-compatible
+// update.
+#[doc(hidden)]
+pub use self::diff::{_PlistOperand, _assert_plist_eq};
+
+/// A property list value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// An `<array>`.
+    Array(Vec<Value>),
+    /// A `<dict>`.
+    Dictionary(Dictionary),
+    /// A `<true/>` or `<false/>`.
+    Boolean(bool),
+    /// The decoded bytes of a `<data>`.
+    Data(Vec<u8>),
+    /// A `<date>`.
+    Date(Date),
+    /// An `<integer>`, which can hold any `i64` or `u64`.
+    Integer(i128),
+    /// A `<real>`.
+    Real(f64),
+    /// A `<string>`.
+    String(String),
+    /// A unique identifier, as used by `NSKeyedArchiver`.
+    ///
+    /// XML has no element for these, so they are written as a `<dict>` with
+    /// only a `CF$UID` integer, which [`from_xml`] reads back as a `Uid`.
+    ///
+    /// [`from_xml`]: #method.from_xml
+    Uid(u64),
+}
+
+impl Value {
+    /// Parses an XML property list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a valid XML property list, or
+    /// [`Error::TooDeep`] if arrays and dictionaries are nested more than 512
+    /// deep.
+    ///
+    /// [`Error::TooDeep`]: ../enum.Error.html#variant.TooDeep
+    #[inline]
+    pub fn from_xml(bytes: &[u8]) -> Result<Self, Error> {
+        xml::parse(bytes)
+    }
+
+    /// Parses an OpenStep (ASCII) property list, such as
+    /// `{ Label = "com.example"; ProgramArguments = (a, b); }`.
+    ///
+    /// This accepts the same syntax as the `openstep` option of the embedding
+    /// macros. Scalars are strings unless written in GNUstep's typed forms,
+    /// such as `<*I1>` or `<*BY>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a valid OpenStep property list, or
+    /// [`Error::TooDeep`] if arrays and dictionaries are nested more than 512
+    /// deep.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use embed_plist::value::Value;
+    ///
+    /// let value = Value::from_openstep(b"{ Label = com.example; RunAtLoad = <*BY>; }")?;
+    /// let dict = value.as_dictionary().unwrap();
+    ///
+    /// assert_eq!(dict.get("Label"), Some(&Value::from("com.example")));
+    /// assert_eq!(dict.get("RunAtLoad"), Some(&Value::Boolean(true)));
+    /// # Ok::<(), embed_plist::Error>(())
+    /// ```
+    ///
+    /// [`Error::TooDeep`]: ../enum.Error.html#variant.TooDeep
+    pub fn from_openstep(bytes: &[u8]) -> Result<Self, Error> {
+        let len = const_eval::openstep_to_xml_into(bytes, &mut [])?;
+        let mut xml = vec![0; len];
+        const_eval::openstep_to_xml_into(bytes, &mut xml)?;
+        xml::parse(&xml)
+    }
+
+    /// Parses a JSON document as a property list.
+    ///
+    /// JSON has no dates, data, or null. Dates and data are written as objects
+    /// with a single tagged key, `{"$date": "2020-08-30T12:34:56Z"}` and
+    /// `{"$data": "<base64>"}`, and null is rejected. Numbers without a
+    /// fraction or exponent are integers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not valid JSON, contains null, or has a
+    /// tagged object whose value is invalid. Returns [`Error::TooDeep`] if
+    /// arrays and objects are nested more than 512 deep.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use embed_plist::value::Value;
+    ///
+    /// let value = Value::from_json(br#"{
+    ///     "Label": "com.example",
+    ///     "StartInterval": 60,
+    ///     "Key": { "$data": "AAEC" }
+    /// }"#)?;
+    /// let dict = value.as_dictionary().unwrap();
+    ///
+    /// assert_eq!(dict.get("StartInterval"), Some(&Value::from(60)));
+    /// assert_eq!(dict.get("Key"), Some(&Value::from(vec![0u8, 1, 2])));
+    /// # Ok::<(), embed_plist::Error>(())
+    /// ```
+    ///
+    /// [`Error::TooDeep`]: ../enum.Error.html#variant.TooDeep
+    #[inline]
+    pub fn from_json(bytes: &[u8]) -> Result<Self, Error> {
+        json::parse(bytes)
+    }
+
+    /// Parses a TOML document as a property list.
+    ///
+    /// Offset date-times become dates in UTC. Local dates and times are
+    /// rejected, since they do not name an instant. Data is written as a table
+    /// with a single `$data` key holding base64, and the `$date` tag of
+    /// [`from_json`] is also accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not valid TOML, contains a local date or
+    /// time, has an integer that does not fit in an `i64`, or has a tagged
+    /// table whose value is invalid. Returns [`Error::TooDeep`] if tables and
+    /// arrays are nested more than 512 deep.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use embed_plist::value::{Date, Value};
+    ///
+    /// let value = Value::from_toml(br#"
+    /// Label = "com.example"
+    /// Built = 2020-08-30T14:34:56+02:00
+    /// Key = { "$data" = "AAEC" }
+    /// "#)?;
+    /// let dict = value.as_dictionary().unwrap();
+    ///
+    /// let built = Date::from_iso8601("2020-08-30T12:34:56Z").unwrap();
+    /// assert_eq!(dict.get("Built"), Some(&Value::Date(built)));
+    /// assert_eq!(dict.get("Key"), Some(&Value::from(vec![0u8, 1, 2])));
+    /// # Ok::<(), embed_plist::Error>(())
+    /// ```
+    ///
+    /// [`from_json`]: #method.from_json
+    /// [`Error::TooDeep`]: ../enum.Error.html#variant.TooDeep
+    #[inline]
+    pub fn from_toml(bytes: &[u8]) -> Result<Self, Error> {
+        toml::parse(bytes)
+    }
+
+    /// Writes `self` as an XML property list, with the same header and tab
+    /// indentation as Xcode.
+    ///
+    /// This is the same as [`to_xml_with(Indent::Tab)`](#method.to_xml_with).
+    #[inline]
+    pub fn to_xml(&self) -> Vec<u8> {
+        self.to_xml_with(Indent::Tab)
+    }
+
+    /// Writes `self` as an XML property list with the given indentation.
+    ///
+    /// The output has Apple's XML declaration and DOCTYPE, one element per
+    /// line, and `&`, `<`, `>`, and carriage returns escaped. Files in this
+    /// format, such as those written by Xcode, are reproduced byte for byte
+    /// when parsed with [`from_xml`] and written with their [`Indent`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use embed_plist::value::{Indent, Value};
+    ///
+    /// let file = include_bytes!("../../src/launchd.plist");
+    /// let indent = Indent::detect(file).unwrap();
+    /// assert_eq!(indent, Indent::Spaces(4));
+    ///
+    /// let value = Value::from_xml(file).unwrap();
+    /// assert_eq!(value.to_xml_with(indent), &file[..]);
+    /// ```
+    ///
+    /// [`from_xml`]: #method.from_xml
+    /// [`Indent`]: enum.Indent.html
+    pub fn to_xml_with(&self, indent: Indent) -> Vec<u8> {
+        let mut out = Vec::new();
+        xml::write(self, indent, &mut out);
+        out
+    }
+
+    /// Parses a binary property list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a valid binary property list,
+    /// including if it is truncated or an array or dictionary contains itself.
+    #[inline]
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, crate::binary::Error> {
+        binary::parse(bytes)
+    }
+
+    /// Writes `self` as a binary property list.
+    ///
+    /// Equal strings are stored once, and integers take the fewest bytes that
+    /// Core Foundation allows.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use embed_plist::value::Value;
+    ///
+    /// let value = Value::from_xml(include_bytes!("../../src/Info.plist")).unwrap();
+    /// let binary = value.to_binary();
+    ///
+    /// assert!(binary.starts_with(b"bplist00"));
+    /// assert_eq!(Value::from_binary(&binary), Ok(value));
+    /// ```
+    #[inline]
+    pub fn to_binary(&self) -> Vec<u8> {
+        binary::write(self)
+    }
+
+    /// Parses entitlements in the DER form that [`embed_entitlements!`] embeds
+    /// in `__TEXT,__ents_der`.
+    ///
+    /// The encoding must be canonical: lengths take their shortest form and
+    /// dictionary keys are sorted. This makes it possible to check that the DER
+    /// section matches the XML one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a valid DER entitlements document.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use embed_plist::{const_eval, value::Value};
+    ///
+    /// let xml = include_bytes!("../../src/app.entitlements");
+    /// let len = const_eval::entitlements_der_into(xml, &mut [])?;
+    /// let mut der = vec![0; len];
+    /// const_eval::entitlements_der_into(xml, &mut der)?;
+    ///
+    /// assert_eq!(Value::from_der(&der)?, Value::from_xml(xml)?);
+    /// # Ok::<(), embed_plist::Error>(())
+    /// ```
+    ///
+    /// [`embed_entitlements!`]: ../macro.embed_entitlements.html
+    #[inline]
+    pub fn from_der(bytes: &[u8]) -> Result<Self, Error> {
+        der::parse(bytes)
+    }
+
+    /// Returns `true` if `self` and `other` are the same property list,
+    /// regardless of the order of dictionary keys.
+    ///
+    /// Formatting is already gone once a property list is parsed, so this
+    /// compares what a file means rather than how it is written. Unlike `==`,
+    /// a NaN `Real` is equal to itself.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use embed_plist::value::Value;
+    ///
+    /// let a = Value::from_xml(b"<dict><key>a</key><true/><key>b</key><false/></dict>")?;
+    /// let b = Value::from_openstep(b"{ b = <*BN>; a = <*BY>; }")?;
+    ///
+    /// assert_ne!(a, b);
+    /// assert!(a.semantic_eq(&b));
+    /// # Ok::<(), embed_plist::Error>(())
+    /// ```
+    #[inline]
+    pub fn semantic_eq(&self, other: &Value) -> bool {
+        diff::semantic_eq(self, other)
+    }
+
+    /// Returns the changes that turn `self` into `new`, ignoring the order of
+    /// dictionary keys.
+    ///
+    /// The [`Diff`] is empty exactly when [`semantic_eq`] is `true`, and
+    /// applying it to `self` produces a value that is semantically equal to
+    /// `new`. Arrays are compared index by index, with elements added or
+    /// removed at the end.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use embed_plist::value::{Change, Value};
+    ///
+    /// let old = Value::from_openstep(b"{ Version = 1; Schemes = (a); Debug = <*BY>; }")?;
+    /// let new = Value::from_openstep(b"{ Version = 2; Schemes = (a, b); }")?;
+    ///
+    /// let diff = old.diff(&new);
+    /// let paths: Vec<String> = diff.iter().map(|c| c.path().to_string()).collect();
+    /// assert_eq!(paths, ["Version", "Schemes[1]", "Debug"]);
+    /// assert!(matches!(diff.changes()[2], Change::Removed { .. }));
+    ///
+    /// let mut patched = old.clone();
+    /// diff.apply(&mut patched).unwrap();
+    /// assert!(patched.semantic_eq(&new));
+    /// # Ok::<(), embed_plist::Error>(())
+    /// ```
+    ///
+    /// [`Diff`]: struct.Diff.html
+    /// [`semantic_eq`]: #method.semantic_eq
+    #[inline]
+    pub fn diff(&self, new: &Value) -> Diff {
+        diff::diff(self, new)
+    }
+
+    /// Returns the string if `self` is a `String`.
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Returns the boolean if `self` is a `Boolean`.
+    #[inline]
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Value::Boolean(boolean) => Some(boolean),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer if `self` is an `Integer`.
+    #[inline]
+    pub fn as_integer(&self) -> Option<i128> {
+        match *self {
+            Value::Integer(int) => Some(int),
+            _ => None,
+        }
+    }
+
+    /// Returns the number if `self` is a `Real`.
+    #[inline]
+    pub fn as_real(&self) -> Option<f64> {
+        match *self {
+            Value::Real(real) => Some(real),
+            _ => None,
+        }
+    }
+
+    /// Returns the date if `self` is a `Date`.
+    #[inline]
+    pub fn as_date(&self) -> Option<Date> {
+        match *self {
+            Value::Date(date) => Some(date),
+            _ => None,
+        }
+    }
+
+    /// Returns the identifier if `self` is a `Uid`.
+    #[inline]
+    pub fn as_uid(&self) -> Option<u64> {
+        match *self {
+            Value::Uid(uid) => Some(uid),
+            _ => None,
+        }
+    }
+
+    /// Returns the bytes if `self` is `Data`.
+    #[inline]
+    pub fn as_data(&self) -> Option<&[u8]> {
+        match self {
+            Value::Data(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements if `self` is an `Array`.
+    #[inline]
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    /// Returns the dictionary if `self` is a `Dictionary`.
+    #[inline]
+    pub fn as_dictionary(&self) -> Option<&Dictionary> {
+        match self {
+            Value::Dictionary(dict) => Some(dict),
+            _ => None,
+        }
+    }
+
+    /// Returns the dictionary if `self` is a `Dictionary`.
+    #[inline]
+    pub fn as_dictionary_mut(&mut self) -> Option<&mut Dictionary> {
+        match self {
+            Value::Dictionary(dict) => Some(dict),
+            _ => None,
+        }
+    }
+}
+
+// Reads a dictionary with only a `$data` or `$date` string as the tagged type,
+// since JSON and TOML have no way to write these directly.
+fn untag(dict: Dictionary) -> Result<Value, Error> {
+    if dict.len() == 1 {
+        if let Some(Value::String(data)) = dict.get("$data") {
+            return xml::decode_base64(data)
+                .map(Value::Data)
+                .ok_or(Error::InvalidValue);
+        }
+        if let Some(Value::String(date)) = dict.get("$date") {
+            return Date::from_iso8601(date)
+                .map(Value::Date)
+                .ok_or(Error::InvalidValue);
+        }
+    }
+    Ok(Value::Dictionary(dict))
+}
+
+impl From<bool> for Value {
+    #[inline]
+    fn from(boolean: bool) -> Self {
+        Value::Boolean(boolean)
+    }
+}
+
+impl From<&str> for Value {
+    #[inline]
+    fn from(string: &str) -> Self {
+        Value::String(string.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    #[inline]
+    fn from(string: String) -> Self {
+        Value::String(string)
+    }
+}
+
+impl From<f64> for Value {
+    #[inline]
+    fn from(real: f64) -> Self {
+        Value::Real(real)
+    }
+}
+
+impl From<Date> for Value {
+    #[inline]
+    fn from(date: Date) -> Self {
+        Value::Date(date)
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    #[inline]
+    fn from(data: Vec<u8>) -> Self {
+        Value::Data(data)
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    #[inline]
+    fn from(array: Vec<Value>) -> Self {
+        Value::Array(array)
+    }
+}
+
+impl From<Dictionary> for Value {
+    #[inline]
+    fn from(dict: Dictionary) -> Self {
+        Value::Dictionary(dict)
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($int:ty),+) => {
+        $(impl From<$int> for Value {
+            #[inline]
+            fn from(int: $int) -> Self {
+                Value::Integer(int.into())
+            }
+        })+
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+/// The indentation of nested elements in an XML property list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Indent {
+    /// One tab per level, as written by Xcode and `plutil`.
+    Tab,
+    /// The given number of spaces per level.
+    Spaces(usize),
+}
+
+impl Default for Indent {
+    #[inline]
+    fn default() -> Self {
+        Indent::Tab
+    }
+}
+
+impl Indent {
+    /// Returns the indentation of the first indented line in `bytes`, or
+    /// `None` if no line is indented.
+    pub fn detect(bytes: &[u8]) -> Option<Indent> {
+        let mut lines = bytes.split(|&byte| byte == b'\n');
+        lines.find_map(|line| match line.first()? {
+            b'\t' => Some(Indent::Tab),
+            b' ' => {
+                let width = line.iter().take_while(|&&b| b == b' ').count();
+                Some(Indent::Spaces(width))
+            }
+            _ => None,
+        })
+    }
+}
+
+/// A `<dict>`, which keeps its entries in insertion order.
+///
+/// Lookups are linear, which is fast for the sizes of typical property lists.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Dictionary {
+    entries: Vec<(String, Value)>,
+}
+
+impl Dictionary {
+    /// Creates an empty dictionary.
+    #[inline]
+    pub fn new() -> Self {
+        Dictionary::default()
+    }
+
+    /// Returns the number of entries.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the value for `key`.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the value for `key`.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.entries
+            .iter_mut()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns `true` if there is a value for `key`.
+    #[inline]
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Sets the value for `key`, returning the previous value.
+    ///
+    /// A new key is added at the end, while an existing key keeps its
+    /// position.
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> Option<Value>
+    where
+        K: Into<String>,
+        V: Into<Value>,
+    {
+        let key = key.into();
+        let value = value.into();
+        match self.get_mut(&key) {
+            Some(existing) => Some(core::mem::replace(existing, value)),
+            None => {
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Removes and returns the value for `key`, keeping the order of the other
+    /// entries.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Returns an iterator over the entries in order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            entries: self.entries.iter(),
+        }
+    }
+
+    /// Returns an iterator over the keys in order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(key, _)| key.as_str())
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> FromIterator<(K, V)> for Dictionary {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut dict = Dictionary::new();
+        for (key, value) in iter {
+            dict.insert(key, value);
+        }
+        dict
+    }
+}
+
+impl<'a> IntoIterator for &'a Dictionary {
+    type Item = (&'a str, &'a Value);
+    type IntoIter = Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Dictionary {
+    type Item = (String, Value);
+    type IntoIter = vec::IntoIter<(String, Value)>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+/// An iterator over the entries of a [`Dictionary`].
+///
+/// [`Dictionary`]: struct.Dictionary.html
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+    entries: slice::Iter<'a, (String, Value)>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a str, &'a Value);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries
+            .next()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
@@ -0,0 +1,628 @@
+// Reading of TOML documents as property lists.
+
+use super::{untag, Date, Dictionary, Value};
+use crate::const_eval::xml::MAX_DEPTH;
+use crate::Error;
+use alloc::{string::String, vec::Vec};
+use core::char;
+
+pub(super) fn parse(bytes: &[u8]) -> Result<Value, Error> {
+    let text = core::str::from_utf8(bytes).map_err(|_| Error::InvalidUtf8)?;
+    let mut parser = Parser {
+        bytes: text.as_bytes(),
+        pos: 0,
+    };
+
+    let mut root = Dictionary::new();
+    // The keys of the current table, and of every table defined by a header.
+    let mut current: Vec<String> = Vec::new();
+    let mut defined: Vec<Vec<String>> = Vec::new();
+
+    loop {
+        parser.skip_blank_lines();
+        let byte = match parser.peek() {
+            Some(byte) => byte,
+            None => break,
+        };
+
+        if byte == b'[' {
+            parser.pos += 1;
+            let is_array = parser.peek() == Some(b'[');
+            if is_array {
+                parser.pos += 1;
+            }
+            let path = parser.key()?;
+            if path.len() >= MAX_DEPTH {
+                return Err(Error::TooDeep);
+            }
+            parser.expect(b']')?;
+            if is_array {
+                parser.expect(b']')?;
+                push_table(&mut root, &path)?;
+                // Tables under an array element start over for each element.
+                defined.retain(|other| !other.starts_with(&path));
+            } else {
+                if defined.contains(&path) {
+                    return Err(Error::DuplicateKey);
+                }
+                table_at(&mut root, &path)?;
+                defined.push(path.clone());
+            }
+            current = path;
+        } else {
+            let path = parser.key()?;
+            parser.expect(b'=')?;
+            let value = parser.value(nested(current.len(), &path)?)?;
+            insert(table_at(&mut root, &current)?, &path, value)?;
+        }
+        parser.end_line()?;
+    }
+
+    untag_all(root)
+}
+
+// Converts tagged tables throughout `dict`, since TOML tables can be reopened
+// and so are only complete at the end.
+fn untag_all(dict: Dictionary) -> Result<Value, Error> {
+    let dict = dict
+        .into_iter()
+        .map(|(key, value)| Ok((key, untag_value(value)?)))
+        .collect::<Result<Dictionary, Error>>()?;
+    untag(dict)
+}
+
+fn untag_value(value: Value) -> Result<Value, Error> {
+    match value {
+        Value::Dictionary(dict) => untag_all(dict),
+        Value::Array(array) => array
+            .into_iter()
+            .map(untag_value)
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        value => Ok(value),
+    }
+}
+
+// Returns the table at `path`, creating missing tables. A key that names an
+// array of tables refers to its last element.
+fn table_at<'a>(
+    mut table: &'a mut Dictionary,
+    path: &[String],
+) -> Result<&'a mut Dictionary, Error> {
+    for key in path {
+        if !table.contains_key(key) {
+            table.insert(key.as_str(), Dictionary::new());
+        }
+        table = match table.get_mut(key) {
+            Some(Value::Dictionary(next)) => next,
+            Some(Value::Array(array)) => match array.last_mut() {
+                Some(Value::Dictionary(next)) => next,
+                _ => return Err(Error::DuplicateKey),
+            },
+            _ => return Err(Error::DuplicateKey),
+        };
+    }
+    Ok(table)
+}
+
+// Appends a table to the array of tables at `path`.
+fn push_table(root: &mut Dictionary, path: &[String]) -> Result<(), Error> {
+    let (last, parent) = path.split_last().ok_or(Error::ExpectedKey)?;
+    let parent = table_at(root, parent)?;
+    if !parent.contains_key(last) {
+        parent.insert(last.as_str(), Vec::<Value>::new());
+    }
+    match parent.get_mut(last) {
+        Some(Value::Array(array)) => {
+            array.push(Value::Dictionary(Dictionary::new()));
+            Ok(())
+        }
+        _ => Err(Error::DuplicateKey),
+    }
+}
+
+// Returns the depth of the value at the dotted key `path` in the table at
+// `depth`, if the tables that `path` creates are not nested too deeply.
+fn nested(depth: usize, path: &[String]) -> Result<usize, Error> {
+    let depth = depth + path.len();
+    if depth > MAX_DEPTH {
+        return Err(Error::TooDeep);
+    }
+    Ok(depth)
+}
+
+// Inserts `value` at the dotted key `path`, which must not exist yet.
+fn insert(
+    table: &mut Dictionary,
+    path: &[String],
+    value: Value,
+) -> Result<(), Error> {
+    let (last, parent) = path.split_last().ok_or(Error::ExpectedKey)?;
+    let table = table_at(table, parent)?;
+    if table.contains_key(last) {
+        return Err(Error::DuplicateKey);
+    }
+    table.insert(last.as_str(), value);
+    Ok(())
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn rest(&self) -> &[u8] {
+        &self.bytes[self.pos..]
+    }
+
+    fn skip_space(&mut self) {
+        while let Some(b' ' | b'\t') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        if self.peek() == Some(b'#') {
+            while !matches!(self.peek(), None | Some(b'\n')) {
+                self.pos += 1;
+            }
+        }
+    }
+
+    // Skips whitespace, comments, and newlines, as allowed between lines and
+    // within arrays.
+    fn skip_blank_lines(&mut self) {
+        loop {
+            self.skip_space();
+            self.skip_comment();
+            match self.peek() {
+                Some(b'\n') => self.pos += 1,
+                Some(b'\r') if self.rest().starts_with(b"\r\n") => {
+                    self.pos += 2
+                }
+                _ => return,
+            }
+        }
+    }
+
+    // Requires the end of a line, after optional whitespace and a comment.
+    fn end_line(&mut self) -> Result<(), Error> {
+        self.skip_space();
+        self.skip_comment();
+        if self.rest().starts_with(b"\r\n") {
+            self.pos += 2;
+        } else if self.peek() == Some(b'\n') {
+            self.pos += 1;
+        } else if self.peek().is_some() {
+            return Err(Error::UnexpectedChar);
+        }
+        Ok(())
+    }
+
+    // Consumes `byte` after any whitespace.
+    fn expect(&mut self, byte: u8) -> Result<(), Error> {
+        self.skip_space();
+        match self.peek() {
+            Some(next) if next == byte => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(_) => Err(Error::UnexpectedChar),
+            None => Err(Error::UnexpectedEnd),
+        }
+    }
+
+    // Reads a dotted key like `a."b.c".d`.
+    fn key(&mut self) -> Result<Vec<String>, Error> {
+        let mut path = Vec::new();
+        loop {
+            self.skip_space();
+            let part = match self.peek() {
+                Some(b'"') => self.basic_string()?,
+                Some(b'\'') => self.literal_string()?,
+                _ => {
+                    let start = self.pos;
+                    while let Some(
+                        b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_' | b'-',
+                    ) = self.peek()
+                    {
+                        self.pos += 1;
+                    }
+                    if start == self.pos {
+                        return Err(Error::ExpectedKey);
+                    }
+                    String::from_utf8_lossy(&self.bytes[start..self.pos])
+                        .into_owned()
+                }
+            };
+            path.push(part);
+            self.skip_space();
+            if self.peek() != Some(b'.') {
+                return Ok(path);
+            }
+            self.pos += 1;
+        }
+    }
+
+    // Reads the value at the current position, within `depth` tables and
+    // arrays.
+    fn value(&mut self, depth: usize) -> Result<Value, Error> {
+        self.skip_space();
+        let rest = self.rest();
+        match self.peek().ok_or(Error::UnexpectedEnd)? {
+            b'"' => self.basic_string().map(Value::String),
+            b'\'' => self.literal_string().map(Value::String),
+            b'[' | b'{' if depth == MAX_DEPTH => Err(Error::TooDeep),
+            b'[' => self.array(depth),
+            b'{' => self.inline_table(depth),
+            b't' if rest.starts_with(b"true") => {
+                self.pos += 4;
+                Ok(Value::Boolean(true))
+            }
+            b'f' if rest.starts_with(b"false") => {
+                self.pos += 5;
+                Ok(Value::Boolean(false))
+            }
+            _ if rest.len() >= 5
+                && rest[..4].iter().all(u8::is_ascii_digit)
+                && rest[4] == b'-' =>
+            {
+                self.date()
+            }
+            _ => self.number(),
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<Value, Error> {
+        self.pos += 1;
+        let mut array = Vec::new();
+        loop {
+            self.skip_blank_lines();
+            if self.peek() == Some(b']') {
+                self.pos += 1;
+                return Ok(Value::Array(array));
+            }
+            array.push(self.value(depth + 1)?);
+            self.skip_blank_lines();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {}
+                Some(_) => return Err(Error::UnexpectedChar),
+                None => return Err(Error::UnexpectedEnd),
+            }
+        }
+    }
+
+    fn inline_table(&mut self, depth: usize) -> Result<Value, Error> {
+        self.pos += 1;
+        let mut table = Dictionary::new();
+        self.skip_space();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Value::Dictionary(table));
+        }
+        loop {
+            let path = self.key()?;
+            self.expect(b'=')?;
+            let value = self.value(nested(depth, &path)?)?;
+            insert(&mut table, &path, value)?;
+            self.skip_space();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Value::Dictionary(table));
+                }
+                Some(_) => return Err(Error::UnexpectedChar),
+                None => return Err(Error::UnexpectedEnd),
+            }
+        }
+    }
+
+    // Reads `"..."` or `"""..."""`, with escapes.
+    fn basic_string(&mut self) -> Result<String, Error> {
+        let multiline = self.rest().starts_with(b"\"\"\"");
+        self.pos += if multiline { 3 } else { 1 };
+        if multiline {
+            self.skip_newline();
+        }
+
+        let mut string = String::new();
+        loop {
+            let start = self.pos;
+            while let Some(byte) = self.peek() {
+                if byte == b'"'
+                    || byte == b'\\'
+                    || (byte == b'\n' && !multiline)
+                {
+                    break;
+                }
+                self.pos += 1;
+            }
+            string.push_str(self.text(start)?);
+
+            match self.peek().ok_or(Error::UnexpectedEnd)? {
+                b'"' if !multiline => {
+                    self.pos += 1;
+                    return Ok(string);
+                }
+                b'"' => {
+                    if self.close_multiline(b'"', &mut string) {
+                        return Ok(string);
+                    }
+                }
+                b'\\' => {
+                    self.pos += 1;
+                    if multiline && self.skip_line_ending_backslash() {
+                        continue;
+                    }
+                    string.push(self.escape()?);
+                }
+                _ => return Err(Error::UnexpectedChar),
+            }
+        }
+    }
+
+    // Reads `'...'` or `'''...'''`, without escapes.
+    fn literal_string(&mut self) -> Result<String, Error> {
+        let multiline = self.rest().starts_with(b"'''");
+        self.pos += if multiline { 3 } else { 1 };
+        if multiline {
+            self.skip_newline();
+        }
+
+        let mut string = String::new();
+        loop {
+            let start = self.pos;
+            while let Some(byte) = self.peek() {
+                if byte == b'\'' || (byte == b'\n' && !multiline) {
+                    break;
+                }
+                self.pos += 1;
+            }
+            string.push_str(self.text(start)?);
+
+            match self.peek().ok_or(Error::UnexpectedEnd)? {
+                b'\'' if !multiline => {
+                    self.pos += 1;
+                    return Ok(string);
+                }
+                b'\'' => {
+                    if self.close_multiline(b'\'', &mut string) {
+                        return Ok(string);
+                    }
+                }
+                _ => return Err(Error::UnexpectedChar),
+            }
+        }
+    }
+
+    // Handles a quote in a multi-line string. Up to two quotes may come right
+    // before the closing three, so a run of 3 to 5 closes the string.
+    fn close_multiline(&mut self, quote: u8, string: &mut String) -> bool {
+        let run = self.rest().iter().take_while(|&&b| b == quote).count();
+        if run < 3 {
+            self.pos += run;
+            string.extend(core::iter::repeat_n(quote as char, run));
+            return false;
+        }
+        let extra = (run - 3).min(2);
+        string.extend(core::iter::repeat_n(quote as char, extra));
+        self.pos += extra + 3;
+        true
+    }
+
+    fn skip_newline(&mut self) {
+        if self.rest().starts_with(b"\r\n") {
+            self.pos += 2;
+        } else if self.peek() == Some(b'\n') {
+            self.pos += 1;
+        }
+    }
+
+    // Skips a backslash at the end of a line along with the whitespace after
+    // it, returning whether there was one.
+    fn skip_line_ending_backslash(&mut self) -> bool {
+        let mut pos = self.pos;
+        while let Some(b' ' | b'\t') = self.bytes.get(pos) {
+            pos += 1;
+        }
+        if !matches!(self.bytes.get(pos), Some(b'\n' | b'\r')) {
+            return false;
+        }
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.bytes.get(pos) {
+            pos += 1;
+        }
+        self.pos = pos;
+        true
+    }
+
+    fn escape(&mut self) -> Result<char, Error> {
+        let byte = self.peek().ok_or(Error::UnexpectedEnd)?;
+        self.pos += 1;
+        let len = match byte {
+            b'b' => return Ok('\u{8}'),
+            b't' => return Ok('\t'),
+            b'n' => return Ok('\n'),
+            b'f' => return Ok('\u{C}'),
+            b'r' => return Ok('\r'),
+            b'"' => return Ok('"'),
+            b'\\' => return Ok('\\'),
+            b'u' => 4,
+            b'U' => 8,
+            _ => return Err(Error::InvalidCharRef),
+        };
+        let digits = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or(Error::UnexpectedEnd)?;
+        let digits =
+            core::str::from_utf8(digits).map_err(|_| Error::InvalidCharRef)?;
+        let ch = u32::from_str_radix(digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(Error::InvalidCharRef)?;
+        self.pos += len;
+        Ok(ch)
+    }
+
+    fn text(&self, start: usize) -> Result<&str, Error> {
+        core::str::from_utf8(&self.bytes[start..self.pos])
+            .map_err(|_| Error::InvalidUtf8)
+    }
+
+    // Reads the characters that can make up a number.
+    fn bare(&mut self) -> Result<&str, Error> {
+        let start = self.pos;
+        while let Some(
+            b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'+' | b'-' | b'.',
+        ) = self.peek()
+        {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(Error::UnexpectedChar);
+        }
+        self.text(start)
+    }
+
+    fn number(&mut self) -> Result<Value, Error> {
+        let text = self.bare()?;
+        let (negative, unsigned) = match text.as_bytes()[0] {
+            b'-' => (true, &text[1..]),
+            b'+' => (false, &text[1..]),
+            _ => (false, text),
+        };
+
+        match unsigned {
+            "inf" => {
+                let inf = f64::INFINITY;
+                return Ok(Value::Real(if negative { -inf } else { inf }));
+            }
+            "nan" => return Ok(Value::Real(f64::NAN)),
+            _ => {}
+        }
+
+        // Underscores must each be between two digits.
+        let bytes = unsigned.as_bytes();
+        for (i, &byte) in bytes.iter().enumerate() {
+            let is_digit = |i: usize| {
+                bytes.get(i).is_some_and(|b: &u8| b.is_ascii_hexdigit())
+            };
+            if byte == b'_' && (i == 0 || !is_digit(i - 1) || !is_digit(i + 1))
+            {
+                return Err(Error::InvalidValue);
+            }
+        }
+        let digits: String = unsigned.chars().filter(|&c| c != '_').collect();
+
+        let radix = match digits.get(..2) {
+            Some("0x") => 16,
+            Some("0o") => 8,
+            Some("0b") => 2,
+            _ => 10,
+        };
+        let magnitude = if radix != 10 {
+            if text.starts_with(['+', '-']) {
+                return Err(Error::InvalidValue);
+            }
+            u64::from_str_radix(&digits[2..], radix)
+                .map_err(|_| Error::InvalidValue)?
+        } else if digits.contains(['.', 'e', 'E']) {
+            let real: f64 = digits.parse().map_err(|_| Error::InvalidValue)?;
+            return Ok(Value::Real(if negative { -real } else { real }));
+        } else {
+            if digits.len() > 1 && digits.starts_with('0') {
+                return Err(Error::InvalidValue);
+            }
+            digits.parse().map_err(|_| Error::InvalidValue)?
+        };
+
+        // TOML integers are 64-bit signed.
+        let int = i128::from(magnitude);
+        let int = if negative { -int } else { int };
+        if int < i128::from(i64::MIN) || int > i128::from(i64::MAX) {
+            return Err(Error::InvalidValue);
+        }
+        Ok(Value::Integer(int))
+    }
+
+    // Reads an offset date-time, such as `1979-05-27T07:32:00.5-07:00`. Local
+    // dates and times are rejected, since a property list date is an instant.
+    fn date(&mut self) -> Result<Value, Error> {
+        let start = self.pos;
+        self.pos = (self.pos + 10).min(self.bytes.len());
+        // The time may be separated by a space rather than `T`.
+        if self.peek() == Some(b' ')
+            && self.bytes.get(self.pos + 3) == Some(&b':')
+        {
+            self.pos += 1;
+        }
+        while let Some(
+            b'0'..=b'9' | b'-' | b'+' | b':' | b'.' | b'T' | b't' | b'Z' | b'z',
+        ) = self.peek()
+        {
+            self.pos += 1;
+        }
+        let text = self.text(start)?.as_bytes();
+        parse_date(text).map(Value::Date).ok_or(Error::InvalidValue)
+    }
+}
+
+fn parse_date(text: &[u8]) -> Option<Date> {
+    if text.len() < 20 || !matches!(text[10], b'T' | b't' | b' ') {
+        return None;
+    }
+
+    // Reuse the XML format for the whole seconds, then apply the rest.
+    let mut whole = [0; 20];
+    whole[..19].copy_from_slice(&text[..19]);
+    whole[10] = b'T';
+    whole[19] = b'Z';
+    let date = Date::from_iso8601(core::str::from_utf8(&whole).ok()?)?;
+
+    let mut rest = &text[19..];
+    let mut fraction = 0.0;
+    if rest.first() == Some(&b'.') {
+        let len = rest[1..].iter().take_while(|b| b.is_ascii_digit()).count();
+        if len == 0 {
+            return None;
+        }
+        fraction = core::str::from_utf8(&rest[..=len]).ok()?.parse().ok()?;
+        rest = &rest[len + 1..];
+    }
+
+    let offset = match rest {
+        b"Z" | b"z" => 0,
+        [sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] => {
+            let digit = |byte: &u8| {
+                if byte.is_ascii_digit() {
+                    Some(i64::from(byte - b'0'))
+                } else {
+                    None
+                }
+            };
+            let hours = digit(h1)? * 10 + digit(h2)?;
+            let minutes = digit(m1)? * 10 + digit(m2)?;
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            let offset = (hours * 60 + minutes) * 60;
+            if *sign == b'-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+        _ => return None,
+    };
+
+    Date::from_seconds_since_2001(
+        date.seconds_since_2001() + fraction - offset as f64,
+    )
+}
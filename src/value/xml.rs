@@ -0,0 +1,387 @@
+// Reading and writing of XML property lists.
+
+use super::{Date, Dictionary, Indent, Value};
+use crate::const_eval::xml::{self, Error, Tag, Text};
+use alloc::{format, string::String, vec::Vec};
+use core::{convert::TryFrom, iter};
+
+pub(super) fn parse(bytes: &[u8]) -> Result<Value, Error> {
+    let pos = xml::skip_misc(bytes, 0)?;
+    let tag = xml::read_tag(bytes, pos)?;
+
+    let (value, end) = if tag.is(bytes, b"plist") {
+        if !tag.is_start() {
+            return Err(Error::ExpectedValue);
+        }
+        let pos = xml::skip_misc(bytes, tag.end)?;
+        let (value, pos) = parse_value(bytes, xml::read_tag(bytes, pos)?, 0)?;
+        let pos = xml::skip_misc(bytes, pos)?;
+        (value, xml::expect_end(bytes, pos, b"plist")?)
+    } else {
+        parse_value(bytes, tag, 0)?
+    };
+
+    if xml::skip_misc(bytes, end)? != bytes.len() {
+        return Err(Error::TrailingData);
+    }
+    Ok(value)
+}
+
+// Parses the value opened by `tag`, within `depth` arrays and dictionaries, and
+// returns the position after it.
+fn parse_value(
+    bytes: &[u8],
+    tag: Tag,
+    depth: usize,
+) -> Result<(Value, usize), Error> {
+    if tag.is_end() || tag.is(bytes, b"key") {
+        return Err(Error::ExpectedValue);
+    }
+
+    if tag.is(bytes, b"dict") {
+        parse_dict(bytes, tag, depth)
+    } else if tag.is(bytes, b"array") {
+        parse_array(bytes, tag, depth)
+    } else if tag.is(bytes, b"string") {
+        let (text, end) = read_text(bytes, tag, b"string")?;
+        Ok((Value::String(text), end))
+    } else if tag.is(bytes, b"date") {
+        let (text, end) = read_text(bytes, tag, b"date")?;
+        let date =
+            Date::from_iso8601(text.trim()).ok_or(Error::InvalidValue)?;
+        Ok((Value::Date(date), end))
+    } else if tag.is(bytes, b"data") {
+        let (text, end) = read_text(bytes, tag, b"data")?;
+        let data = decode_base64(&text).ok_or(Error::InvalidValue)?;
+        Ok((Value::Data(data), end))
+    } else if tag.is(bytes, b"integer") {
+        let (text, end) = read_text(bytes, tag, b"integer")?;
+        let int = parse_integer(text.trim()).ok_or(Error::InvalidValue)?;
+        Ok((Value::Integer(int), end))
+    } else if tag.is(bytes, b"real") {
+        let (text, end) = read_text(bytes, tag, b"real")?;
+        let real = text.trim().parse().map_err(|_| Error::InvalidValue)?;
+        Ok((Value::Real(real), end))
+    } else if tag.is(bytes, b"true") || tag.is(bytes, b"false") {
+        let value = Value::Boolean(tag.is(bytes, b"true"));
+        if tag.is_empty() {
+            return Ok((value, tag.end));
+        }
+        let pos = xml::skip_misc(bytes, tag.end)?;
+        let name = if tag.is(bytes, b"true") {
+            &b"true"[..]
+        } else {
+            &b"false"[..]
+        };
+        Ok((value, xml::expect_end(bytes, pos, name)?))
+    } else {
+        Err(Error::UnexpectedElement)
+    }
+}
+
+fn parse_array(
+    bytes: &[u8],
+    tag: Tag,
+    depth: usize,
+) -> Result<(Value, usize), Error> {
+    // Keeps deeply nested input from overflowing the stack.
+    if depth == xml::MAX_DEPTH {
+        return Err(Error::TooDeep);
+    }
+    let mut array = Vec::new();
+    if tag.is_empty() {
+        return Ok((Value::Array(array), tag.end));
+    }
+
+    let mut pos = tag.end;
+    loop {
+        pos = xml::skip_misc(bytes, pos)?;
+        let next = xml::read_tag(bytes, pos)?;
+        if next.is_end() {
+            let end = xml::expect_end(bytes, pos, b"array")?;
+            return Ok((Value::Array(array), end));
+        }
+        let (value, end) = parse_value(bytes, next, depth + 1)?;
+        array.push(value);
+        pos = end;
+    }
+}
+
+fn parse_dict(
+    bytes: &[u8],
+    tag: Tag,
+    depth: usize,
+) -> Result<(Value, usize), Error> {
+    if depth == xml::MAX_DEPTH {
+        return Err(Error::TooDeep);
+    }
+    let mut dict = Dictionary::new();
+    if tag.is_empty() {
+        return Ok((Value::Dictionary(dict), tag.end));
+    }
+
+    let mut pos = tag.end;
+    loop {
+        pos = xml::skip_misc(bytes, pos)?;
+        let key_tag = xml::read_tag(bytes, pos)?;
+        if key_tag.is_end() {
+            let end = xml::expect_end(bytes, pos, b"dict")?;
+            return Ok((uid_or_dict(dict), end));
+        }
+        if !key_tag.is(bytes, b"key") {
+            return Err(Error::ExpectedKey);
+        }
+
+        let (key, value_pos) = read_text(bytes, key_tag, b"key")?;
+        if dict.contains_key(&key) {
+            return Err(Error::DuplicateKey);
+        }
+
+        let value_pos = xml::skip_misc(bytes, value_pos)?;
+        let (value, end) =
+            parse_value(bytes, xml::read_tag(bytes, value_pos)?, depth + 1)?;
+        dict.insert(key, value);
+        pos = end;
+    }
+}
+
+// Reads a dictionary with only a `CF$UID` integer as a UID, like Core
+// Foundation does.
+fn uid_or_dict(dict: Dictionary) -> Value {
+    if dict.len() == 1 {
+        if let Some(&Value::Integer(uid)) = dict.get("CF$UID") {
+            if let Ok(uid) = u64::try_from(uid) {
+                return Value::Uid(uid);
+            }
+        }
+    }
+    Value::Dictionary(dict)
+}
+
+// Decodes the text of the element opened by `tag`.
+fn read_text(
+    bytes: &[u8],
+    tag: Tag,
+    name: &[u8],
+) -> Result<(String, usize), Error> {
+    let mut string = String::new();
+    if tag.is_empty() {
+        return Ok((string, tag.end));
+    }
+
+    let mut text = Text::new(tag.end);
+    while let Some(ch) = text.next(bytes)? {
+        // `Text` only produces Unicode scalar values.
+        string.push(core::char::from_u32(ch).ok_or(Error::InvalidUtf8)?);
+    }
+    Ok((string, xml::expect_end(bytes, text.pos(), name)?))
+}
+
+// Parses decimal or `0x`-prefixed hexadecimal with an optional sign, which is
+// what Core Foundation accepts.
+fn parse_integer(text: &str) -> Option<i128> {
+    let (negative, digits) = match text.as_bytes().first()? {
+        b'-' => (true, &text[1..]),
+        b'+' => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let magnitude = if digits.starts_with("0x") || digits.starts_with("0X") {
+        u64::from_str_radix(&digits[2..], 16).ok()?
+    } else {
+        digits.parse::<u64>().ok()?
+    };
+    let magnitude = magnitude as i128;
+    Some(if negative { -magnitude } else { magnitude })
+}
+
+// Decodes standard base64, ignoring whitespace.
+pub(super) fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut data = Vec::with_capacity(text.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    let mut padding = 0;
+
+    for byte in text.bytes() {
+        let sextet = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => {
+                padding += 1;
+                continue;
+            }
+            b' ' | b'\t' | b'\n' | b'\r' => continue,
+            _ => return None,
+        };
+        if padding > 0 {
+            return None;
+        }
+        buffer = (buffer << 6) | u32::from(sextet);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            data.push((buffer >> bits) as u8);
+        }
+    }
+
+    if padding > 2 {
+        return None;
+    }
+    Some(data)
+}
+
+const HEADER: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+    "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" ",
+    "\"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+    "<plist version=\"1.0\">\n",
+);
+
+pub(super) fn write(value: &Value, indent: Indent, out: &mut Vec<u8>) {
+    out.extend_from_slice(HEADER.as_bytes());
+    Writer { indent, out }.write_value(value, 0);
+    out.extend_from_slice(b"</plist>\n");
+}
+
+struct Writer<'a> {
+    indent: Indent,
+    out: &'a mut Vec<u8>,
+}
+
+impl Writer<'_> {
+    fn write_value(&mut self, value: &Value, depth: usize) {
+        self.write_indent(depth);
+        match value {
+            Value::Array(array) if array.is_empty() => self.push(b"<array/>\n"),
+            Value::Array(array) => {
+                self.push(b"<array>\n");
+                for value in array {
+                    self.write_value(value, depth + 1);
+                }
+                self.write_indent(depth);
+                self.push(b"</array>\n");
+            }
+            Value::Dictionary(dict) if dict.is_empty() => {
+                self.push(b"<dict/>\n");
+            }
+            Value::Dictionary(dict) => {
+                self.push(b"<dict>\n");
+                for (key, value) in dict {
+                    self.write_indent(depth + 1);
+                    self.write_text("key", key);
+                    self.write_value(value, depth + 1);
+                }
+                self.write_indent(depth);
+                self.push(b"</dict>\n");
+            }
+            Value::Boolean(true) => self.push(b"<true/>\n"),
+            Value::Boolean(false) => self.push(b"<false/>\n"),
+            Value::Data(data) => {
+                self.push(b"<data>\n");
+                self.write_data(data, depth);
+                self.write_indent(depth);
+                self.push(b"</data>\n");
+            }
+            Value::Date(date) => self.write_text("date", &format!("{}", date)),
+            Value::Integer(int) => {
+                self.write_text("integer", &format!("{}", int))
+            }
+            Value::Real(real) => self.write_text("real", &format_real(*real)),
+            Value::String(string) => self.write_text("string", string),
+            Value::Uid(uid) => {
+                self.push(b"<dict>\n");
+                self.write_indent(depth + 1);
+                self.write_text("key", "CF$UID");
+                self.write_indent(depth + 1);
+                self.write_text("integer", &format!("{}", uid));
+                self.write_indent(depth);
+                self.push(b"</dict>\n");
+            }
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.out.extend_from_slice(bytes);
+    }
+
+    fn write_indent(&mut self, depth: usize) {
+        match self.indent {
+            Indent::Tab => self.out.extend(iter::repeat_n(b'\t', depth)),
+            Indent::Spaces(width) => {
+                self.out.extend(iter::repeat_n(b' ', depth * width))
+            }
+        }
+    }
+
+    // Writes an element with escaped text, followed by a newline.
+    fn write_text(&mut self, name: &str, text: &str) {
+        self.out.push(b'<');
+        self.push(name.as_bytes());
+        self.out.push(b'>');
+        for byte in text.bytes() {
+            match byte {
+                b'&' => self.push(b"&amp;"),
+                b'<' => self.push(b"&lt;"),
+                b'>' => self.push(b"&gt;"),
+                // Parsers would otherwise read this as a line feed.
+                b'\r' => self.push(b"&#13;"),
+                _ => self.out.push(byte),
+            }
+        }
+        self.push(b"</");
+        self.push(name.as_bytes());
+        self.push(b">\n");
+    }
+
+    // Wraps lines like Core Foundation, which shortens them as the indentation
+    // grows, up to 8 levels.
+    fn write_data(&mut self, data: &[u8], depth: usize) {
+        let depth = depth.min(8);
+        let line_len = 76 - 8 * depth;
+        let encoded = encode_base64(data);
+        for line in encoded.as_bytes().chunks(line_len) {
+            self.write_indent(depth);
+            self.push(line);
+            self.out.push(b'\n');
+        }
+    }
+}
+
+// Formats like Core Foundation, which spells out non-finite values.
+fn format_real(real: f64) -> String {
+    if real.is_nan() {
+        "nan".into()
+    } else if real.is_infinite() {
+        if real > 0.0 { "+infinity" } else { "-infinity" }.into()
+    } else if real.abs() < 1e16 && real == (real as i64) as f64 {
+        format!("{}", real as i64)
+    } else {
+        format!("{:?}", real)
+    }
+}
+
+// Encodes standard base64 with padding.
+fn encode_base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let mut buffer = [0; 3];
+        buffer[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from(buffer[0]) << 16
+            | u32::from(buffer[1]) << 8
+            | u32::from(buffer[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let sextet = (bits >> (18 - 6 * i)) & 0x3F;
+                encoded.push(ALPHABET[sextet as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,260 @@
+// Runtime checks that the embedded sections contain only the macro's bytes.
+
+use core::{ascii, fmt};
+
+/// Checks that the `__TEXT,__info_plist` section contains exactly the bytes
+/// embedded by [`embed_info_plist!`] with the `verify` option, and returns
+/// them.
+///
+/// [`get_info_plist`] measures from the start of the embedded bytes to the end
+/// of the section. If a C object or `-sectcreate` linker flag also added data
+/// to the section, that data is silently returned along with the property
+/// list. This catches that case.
+///
+/// The `verify` option stores the length of the embedded bytes, which this
+/// needs. Without it, linking fails on Apple targets, the same as calling
+/// [`get_info_plist`] without embedding.
+///
+/// With the `portable` feature on non-Apple targets, there is no section to
+/// share, so this always succeeds.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(any(target_vendor = "apple", feature = "portable"))] {
+/// embed_plist::embed_info_plist!("Info.plist", verify);
+///
+/// match embed_plist::verify_info_plist() {
+///     Ok(plist) => assert_eq!(plist, embed_plist::get_info_plist()),
+///     Err(mismatch) => panic!("{}", mismatch),
+/// }
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns a [`SectionMismatch`] describing the foreign contents if the
+/// section does not start at the embedded bytes or is longer than them.
+///
+/// [`embed_info_plist!`]: macro.embed_info_plist.html
+/// [`get_info_plist`]: fn.get_info_plist.html
+/// [`SectionMismatch`]: struct.SectionMismatch.html
+pub fn verify_info_plist() -> Result<&'static [u8], SectionMismatch> {
+    #[cfg(not(all(feature = "portable", not(target_vendor = "apple"))))]
+    {
+        extern "C" {
+            #[link_name = "_EMBED_INFO_PLIST"]
+            static START: [u8; 0];
+
+            #[link_name = "_EMBED_INFO_PLIST_LEN"]
+            static LEN: usize;
+
+            #[link_name = "\x01section$start$__TEXT$__info_plist"]
+            static SECTION_START: [u8; 0];
+
+            #[link_name = "\x01section$end$__TEXT$__info_plist"]
+            static SECTION_END: [u8; 0];
+        }
+
+        unsafe {
+            SectionMismatch::check(
+                "__TEXT,__info_plist",
+                SECTION_START.as_ptr(),
+                SECTION_END.as_ptr(),
+                START.as_ptr(),
+                LEN,
+            )
+        }
+    }
+
+    #[cfg(all(feature = "portable", not(target_vendor = "apple")))]
+    {
+        Ok(crate::get_info_plist())
+    }
+}
+
+/// Checks that the `__TEXT,__launchd_plist` section contains exactly the bytes
+/// embedded by [`embed_launchd_plist!`] with the `verify` option, and returns
+/// them.
+///
+/// This works the same as [`verify_info_plist`].
+///
+/// # Errors
+///
+/// Returns a [`SectionMismatch`] describing the foreign contents if the
+/// section does not start at the embedded bytes or is longer than them.
+///
+/// [`embed_launchd_plist!`]: macro.embed_launchd_plist.html
+/// [`verify_info_plist`]: fn.verify_info_plist.html
+/// [`SectionMismatch`]: struct.SectionMismatch.html
+pub fn verify_launchd_plist() -> Result<&'static [u8], SectionMismatch> {
+    #[cfg(not(all(feature = "portable", not(target_vendor = "apple"))))]
+    {
+        extern "C" {
+            #[link_name = "_EMBED_LAUNCHD_PLIST"]
+            static START: [u8; 0];
+
+            #[link_name = "_EMBED_LAUNCHD_PLIST_LEN"]
+            static LEN: usize;
+
+            #[link_name = "\x01section$start$__TEXT$__launchd_plist"]
+            static SECTION_START: [u8; 0];
+
+            #[link_name = "\x01section$end$__TEXT$__launchd_plist"]
+            static SECTION_END: [u8; 0];
+        }
+
+        unsafe {
+            SectionMismatch::check(
+                "__TEXT,__launchd_plist",
+                SECTION_START.as_ptr(),
+                SECTION_END.as_ptr(),
+                START.as_ptr(),
+                LEN,
+            )
+        }
+    }
+
+    #[cfg(all(feature = "portable", not(target_vendor = "apple")))]
+    {
+        Ok(crate::get_launchd_plist())
+    }
+}
+
+/// A section that contains more than the bytes embedded by this crate, as
+/// returned by [`verify_info_plist`] and [`verify_launchd_plist`].
+///
+/// Its [`Display`] implementation is a diagnostic describing the foreign
+/// contents.
+///
+/// [`verify_info_plist`]: fn.verify_info_plist.html
+/// [`verify_launchd_plist`]: fn.verify_launchd_plist.html
+/// [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SectionMismatch {
+    name: &'static str,
+    section: &'static [u8],
+    offset: usize,
+    len: usize,
+}
+
+impl SectionMismatch {
+    // Compares the section bounds with the embedded symbol's.
+    #[cfg(not(all(feature = "portable", not(target_vendor = "apple"))))]
+    unsafe fn check(
+        name: &'static str,
+        section_start: *const u8,
+        section_end: *const u8,
+        start: *const u8,
+        len: usize,
+    ) -> Result<&'static [u8], Self> {
+        let section = core::slice::from_raw_parts(
+            section_start,
+            section_end as usize - section_start as usize,
+        );
+
+        // The symbol is always placed within the section, but clamping keeps
+        // the accessors from panicking if that is ever not the case.
+        let offset = (start as usize)
+            .saturating_sub(section_start as usize)
+            .min(section.len());
+        let len = len.min(section.len() - offset);
+
+        if offset == 0 && len == section.len() {
+            Ok(section)
+        } else {
+            Err(Self {
+                name,
+                section,
+                offset,
+                len,
+            })
+        }
+    }
+
+    /// Returns the name of the section, such as `__TEXT,__info_plist`.
+    #[inline]
+    pub fn section_name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the entire contents of the section.
+    #[inline]
+    pub fn section(&self) -> &'static [u8] {
+        self.section
+    }
+
+    /// Returns the bytes embedded by this crate.
+    #[inline]
+    pub fn embedded(&self) -> &'static [u8] {
+        &self.section[self.offset..][..self.len]
+    }
+
+    /// Returns `true` if the section starts at the embedded bytes.
+    #[inline]
+    pub fn starts_at_symbol(&self) -> bool {
+        self.offset == 0
+    }
+
+    /// Returns `true` if the section is as long as the embedded bytes.
+    #[inline]
+    pub fn lengths_match(&self) -> bool {
+        self.len == self.section.len()
+    }
+
+    /// Returns the foreign bytes placed before the embedded bytes.
+    #[inline]
+    pub fn foreign_before(&self) -> &'static [u8] {
+        &self.section[..self.offset]
+    }
+
+    /// Returns the foreign bytes placed after the embedded bytes.
+    #[inline]
+    pub fn foreign_after(&self) -> &'static [u8] {
+        &self.section[self.offset + self.len..]
+    }
+}
+
+impl fmt::Display for SectionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "section `{}` has {} bytes, but only {} were embedded",
+            self.name,
+            self.section.len(),
+            self.len,
+        )?;
+
+        let parts = [
+            ("before", self.foreign_before()),
+            ("after", self.foreign_after()),
+        ];
+        for &(position, bytes) in parts.iter() {
+            if !bytes.is_empty() {
+                write!(f, "; {} foreign bytes {}: ", bytes.len(), position)?;
+                preview(bytes, f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SectionMismatch {}
+
+// Writes up to the first 32 bytes of `bytes` as an escaped byte string.
+fn preview(bytes: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
+    const MAX: usize = 32;
+
+    f.write_str("b\"")?;
+    for &byte in bytes.iter().take(MAX) {
+        for escaped in ascii::escape_default(byte) {
+            fmt::Write::write_char(f, escaped as char)?;
+        }
+    }
+    f.write_str("\"")?;
+    if bytes.len() > MAX {
+        f.write_str("...")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,285 @@
+#![cfg(feature = "binary")]
+
+use embed_plist::binary::{Document, Error, Object};
+
+// Assembles a binary property list whose top object is the first in `objects`.
+fn bplist(objects: &[&[u8]], offset_size: u8, ref_size: u8) -> Vec<u8> {
+    let mut bytes = b"bplist00".to_vec();
+    let mut offsets = Vec::new();
+    for object in objects {
+        offsets.push(bytes.len() as u64);
+        bytes.extend_from_slice(object);
+    }
+    let offset_table = bytes.len() as u64;
+    for offset in offsets {
+        let offset = offset.to_be_bytes();
+        bytes.extend_from_slice(&offset[8 - usize::from(offset_size)..]);
+    }
+    bytes.extend_from_slice(&[0, 0, 0, 0, 0, 0, offset_size, ref_size]);
+    bytes.extend_from_slice(&(objects.len() as u64).to_be_bytes());
+    bytes.extend_from_slice(&0u64.to_be_bytes());
+    bytes.extend_from_slice(&offset_table.to_be_bytes());
+    bytes
+}
+
+fn utf16(string: &str) -> Vec<u8> {
+    let units: Vec<u16> = string.encode_utf16().collect();
+    let mut bytes = vec![0x60 | units.len() as u8];
+    for unit in units {
+        bytes.extend_from_slice(&unit.to_be_bytes());
+    }
+    bytes
+}
+
+// A dictionary of every kind of scalar, with 2-byte offsets and 2-byte refs.
+fn scalars() -> Vec<u8> {
+    let mut data = vec![0x4F, 0x10, 20];
+    data.extend(0..20);
+    let mut big = vec![0x14];
+    big.extend_from_slice(&u128::from(u64::MAX).to_be_bytes());
+    let mut real = vec![0x22];
+    real.extend_from_slice(&1.5f32.to_be_bytes());
+    let mut date = vec![0x33];
+    date.extend_from_slice(&(-0.5f64).to_be_bytes());
+    let snowman = utf16("Grüße ☃");
+
+    let mut dict = vec![0xD7];
+    for index in 1..=14u16 {
+        dict.extend_from_slice(&index.to_be_bytes());
+    }
+    bplist(
+        &[
+            &dict,
+            b"UASCII",
+            b"UUTF16",
+            b"VReal32",
+            b"TDate",
+            b"SUid",
+            b"SBig",
+            b"TLong",
+            b"Uplain",
+            &snowman,
+            &real,
+            &date,
+            &[0x81, 0x12, 0x34],
+            &big,
+            &data,
+        ],
+        2,
+        2,
+    )
+}
+
+#[test]
+fn read_scalars() {
+    let bytes = scalars();
+    let document = Document::parse(&bytes).unwrap();
+    let dict = match document.root().unwrap() {
+        Object::Dictionary(dict) => dict,
+        root => panic!("unexpected root {:?}", root),
+    };
+    assert_eq!(dict.len(), 7);
+
+    match dict.get("ASCII").unwrap() {
+        Some(Object::String(string)) => {
+            assert_eq!(string.as_str(), Some("plain"))
+        }
+        value => panic!("unexpected value {:?}", value),
+    }
+    match dict.get("UTF16").unwrap() {
+        Some(Object::String(string)) => {
+            assert_eq!(string.as_str(), None);
+            assert!(string.eq_str("Grüße ☃"));
+            assert_eq!(string.to_string(), "Grüße ☃");
+        }
+        value => panic!("unexpected value {:?}", value),
+    }
+    assert_eq!(dict.get("Real32"), Ok(Some(Object::Real(1.5))));
+    assert_eq!(dict.get("Date"), Ok(Some(Object::Date(-0.5))));
+    assert_eq!(dict.get("Uid"), Ok(Some(Object::Uid(0x1234))));
+    assert_eq!(dict.get("Big"), Ok(Some(Object::Integer(u64::MAX.into()))));
+    let data: Vec<u8> = (0..20).collect();
+    assert_eq!(dict.get("Long"), Ok(Some(Object::Data(&data))));
+    assert_eq!(dict.get("Missing"), Ok(None));
+
+    let keys: Vec<String> = dict
+        .iter()
+        .map(|entry| entry.unwrap().0.to_string())
+        .collect();
+    assert_eq!(
+        keys,
+        ["ASCII", "UTF16", "Real32", "Date", "Uid", "Big", "Long"]
+    );
+}
+
+#[test]
+fn reject_truncated() {
+    let bytes = scalars();
+    for len in 0..bytes.len() {
+        let truncated = &bytes[..len];
+        assert!(
+            Document::parse(truncated).is_err(),
+            "accepted {} bytes",
+            len
+        );
+    }
+
+    // An object whose length runs into the offset table.
+    let bytes = bplist(&[b"\x5Ftoo long"], 1, 1);
+    let document = Document::parse(&bytes).unwrap();
+    assert_eq!(document.root(), Err(Error::InvalidObject));
+    let bytes = bplist(&[b"\x59short"], 1, 1);
+    let document = Document::parse(&bytes).unwrap();
+    assert_eq!(document.root(), Err(Error::OutOfBounds));
+}
+
+#[test]
+fn reject_invalid() {
+    let cases: [(&[u8], Error); 4] = [
+        (b"\xA1\x05", Error::InvalidReference),
+        (b"\xD1\x00\x00", Error::InvalidKey),
+        (b"\x61\xD8\x00", Error::InvalidString),
+        (b"\x70", Error::InvalidObject),
+    ];
+    for (object, error) in cases.iter() {
+        let bytes = bplist(&[object], 1, 1);
+        let document = Document::parse(&bytes).unwrap();
+        let result = match document.root() {
+            Ok(Object::Array(array)) => array.get(0).map(|_| ()),
+            Ok(Object::Dictionary(dict)) => dict.get("").map(|_| ()),
+            result => result.map(|_| ()),
+        };
+        assert_eq!(result, Err(*error));
+    }
+
+    let mut bytes = bplist(&[b"\x09"], 1, 1);
+    let len = bytes.len();
+    bytes[len - 25] = 9;
+    assert_eq!(Document::parse(&bytes).err(), Some(Error::InvalidTrailer));
+    assert_eq!(
+        Document::parse(b"bplist01").err(),
+        Some(Error::InvalidHeader)
+    );
+}
+
+#[test]
+fn corrupted_bytes_do_not_panic() {
+    let bytes = scalars();
+    for i in 0..bytes.len() {
+        for &byte in &[0x00, 0x0F, 0x7F, 0x80, 0xFF] {
+            let mut corrupted = bytes.clone();
+            corrupted[i] = byte;
+            if let Ok(document) = Document::parse(&corrupted) {
+                if let Ok(Object::Dictionary(dict)) = document.root() {
+                    dict.iter().for_each(drop);
+                }
+            }
+            #[cfg(feature = "alloc")]
+            let _ = embed_plist::value::Value::from_binary(&corrupted);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod value {
+    use super::*;
+    use embed_plist::value::{Date, Dictionary, Value};
+
+    #[test]
+    fn read_value() {
+        let value = Value::from_binary(&scalars()).unwrap();
+        let expected: Dictionary = vec![
+            ("ASCII", Value::from("plain")),
+            ("UTF16", Value::from("Grüße ☃")),
+            ("Real32", Value::Real(1.5)),
+            ("Date", Date::from_seconds_since_2001(-0.5).unwrap().into()),
+            ("Uid", Value::Uid(0x1234)),
+            ("Big", Value::from(u64::MAX)),
+            ("Long", Value::Data((0..20).collect())),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(value, Value::Dictionary(expected));
+    }
+
+    #[test]
+    fn round_trip() {
+        let fixtures: [&[u8]; 2] = [
+            include_bytes!("../src/Info.plist"),
+            include_bytes!("../src/launchd.plist"),
+        ];
+        for fixture in fixtures.iter() {
+            let value = Value::from_xml(fixture).unwrap();
+            assert_eq!(Value::from_binary(&value.to_binary()), Ok(value));
+        }
+
+        // Enough objects for 2-byte references and offsets.
+        let mut array: Vec<Value> = (0..300).map(Value::from).collect();
+        array.extend(vec![
+            Value::from(-1),
+            Value::from(i64::MIN),
+            Value::from(u64::MAX),
+            Value::Real(f64::NEG_INFINITY),
+            Value::Uid(u64::MAX),
+            Value::from("Grüße ☃"),
+            Value::from("long ASCII string"),
+            Value::from(vec![0u8; 100]),
+            Value::Date(Date::from_seconds_since_2001(-1e9 + 0.25).unwrap()),
+            Value::Array(Vec::new()),
+            Value::Dictionary(Dictionary::new()),
+        ]);
+        let value = Value::Array(array);
+        assert_eq!(Value::from_binary(&value.to_binary()), Ok(value));
+    }
+
+    #[test]
+    fn strings_are_shared() {
+        let value = Value::Array(vec!["same".into(), "same".into()]);
+        let binary = value.to_binary();
+        let document = Document::parse(&binary).unwrap();
+        match document.root().unwrap() {
+            Object::Array(array) => {
+                assert_eq!(array.len(), 2);
+                assert_eq!(array.get(0), array.get(1));
+            }
+            root => panic!("unexpected root {:?}", root),
+        }
+        // Header, array, string, offset table, and trailer.
+        assert_eq!(binary.len(), 8 + 3 + 5 + 2 + 32);
+    }
+
+    #[test]
+    fn reject_cycles() {
+        let cases: [&[&[u8]]; 3] = [
+            &[b"\xA1\x00"],
+            &[b"\xA1\x01", b"\xA1\x00"],
+            &[b"\xD1\x01\x02", b"QK", b"\xA1\x00"],
+        ];
+        for objects in cases.iter() {
+            let bytes = bplist(objects, 1, 1);
+            assert_eq!(Value::from_binary(&bytes), Err(Error::Cycle));
+        }
+
+        // Shared objects are not cycles.
+        let bytes = bplist(&[b"\xA2\x01\x01", b"\xA0"], 1, 1);
+        let empty = Value::Array(Vec::new());
+        assert_eq!(
+            Value::from_binary(&bytes),
+            Ok(Value::Array(vec![empty.clone(), empty]))
+        );
+    }
+
+    #[test]
+    fn reject_deep_nesting() {
+        let arrays: Vec<[u8; 3]> = (1..=1000u16)
+            .map(|next| {
+                let [high, low] = next.to_be_bytes();
+                [0xA1, high, low]
+            })
+            .collect();
+        let mut objects: Vec<&[u8]> = arrays.iter().map(|a| &a[..]).collect();
+        objects.push(b"\xA0");
+        let bytes = bplist(&objects, 2, 2);
+        assert_eq!(Value::from_binary(&bytes), Err(Error::TooDeep));
+    }
+}
@@ -0,0 +1,141 @@
+#![cfg(feature = "std")]
+
+use std::{fs, io, path::PathBuf};
+
+fn canonicalize(name: &str, input: &str) -> io::Result<String> {
+    let dir = std::env::temp_dir().join("embed_plist_tests");
+    fs::create_dir_all(&dir)?;
+
+    let input_path = dir.join(format!("{}.plist", name));
+    let output_path = dir.join(format!("{}.canonical.plist", name));
+    fs::write(&input_path, input)?;
+
+    let result = embed_plist::build::canonicalize(&input_path, &output_path)?;
+    let output = fs::read_to_string(&output_path)?;
+
+    assert_eq!(result.input_len, input.len());
+    assert_eq!(result.output_len, output.len());
+    assert_eq!(result.saved(), input.len().saturating_sub(output.len()));
+    Ok(output)
+}
+
+fn plist(body: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><plist version="1.0">{}</plist>"#,
+        body
+    )
+}
+
+#[test]
+fn canonicalize_info_plist() {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "src", "Info.plist"]
+        .iter()
+        .collect();
+    let input = fs::read_to_string(path).unwrap();
+    let output = canonicalize("info", &input).unwrap();
+    assert_eq!(
+        output,
+        plist(
+            "<dict><key>Why</key><string>To use in doc tests</string></dict>"
+        )
+    );
+}
+
+#[test]
+fn canonicalize_nested() {
+    let input = r#"
+        <?xml version="1.0" encoding="UTF-8"?>
+        <!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+        <!-- Comment -->
+        <plist version="1.0">
+        <dict>
+            <key>b</key>
+            <dict>
+                <key>z</key>
+                <true></true>
+                <key>y</key>
+                <array>
+                    <integer> 1 </integer>
+                    <data>
+                        AAEC
+                        Aw==
+                    </data>
+                </array>
+            </dict>
+            <key>a &amp; &#x3C;</key>
+            <string><![CDATA[<raw>]]> &#233;</string>
+            <key>ä</key>
+            <array></array>
+            <key/>
+            <string/>
+        </dict>
+        </plist>
+    "#;
+    let output = canonicalize("nested", input).unwrap();
+    assert_eq!(
+        output,
+        plist(concat!(
+            "<dict>",
+            "<key></key><string></string>",
+            "<key>a &amp; &lt;</key><string>&lt;raw&gt; é</string>",
+            "<key>b</key><dict>",
+            "<key>y</key><array><integer>1</integer><data>AAECAw==</data></array>",
+            "<key>z</key><true/>",
+            "</dict>",
+            "<key>ä</key><array/>",
+            "</dict>",
+        ))
+    );
+}
+
+#[test]
+fn canonicalize_invalid() {
+    let cases = [
+        ("duplicate", "<plist><dict><key>a</key><true/><key>a</key><false/></dict></plist>"),
+        ("mismatched", "<plist><string>a</integer></plist>"),
+        ("missing_key", "<plist><dict><string>a</string></dict></plist>"),
+        ("entity", "<plist><string>&custom;</string></plist>"),
+        ("truncated", "<plist><dict><key>a</key>"),
+        ("trailing", "<plist><true/></plist><plist/>"),
+    ];
+    for (name, input) in cases.iter() {
+        let error = canonicalize(name, input).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{}", name);
+    }
+}
+
+#[test]
+fn canonicalize_too_deep() {
+    let nested = |depth: usize| {
+        plist(&format!(
+            "{}{}",
+            "<array>".repeat(depth),
+            "</array>".repeat(depth)
+        ))
+    };
+    assert!(canonicalize("deep", &nested(512)).is_ok());
+
+    for (name, depth) in [("too_deep", 513), ("far_too_deep", 200_000)].iter() {
+        let error = canonicalize(name, &nested(*depth)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "property list is nested too deeply");
+    }
+}
+
+#[test]
+fn canonicalize_too_many_keys() {
+    let dict = |count: usize| {
+        let entries: String = (0..count)
+            .map(|i| format!("<key>{}</key><true/>", i))
+            .collect();
+        plist(&format!("<dict>{}</dict>", entries))
+    };
+    assert!(canonicalize("many_keys", &dict(1024)).is_ok());
+
+    let error = canonicalize("too_many_keys", &dict(1025)).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        error.to_string(),
+        "property list dictionaries have too many keys to sort"
+    );
+}
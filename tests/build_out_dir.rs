@@ -0,0 +1,151 @@
+#![cfg(feature = "std")]
+
+use embed_plist::value::Value;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+fn plist(body: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><plist version="1.0">{}</plist>"#,
+        body
+    )
+}
+
+// Setting `OUT_DIR` is only sound while no other thread reads the environment,
+// so the helpers that write to it are tested from this one test, in a binary of
+// its own.
+#[test]
+fn out_dir() {
+    let dir = std::env::temp_dir().join("embed_plist_out_dir_tests");
+    fs::create_dir_all(&dir).unwrap();
+    std::env::set_var("OUT_DIR", &dir);
+
+    consts_info_plist(&dir);
+    consts_collision(&dir);
+    convert_json_and_toml(&dir);
+}
+
+// Generates constants for `input` and returns the source of the module.
+fn consts(dir: &Path, name: &str, input: &str) -> io::Result<String> {
+    let input_path = dir.join(format!("{}.plist", name));
+    fs::write(&input_path, input)?;
+
+    embed_plist::build::info_plist_consts(&input_path)?;
+
+    let mut output_path = dir.join("embed_plist");
+    output_path.extend(input_path.components().skip(1));
+    output_path.set_extension("plist.rs");
+    fs::read_to_string(output_path)
+}
+
+fn consts_info_plist(dir: &Path) {
+    let input = plist(concat!(
+        "<dict>",
+        "<key>CFBundleIdentifier</key><string>com.example \"app\"</string>",
+        "<key>LSUIElement</key><true/>",
+        "<key>NSHumanReadableCopyright</key><string/>",
+        "<key>Build</key><integer>0xFFFFFFFFFFFFFFFF</integer>",
+        "<key>Offset</key><integer>-1</integer>",
+        "<key>Scale</key><real>0.5</real>",
+        "<key>Data</key><data>AAEC</data>",
+        "<key>CFBundleURLSchemes</key><array><string>a</string><string>b</string></array>",
+        "<key>Mixed</key><array><string>a</string><true/></array>",
+        "<key>NSAppTransportSecurity</key><dict>",
+        "<key>NSAllowsArbitraryLoads</key><false/>",
+        "<key>type</key><dict/>",
+        "</dict>",
+        "</dict>",
+    ));
+    let output = consts(dir, "consts", &input).unwrap();
+    let body = output.split("(BYTES);\n\n").nth(1).unwrap();
+    assert_eq!(
+        body,
+        r#"    /// The unique identifier of the bundle, in reverse-DNS form.
+    ///
+    /// The value of `CFBundleIdentifier`.
+    pub const CF_BUNDLE_IDENTIFIER: &str = "com.example \"app\"";
+
+    /// Whether the app runs without a Dock icon or menu bar.
+    ///
+    /// The value of `LSUIElement`.
+    pub const LS_UI_ELEMENT: bool = true;
+
+    /// The copyright notice of the bundle.
+    ///
+    /// The value of `NSHumanReadableCopyright`.
+    pub const NS_HUMAN_READABLE_COPYRIGHT: &str = "";
+
+    /// The value of `Build`.
+    pub const BUILD: u64 = 18446744073709551615;
+
+    /// The value of `Offset`.
+    pub const OFFSET: i64 = -1;
+
+    /// The value of `Scale`.
+    pub const SCALE: f64 = 0.5;
+
+    /// The value of `Data`.
+    pub const DATA: &[u8] = &[0, 1, 2];
+
+    /// The value of `CFBundleURLSchemes`.
+    pub const CF_BUNDLE_URL_SCHEMES: &[&str] = &["a", "b"];
+
+    // `Mixed` is skipped because it is an empty or mixed array.
+
+    /// Exceptions to the security requirements for network connections.
+    ///
+    /// The entries of `NSAppTransportSecurity`.
+    pub mod ns_app_transport_security {
+        /// The value of `NSAllowsArbitraryLoads`.
+        pub const NS_ALLOWS_ARBITRARY_LOADS: bool = false;
+
+        /// The entries of `type`.
+        pub mod r#type {
+        }
+    }
+}
+"#
+    );
+}
+
+fn consts_collision(dir: &Path) {
+    let input = plist(
+        "<dict><key>CFBundleName</key><true/><key>CF_BUNDLE_NAME</key><true/></dict>",
+    );
+    let error = consts(dir, "collision", &input).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+fn convert_json_and_toml(dir: &Path) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "src", "Info.plist"]
+        .iter()
+        .collect();
+    let expected = Value::from_xml(&fs::read(path).unwrap()).unwrap().to_xml();
+    let cases = [
+        ("info.json", r#"{ "Why": "To use in doc tests" }"#),
+        ("info.toml", r#"Why = "To use in doc tests""#),
+    ];
+    for (name, input) in cases.iter() {
+        let input_path = dir.join(name);
+        fs::write(&input_path, input).unwrap();
+        if name.ends_with(".json") {
+            embed_plist::build::json_to_plist(&input_path).unwrap();
+        } else {
+            embed_plist::build::toml_to_plist(&input_path).unwrap();
+        }
+
+        let mut output_path = dir.join("embed_plist");
+        output_path.extend(input_path.components().skip(1));
+        let mut file_name = output_path.file_name().unwrap().to_owned();
+        file_name.push(".plist");
+        output_path.set_file_name(file_name);
+        assert_eq!(fs::read(output_path).unwrap(), expected, "{}", name);
+    }
+
+    let input_path = dir.join("invalid.json");
+    fs::write(&input_path, "{ null }").unwrap();
+    let error = embed_plist::build::json_to_plist(&input_path).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "alloc")]
+
+use embed_plist::builder::{InfoPlistBuilder, KeepAlive, LaunchdPlistBuilder};
+use embed_plist::value::Value;
+
+#[test]
+fn launchd_plist() {
+    let builder = LaunchdPlistBuilder::new("com.example.daemon")
+        .program_arguments(&["/usr/local/bin/daemon", "--flag=<&>"])
+        .keep_alive(KeepAlive::SuccessfulExit(false))
+        .keep_alive(KeepAlive::PathState("/tmp/run".into(), true))
+        .mach_service("com.example.daemon.xpc")
+        .environment_variable("RUST_LOG", "info")
+        .start_interval(3600)
+        .run_at_load(true);
+
+    let xml = builder.to_xml();
+    assert_eq!(
+        std::str::from_utf8(&xml).unwrap(),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Label</key>
+	<string>com.example.daemon</string>
+	<key>ProgramArguments</key>
+	<array>
+		<string>/usr/local/bin/daemon</string>
+		<string>--flag=&lt;&amp;&gt;</string>
+	</array>
+	<key>KeepAlive</key>
+	<dict>
+		<key>SuccessfulExit</key>
+		<false/>
+		<key>PathState</key>
+		<dict>
+			<key>/tmp/run</key>
+			<true/>
+		</dict>
+	</dict>
+	<key>MachServices</key>
+	<dict>
+		<key>com.example.daemon.xpc</key>
+		<true/>
+	</dict>
+	<key>EnvironmentVariables</key>
+	<dict>
+		<key>RUST_LOG</key>
+		<string>info</string>
+	</dict>
+	<key>StartInterval</key>
+	<integer>3600</integer>
+	<key>RunAtLoad</key>
+	<true/>
+</dict>
+</plist>
+"#
+    );
+    assert_eq!(Value::from_xml(&xml), Ok(builder.build()));
+}
+
+#[test]
+fn keep_alive_always_replaces_conditions() {
+    let plist = LaunchdPlistBuilder::new("job")
+        .keep_alive(KeepAlive::Crashed(true))
+        .keep_alive(KeepAlive::Always(true))
+        .build();
+    let dict = plist.as_dictionary().unwrap();
+    assert_eq!(dict.get("KeepAlive"), Some(&Value::Boolean(true)));
+}
+
+#[test]
+fn info_plist() {
+    let plist = InfoPlistBuilder::new()
+        .bundle_identifier("com.example.app")
+        .bundle_version("1")
+        .ui_element(true)
+        .bundle_version("2")
+        .set("LSApplicationCategoryType", "public.app-category.utilities")
+        .build();
+
+    let dict = plist.as_dictionary().unwrap();
+    let keys: Vec<&str> = dict.keys().collect();
+    assert_eq!(
+        keys,
+        [
+            "CFBundleIdentifier",
+            "CFBundleVersion",
+            "LSUIElement",
+            "LSApplicationCategoryType",
+        ]
+    );
+    assert_eq!(
+        dict.get("CFBundleVersion").and_then(Value::as_str),
+        Some("2")
+    );
+}
@@ -0,0 +1,56 @@
+#![cfg(all(
+    feature = "const_eval",
+    any(target_vendor = "apple", feature = "portable"),
+))]
+
+// The number of keys in `MANY_KEYS`, which is more than any real `Info.plist`
+// has.
+const COUNT: usize = 300;
+
+const ENTRY: &[u8] = b"<key>000</key><true/>";
+
+// A dictionary whose keys are `000` through `299` in descending order.
+const MANY_KEYS: [u8; 13 + COUNT * ENTRY.len()] = {
+    let mut out = [0; 13 + COUNT * ENTRY.len()];
+    let mut pos = 0;
+    while pos < 6 {
+        out[pos] = b"<dict>"[pos];
+        pos += 1;
+    }
+    let mut i = COUNT;
+    while i > 0 {
+        i -= 1;
+        let mut j = 0;
+        while j < ENTRY.len() {
+            out[pos + j] = ENTRY[j];
+            j += 1;
+        }
+        out[pos + 5] = b'0' + (i / 100) as u8;
+        out[pos + 6] = b'0' + (i / 10 % 10) as u8;
+        out[pos + 7] = b'0' + (i % 10) as u8;
+        pos += ENTRY.len();
+    }
+    let mut j = 0;
+    while j < 7 {
+        out[pos + j] = b"</dict>"[j];
+        j += 1;
+    }
+    out
+};
+
+embed_plist::embed_info_plist_bytes!(&MANY_KEYS, canonicalize);
+
+#[test]
+fn many_keys_sorted() {
+    let mut expected = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><plist version="1.0">"#,
+        "<dict>",
+    ));
+    for i in 0..COUNT {
+        expected.push_str(&format!("<key>{:03}</key><true/>", i));
+    }
+    expected.push_str("</dict></plist>");
+
+    let embedded = embed_plist::get_info_plist();
+    assert_eq!(std::str::from_utf8(embedded).unwrap(), expected);
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "const_eval")]
+
+use embed_plist::const_value;
+
+const LAUNCHD_PLIST: &[u8] = include_bytes!("../src/launchd.plist");
+
+const PLIST: &[u8] = br#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Nested</key>
+    <dict>
+        <key>Escaped</key>
+        <string>nested</string>
+    </dict>
+    <key>Esc&#x61;ped</key>
+    <string>Tom &amp; <![CDATA[<Jerry>]]> &#233;</string>
+    <key>Empty</key>
+    <string/>
+    <key>Enabled</key>
+    <false/>
+    <key>Visible</key>
+    <true></true>
+    <key>Negative</key>
+    <integer> -12 </integer>
+    <key>Hex</key>
+    <integer>0xFFFFFFFFFFFFFFFF</integer>
+</dict>
+</plist>
+"#;
+
+#[test]
+fn strings() {
+    const LABEL: &str = const_value!(LAUNCHD_PLIST, "Label");
+    const WHY: &str = const_value!(LAUNCHD_PLIST, "Why", str);
+    const ESCAPED: &str = const_value!(PLIST, "Escaped");
+    const EMPTY: &str = const_value!(PLIST, "Empty");
+
+    assert_eq!(LABEL, "example");
+    assert_eq!(WHY, "To use in doc tests");
+    assert_eq!(ESCAPED, "Tom & <Jerry> é");
+    assert_eq!(EMPTY, "");
+}
+
+#[test]
+fn bools() {
+    const ENABLED: bool = const_value!(PLIST, "Enabled", bool);
+    const VISIBLE: bool = const_value!(PLIST, "Visible", bool);
+
+    assert_eq!([ENABLED, VISIBLE], [false, true]);
+}
+
+#[test]
+fn integers() {
+    const NEGATIVE: i64 = const_value!(PLIST, "Negative", i64);
+    const HEX: u64 = const_value!(PLIST, "Hex", u64);
+
+    assert_eq!(NEGATIVE, -12);
+    assert_eq!(HEX, u64::MAX);
+}
+
+// Non-string values are read at runtime outside of a `const`, where panics can
+// be caught.
+#[test]
+fn whitespace_within_integer() {
+    let plist = |value: &str| {
+        format!("<dict><key>a</key><integer>{}</integer></dict>", value)
+    };
+    assert_eq!(const_value!(plist("\n\t+12 ").as_bytes(), "a", i64), 12);
+
+    for value in ["1 2", "- 1", "0x 1F", "0x1 F"].iter() {
+        let bytes = plist(value).into_bytes();
+        let result =
+            std::panic::catch_unwind(|| const_value!(&bytes, "a", i64));
+        assert!(result.is_err(), "{:?}", value);
+    }
+}
+
+#[test]
+#[should_panic(expected = "malformed value in property list")]
+fn bool_with_content() {
+    const_value!(b"<dict><key>a</key><true>junk</true></dict>", "a", bool);
+}
+
+#[test]
+#[should_panic(expected = "key `Missing` not found in property list")]
+fn missing_key() {
+    const_value!(PLIST, "Missing", u64);
+}
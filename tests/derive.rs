@@ -0,0 +1,132 @@
+#![cfg(feature = "derive")]
+
+use embed_plist::{Error, InfoPlist};
+
+#[derive(Debug, PartialEq, InfoPlist)]
+struct Info {
+    #[plist(rename = "CFBundleIdentifier")]
+    identifier: &'static str,
+    #[plist(rename = "LSUIElement")]
+    ui_element: bool,
+    #[plist(rename = "NSHumanReadableCopyright")]
+    copyright: Option<&'static str>,
+    #[plist(rename = "LSMinimumSystemVersion")]
+    minimum_version: Option<&'static str>,
+    build: u64,
+    offset: i64,
+    #[plist(rename = "NSAppTransportSecurity", nested)]
+    transport: Transport,
+}
+
+#[derive(Debug, PartialEq, InfoPlist)]
+struct Transport {
+    #[plist(rename = "NSAllowsArbitraryLoads")]
+    arbitrary_loads: bool,
+    r#type: Option<&'static str>,
+}
+
+const INFO: Info = Info {
+    identifier: "com.example.app",
+    ui_element: true,
+    copyright: Some("Tom & <Jerry>"),
+    minimum_version: None,
+    build: u64::MAX,
+    offset: -1,
+    transport: Transport {
+        arbitrary_loads: false,
+        r#type: Some("web"),
+    },
+};
+
+const PLIST: &[u8] = &INFO.to_plist::<{ INFO.plist_len() }>();
+
+#[test]
+fn to_plist() {
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><plist version="1.0"><dict>"#,
+        "<key>CFBundleIdentifier</key><string>com.example.app</string>",
+        "<key>LSUIElement</key><true/>",
+        "<key>NSHumanReadableCopyright</key>",
+        "<string><![CDATA[Tom & <Jerry>]]></string>",
+        "<key>build</key><integer>18446744073709551615</integer>",
+        "<key>offset</key><integer>-1</integer>",
+        "<key>NSAppTransportSecurity</key><dict>",
+        "<key>NSAllowsArbitraryLoads</key><false/>",
+        "<key>type</key><string>web</string>",
+        "</dict>",
+        "</dict></plist>",
+    );
+    assert_eq!(std::str::from_utf8(PLIST).unwrap(), expected);
+}
+
+#[test]
+fn from_plist() {
+    assert_eq!(Info::from_plist(PLIST), Ok(INFO));
+}
+
+#[test]
+fn from_plist_errors() {
+    let cases: [(&'static [u8], Error); 4] = [
+        (b"<plist><dict/></plist>", Error::MissingKey),
+        (b"<plist><array/></plist>", Error::TypeMismatch),
+        (
+            b"<plist><dict><key>arbitrary_loads</key><true/></dict></plist>",
+            Error::MissingKey,
+        ),
+        (
+            b"<plist><dict><key>NSAllowsArbitraryLoads</key><true/>\
+              <key>type</key><string>&amp;</string></dict></plist>",
+            Error::UnsupportedString,
+        ),
+    ];
+    for (bytes, error) in cases.iter() {
+        assert_eq!(Transport::from_plist(bytes).err(), Some(*error));
+    }
+}
+
+#[cfg(any(target_os = "macos", feature = "portable"))]
+embed_plist::embed_info_plist_bytes!(PLIST);
+
+#[test]
+#[cfg(any(target_os = "macos", feature = "portable"))]
+fn from_embedded() {
+    assert_eq!(Info::from_plist(embed_plist::get_info_plist()), Ok(INFO));
+}
+
+#[test]
+fn string_round_trip() {
+    macro_rules! round_trip {
+        ($($value:expr),+) => {$({
+            const TRANSPORT: Transport = Transport {
+                arbitrary_loads: true,
+                r#type: Some($value),
+            };
+            const PLIST: &[u8] =
+                &TRANSPORT.to_plist::<{ TRANSPORT.plist_len() }>();
+            assert_eq!(Transport::from_plist(PLIST), Ok(TRANSPORT));
+        })+};
+    }
+    round_trip!("a]]b>", "]]", "x > y", "<![CDATA[", "\n\t");
+}
+
+#[test]
+fn string_cannot_round_trip() {
+    for value in ["a]]>b", "a\r\nb"].iter() {
+        let transport = Transport {
+            arbitrary_loads: true,
+            r#type: Some(value),
+        };
+        let message = std::panic::catch_unwind(|| transport.plist_len())
+            .unwrap_err()
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string());
+        assert_eq!(
+            message.as_deref(),
+            Some(
+                "`InfoPlist` strings cannot contain `]]>` or carriage returns"
+            ),
+            "{:?}",
+            value
+        );
+    }
+}
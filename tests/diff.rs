@@ -0,0 +1,149 @@
+#![cfg(feature = "alloc")]
+
+use embed_plist::value::{
+    Change, Dictionary, KeyPath, PatchError, Segment, Value,
+};
+
+fn openstep(text: &str) -> Value {
+    Value::from_openstep(text.as_bytes()).unwrap()
+}
+
+#[test]
+fn semantic_eq() {
+    let a = openstep("{ a = (1, { x = 1; y = 2; }); b = <*R1>; }");
+    let b = openstep("{ b = <*R1>; a = (1, { y = 2; x = 1; }); }");
+    assert_ne!(a, b);
+    assert!(a.semantic_eq(&b));
+    assert!(a.diff(&b).is_empty());
+
+    assert!(!a.semantic_eq(&openstep("{ a = (1, { x = 1; }); b = <*R1>; }")));
+    assert!(
+        !a.semantic_eq(&openstep("{ a = ({ x = 1; y = 2; }, 1); b = <*R1>; }"))
+    );
+    assert!(!Value::Real(1.0).semantic_eq(&Value::Integer(1)));
+    assert!(Value::Real(f64::NAN).semantic_eq(&Value::Real(f64::NAN)));
+}
+
+#[test]
+fn diff_paths() {
+    let old = openstep(
+        "{ Label = old; Types = ({ Schemes = (a, b, c); }); Removed = 1; }",
+    );
+    let new = openstep(
+        "{ Label = new; Types = ({ Schemes = (a); Name = n; }); Added = { x = 1; }; }",
+    );
+    let diff = old.diff(&new);
+    let lines: Vec<String> =
+        diff.iter().map(|change| change.to_string()).collect();
+    assert_eq!(
+        lines,
+        [
+            r#"~ Label: String("old") -> String("new")"#,
+            r#"- Types[0].Schemes[2]: String("c")"#,
+            r#"- Types[0].Schemes[1]: String("b")"#,
+            r#"+ Types[0].Name: String("n")"#,
+            r#"- Removed: String("1")"#,
+            r#"+ Added: Dictionary(Dictionary { entries: [("x", String("1"))] })"#,
+        ]
+    );
+
+    assert!(matches!(diff.changes()[1], Change::Removed { .. }));
+    let path = diff.changes()[1].path();
+    assert_eq!(
+        path.segments(),
+        [
+            Segment::Key("Types".into()),
+            Segment::Index(0),
+            Segment::Key("Schemes".into()),
+            Segment::Index(2),
+        ]
+    );
+
+    let root = Value::from(1).diff(&Value::from(2));
+    assert_eq!(root.to_string(), "~ (root): Integer(1) -> Integer(2)\n");
+    assert_eq!(root.changes()[0].path(), &KeyPath::root());
+}
+
+#[test]
+fn patch() {
+    let old = openstep("{ a = (1, 2, 3); b = { c = d; }; e = f; }");
+    let new = openstep("{ a = (1, 4); b = { c = g; h = i; }; j = (k); }");
+    let diff = old.diff(&new);
+
+    let mut patched = old.clone();
+    diff.apply(&mut patched).unwrap();
+    assert!(patched.semantic_eq(&new));
+
+    // Unrelated edits are kept.
+    let mut edited = old.clone();
+    if let Value::Dictionary(dict) = &mut edited {
+        dict.insert("unrelated", true);
+    }
+    diff.apply(&mut edited).unwrap();
+    let mut expected = new.clone();
+    if let Value::Dictionary(dict) = &mut expected {
+        dict.insert("unrelated", true);
+    }
+    assert!(edited.semantic_eq(&expected));
+
+    // Applying twice conflicts, and leaves the value alone.
+    let before = patched.clone();
+    let error = diff.apply(&mut patched).unwrap_err();
+    assert!(matches!(error, PatchError::Conflict(_)), "{}", error);
+    assert_eq!(patched, before);
+
+    let mut missing = Value::Dictionary(Dictionary::new());
+    let error = diff.apply(&mut missing).unwrap_err();
+    assert_eq!(error.to_string(), "path `a[1]` does not exist");
+}
+
+#[test]
+fn patch_arrays() {
+    let cases = [
+        ("(a, b, c)", "()"),
+        ("()", "(a, b, c)"),
+        ("(a, (b, c))", "(a, (c), d)"),
+        ("(a, { b = c; })", "({ b = c; }, a)"),
+    ];
+    for &(old, new) in cases.iter() {
+        let (old, new) = (openstep(old), openstep(new));
+        let diff = old.diff(&new);
+        let mut patched = old.clone();
+        diff.apply(&mut patched).unwrap();
+        assert_eq!(patched, new, "{}", diff);
+    }
+}
+
+#[test]
+fn assert_plist_eq() {
+    let xml = include_bytes!("../src/launchd.plist");
+    let value = Value::from_xml(xml).unwrap();
+    embed_plist::assert_plist_eq!(xml, value.to_binary());
+    embed_plist::assert_plist_eq!(&value, xml, "launchd.plist");
+    embed_plist::assert_plist_eq!(
+        String::from_utf8(value.to_xml()).unwrap(),
+        openstep("{ Label = example; ProgramArguments = (Hello, World); Why = \"To use in doc tests\"; }"),
+    );
+
+    let result = std::panic::catch_unwind(|| {
+        embed_plist::assert_plist_eq!(xml, "<dict/>", "{} job", "launchd");
+    });
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(
+        message.starts_with("assertion `left == right` failed: launchd job\n")
+    );
+    assert!(
+        message.contains("\n- Label: String(\"example\")\n"),
+        "{}",
+        message
+    );
+
+    let result = std::panic::catch_unwind(|| {
+        embed_plist::assert_plist_eq!(xml, "<dict>");
+    });
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(
+        message,
+        "`right` is not a valid property list: unexpected end of property list"
+    );
+}
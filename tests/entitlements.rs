@@ -0,0 +1,191 @@
+#![cfg(feature = "const_eval")]
+
+use embed_plist::{const_eval::entitlements_der_into, Error};
+
+#[cfg(any(target_vendor = "apple", feature = "portable"))]
+embed_plist::embed_entitlements!("../src/app.entitlements");
+
+fn der(xml: &[u8]) -> Result<Vec<u8>, Error> {
+    let len = entitlements_der_into(xml, &mut [])?;
+    let mut out = vec![0; len];
+    assert_eq!(entitlements_der_into(xml, &mut out), Ok(len));
+    Ok(out)
+}
+
+// Wraps the contents of a root dictionary in the document header.
+fn document(dict: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x70, dict.len() as u8 + 5, 0x02, 0x01, 0x01];
+    out.push(0xB0);
+    out.push(dict.len() as u8);
+    out.extend_from_slice(dict);
+    out
+}
+
+#[test]
+#[cfg(any(target_vendor = "apple", feature = "portable"))]
+fn embedded() {
+    let xml = include_bytes!("../src/app.entitlements");
+    assert_eq!(embed_plist::get_entitlements(), &xml[..]);
+    assert_eq!(embed_plist::get_entitlements_der(), der(xml).unwrap());
+}
+
+#[test]
+fn values() {
+    assert_eq!(der(b"<dict/>"), Ok(document(&[])));
+    assert_eq!(der(b"<plist><dict></dict></plist>"), Ok(document(&[])));
+
+    assert_eq!(
+        der(b"<dict><key>a</key><string>x&amp;y</string></dict>"),
+        Ok(document(&[
+            0x30, 0x08, 0x0C, 0x01, b'a', 0x0C, 0x03, b'x', b'&', b'y',
+        ]))
+    );
+    assert_eq!(
+        der(
+            b"<dict><key>a</key><array><true/><false/><string/></array></dict>"
+        ),
+        Ok(document(&[
+            0x30, 0x0D, 0x0C, 0x01, b'a', 0x30, 0x08, 0x01, 0x01, 0xFF, 0x01,
+            0x01, 0x00, 0x0C, 0x00,
+        ]))
+    );
+    assert_eq!(
+        der(b"<dict><key>a</key><dict><key>b</key><true/></dict></dict>"),
+        Ok(document(&[
+            0x30, 0x0D, 0x0C, 0x01, b'a', 0xB0, 0x08, 0x30, 0x06, 0x0C, 0x01,
+            b'b', 0x01, 0x01, 0xFF,
+        ]))
+    );
+}
+
+#[test]
+fn integers() {
+    fn integer(text: &str) -> Vec<u8> {
+        let xml =
+            format!("<dict><key>i</key><integer>{}</integer></dict>", text);
+        let der = der(xml.as_bytes()).unwrap();
+        // Skip the header, the entry's sequence, and its key.
+        der[12..].to_vec()
+    }
+
+    assert_eq!(integer("0"), [0x02, 0x01, 0x00]);
+    assert_eq!(integer("127"), [0x02, 0x01, 0x7F]);
+    assert_eq!(integer("128"), [0x02, 0x02, 0x00, 0x80]);
+    assert_eq!(integer("-128"), [0x02, 0x01, 0x80]);
+    assert_eq!(integer("-129"), [0x02, 0x02, 0xFF, 0x7F]);
+    assert_eq!(integer("0x100"), [0x02, 0x02, 0x01, 0x00]);
+    assert_eq!(
+        integer("18446744073709551615"),
+        [0x02, 0x09, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]
+    );
+}
+
+#[test]
+fn sorted_keys() {
+    assert_eq!(
+        der(b"<dict><key>b</key><true/><key>a</key><false/></dict>"),
+        Ok(document(&[
+            0x30, 0x06, 0x0C, 0x01, b'a', 0x01, 0x01, 0x00, 0x30, 0x06, 0x0C,
+            0x01, b'b', 0x01, 0x01, 0xFF,
+        ]))
+    );
+}
+
+#[test]
+fn long_lengths() {
+    let key = "k".repeat(200);
+    let xml = format!("<dict><key>{}</key><true/></dict>", key);
+    let der = der(xml.as_bytes()).unwrap();
+
+    assert_eq!(der[..5], [0x70, 0x81, 0xD7, 0x02, 0x01]);
+    assert_eq!(
+        der[5..15],
+        [0x01, 0xB0, 0x81, 0xD1, 0x30, 0x81, 0xCE, 0x0C, 0x81, 0xC8]
+    );
+    assert_eq!(der.len(), 3 + 0xD7);
+}
+
+#[test]
+fn errors() {
+    assert_eq!(der(b"<array/>"), Err(Error::TypeMismatch));
+    assert_eq!(der(b"<plist><string/></plist>"), Err(Error::TypeMismatch));
+    for value in ["<data>AA==</data>", "<real>1</real>", "<date/>"].iter() {
+        let xml = format!("<dict><key>a</key>{}</dict>", value);
+        assert_eq!(der(xml.as_bytes()), Err(Error::UnsupportedValue));
+    }
+    assert_eq!(
+        der(b"<dict><key>a</key><true/><key>a</key><true/></dict>"),
+        Err(Error::DuplicateKey)
+    );
+    assert_eq!(der(b"<dict/><dict/>"), Err(Error::TrailingData));
+    assert_eq!(der(b"<dict><key>a</key></dict>"), Err(Error::ExpectedValue));
+}
+
+#[cfg(feature = "alloc")]
+mod from_der {
+    use super::{der, document};
+    use embed_plist::{value::Value, Error};
+
+    #[test]
+    fn round_trip() {
+        let xml = include_bytes!("../src/app.entitlements");
+        let value = Value::from_der(&der(xml).unwrap());
+        assert_eq!(value, Value::from_xml(xml));
+
+        let xml = b"<dict><key>n</key><integer>-129</integer>\
+            <key>u</key><integer>18446744073709551615</integer>\
+            <key>a</key><array><dict/><array/></array></dict>";
+        let value = Value::from_der(&der(xml).unwrap()).unwrap();
+        assert!(value.semantic_eq(&Value::from_xml(xml).unwrap()));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Value::from_der(&[]), Err(Error::UnexpectedEnd));
+        assert_eq!(
+            Value::from_der(&[0x70, 0x03, 0x02, 0x01, 0x02]),
+            Err(Error::InvalidValue)
+        );
+
+        let mut trailing = document(&[]);
+        trailing.push(0);
+        assert_eq!(Value::from_der(&trailing), Err(Error::TrailingData));
+
+        // Keys out of order.
+        assert_eq!(
+            Value::from_der(&document(&[
+                0x30, 0x06, 0x0C, 0x01, b'b', 0x01, 0x01, 0x00, 0x30, 0x06,
+                0x0C, 0x01, b'a', 0x01, 0x01, 0xFF,
+            ])),
+            Err(Error::InvalidValue)
+        );
+        assert_eq!(
+            Value::from_der(&document(&[
+                0x30, 0x06, 0x0C, 0x01, b'a', 0x01, 0x01, 0x00, 0x30, 0x06,
+                0x0C, 0x01, b'a', 0x01, 0x01, 0xFF,
+            ])),
+            Err(Error::DuplicateKey)
+        );
+
+        // A redundant leading byte, a non-canonical boolean, and a long-form
+        // length that fits in the short form.
+        assert_eq!(
+            Value::from_der(&document(&[
+                0x30, 0x07, 0x0C, 0x01, b'a', 0x02, 0x02, 0x00, 0x01,
+            ])),
+            Err(Error::InvalidValue)
+        );
+        assert_eq!(
+            Value::from_der(&document(&[
+                0x30, 0x06, 0x0C, 0x01, b'a', 0x01, 0x01, 0x01,
+            ])),
+            Err(Error::InvalidValue)
+        );
+        assert_eq!(
+            Value::from_der(&document(&[
+                0x30, 0x07, 0x0C, 0x81, 0x01, b'a', 0x01, 0x01, 0xFF,
+            ])),
+            Err(Error::InvalidValue)
+        );
+    }
+}
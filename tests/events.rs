@@ -0,0 +1,164 @@
+#![cfg(feature = "const_eval")]
+
+use embed_plist::events::{Event, PlistEvents};
+use embed_plist::Error;
+
+// Renders the events of `bytes` on one line, or the first error.
+fn render(bytes: &[u8]) -> Result<String, Error> {
+    let mut out = String::new();
+    for event in PlistEvents::new(bytes) {
+        let item = match event? {
+            Event::StartArray => "[".to_string(),
+            Event::EndArray => "]".to_string(),
+            Event::StartDict => "{".to_string(),
+            Event::EndDict => "}".to_string(),
+            Event::Key(key) => format!("{}=", key),
+            Event::String(string) => format!("{:?}", string),
+            Event::Boolean(boolean) => boolean.to_string(),
+            Event::Data(data) => format!("data({})", data),
+            Event::Date(date) => format!("date({})", date),
+            Event::Integer(int) => int.to_string(),
+            Event::Real(real) => format!("{:?}", real),
+        };
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(&item);
+    }
+    Ok(out)
+}
+
+#[test]
+fn fixtures() {
+    assert_eq!(
+        render(include_bytes!("../src/launchd.plist")).unwrap(),
+        r#"{ Why= "To use in doc tests" Label= "example" ProgramArguments= [ "Hello" "World" ] }"#
+    );
+}
+
+#[test]
+fn values() {
+    let plist = br#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<!-- Leading comment -->
+<plist version="1.0">
+<dict>
+    <key>a &amp; b</key>
+    <string>&lt;&#x41;&#66;&gt; <!-- skipped --><![CDATA[<raw> &amp;]]></string>
+    <key/>
+    <string/>
+    <key>list</key>
+    <array>
+        <true/>
+        <false></false>
+        <integer>-0x10</integer>
+        <real> 1.5e3 </real>
+        <data>AAEC</data>
+        <date>2020-08-30T12:34:56Z</date>
+        <array/>
+        <dict></dict>
+    </array>
+</dict>
+</plist>
+"#;
+    assert_eq!(
+        render(plist).unwrap(),
+        concat!(
+            r#"{ a & b= "<AB> <raw> &amp;" = "" list= [ true false -16 1500.0 "#,
+            "data(AAEC) date(2020-08-30T12:34:56Z) [ ] { } ] }",
+        )
+    );
+
+    // Roots other than a dictionary, with or without `<plist>`.
+    assert_eq!(
+        render(b"<plist><string>a</string></plist>").unwrap(),
+        r#""a""#
+    );
+    assert_eq!(
+        render(b"<array><integer>1</integer></array>").unwrap(),
+        "[ 1 ]"
+    );
+    assert_eq!(render(b"<plist><dict/></plist>").unwrap(), "{ }");
+}
+
+#[test]
+fn borrowed_text() {
+    let plist =
+        b"<dict><key>plain</key><string><![CDATA[a&b]]></string></dict>";
+    let mut events = PlistEvents::new(plist);
+    assert_eq!(events.next(), Some(Ok(Event::StartDict)));
+    assert_eq!(events.depth(), 1);
+    match events.next() {
+        Some(Ok(Event::Key(key))) => assert_eq!(key.as_str(), Some("plain")),
+        event => panic!("unexpected event {:?}", event),
+    }
+    match events.next() {
+        Some(Ok(Event::String(string))) => {
+            assert_eq!(string.as_str(), Some("a&b"));
+            assert!(string.eq_str("a&b"));
+            assert!(!string.eq_str("a&"));
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+    assert_eq!(events.next(), Some(Ok(Event::EndDict)));
+    assert_eq!(events.depth(), 0);
+    assert_eq!(events.next(), None);
+}
+
+#[test]
+fn errors() {
+    let cases: [(&[u8], Error); 14] = [
+        (b"<dict><string>a</string></dict>", Error::ExpectedKey),
+        (b"<dict><key>a</key></dict>", Error::ExpectedValue),
+        (
+            b"<dict><key>a</key><key>b</key></dict>",
+            Error::ExpectedValue,
+        ),
+        (b"<array></dict>", Error::MismatchedTag),
+        (b"<array><key>a</key></array>", Error::UnexpectedElement),
+        (b"<string>a</integer>", Error::MismatchedTag),
+        (b"<string>&bogus;</string>", Error::UnknownEntity),
+        (b"<string>&#xD800;</string>", Error::InvalidCharRef),
+        (b"<integer>1.5</integer>", Error::InvalidValue),
+        (b"<real>one</real>", Error::InvalidValue),
+        (b"<plist><true/>", Error::UnexpectedEnd),
+        (b"<plist></plist>", Error::ExpectedValue),
+        (b"<true/><true/>", Error::TrailingData),
+        (b"<array>", Error::UnexpectedEnd),
+    ];
+    for (bytes, error) in cases.iter() {
+        assert_eq!(
+            render(bytes),
+            Err(*error),
+            "{}",
+            String::from_utf8_lossy(bytes)
+        );
+    }
+
+    // Iteration stops after an error.
+    let mut events = PlistEvents::new(b"<array><bogus/><true/></array>");
+    assert_eq!(events.next(), Some(Ok(Event::StartArray)));
+    assert_eq!(events.next(), Some(Err(Error::UnexpectedElement)));
+    assert_eq!(events.next(), None);
+}
+
+#[test]
+fn nesting_limit() {
+    let nested = |depth: usize| {
+        format!("{}{}", "<array>".repeat(depth), "</array>".repeat(depth))
+    };
+    assert!(render(nested(128).as_bytes()).is_ok());
+    assert_eq!(render(nested(129).as_bytes()), Err(Error::TooDeep));
+}
+
+#[test]
+fn entities_are_not_expanded() {
+    let plist = br#"<?xml version="1.0"?>
+<!DOCTYPE plist [
+  <!ENTITY lol "lol">
+  <!ENTITY lol2 "&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;">
+  <!ENTITY lol3 "&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;">
+]>
+<plist><string>&lol3;</string></plist>"#;
+    assert_eq!(render(plist), Err(Error::UnknownEntity));
+}
@@ -0,0 +1,69 @@
+#![cfg(all(
+    feature = "const_eval",
+    any(target_vendor = "apple", feature = "portable"),
+))]
+
+use embed_plist::const_eval::sha256;
+
+embed_plist::embed_info_plist!("../src/Info.plist", integrity);
+
+embed_plist::embed_launchd_plist!("../src/launchd.plist", integrity);
+
+fn hex(digest: &[u8; 32]) -> String {
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[test]
+fn embedded_hashes() {
+    assert_eq!(
+        hex(embed_plist::info_plist_sha256()),
+        "aa9f053927692a86353e4ec5328be8b731339434d4270186b3f8426722b5e9cd"
+    );
+    assert_eq!(
+        hex(embed_plist::launchd_plist_sha256()),
+        "d6a836af4316bccd02624604919d4f5b309dc81185376de9c8971ef83fd238b1"
+    );
+
+    assert_eq!(
+        embed_plist::check_info_plist_integrity(),
+        Ok(embed_plist::get_info_plist())
+    );
+    assert_eq!(
+        embed_plist::check_launchd_plist_integrity(),
+        Ok(embed_plist::get_launchd_plist())
+    );
+}
+
+#[test]
+fn sha256_vectors() {
+    // Evaluated at compile time, like the embedded hashes.
+    const EMPTY: [u8; 32] = sha256(b"");
+    const ABC: [u8; 32] = sha256(b"abc");
+
+    assert_eq!(
+        hex(&EMPTY),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        hex(&ABC),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+
+    // The padding takes a second block.
+    assert_eq!(
+        hex(&sha256(
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+        )),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+    assert_eq!(
+        hex(&sha256(&[b'a'; 119])),
+        "31eba51c313a5c08226adf18d4a359cfdfd8d2e816b13f4af952f7ea6584dcfb"
+    );
+
+    let bytes: Vec<u8> = (0..768).map(|i| i as u8).collect();
+    assert_eq!(
+        hex(&sha256(&bytes)),
+        "f3a25aa93aa2fbba28d79260535bbd6a5eb0fc1c24a8b0f04e12b484c1dfe363"
+    );
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "alloc")]
+
+use embed_plist::value::{Date, Value};
+use embed_plist::Error;
+
+#[test]
+fn same_as_xml() {
+    let json = br#"{
+        "Why": "To use in doc tests",
+        "Label": "example",
+        "ProgramArguments": ["Hello", "World"]
+    }"#;
+    let xml = Value::from_xml(include_bytes!("../src/launchd.plist"));
+    assert_eq!(Value::from_json(json), xml);
+}
+
+#[test]
+fn values() {
+    let value = Value::from_json(
+        br#"{
+            "escaped \"key\"": "a\tb\u00e9\ud83d\ude00\/",
+            "int": -42,
+            "big": 18446744073709551615,
+            "real": 1.5e3,
+            "yes": true,
+            "no": false,
+            "list": [1, [], {}],
+            "data": { "$data": "AAEC/w==" },
+            "date": { "$date": "2020-08-30T12:34:56Z" },
+            "not tagged": { "$data": "AAEC", "other": 1 }
+        }"#,
+    )
+    .unwrap();
+
+    let date = Date::from_iso8601("2020-08-30T12:34:56Z").unwrap();
+    let not_tagged: Vec<(&str, Value)> =
+        vec![("$data", "AAEC".into()), ("other", 1.into())];
+    let expected = Value::Dictionary(
+        vec![
+            ("escaped \"key\"", Value::from("a\tbé😀/")),
+            ("int", Value::Integer(-42)),
+            ("big", Value::from(u64::MAX)),
+            ("real", Value::Real(1500.0)),
+            ("yes", Value::Boolean(true)),
+            ("no", Value::Boolean(false)),
+            (
+                "list",
+                Value::Array(vec![
+                    1.into(),
+                    Value::Array(Vec::new()),
+                    Value::Dictionary(Default::default()),
+                ]),
+            ),
+            ("data", Value::Data(vec![0, 1, 2, 0xFF])),
+            ("date", Value::Date(date)),
+            (
+                "not tagged",
+                Value::Dictionary(not_tagged.into_iter().collect()),
+            ),
+        ]
+        .into_iter()
+        .collect(),
+    );
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn errors() {
+    let cases: [(&[u8], Error); 12] = [
+        (b"null", Error::InvalidValue),
+        (b"[1, null]", Error::InvalidValue),
+        (br#"{"a": 1, "a": 2}"#, Error::DuplicateKey),
+        (b"{1: 2}", Error::ExpectedKey),
+        (b"[1 2]", Error::UnexpectedChar),
+        (b"[1,", Error::UnexpectedEnd),
+        (b"{} {}", Error::TrailingData),
+        (b"01", Error::InvalidValue),
+        (b"18446744073709551616", Error::InvalidValue),
+        (br#""\x""#, Error::InvalidCharRef),
+        (br#"{"$data": "!"}"#, Error::InvalidValue),
+        (br#"{"$date": "2020-08-30"}"#, Error::InvalidValue),
+    ];
+    for (bytes, error) in cases.iter() {
+        assert_eq!(
+            Value::from_json(bytes),
+            Err(*error),
+            "{}",
+            String::from_utf8_lossy(bytes),
+        );
+    }
+}
+
+#[test]
+fn deep_nesting() {
+    let nested = |open: &str, close: &str, depth: usize| {
+        format!("{}{}", open.repeat(depth), close.repeat(depth))
+    };
+    assert!(Value::from_json(nested("[", "]", 512).as_bytes()).is_ok());
+
+    let cases = [
+        nested("[", "]", 513),
+        nested("[", "]", 200_000),
+        nested("{\"a\":", "}", 200_000),
+    ];
+    for json in cases.iter() {
+        assert_eq!(Value::from_json(json.as_bytes()), Err(Error::TooDeep));
+    }
+}
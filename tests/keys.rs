@@ -0,0 +1,87 @@
+use embed_plist::keys::{info, launchd, Key, Platforms, ValueType};
+
+fn assert_sorted(keys: &[Key]) {
+    for pair in keys.windows(2) {
+        assert!(
+            pair[0].name() < pair[1].name(),
+            "`{}` is not before `{}`",
+            pair[0],
+            pair[1]
+        );
+    }
+    for key in keys {
+        assert!(!key.types().is_empty(), "`{}` has no types", key);
+        assert!(!key.platforms().is_empty(), "`{}` has no platforms", key);
+        assert!(!key.description().is_empty(), "`{}` is undocumented", key);
+    }
+}
+
+#[test]
+fn tables_are_sorted() {
+    assert_sorted(info::ALL);
+    assert_sorted(launchd::ALL);
+}
+
+#[test]
+fn find() {
+    for key in info::ALL {
+        assert_eq!(info::find(key.name()), Some(key));
+    }
+    for key in launchd::ALL {
+        assert_eq!(launchd::find(key.name()), Some(key));
+    }
+    assert_eq!(info::find("LSUIElement"), Some(&info::LS_UI_ELEMENT));
+    assert_eq!(info::find("lsuielement"), None);
+    assert_eq!(info::find("Label"), None);
+    assert_eq!(launchd::find("Label"), Some(&launchd::LABEL));
+}
+
+#[test]
+fn closest() {
+    assert_eq!(
+        info::closest("CFBundleIdentifer"),
+        Some(&info::CF_BUNDLE_IDENTIFIER)
+    );
+    assert_eq!(info::closest("lsuielement"), Some(&info::LS_UI_ELEMENT));
+    assert_eq!(launchd::closest("RunAtLoda"), Some(&launchd::RUN_AT_LOAD));
+    assert_eq!(info::closest("CFBundleIdentifier"), None);
+    assert_eq!(info::closest("Custom"), None);
+    assert_eq!(info::closest(""), None);
+    assert_eq!(info::closest(&"A".repeat(100)), None);
+}
+
+#[test]
+fn metadata() {
+    let key = launchd::KEEP_ALIVE;
+    assert_eq!(key.types(), [ValueType::Boolean, ValueType::Dictionary]);
+    assert!(key.accepts(ValueType::Dictionary));
+    assert!(!key.accepts(ValueType::String));
+    assert!(!key.is_deprecated());
+
+    assert!(info::CF_BUNDLE_SIGNATURE.is_deprecated());
+    assert_eq!(info::LS_UI_ELEMENT.to_string(), "LSUIElement");
+    assert_eq!(info::LS_UI_ELEMENT.as_ref(), "LSUIElement");
+}
+
+#[test]
+fn platforms() {
+    let platforms = Platforms::MACOS.union(Platforms::IOS);
+    assert!(platforms.contains(Platforms::IOS));
+    assert!(!platforms.contains(Platforms::TVOS));
+    assert!(Platforms::ALL.contains(platforms));
+    assert!(Platforms::NONE.is_empty());
+    assert_eq!(platforms.to_string(), "macOS, iOS");
+    assert_eq!(format!("{:?}", platforms), "{macOS, iOS}");
+    assert_eq!(launchd::LABEL.platforms(), Platforms::MACOS);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn value_type() {
+    use embed_plist::value::Value;
+
+    assert_eq!(ValueType::of(&Value::from(true)), ValueType::Boolean);
+    assert_eq!(ValueType::of(&Value::from("a")), ValueType::String);
+    assert_eq!(ValueType::String.to_string(), "string");
+    assert_eq!(ValueType::Dictionary.to_string(), "dict");
+}
@@ -0,0 +1,542 @@
+#![cfg(feature = "macho")]
+
+use embed_plist::macho::{
+    self, BuildVersion, BuildVersionCheck, Error, HashType, Image, Platform,
+    SignatureCheck, Version,
+};
+
+const PLIST: &[u8] = include_bytes!("../src/Info.plist");
+
+const CPU_TYPE_ARM64: u32 = 0x0100_000c;
+const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+
+// How the synthetic image is signed.
+struct Signature {
+    hash_type: u8,
+    hash_size: u8,
+    special_slots: u32,
+    info_hash: Vec<u8>,
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_name(out: &mut Vec<u8>, name: &str) {
+    let mut bytes = [0; 16];
+    bytes[..name.len()].copy_from_slice(name.as_bytes());
+    out.extend_from_slice(&bytes);
+}
+
+fn super_blob(signature: &Signature) -> Vec<u8> {
+    let slots_len =
+        signature.special_slots as usize * signature.hash_size as usize;
+    let hash_offset = 48 + slots_len as u32;
+
+    let mut directory = Vec::new();
+    for &field in [
+        0xfade_0c02,
+        hash_offset, // length, without code slots
+        0x20400,     // version
+        0,           // flags
+        hash_offset,
+        44, // identOffset
+        signature.special_slots,
+        0, // nCodeSlots
+        0, // codeLimit
+    ]
+    .iter()
+    {
+        directory.extend_from_slice(&u32::to_be_bytes(field));
+    }
+    directory.extend_from_slice(&[
+        signature.hash_size,
+        signature.hash_type,
+        0,
+        12,
+    ]);
+    directory.extend_from_slice(&[0; 4]); // spare2
+    directory.extend_from_slice(b"app\0");
+
+    // Special slots are stored from the highest number down to slot 1.
+    let mut slots = vec![0; slots_len];
+    if signature.special_slots >= 1 {
+        slots[slots_len - signature.info_hash.len()..]
+            .copy_from_slice(&signature.info_hash);
+    }
+    directory.extend_from_slice(&slots);
+
+    let mut blob = Vec::new();
+    for &field in [0xfade_0cc0, 20 + directory.len() as u32, 1, 0, 20].iter() {
+        blob.extend_from_slice(&u32::to_be_bytes(field));
+    }
+    blob.extend_from_slice(&directory);
+    blob
+}
+
+// Builds a 64-bit image with an optional `__TEXT,__info_plist` section and
+// code signature.
+fn image(
+    cpu_type: u32,
+    plist: Option<&[u8]>,
+    signature: Option<&Signature>,
+) -> Vec<u8> {
+    image_with(cpu_type, plist, signature, &[])
+}
+
+// Like `image`, with `commands` placed after the segment.
+fn image_with(
+    cpu_type: u32,
+    plist: Option<&[u8]>,
+    signature: Option<&Signature>,
+    commands: &[&[u8]],
+) -> Vec<u8> {
+    let segment_len = 72 + if plist.is_some() { 80 } else { 0 };
+    let extra_len: usize = commands.iter().map(|command| command.len()).sum();
+    let sizeofcmds = segment_len
+        + extra_len as u32
+        + if signature.is_some() { 16 } else { 0 };
+    let data_offset = 32 + sizeofcmds;
+    let plist = plist.unwrap_or(&[]);
+    let blob = signature.map(super_blob).unwrap_or_default();
+
+    let mut out = Vec::new();
+    for &field in [
+        0xfeed_facf,
+        cpu_type,
+        0,
+        2,
+        1 + commands.len() as u32 + signature.is_some() as u32,
+        sizeofcmds,
+        0,
+        0,
+    ]
+    .iter()
+    {
+        put_u32(&mut out, field);
+    }
+
+    put_u32(&mut out, 0x19);
+    put_u32(&mut out, segment_len);
+    put_name(&mut out, "__TEXT");
+    put_u64(&mut out, 0); // vmaddr
+    put_u64(&mut out, (data_offset as usize + plist.len()) as u64);
+    put_u64(&mut out, 0); // fileoff
+    put_u64(&mut out, (data_offset as usize + plist.len()) as u64);
+    put_u32(&mut out, 5);
+    put_u32(&mut out, 5);
+    put_u32(&mut out, (segment_len - 72) / 80);
+    put_u32(&mut out, 0);
+    if segment_len > 72 {
+        put_name(&mut out, "__info_plist");
+        put_name(&mut out, "__TEXT");
+        put_u64(&mut out, u64::from(data_offset));
+        put_u64(&mut out, plist.len() as u64);
+        put_u32(&mut out, data_offset);
+        for _ in 0..7 {
+            put_u32(&mut out, 0);
+        }
+    }
+
+    for command in commands {
+        out.extend_from_slice(command);
+    }
+
+    if signature.is_some() {
+        put_u32(&mut out, 0x1d);
+        put_u32(&mut out, 16);
+        put_u32(&mut out, data_offset + plist.len() as u32);
+        put_u32(&mut out, blob.len() as u32);
+    }
+
+    assert_eq!(out.len(), data_offset as usize);
+    out.extend_from_slice(plist);
+    out.extend_from_slice(&blob);
+    out
+}
+
+fn sha256_signature(plist: &[u8]) -> Signature {
+    Signature {
+        hash_type: 2,
+        hash_size: 32,
+        special_slots: 2,
+        info_hash: HashType::Sha256.hash(plist).as_bytes().to_vec(),
+    }
+}
+
+fn check(bytes: &[u8]) -> Result<SignatureCheck, Error> {
+    Image::parse(bytes)?.check_info_plist_signature()
+}
+
+#[test]
+fn sections() {
+    let bytes = image(CPU_TYPE_ARM64, Some(PLIST), None);
+    let image = Image::parse(&bytes).unwrap();
+    assert!(image.is_64());
+    assert_eq!(image.cpu_type(), CPU_TYPE_ARM64);
+    assert_eq!(image.file_type(), 2);
+    assert_eq!(image.info_plist(), Ok(Some(PLIST)));
+    assert_eq!(image.launchd_plist(), Ok(None));
+    assert_eq!(image.section("__DATA", "__info_plist"), Ok(None));
+    assert_eq!(image.load_commands().count(), 1);
+}
+
+#[test]
+fn signature_checks() {
+    let signature = sha256_signature(PLIST);
+    let signed = image(CPU_TYPE_ARM64, Some(PLIST), Some(&signature));
+    assert_eq!(check(&signed), Ok(SignatureCheck::Match));
+
+    // Patch the plist in place after signing.
+    let mut patched = signed.clone();
+    let offset = 32 + 152 + 16 + 100;
+    patched[offset] ^= 0x20;
+    match check(&patched) {
+        Ok(SignatureCheck::Mismatch {
+            hash_type,
+            expected,
+            actual,
+        }) => {
+            assert_eq!(hash_type, HashType::Sha256);
+            assert_eq!(expected.as_bytes(), &signature.info_hash[..]);
+            assert_eq!(
+                actual,
+                HashType::Sha256.hash(&patched[200..200 + PLIST.len()])
+            );
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    let unsigned = image(CPU_TYPE_ARM64, Some(PLIST), None);
+    assert_eq!(check(&unsigned), Ok(SignatureCheck::Unsigned));
+
+    let unbound = Signature {
+        special_slots: 0,
+        ..sha256_signature(PLIST)
+    };
+    let unbound = image(CPU_TYPE_ARM64, Some(PLIST), Some(&unbound));
+    assert_eq!(check(&unbound), Ok(SignatureCheck::Unbound));
+
+    let zeroed = Signature {
+        info_hash: vec![0; 32],
+        ..sha256_signature(PLIST)
+    };
+    let zeroed = image(CPU_TYPE_ARM64, Some(PLIST), Some(&zeroed));
+    assert_eq!(check(&zeroed), Ok(SignatureCheck::Unbound));
+
+    let bundled = image(CPU_TYPE_ARM64, None, Some(&signature));
+    assert_eq!(check(&bundled), Ok(SignatureCheck::NotEmbedded));
+
+    let sha1 = Signature {
+        hash_type: 1,
+        hash_size: 20,
+        special_slots: 5,
+        info_hash: HashType::Sha1.hash(PLIST).as_bytes().to_vec(),
+    };
+    let sha1 = image(CPU_TYPE_X86_64, Some(PLIST), Some(&sha1));
+    assert_eq!(check(&sha1), Ok(SignatureCheck::Match));
+}
+
+#[test]
+fn universal() {
+    let signature = sha256_signature(PLIST);
+    let thin = [
+        image(CPU_TYPE_X86_64, Some(PLIST), Some(&signature)),
+        image(CPU_TYPE_ARM64, Some(PLIST), None),
+    ];
+
+    let mut fat = Vec::new();
+    let mut offset = 0x1000;
+    for &field in [0xcafe_babe, thin.len() as u32].iter() {
+        fat.extend_from_slice(&u32::to_be_bytes(field));
+    }
+    for (image, &cpu_type) in
+        thin.iter().zip([CPU_TYPE_X86_64, CPU_TYPE_ARM64].iter())
+    {
+        for &field in [cpu_type, 3, offset, image.len() as u32, 12].iter() {
+            fat.extend_from_slice(&u32::to_be_bytes(field));
+        }
+        offset += 0x1000 * (image.len() as u32).div_ceil(0x1000);
+    }
+    for image in thin.iter() {
+        fat.resize(fat.len().div_ceil(0x1000) * 0x1000, 0);
+        fat.extend_from_slice(image);
+    }
+
+    let images: Vec<Image> = macho::images(&fat)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(images.len(), 2);
+    assert_eq!(images[0].cpu_type(), CPU_TYPE_X86_64);
+    assert_eq!(images[1].cpu_type(), CPU_TYPE_ARM64);
+    assert_eq!(
+        images[0].check_info_plist_signature(),
+        Ok(SignatureCheck::Match)
+    );
+    assert_eq!(
+        images[1].check_info_plist_signature(),
+        Ok(SignatureCheck::Unsigned)
+    );
+
+    // A thin image yields only itself.
+    assert_eq!(macho::images(&thin[1]).unwrap().count(), 1);
+}
+
+#[test]
+fn errors() {
+    assert_eq!(Image::parse(b"\x7fELF").unwrap_err(), Error::InvalidMagic);
+    assert_eq!(macho::images(b"").unwrap_err(), Error::InvalidMagic);
+    assert_eq!(
+        macho::images(b"\xca\xfe\xba\xbe\0\0\0\x02").unwrap_err(),
+        Error::OutOfBounds
+    );
+
+    let signed =
+        image(CPU_TYPE_ARM64, Some(PLIST), Some(&sha256_signature(PLIST)));
+    assert_eq!(
+        Image::parse(&signed[..100]).unwrap_err(),
+        Error::OutOfBounds
+    );
+
+    // `cmdsize` of the segment is less than 8.
+    let mut bad_command = signed.clone();
+    bad_command[36..40].copy_from_slice(&4u32.to_le_bytes());
+    assert_eq!(check(&bad_command), Err(Error::InvalidLoadCommand));
+
+    // The `SuperBlob` magic number.
+    let blob = 32 + 152 + 16 + PLIST.len();
+    let mut bad_magic = signed.clone();
+    bad_magic[blob] = 0;
+    assert_eq!(check(&bad_magic), Err(Error::InvalidSignature));
+
+    // The `hashType` of the code directory.
+    let mut bad_hash = signed;
+    bad_hash[blob + 20 + 37] = 9;
+    assert_eq!(check(&bad_hash), Err(Error::UnsupportedHashType));
+}
+
+#[test]
+fn hash_vectors() {
+    fn hex(hash_type: HashType, bytes: &[u8]) -> String {
+        hash_type.hash(bytes).to_string()
+    }
+
+    let long: Vec<u8> = (0..768).map(|i| i as u8).collect();
+    let cases: [(&[u8], &str, &str); 5] = [
+        (
+            b"",
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709",
+            "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b",
+        ),
+        (
+            b"abc",
+            "a9993e364706816aba3e25717850c26c9cd0d89d",
+            "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7",
+        ),
+        (
+            &[b'a'; 111],
+            "ac877859d427d9192054eea8feb3b8a403ef83a5",
+            "3c37955051cb5c3026f94d551d5b5e2ac38d572ae4e07172085fed81f8466b8f90dc23a8ffcdea0b8d8e58e8fdacc80a",
+        ),
+        (
+            &[b'a'; 112],
+            "689993727ba37386bb032495e9dbdfb4dd1ba744",
+            "187d4e07cb306103c69967bf544d0dfbe9042577599c73c330abc0cb64c61236d5ed565ee19119d8c31779a38f791fcd",
+        ),
+        (
+            &long,
+            "ac2a264c8ec1f4232a40854e8239bc3a697ab1d2",
+            "6bee04118003cf5630ccb22cdb53f78dc079231c7d09209955dfc34234cbab03d41da198e30bf1e77ef8b289ddd794ec",
+        ),
+    ];
+    for &(bytes, sha1, sha384) in cases.iter() {
+        assert_eq!(hex(HashType::Sha1, bytes), sha1);
+        assert_eq!(hex(HashType::Sha384, bytes), sha384);
+    }
+
+    assert_eq!(
+        hex(HashType::Sha256Truncated, b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a3"
+    );
+}
+
+// `LC_BUILD_VERSION` with no tools.
+fn build_version(platform: u32, minimum: u32, sdk: u32) -> Vec<u8> {
+    let mut out = Vec::new();
+    for &field in [0x32, 24, platform, minimum, sdk, 0].iter() {
+        put_u32(&mut out, field);
+    }
+    out
+}
+
+fn version_min(cmd: u32, minimum: u32) -> Vec<u8> {
+    let mut out = Vec::new();
+    for &field in [cmd, 16, minimum, minimum].iter() {
+        put_u32(&mut out, field);
+    }
+    out
+}
+
+fn plist(entries: &str) -> Vec<u8> {
+    format!("<plist><dict>{}</dict></plist>", entries).into_bytes()
+}
+
+fn check_build(plist: Option<&[u8]>, command: &[u8]) -> BuildVersionCheck {
+    let bytes = image_with(CPU_TYPE_ARM64, plist, None, &[command]);
+    Image::parse(&bytes)
+        .unwrap()
+        .check_info_plist_build_version()
+        .unwrap()
+}
+
+#[test]
+fn build_versions() {
+    let macos_11 = BuildVersion {
+        platform: Platform::MacOs,
+        minimum: Version::parse("11.0").unwrap(),
+        sdk: Version::parse("14.2").unwrap(),
+    };
+    let command = build_version(1, 0x000b_0000, 0x000e_0200);
+
+    let bytes = image_with(CPU_TYPE_ARM64, None, None, &[&command]);
+    assert_eq!(
+        Image::parse(&bytes).unwrap().build_version(),
+        Ok(Some(macos_11))
+    );
+
+    let matching = plist(
+        "<key>CFBundleSupportedPlatforms</key><array><string>MacOSX</string></array>\
+         <key>LSMinimumSystemVersion</key><string>11.0.0</string>",
+    );
+    assert_eq!(
+        check_build(Some(&matching), &command),
+        BuildVersionCheck::Match(macos_11)
+    );
+
+    // The plist claims an older system than the binary requires.
+    let older =
+        plist("<key>LSMinimumSystemVersion</key><string>10.13</string>");
+    match check_build(Some(&older), &command) {
+        BuildVersionCheck::VersionMismatch { build, key, plist } => {
+            assert_eq!(build, macos_11);
+            assert_eq!(key, "LSMinimumSystemVersion");
+            assert_eq!(plist.to_string(), "10.13");
+            assert!(plist < build.minimum);
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    let ios = plist(
+        "<key>CFBundleSupportedPlatforms</key><array><string>iPhoneOS</string></array>\
+         <key>MinimumOSVersion</key><string>11.0</string>",
+    );
+    assert_eq!(
+        check_build(Some(&ios), &command),
+        BuildVersionCheck::PlatformMismatch {
+            build: macos_11,
+            plist: Platform::IOs,
+        }
+    );
+
+    let missing = plist("<key>MinimumOSVersion</key><string>11.0</string>");
+    assert_eq!(
+        check_build(Some(&missing), &command),
+        BuildVersionCheck::MissingMinimumVersion {
+            build: macos_11,
+            key: "LSMinimumSystemVersion",
+        }
+    );
+
+    assert_eq!(check_build(None, &command), BuildVersionCheck::NotEmbedded);
+    let bytes = image(CPU_TYPE_ARM64, Some(&matching), None);
+    assert_eq!(
+        Image::parse(&bytes)
+            .unwrap()
+            .check_info_plist_build_version(),
+        Ok(BuildVersionCheck::NoBuildVersion)
+    );
+
+    let invalid =
+        plist("<key>LSMinimumSystemVersion</key><string>eleven</string>");
+    let bytes = image_with(CPU_TYPE_ARM64, Some(&invalid), None, &[&command]);
+    assert_eq!(
+        Image::parse(&bytes)
+            .unwrap()
+            .check_info_plist_build_version(),
+        Err(Error::InvalidInfoPlist)
+    );
+}
+
+#[test]
+fn version_min_commands() {
+    // `LC_VERSION_MIN_IPHONEOS` for iOS 12.1.
+    let command = version_min(0x25, 0x000c_0100);
+    let ios = plist("<key>MinimumOSVersion</key><string>12.1</string>");
+    match check_build(Some(&ios), &command) {
+        BuildVersionCheck::Match(build) => {
+            assert_eq!(build.platform, Platform::IOs);
+            assert_eq!(build.minimum.to_string(), "12.1");
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    // Mac Catalyst binaries hold an iOS version, so only the platform is
+    // checked.
+    let catalyst = build_version(6, 0x000d_0100, 0x0011_0000);
+    let mac = plist(
+        "<key>CFBundleSupportedPlatforms</key><array><string>MacOSX</string></array>\
+         <key>LSMinimumSystemVersion</key><string>10.15</string>",
+    );
+    match check_build(Some(&mac), &catalyst) {
+        BuildVersionCheck::Match(build) => {
+            assert_eq!(build.platform, Platform::MacCatalyst)
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn versions() {
+    assert_eq!(
+        Version::parse("10.13.4"),
+        Some(Version {
+            major: 10,
+            minor: 13,
+            patch: 4
+        })
+    );
+    assert_eq!(Version::parse("11"), Version::parse("11.0.0"));
+    assert_eq!(
+        Version::from_raw(0x000a_0d04),
+        Version::parse("10.13.4").unwrap()
+    );
+    assert_eq!(Version::parse("1.2.3.4"), None);
+    assert_eq!(Version::parse("1.x"), None);
+    assert_eq!(Version::parse("10.13.4").unwrap().to_string(), "10.13.4");
+}
+
+#[cfg(target_os = "macos")]
+mod current_exe {
+    embed_plist::embed_info_plist!("../src/Info.plist");
+
+    #[test]
+    fn embedded_section() {
+        let bytes = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        for image in embed_plist::macho::images(&bytes).unwrap() {
+            let image = image.unwrap();
+            assert_eq!(image.info_plist(), Ok(Some(super::PLIST)));
+            assert_ne!(
+                image.check_info_plist_signature().map(|check| match check {
+                    super::SignatureCheck::Mismatch { .. } => false,
+                    _ => true,
+                }),
+                Ok(false)
+            );
+        }
+    }
+}
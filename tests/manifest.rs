@@ -0,0 +1,19 @@
+#![cfg(any(target_os = "macos", feature = "portable"))]
+
+embed_plist::embed_info_plist_from_manifest!("src/Info.plist");
+
+embed_plist::embed_launchd_plist_from_manifest!("src/launchd.plist");
+
+#[test]
+fn info_plist_contents() {
+    let embedded = embed_plist::get_info_plist();
+    let included = include_bytes!("../src/Info.plist");
+    assert_eq!(embedded, &included[..]);
+}
+
+#[test]
+fn launchd_plist_contents() {
+    let embedded = embed_plist::get_launchd_plist();
+    let included = include_bytes!("../src/launchd.plist");
+    assert_eq!(embedded, &included[..]);
+}
@@ -0,0 +1,198 @@
+#![cfg(all(feature = "std", feature = "macho"))]
+
+use embed_plist::{
+    build::{self, Arch, ObjectTarget, Platform},
+    const_eval::sha256,
+    macho::{self, Image},
+};
+use std::{convert::TryInto, fs, path::PathBuf};
+
+const PLIST: &str = include_str!("../src/Info.plist");
+
+fn object(name: &str, plist: &str, target: ObjectTarget) -> Vec<u8> {
+    let dir = std::env::temp_dir().join("embed_plist_tests");
+    fs::create_dir_all(&dir).unwrap();
+
+    let input = dir.join(format!("{}.plist", name));
+    let output = dir.join(format!("{}.o", name));
+    fs::write(&input, plist).unwrap();
+
+    if name.starts_with("launchd") {
+        build::launchd_plist_object(&input, &output, target).unwrap();
+    } else {
+        build::info_plist_object(&input, &output, target).unwrap();
+    }
+    fs::read(&output).unwrap()
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+// Returns each symbol's name, type, section number, and value.
+fn symbols(image: &Image) -> Vec<(String, u8, u8, u64)> {
+    let symtab = image
+        .load_commands()
+        .map(Result::unwrap)
+        .find(|command| command.cmd() == 0x2)
+        .unwrap();
+    let data = symtab.data();
+    let (symoff, nsyms) = (u32_at(data, 8) as usize, u32_at(data, 12));
+    let stroff = u32_at(data, 16) as usize;
+
+    let bytes = image.bytes();
+    (0..nsyms as usize)
+        .map(|index| {
+            let entry = &bytes[symoff + 16 * index..][..16];
+            let name = &bytes[stroff + u32_at(entry, 0) as usize..];
+            let name = &name[..name.iter().position(|&b| b == 0).unwrap()];
+            (
+                String::from_utf8(name.to_vec()).unwrap(),
+                entry[4],
+                entry[5],
+                u64_at(entry, 8),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn info_plist_object() {
+    let target = ObjectTarget {
+        arch: Arch::Arm64,
+        platform: Platform::MacOs,
+        minimum_os: (11, 0, 0),
+    };
+    let bytes = object("info_object", PLIST, target);
+    let image = Image::parse(&bytes).unwrap();
+
+    assert!(image.is_64());
+    assert_eq!(image.cpu_type(), 0x0100_000c);
+    assert_eq!(image.file_type(), 1);
+    assert_eq!(image.info_plist(), Ok(Some(PLIST.as_bytes())));
+    assert_eq!(image.launchd_plist(), Ok(None));
+
+    let build = image.build_version().unwrap().unwrap();
+    assert_eq!(build.platform, macho::Platform::MacOs);
+    assert_eq!(build.minimum.to_string(), "11.0");
+
+    // The section must survive `-dead_strip`.
+    let segment = image
+        .load_commands()
+        .map(Result::unwrap)
+        .find(|command| command.cmd() == 0x19)
+        .unwrap();
+    assert_eq!(u32_at(segment.data(), 72 + 64), 0x1000_0000);
+
+    let len = PLIST.len() as u64;
+    let consts = image.section("__TEXT", "__const").unwrap().unwrap();
+    assert_eq!(u64_at(consts, 0), len);
+    assert_eq!(consts[8..], sha256(PLIST.as_bytes()));
+
+    let const_addr = (len + 7) & !7;
+    assert_eq!(
+        symbols(&image),
+        [
+            ("__EMBED_INFO_PLIST".to_string(), 0x0f, 1, 0),
+            ("__EMBED_INFO_PLIST_LEN".to_string(), 0x0f, 2, const_addr),
+            (
+                "__EMBED_INFO_PLIST_SHA256".to_string(),
+                0x0f,
+                2,
+                const_addr + 8
+            ),
+        ]
+    );
+}
+
+#[test]
+fn launchd_plist_object() {
+    let plist = include_str!("../src/launchd.plist");
+    let target = ObjectTarget {
+        arch: Arch::X86_64,
+        platform: Platform::IOsSimulator,
+        minimum_os: (14, 5, 1),
+    };
+    let bytes = object("launchd_object", plist, target);
+    let image = Image::parse(&bytes).unwrap();
+
+    assert_eq!(image.cpu_type(), 0x0100_0007);
+    assert_eq!(image.launchd_plist(), Ok(Some(plist.as_bytes())));
+    assert_eq!(image.info_plist(), Ok(None));
+
+    let build = image.build_version().unwrap().unwrap();
+    assert_eq!(build.platform, macho::Platform::IOsSimulator);
+    assert_eq!(build.minimum.to_string(), "14.5.1");
+
+    let names: Vec<String> =
+        symbols(&image).into_iter().map(|symbol| symbol.0).collect();
+    assert_eq!(
+        names,
+        [
+            "__EMBED_LAUNCHD_PLIST",
+            "__EMBED_LAUNCHD_PLIST_LEN",
+            "__EMBED_LAUNCHD_PLIST_SHA256",
+        ]
+    );
+}
+
+#[test]
+fn missing_input() {
+    let target = ObjectTarget {
+        arch: Arch::Arm64,
+        platform: Platform::IOs,
+        minimum_os: (17, 0, 0),
+    };
+    let missing: PathBuf = [env!("CARGO_MANIFEST_DIR"), "missing.plist"]
+        .iter()
+        .collect();
+    let output = std::env::temp_dir().join("embed_plist_missing.o");
+    assert!(build::info_plist_object(missing, output, target).is_err());
+}
+
+#[test]
+fn target_from_env() {
+    use std::env;
+
+    let set = |arch: &str, os: &str, abi: &str| {
+        env::set_var("CARGO_CFG_TARGET_ARCH", arch);
+        env::set_var("CARGO_CFG_TARGET_OS", os);
+        env::set_var("CARGO_CFG_TARGET_ABI", abi);
+        ObjectTarget::from_env()
+    };
+
+    env::remove_var("MACOSX_DEPLOYMENT_TARGET");
+    assert_eq!(
+        set("aarch64", "macos", "").unwrap(),
+        ObjectTarget {
+            arch: Arch::Arm64,
+            platform: Platform::MacOs,
+            minimum_os: (11, 0, 0),
+        }
+    );
+    env::set_var("MACOSX_DEPLOYMENT_TARGET", "10.15");
+    assert_eq!(set("x86_64", "macos", "").unwrap().minimum_os, (10, 15, 0));
+    env::set_var("MACOSX_DEPLOYMENT_TARGET", "latest");
+    assert!(set("x86_64", "macos", "").is_err());
+
+    assert_eq!(
+        set("aarch64", "ios", "sim").unwrap().platform,
+        Platform::IOsSimulator
+    );
+    assert_eq!(
+        set("x86_64", "ios", "").unwrap().platform,
+        Platform::IOsSimulator
+    );
+    assert_eq!(
+        set("aarch64", "ios", "macabi").unwrap().platform,
+        Platform::MacCatalyst
+    );
+    assert_eq!(set("aarch64", "ios", "").unwrap().platform, Platform::IOs);
+
+    assert!(set("aarch64", "linux", "").is_err());
+    assert!(set("arm", "ios", "").is_err());
+}
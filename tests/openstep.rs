@@ -0,0 +1,133 @@
+#![cfg(feature = "const_eval")]
+
+#[cfg(any(target_os = "macos", feature = "portable", feature = "alloc"))]
+const LEGACY: &[u8] = br#"
+// A launchd job from before XML property lists.
+{
+    Why = "To use in doc tests";
+    Label = example;
+    /* Run with arguments. */
+    ProgramArguments = (Hello, "World");
+}
+"#;
+
+#[cfg(any(target_os = "macos", feature = "portable"))]
+embed_plist::embed_launchd_plist_bytes!(LEGACY, openstep);
+
+#[test]
+#[cfg(any(target_os = "macos", feature = "portable"))]
+fn launchd_plist_converted() {
+    let embedded = embed_plist::get_launchd_plist();
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><plist version="1.0">"#,
+        "<dict>",
+        "<key>Why</key><string>To use in doc tests</string>",
+        "<key>Label</key><string>example</string>",
+        "<key>ProgramArguments</key>",
+        "<array><string>Hello</string><string>World</string></array>",
+        "</dict>",
+        "</plist>",
+    );
+    assert_eq!(std::str::from_utf8(embedded).unwrap(), expected);
+}
+
+#[cfg(feature = "alloc")]
+mod value {
+    use super::LEGACY;
+    use embed_plist::value::{Date, Value};
+    use embed_plist::Error;
+
+    #[test]
+    fn same_as_xml() {
+        let xml = Value::from_xml(include_bytes!("../src/launchd.plist"));
+        assert_eq!(Value::from_openstep(LEGACY), xml);
+    }
+
+    #[test]
+    fn values() {
+        let value = Value::from_openstep(
+            br#"{
+                "quoted \"key\"" = 'a\tb\U00e9\UD83D\UDE00\101 & <c>';
+                data = <0001 02ff 7F>;
+                empty = { };
+                list = (1, (), {}, <>,);
+                int = <*I-42>;
+                real = <*R1.5e3>;
+                yes = <*BY>;
+                no = <*BN>;
+                date = <*D2020-08-30 12:34:56 +0000>;
+            }"#,
+        )
+        .unwrap();
+
+        let date = Date::from_iso8601("2020-08-30T12:34:56Z").unwrap();
+        let expected = Value::Dictionary(
+            vec![
+                ("quoted \"key\"", Value::from("a\tbé😀A & <c>")),
+                ("data", Value::Data(vec![0, 1, 2, 0xFF, 0x7F])),
+                ("empty", Value::Dictionary(Default::default())),
+                (
+                    "list",
+                    Value::Array(vec![
+                        "1".into(),
+                        Value::Array(Vec::new()),
+                        Value::Dictionary(Default::default()),
+                        Value::Data(Vec::new()),
+                    ]),
+                ),
+                ("int", Value::Integer(-42)),
+                ("real", Value::Real(1500.0)),
+                ("yes", Value::Boolean(true)),
+                ("no", Value::Boolean(false)),
+                ("date", Value::Date(date)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn errors() {
+        let cases: [(&[u8], Error); 10] = [
+            (b"{ a = b; a = c; }", Error::DuplicateKey),
+            (b"{ a = b; \"a\" = c; }", Error::DuplicateKey),
+            (b"{ a = b }", Error::UnexpectedChar),
+            (b"{ a b; }", Error::UnexpectedChar),
+            (b"{ (a) = b; }", Error::ExpectedKey),
+            (b"(a b)", Error::UnexpectedChar),
+            (b"\"unterminated", Error::UnexpectedEnd),
+            (b"a b", Error::TrailingData),
+            (b"<0g>", Error::InvalidValue),
+            (b"\"\\a\"", Error::InvalidCharRef),
+        ];
+        for (bytes, error) in cases.iter() {
+            assert_eq!(
+                Value::from_openstep(bytes),
+                Err(*error),
+                "{}",
+                String::from_utf8_lossy(bytes),
+            );
+        }
+    }
+
+    #[test]
+    fn deep_nesting() {
+        let nested = |open: &str, close: &str, depth: usize| {
+            format!("{}{}", open.repeat(depth), close.repeat(depth))
+        };
+        assert!(Value::from_openstep(nested("(", ")", 512).as_bytes()).is_ok());
+
+        let cases = [
+            nested("(", ")", 513),
+            nested("(", ")", 200_000),
+            nested("{a=", ";}", 200_000),
+        ];
+        for bytes in cases.iter() {
+            assert_eq!(
+                Value::from_openstep(bytes.as_bytes()),
+                Err(Error::TooDeep)
+            );
+        }
+    }
+}
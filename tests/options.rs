@@ -0,0 +1,33 @@
+#![cfg(all(
+    feature = "const_eval",
+    any(target_vendor = "apple", feature = "portable"),
+))]
+
+embed_plist::embed_info_plist_bytes!(
+    b"\xEF\xBB\xBF<plist>\r\n<dict/>\r</plist>\r\n",
+    normalize,
+);
+
+embed_plist::embed_launchd_plist!("../src/launchd.plist", canonicalize);
+
+#[test]
+fn info_plist_normalized() {
+    let embedded = embed_plist::get_info_plist();
+    assert_eq!(embedded, &b"<plist>\n<dict/>\n</plist>\n"[..]);
+}
+
+#[test]
+fn launchd_plist_canonicalized() {
+    let embedded = embed_plist::get_launchd_plist();
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><plist version="1.0">"#,
+        "<dict>",
+        "<key>Label</key><string>example</string>",
+        "<key>ProgramArguments</key>",
+        "<array><string>Hello</string><string>World</string></array>",
+        "<key>Why</key><string>To use in doc tests</string>",
+        "</dict>",
+        "</plist>",
+    );
+    assert_eq!(std::str::from_utf8(embedded).unwrap(), expected);
+}
@@ -0,0 +1,31 @@
+#![cfg(all(feature = "portable", not(target_vendor = "apple")))]
+
+embed_plist::embed_info_plist!("../src/Info.plist", verify);
+
+embed_plist::embed_launchd_plist!("../src/launchd.plist", verify);
+
+#[test]
+fn info_plist_contents() {
+    let embedded = embed_plist::get_info_plist();
+    let included = include_bytes!("../src/Info.plist");
+    assert_eq!(embedded, &included[..]);
+}
+
+#[test]
+fn launchd_plist_contents() {
+    let embedded = embed_plist::get_launchd_plist();
+    let included = include_bytes!("../src/launchd.plist");
+    assert_eq!(embedded, &included[..]);
+}
+
+#[test]
+fn verify_contents() {
+    assert_eq!(
+        embed_plist::verify_info_plist(),
+        Ok(embed_plist::get_info_plist())
+    );
+    assert_eq!(
+        embed_plist::verify_launchd_plist(),
+        Ok(embed_plist::get_launchd_plist())
+    );
+}
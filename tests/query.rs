@@ -0,0 +1,135 @@
+#![cfg(feature = "const_eval")]
+
+use embed_plist::{query, Error, Node, NodeKind};
+
+const PLIST: &[u8] = br#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <!-- URL schemes -->
+    <key>CFBundleURLTypes</key>
+    <array>
+        <dict>
+            <key>CFBundleURLName</key>
+            <string>com.example</string>
+            <key>CFBundleURLSchemes</key>
+            <array>
+                <string>example</string>
+                <string><![CDATA[example-<dev>]]></string>
+            </array>
+        </dict>
+        <dict/>
+    </array>
+    <key>Escaped &amp; key</key>
+    <string>a &amp; b</string>
+    <key>Build</key>
+    <integer>0x10</integer>
+    <key>Scale</key>
+    <real> 0.5 </real>
+    <key>LSUIElement</key>
+    <false/>
+    <key>Data</key>
+    <data>AAEC</data>
+    <key>Empty</key>
+    <array></array>
+</dict>
+</plist>
+"#;
+
+#[test]
+fn paths() {
+    let scheme = query(PLIST, "CFBundleURLTypes[0].CFBundleURLSchemes[1]");
+    assert_eq!(scheme.and_then(|node| node.as_str()), Ok("example-<dev>"));
+
+    let types = query(PLIST, "CFBundleURLTypes").unwrap();
+    assert_eq!(types.kind(), NodeKind::Array);
+    assert_eq!(types.len(), Ok(2));
+    assert_eq!(types.query("[1]").and_then(|n| n.is_empty()), Ok(true));
+    let name = types.index(0).and_then(|n| n.get("CFBundleURLName"));
+    assert_eq!(name.and_then(|n| n.as_str()), Ok("com.example"));
+
+    let root = Node::root(PLIST).unwrap();
+    assert_eq!(root.kind(), NodeKind::Dictionary);
+    assert_eq!(root.len(), Ok(7));
+    assert_eq!(query(PLIST, "").unwrap().kind(), NodeKind::Dictionary);
+
+    let key = root.get("Escaped & key").unwrap();
+    assert_eq!(key.as_str(), Err(Error::UnsupportedString));
+    let mut buf = [0; 5];
+    assert_eq!(key.read_str(&mut buf), Ok("a & b"));
+    assert_eq!(key.read_str(&mut [0; 4]), Err(Error::BufferTooSmall));
+    assert_eq!(query(PLIST, "Build").unwrap().as_integer(), Ok(16));
+    assert_eq!(query(PLIST, "Scale").unwrap().as_real(), Ok(0.5));
+    assert_eq!(query(PLIST, "LSUIElement").unwrap().as_bool(), Ok(false));
+    assert_eq!(query(PLIST, "Data").unwrap().kind(), NodeKind::Data);
+    assert_eq!(query(PLIST, "Empty").unwrap().is_empty(), Ok(true));
+
+    let array = b"<plist><array><integer>1</integer><array><true/></array></array></plist>";
+    assert_eq!(query(array, "[1][0]").unwrap().as_bool(), Ok(true));
+}
+
+#[test]
+fn errors() {
+    let cases: [(&str, Error); 12] = [
+        ("Missing", Error::MissingKey),
+        ("CFBundleURLTypes[0].Missing", Error::MissingKey),
+        ("CFBundleURLTypes[2]", Error::IndexOutOfRange),
+        (
+            "CFBundleURLTypes[99999999999999999999999]",
+            Error::IndexOutOfRange,
+        ),
+        ("Empty[0]", Error::IndexOutOfRange),
+        ("Build.Key", Error::TypeMismatch),
+        ("[0]", Error::TypeMismatch),
+        ("Build[0]", Error::TypeMismatch),
+        ("CFBundleURLTypes..x", Error::InvalidPath),
+        ("CFBundleURLTypes[x]", Error::InvalidPath),
+        ("CFBundleURLTypes[0]CFBundleURLName", Error::InvalidPath),
+        ("Build.", Error::InvalidPath),
+    ];
+    for (path, error) in cases.iter() {
+        assert_eq!(query(PLIST, path).map(drop), Err(*error), "{}", path);
+    }
+
+    let build = query(PLIST, "Build").unwrap();
+    assert_eq!(build.as_str(), Err(Error::TypeMismatch));
+    assert_eq!(build.read_str(&mut [0; 8]), Err(Error::TypeMismatch));
+    assert_eq!(build.as_bool(), Err(Error::TypeMismatch));
+    assert_eq!(build.len(), Err(Error::TypeMismatch));
+
+    let invalid = b"<plist><dict><key>a</key><bogus/></dict></plist>";
+    assert_eq!(query(invalid, "a").map(drop), Err(Error::UnexpectedElement));
+    let truncated = b"<plist><array><string>a</string>";
+    assert_eq!(query(truncated, "[1]").map(drop), Err(Error::UnexpectedEnd));
+
+    let malformed = b"<plist><array><true>junk</true><integer>1 2</integer></array></plist>";
+    assert_eq!(
+        query(malformed, "[0]").unwrap().as_bool(),
+        Err(Error::InvalidValue)
+    );
+    assert_eq!(
+        query(malformed, "[1]").unwrap().as_integer(),
+        Err(Error::InvalidValue)
+    );
+}
+
+#[test]
+fn read_str() {
+    let plist = "<plist><array>\
+        <string>caf&#233; <!-- note --><![CDATA[<&>]]></string>\
+        <string/>\
+        <string>a\r\nb</string>\
+        <string>&bogus;</string>\
+    </array></plist>";
+    let node = |index: usize| query(plist.as_bytes(), &format!("[{}]", index));
+
+    let mut buf = [0; 16];
+    let decoded = node(0).and_then(|n| n.read_str(&mut buf));
+    assert_eq!(decoded, Ok("café <&>"));
+    assert_eq!(node(1).and_then(|n| n.read_str(&mut [])), Ok(""));
+    assert_eq!(node(2).and_then(|n| n.read_str(&mut buf)), Ok("a\nb"));
+    assert_eq!(
+        node(3).and_then(|n| n.read_str(&mut buf)),
+        Err(Error::UnknownEntity)
+    );
+}
@@ -1,8 +1,8 @@
 #![cfg(target_os = "macos")]
 
-embed_plist::embed_info_plist!("../src/Info.plist");
+embed_plist::embed_info_plist!("../src/Info.plist", verify);
 
-embed_plist::embed_launchd_plist!("../src/launchd.plist");
+embed_plist::embed_launchd_plist!("../src/launchd.plist", verify);
 
 fn get_info_plist_section() -> &'static [u8] {
     extern "C" {
@@ -63,3 +63,28 @@ fn launchd_plist_contents() {
     let included = include_str!("../src/launchd.plist");
     assert_eq!(embedded, included);
 }
+
+#[test]
+#[cfg(feature = "alloc")]
+fn semantic_contents() {
+    embed_plist::assert_plist_eq!(
+        get_info_plist_section(),
+        include_bytes!("../src/Info.plist"),
+    );
+    embed_plist::assert_plist_eq!(
+        get_launchd_plist_section(),
+        include_bytes!("../src/launchd.plist"),
+    );
+}
+
+#[test]
+fn verify_contents() {
+    assert_eq!(
+        embed_plist::verify_info_plist(),
+        Ok(get_info_plist_section())
+    );
+    assert_eq!(
+        embed_plist::verify_launchd_plist(),
+        Ok(get_launchd_plist_section())
+    );
+}
@@ -0,0 +1,34 @@
+#![cfg(target_os = "macos")]
+
+embed_plist::embed_info_plist!("../src/Info.plist", verify);
+
+// Stands in for data that a C object or `-sectcreate` adds to the section.
+#[used]
+#[link_section = "__TEXT,__info_plist,regular,no_dead_strip"]
+static FOREIGN: [u8; 6] = *b"\x00junk\n";
+
+#[test]
+fn foreign_contents() {
+    let included = include_bytes!("../src/Info.plist");
+
+    let mismatch = embed_plist::verify_info_plist().unwrap_err();
+    assert_eq!(mismatch.section_name(), "__TEXT,__info_plist");
+    assert_eq!(mismatch.embedded(), &included[..]);
+    assert_eq!(mismatch.section().len(), included.len() + FOREIGN.len());
+
+    // The linker decides the order of the two statics.
+    let foreign = if mismatch.starts_at_symbol() {
+        assert!(mismatch.foreign_before().is_empty());
+        ("after", mismatch.foreign_after())
+    } else {
+        assert!(mismatch.foreign_after().is_empty());
+        ("before", mismatch.foreign_before())
+    };
+    assert!(!mismatch.lengths_match());
+    assert_eq!(foreign.1, &FOREIGN[..]);
+
+    assert!(mismatch.to_string().ends_with(&format!(
+        "; 6 foreign bytes {}: b\"\\x00junk\\n\"",
+        foreign.0
+    )));
+}
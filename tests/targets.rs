@@ -0,0 +1,82 @@
+embed_plist::embed_info_plist! {
+    macos    => "../src/Info-macOS.plist",
+    ios      => "../src/Info-iOS.plist",
+    catalyst => "../src/Info-macOS.plist",
+}
+
+#[cfg(feature = "const_eval")]
+embed_plist::check_info_plist_targets! {
+    macos    => "../src/Info-macOS.plist",
+    ios      => "../src/Info-iOS.plist",
+    tvos     => "../src/Info-iOS.plist",
+    watchos  => "../src/Info-iOS.plist",
+    catalyst => "../src/Info-macOS.plist",
+}
+
+#[cfg(target_os = "macos")]
+#[test]
+fn info_plist_for_target() {
+    let embedded = embed_plist::get_info_plist();
+    let included = include_bytes!("../src/Info-macOS.plist");
+    assert_eq!(embedded, &included[..]);
+}
+
+#[cfg(feature = "const_eval")]
+mod check {
+    use embed_plist::const_eval::check_info_plist_target;
+    use std::panic;
+
+    const MACOS: &[u8] = include_bytes!("../src/Info-macOS.plist");
+
+    fn message(bytes: &[u8], target: &str) -> Option<String> {
+        let error =
+            panic::catch_unwind(|| check_info_plist_target(bytes, target))
+                .err()?;
+        Some(match error.downcast::<String>() {
+            Ok(message) => *message,
+            Err(error) => error.downcast_ref::<&str>().unwrap().to_string(),
+        })
+    }
+
+    #[test]
+    fn key_of_another_platform() {
+        assert_eq!(
+            message(MACOS, "ios").unwrap(),
+            "`Info.plist` for `ios` has `LSMinimumSystemVersion`, which is not \
+             read there"
+        );
+        let both = b"<dict>\
+            <key>LSMinimumSystemVersion</key><string>11.0</string>\
+            <key>MinimumOSVersion</key><string>14.0</string>\
+            <key>UIRequiresFullScreen</key><true/>\
+            </dict>";
+        assert_eq!(message(both, "catalyst"), None);
+        assert_eq!(
+            message(both, "macos").unwrap(),
+            "`Info.plist` for `macos` has `MinimumOSVersion`, which is not \
+             read there"
+        );
+    }
+
+    #[test]
+    fn no_required_keys() {
+        let ios = b"<dict><key>LSRequiresIPhoneOS</key><true/></dict>";
+        for target in ["ios", "tvos", "watchos"].iter() {
+            assert_eq!(message(ios, target), None);
+        }
+        assert_eq!(message(b"<dict/>", "macos"), None);
+        assert_eq!(
+            message(b"<array/>", "ios").unwrap(),
+            "property list is not a dictionary"
+        );
+    }
+
+    #[test]
+    fn unknown_target() {
+        assert_eq!(
+            message(b"<dict/>", "linux").unwrap(),
+            "unsupported target `linux`, expected one of: `macos`, `ios`, \
+             `tvos`, `watchos`, `catalyst`"
+        );
+    }
+}
@@ -0,0 +1,179 @@
+#![cfg(feature = "alloc")]
+
+use embed_plist::value::{Date, Dictionary, Value};
+use embed_plist::Error;
+
+fn dict(entries: Vec<(&str, Value)>) -> Value {
+    Value::Dictionary(entries.into_iter().collect())
+}
+
+#[test]
+fn same_as_xml() {
+    let toml = br#"
+        # A launchd job.
+        Why = "To use in doc tests"
+        Label = 'example'
+        ProgramArguments = [
+            "Hello",
+            "World", # Trailing comma.
+        ]
+    "#;
+    let xml = Value::from_xml(include_bytes!("../src/launchd.plist"));
+    assert_eq!(Value::from_toml(toml), xml);
+}
+
+#[test]
+fn values() {
+    let value = Value::from_toml(
+        br#"
+basic = "a\tb\u00e9\U0001F600"
+literal = 'C:\path'
+multiline = """
+one \
+    two ""quoted"""""
+raw = '''
+line
+'''
+int = +1_000
+hex = 0xdead_BEEF
+octal = 0o17
+binary = 0b101
+negative = -42
+big = 9223372036854775807
+real = 6.5e-1
+infinite = -inf
+date = 2020-08-30T14:34:56.5+02:00
+utc = 2020-08-30 12:34:56Z
+data = { "$data" = "AAEC" }
+inline = { a.b = true, c = [] }
+
+[table."dotted.key"]
+x = 1
+
+[table]
+y = 2
+
+[[items]]
+name = "first"
+
+[[items]]
+name = "second"
+[items.nested]
+z = 3
+"#,
+    )
+    .unwrap();
+
+    let date = Date::from_iso8601("2020-08-30T12:34:56Z").unwrap();
+    let half = Date::from_seconds_since_2001(date.seconds_since_2001() + 0.5);
+    let expected = dict(vec![
+        ("basic", "a\tbé😀".into()),
+        ("literal", "C:\\path".into()),
+        ("multiline", "one two \"\"quoted\"\"".into()),
+        ("raw", "line\n".into()),
+        ("int", 1000.into()),
+        ("hex", 0xDEAD_BEEFu32.into()),
+        ("octal", 0o17.into()),
+        ("binary", 0b101.into()),
+        ("negative", (-42).into()),
+        ("big", i64::MAX.into()),
+        ("real", 0.65.into()),
+        ("infinite", f64::NEG_INFINITY.into()),
+        ("date", half.unwrap().into()),
+        ("utc", date.into()),
+        ("data", vec![0u8, 1, 2].into()),
+        (
+            "inline",
+            dict(vec![
+                ("a", dict(vec![("b", true.into())])),
+                ("c", Value::Array(Vec::new())),
+            ]),
+        ),
+        (
+            "table",
+            dict(vec![
+                ("dotted.key", dict(vec![("x", 1.into())])),
+                ("y", 2.into()),
+            ]),
+        ),
+        (
+            "items",
+            Value::Array(vec![
+                dict(vec![("name", "first".into())]),
+                dict(vec![
+                    ("name", "second".into()),
+                    ("nested", dict(vec![("z", 3.into())])),
+                ]),
+            ]),
+        ),
+    ]);
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn tagged_table() {
+    let value = Value::from_toml(
+        b"[data]\n\"$data\" = \"AAEC\"\n[date]\n\"$date\" = \"2001-01-01T00:00:00Z\"",
+    )
+    .unwrap();
+    let expected: Dictionary = vec![
+        ("data", Value::Data(vec![0, 1, 2])),
+        ("date", Date::from_seconds_since_2001(0.0).unwrap().into()),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(value, Value::Dictionary(expected));
+}
+
+#[test]
+fn errors() {
+    let cases: [(&[u8], Error); 17] = [
+        (b"a = 1\na = 2", Error::DuplicateKey),
+        (b"a.b = 1\na.b = 2", Error::DuplicateKey),
+        (b"a = 1\n[a]", Error::DuplicateKey),
+        (b"[a]\n[a]", Error::DuplicateKey),
+        (b"[a]\n[[a]]", Error::DuplicateKey),
+        (b"a = 1 b = 2", Error::UnexpectedChar),
+        (b"= 1", Error::ExpectedKey),
+        (b"a = \"unterminated", Error::UnexpectedEnd),
+        (b"a = \"new\nline\"", Error::UnexpectedChar),
+        (b"a = 01", Error::InvalidValue),
+        (b"a = 1__0", Error::InvalidValue),
+        (b"a = 9223372036854775808", Error::InvalidValue),
+        (b"a = -9223372036854775809", Error::InvalidValue),
+        (b"a = 0xFFFFFFFFFFFFFFFF", Error::InvalidValue),
+        (b"a = 2020-08-30", Error::InvalidValue),
+        (b"a = 2020-08-30T12:34:56", Error::InvalidValue),
+        (b"a = { \"$data\" = \"!\" }", Error::InvalidValue),
+    ];
+    for (bytes, error) in cases.iter() {
+        assert_eq!(
+            Value::from_toml(bytes),
+            Err(*error),
+            "{}",
+            String::from_utf8_lossy(bytes),
+        );
+    }
+}
+
+#[test]
+fn deep_nesting() {
+    let nested = |open: &str, close: &str, depth: usize| {
+        format!("a = {}{}", open.repeat(depth), close.repeat(depth))
+    };
+    // The root table holds the outermost value.
+    assert!(Value::from_toml(nested("[", "]", 511).as_bytes()).is_ok());
+
+    let keys = |len: usize| vec!["k"; len].join(".");
+    let cases = [
+        nested("[", "]", 512),
+        nested("[", "]", 200_000),
+        nested("{b=", "}", 200_000),
+        format!("{} = 1", keys(513)),
+        format!("[{}]", keys(200_000)),
+    ];
+    for toml in cases.iter() {
+        assert_eq!(Value::from_toml(toml.as_bytes()), Err(Error::TooDeep));
+    }
+    assert!(Value::from_toml(format!("{} = 1", keys(512)).as_bytes()).is_ok());
+}
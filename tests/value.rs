@@ -0,0 +1,145 @@
+#![cfg(feature = "alloc")]
+
+use embed_plist::{
+    value::{Date, Dictionary, Indent, Value},
+    Error,
+};
+
+fn round_trip(file: &[u8], indent: Indent) {
+    assert_eq!(Indent::detect(file), Some(indent));
+    let value = Value::from_xml(file).unwrap();
+    let written = value.to_xml_with(indent);
+    assert_eq!(
+        std::str::from_utf8(&written).unwrap(),
+        std::str::from_utf8(file).unwrap(),
+    );
+}
+
+#[test]
+fn round_trip_fixtures() {
+    round_trip(include_bytes!("../src/Info.plist"), Indent::Spaces(4));
+    round_trip(include_bytes!("../src/launchd.plist"), Indent::Spaces(4));
+}
+
+#[test]
+fn round_trip_xcode_style() {
+    let file = concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+        "<plist version=\"1.0\">\n",
+        "<dict>\n",
+        "\t<key>Tom &amp; Jerry</key>\n",
+        "\t<string>&lt;cat&gt; &#13;</string>\n",
+        "\t<key>Empty</key>\n",
+        "\t<array>\n",
+        "\t\t<string></string>\n",
+        "\t\t<array/>\n",
+        "\t\t<dict/>\n",
+        "\t</array>\n",
+        "\t<key>Data</key>\n",
+        "\t<data>\n",
+        "\tAAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEy\n",
+        "\tMzQ1Njc4OTo7\n",
+        "\t</data>\n",
+        "\t<key>Date</key>\n",
+        "\t<date>2020-08-30T00:00:00Z</date>\n",
+        "\t<key>Numbers</key>\n",
+        "\t<array>\n",
+        "\t\t<integer>-1</integer>\n",
+        "\t\t<integer>18446744073709551615</integer>\n",
+        "\t\t<real>1.5</real>\n",
+        "\t\t<real>2</real>\n",
+        "\t\t<real>+infinity</real>\n",
+        "\t\t<true/>\n",
+        "\t\t<false/>\n",
+        "\t</array>\n",
+        "</dict>\n",
+        "</plist>\n",
+    );
+    round_trip(file.as_bytes(), Indent::Tab);
+}
+
+#[test]
+fn carriage_return() {
+    let value = Value::from("a\r\nb");
+    let written = value.to_xml();
+    assert_eq!(Value::from_xml(&written), Ok(value));
+}
+
+#[test]
+fn dictionary_order() {
+    let mut dict: Dictionary = vec![("b", 1), ("a", 2)].into_iter().collect();
+    assert_eq!(dict.insert("b", 3), Some(Value::Integer(1)));
+    assert_eq!(dict.insert("c", 4), None);
+    assert_eq!(dict.remove("a"), Some(Value::Integer(2)));
+
+    let entries: Vec<(&str, &Value)> = dict.iter().collect();
+    assert_eq!(
+        entries,
+        [("b", &Value::Integer(3)), ("c", &Value::Integer(4))]
+    );
+}
+
+#[test]
+fn dates() {
+    let cases = [
+        ("2001-01-01T00:00:00Z", 0.0),
+        ("2020-08-30T00:00:00Z", 620_438_400.0),
+        ("2000-02-29T23:59:59Z", -26_438_401.0),
+        ("0001-01-01T00:00:00Z", -63_113_904_000.0),
+    ];
+    for &(text, seconds) in cases.iter() {
+        let date = Date::from_iso8601(text).unwrap();
+        assert_eq!(date.seconds_since_2001(), seconds);
+        assert_eq!(date.to_string(), text);
+    }
+
+    for text in [
+        "2021-02-29T00:00:00Z",
+        "2020-13-01T00:00:00Z",
+        "2020-08-30 00:00:00Z",
+        "2020-08-30T00:00:00",
+        "0000-01-01T00:00:00Z",
+    ]
+    .iter()
+    {
+        assert_eq!(Date::from_iso8601(text), None, "{}", text);
+    }
+
+    let date = Date::from_seconds_since_2001(-0.5).unwrap();
+    assert_eq!(date.to_string(), "2000-12-31T23:59:59Z");
+    assert_eq!(Date::from_seconds_since_2001(f64::NAN), None);
+    assert_eq!(Date::from_seconds_since_2001(1e12), None);
+}
+
+#[test]
+fn uid() {
+    let value = Value::Array(vec![Value::Uid(7)]);
+    let written = value.to_xml();
+    assert!(std::str::from_utf8(&written).unwrap().contains(concat!(
+        "\t<dict>\n",
+        "\t\t<key>CF$UID</key>\n",
+        "\t\t<integer>7</integer>\n",
+        "\t</dict>\n",
+    )));
+    assert_eq!(Value::from_xml(&written), Ok(value));
+}
+
+#[test]
+fn deep_nesting() {
+    let nested = |open: &str, close: &str, depth: usize| {
+        format!("{}{}", open.repeat(depth), close.repeat(depth)).into_bytes()
+    };
+    assert!(Value::from_xml(&nested("<array>", "</array>", 512)).is_ok());
+
+    let dict = "<dict><key>a</key>";
+    for xml in [
+        nested("<array>", "</array>", 513),
+        nested("<array>", "</array>", 200_000),
+        nested(dict, "</dict>", 200_000),
+    ]
+    .iter()
+    {
+        assert_eq!(Value::from_xml(xml), Err(Error::TooDeep));
+    }
+}